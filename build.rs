@@ -5,11 +5,13 @@ use std::{
 };
 
 use png::Decoder;
+#[cfg(windows)]
 use winres::WindowsResource;
 
 const SMALL_ICON_PATH: &str = "resources/icons/icon16.png";
 const MEDIUM_ICON_PATH: &str = "resources/icons/icon32.png";
 const LARGE_ICON_PATH: &str = "resources/icons/icon64.png";
+#[cfg(windows)]
 const WINDOWS_ICON_PATH: &str = "resources/icons/windows_icon.ico";
 
 fn read_icon(path: &str, size: usize) -> Vec<u8> {
@@ -25,6 +27,7 @@ fn read_icon(path: &str, size: usize) -> Vec<u8> {
     buf
 }
 
+#[cfg(windows)]
 fn set_windows_icon() {
     let mut res = WindowsResource::new();
     res.set_icon(WINDOWS_ICON_PATH);
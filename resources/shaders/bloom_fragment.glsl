@@ -0,0 +1,32 @@
+#version 100
+
+precision mediump float;
+
+varying vec2 uv;
+
+uniform sampler2D Texture;
+// offset between the blur samples, zero copies the texture
+uniform vec2 texelDirection;
+// luminance below which pixels don't glow, zero keeps every pixel
+uniform float threshold;
+uniform float intensity;
+
+const vec3 LUMINANCE = vec3(0.2126, 0.7152, 0.0722);
+
+vec3 sampleBright(vec2 offset) {
+    vec3 color = texture2D(Texture, uv + offset).rgb;
+    float luminance = dot(color, LUMINANCE);
+    float bright = max(luminance - threshold, 0.0) / max(1.0 - threshold, 0.001);
+
+    return color * min(bright, 1.0);
+}
+
+void main() {
+    vec3 result = sampleBright(vec2(0.0)) * 0.227027;
+    result += (sampleBright(texelDirection) + sampleBright(-texelDirection)) * 0.1945946;
+    result += (sampleBright(texelDirection * 2.0) + sampleBright(-texelDirection * 2.0)) * 0.1216216;
+    result += (sampleBright(texelDirection * 3.0) + sampleBright(-texelDirection * 3.0)) * 0.054054;
+    result += (sampleBright(texelDirection * 4.0) + sampleBright(-texelDirection * 4.0)) * 0.016216;
+
+    gl_FragColor = vec4(result * intensity, 1.0);
+}
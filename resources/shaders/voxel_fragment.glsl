@@ -5,26 +5,38 @@ precision highp float;
 varying vec2 uv;
 varying vec3 fragNormal;
 varying vec3 facePosition;
+varying float vertexLight;
+varying float reflectivity;
 
 uniform sampler2D Texture;
+#ifndef STATIC_LIGHTING
 uniform sampler2D heightMap;
+#endif
 uniform vec3 cameraPos;
 uniform vec3 cameraTarget;
 uniform float lightLevel;
+uniform vec3 ambientColor;
 
 uniform float fogFar;
 uniform float fogNear;
-uniform vec3 fogBaseColorLight;
-uniform vec3 fogBaseColorDark;
+uniform vec3 fogColor;
+uniform int hasFog;
 
+#ifndef STATIC_LIGHTING
 uniform int lightsCount;
 uniform vec3 lights[64];
+uniform vec3 lightColors[64];
+uniform float lightRadii[64];
 
 uniform int explosionsCount;
 uniform vec3 explosions[16];
 
 uniform int hasDynamicShadows;
+#endif
 uniform int showDropShadow;
+uniform int smoothLighting;
+uniform int xRay;
+uniform int waterReflections;
 
 // static world lighting
 const vec3 lightDir = normalize(vec3(0.2, 0.8, -1.0));
@@ -37,14 +49,17 @@ const float dropShadowRadius = 0.4;
 const float dropShadowLight = 0.2;
 const float playerLightStrength = 15.0;
 
-// placed lamps
-const float lampStrength = 6.0;
-const vec3 lampColor = vec3(0.95, 1.0, 0.6);
-
 // explosions
 const float explosionLightStrength = 12.0;
 const vec3 explosionLightColor = vec3(1.0, 0.7, 0.2);
 
+// sky reflections on water surfaces
+const float minSkyReflection = 0.15;
+const float maxSkyReflection = 0.75;
+
+// debug see-through mode
+const float xRayAlpha = 0.25;
+
 // dynamic shadows
 const float dynamicShadowStrength = 0.6;
 const float halfVoxelSize = 0.5;
@@ -66,12 +81,14 @@ float calculateDiffuseLight(vec3 normal, float shadowedLightLevel) {
     return diffuse;
 }
 
-// draws the fog based on the fog uniforms and the time of day
-vec3 addFog(vec3 preFogColor, float distanceToFace, float darkLevel) {
-    vec3 fogBaseColor = fogBaseColorLight * lightLevel + fogBaseColorDark * darkLevel;
+// fades distant faces into the sky color to hide the render distance edge
+vec3 addFog(vec3 preFogColor, float distanceToFace) {
+    if (hasFog == 0) {
+        return preFogColor;
+    }
     float fogFactor = clamp((fogFar - distanceToFace) / (fogFar - fogNear), 0.0, 1.0);
 
-    return fogBaseColor * (1.0 - fogFactor) + preFogColor * fogFactor;
+    return fogColor * (1.0 - fogFactor) + preFogColor * fogFactor;
 }
 
 // draws the player light at night
@@ -95,9 +112,12 @@ vec3 addWorldLight(vec3 lightPosition, vec3 lightColor, float lightStrength) {
 
 // draws placed lamp and explosion lighting
 vec3 addWorldLighting(float lighting) {
+#ifdef STATIC_LIGHTING
+    return vec3(lighting);
+#else
     vec3 coloredLighting = vec3(lighting);
     for (int i = 0; i < lightsCount; i++) {
-        coloredLighting += addWorldLight(lights[i], lampColor, lampStrength);
+        coloredLighting += addWorldLight(lights[i], lightColors[i], lightRadii[i]);
     }
 
     for (int i = 0; i < explosionsCount; i++) {
@@ -109,10 +129,14 @@ vec3 addWorldLighting(float lighting) {
         min(coloredLighting.g, 1.0),
         min(coloredLighting.b, 1.0)
     );
+#endif
 }
 
 // calculates dynamic shadows from the height map
 float calculateAmountInShadow() {
+#ifdef STATIC_LIGHTING
+    return 0.0;
+#else
     if (hasDynamicShadows == 0) {
         return 0.0;
     }
@@ -130,6 +154,19 @@ float calculateAmountInShadow() {
     float edgeHigh = worldHeight + fadeAmount;
 
     return 1.0 - smoothstep(edgeLow, edgeHigh, sampledHeight);
+#endif
+}
+
+// returns how much of the sky is reflected, stronger at grazing angles
+float calculateSkyReflection(vec3 normal, vec3 viewDir) {
+    if (waterReflections == 0 || reflectivity <= 0.0) {
+        return 0.0;
+    }
+
+    float facing = max(dot(normal, viewDir), 0.0);
+    float fresnel = minSkyReflection + (1.0 - minSkyReflection) * pow(1.0 - facing, 5.0);
+
+    return min(fresnel, maxSkyReflection) * reflectivity;
 }
 
 void main() {
@@ -144,6 +181,9 @@ void main() {
 
     float sunLighting = min(lightLevel, 1.0) * (ambient + diffuse * (1.0 - ambient));
     sunLighting *= (1.0 - dynamicShadowStrength * amountInShadow);
+    if (smoothLighting == 1) {
+        sunLighting *= vertexLight;
+    }
     vec3 coloredLighting = addWorldLighting(addPlayerLight(sunLighting, distanceToFace, darkLevel));
 
     vec3 viewDir = normalize(-facePosition);
@@ -153,9 +193,19 @@ void main() {
     float fresnel = pow(1.0 - max(dot(normal, viewDir), 0.0), 3.0);
     float rim = fresnel * reflectionIntensity;
 
-    vec3 preFogColor = texColor.rgb * coloredLighting + vec3(specular) + vec3(rim);
+    // the fog has the sky color so the reflection matches the sky at any time of day
+    float skyReflection = calculateSkyReflection(normal, viewDir);
+    vec3 surfaceColor = mix(texColor.rgb * coloredLighting, fogColor, skyReflection);
 
-    vec3 finalColor = addFog(preFogColor, distanceToFace, darkLevel);
+    // the fog has its own day and night colors so the tint is applied only before it
+    vec3 preFogColor = (surfaceColor + vec3(specular) + vec3(rim)) * ambientColor;
+
+    vec3 finalColor = addFog(preFogColor, distanceToFace);
+
+    float alpha = max(texColor.a, skyReflection);
+    if (xRay == 1) {
+        alpha *= xRayAlpha;
+    }
 
-    gl_FragColor = vec4(finalColor, texColor.a);
+    gl_FragColor = vec4(finalColor, alpha);
 }
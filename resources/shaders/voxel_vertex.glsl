@@ -2,11 +2,14 @@
 
 attribute vec3 position;
 attribute vec2 texcoord;
+attribute vec4 color0;
 attribute vec3 normal;
 
 varying lowp vec2 uv;
 varying lowp vec3 fragNormal;
 varying vec3 facePosition;
+varying lowp float vertexLight;
+varying lowp float reflectivity;
 
 uniform mat4 Model;
 uniform mat4 Projection;
@@ -19,4 +22,6 @@ void main() {
     gl_Position = Projection * Model * vec4(facePosition, 1.0);
     uv = texcoord;
     fragNormal = normalize(mat3(Model) * normal);
+    vertexLight = color0.r;
+    reflectivity = 1.0 - color0.a;
 }
\ No newline at end of file
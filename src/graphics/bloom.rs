@@ -0,0 +1,151 @@
+use macroquad::{
+    camera::{Camera2D, Camera3D, set_camera, set_default_camera},
+    color::{BLACK, WHITE},
+    material::gl_use_default_material,
+    math::{Rect, vec2},
+    texture::{
+        DrawTextureParams, FilterMode, RenderTarget, RenderTargetParams, Texture2D,
+        draw_texture_ex, render_target_ex,
+    },
+    window::clear_background,
+};
+
+use crate::graphics::shader_manager::SHADER_MANAGER_INSTANCE;
+
+/// the glow is blurred at a lower resolution, making it wider and cheaper
+const BLUR_RESOLUTION_DIVISOR: u32 = 2;
+/// spreads the blur samples further apart
+const BLUR_SPREAD: f32 = 1.5;
+/// only very bright pixels glow during the day so the sky doesn't wash out the scene
+const DAY_THRESHOLD: f32 = 0.93;
+const NIGHT_THRESHOLD: f32 = 0.55;
+
+struct BloomTargets {
+    width: u32,
+    height: u32,
+    scene: RenderTarget,
+    extracted: RenderTarget,
+    blurred: RenderTarget,
+}
+impl BloomTargets {
+    fn new(width: u32, height: u32) -> Self {
+        let blur_width = (width / BLUR_RESOLUTION_DIVISOR).max(1);
+        let blur_height = (height / BLUR_RESOLUTION_DIVISOR).max(1);
+        let scene = render_target_ex(
+            width,
+            height,
+            RenderTargetParams {
+                sample_count: 1,
+                depth: true,
+            },
+        );
+        let extracted = render_target_ex(blur_width, blur_height, RenderTargetParams::default());
+        let blurred = render_target_ex(blur_width, blur_height, RenderTargetParams::default());
+        for target in [&scene, &extracted, &blurred] {
+            target.texture.set_filter(FilterMode::Linear);
+        }
+
+        Self {
+            width,
+            height,
+            scene,
+            extracted,
+            blurred,
+        }
+    }
+}
+
+/// post-process making bright pixels like lamps, lava, the sun and lightning glow,
+/// the 3d scene is drawn to an off-screen target and composited with its blurred highlights
+pub struct Bloom {
+    targets: Option<BloomTargets>,
+}
+impl Bloom {
+    pub fn new() -> Self {
+        Self { targets: None }
+    }
+
+    /// redirects the camera to the off-screen target if bloom is enabled,
+    /// the targets are released when disabled
+    pub fn prepare_camera(&mut self, camera: &mut Camera3D, is_enabled: bool, size: (f32, f32)) {
+        if !is_enabled {
+            self.targets = None;
+            return;
+        }
+
+        let (width, height) = (size.0.max(1.0) as u32, size.1.max(1.0) as u32);
+        let targets = match self.targets.take() {
+            Some(targets) if targets.width == width && targets.height == height => targets,
+            _ => BloomTargets::new(width, height),
+        };
+        camera.render_target = Some(targets.scene.clone());
+        self.targets = Some(targets);
+    }
+
+    /// draws the scene with the glow to the screen, does nothing if not prepared,
+    /// the light level raises the threshold during the day
+    pub fn draw(&self, width: f32, height: f32, light_level: f32) {
+        let Some(targets) = &self.targets else {
+            return;
+        };
+        let shader = &SHADER_MANAGER_INSTANCE.bloom_shader;
+        let blur_size = vec2(
+            targets.extracted.texture.width(),
+            targets.extracted.texture.height(),
+        );
+        let texel_size = BLUR_SPREAD / blur_size;
+        let threshold =
+            NIGHT_THRESHOLD + (DAY_THRESHOLD - NIGHT_THRESHOLD) * light_level.clamp(0.0, 1.0);
+
+        Self::draw_pass(&targets.scene.texture, &targets.extracted, || {
+            shader.set_extract_material(texel_size, threshold)
+        });
+        Self::draw_pass(&targets.extracted.texture, &targets.blurred, || {
+            shader.set_blur_material(texel_size)
+        });
+
+        set_default_camera();
+        shader.set_copy_material();
+        Self::draw_flipped_texture(&targets.scene.texture, width, height);
+        shader.set_glow_material();
+        Self::draw_flipped_texture(&targets.blurred.texture, width, height);
+        gl_use_default_material();
+    }
+
+    fn draw_pass(source: &Texture2D, target: &RenderTarget, set_material: impl FnOnce()) {
+        let width = target.texture.width();
+        let height = target.texture.height();
+        let mut camera = Camera2D::from_display_rect(Rect::new(0.0, 0.0, width, height));
+        camera.render_target = Some(target.clone());
+        set_camera(&camera);
+        clear_background(BLACK);
+
+        set_material();
+        draw_texture_ex(
+            source,
+            0.0,
+            0.0,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(vec2(width, height)),
+                ..Default::default()
+            },
+        );
+        gl_use_default_material();
+    }
+
+    /// 3d render targets are stored upside down
+    fn draw_flipped_texture(texture: &Texture2D, width: f32, height: f32) {
+        draw_texture_ex(
+            texture,
+            0.0,
+            0.0,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(vec2(width, height)),
+                flip_y: true,
+                ..Default::default()
+            },
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use macroquad::{
+    math::{Vec2, vec2},
+    miniquad::{BlendFactor, BlendState, Equation},
+    prelude::{
+        Material, MaterialParams, PipelineParams, ShaderSource, UniformDesc, UniformType,
+        gl_use_material, load_material,
+    },
+};
+
+const BLOOM_VERTEX_SHADER: &str = include_str!("../../resources/shaders/bloom_vertex.glsl");
+const BLOOM_FRAGMENT_SHADER: &str = include_str!("../../resources/shaders/bloom_fragment.glsl");
+
+const TEXEL_DIRECTION_UNIFORM: &str = "texelDirection";
+const THRESHOLD_UNIFORM: &str = "threshold";
+const INTENSITY_UNIFORM: &str = "intensity";
+
+/// strength of the glow added over the scene
+const BLOOM_INTENSITY: f32 = 0.8;
+
+/// shader used for the bloom post-process passes
+pub struct BloomShader {
+    /// extracts the bright pixels and blurs them horizontally
+    extract_material: Material,
+    blur_material: Material,
+    /// copies the scene without blending
+    copy_material: Material,
+    /// adds the blurred glow over the scene
+    glow_material: Material,
+}
+impl BloomShader {
+    pub fn new() -> Self {
+        let extract_material = Self::load_bloom_material(PipelineParams::default());
+        let blur_material = Self::load_bloom_material(PipelineParams::default());
+        let copy_material = Self::load_bloom_material(PipelineParams::default());
+        let glow_material = Self::load_bloom_material(PipelineParams {
+            color_blend: Some(BlendState::new(
+                Equation::Add,
+                BlendFactor::One,
+                BlendFactor::One,
+            )),
+            ..Default::default()
+        });
+
+        Self::set_pass_uniforms(&blur_material, Vec2::ZERO, 0.0, 1.0);
+        Self::set_pass_uniforms(&copy_material, Vec2::ZERO, 0.0, 1.0);
+        Self::set_pass_uniforms(&glow_material, Vec2::ZERO, 0.0, BLOOM_INTENSITY);
+
+        Self {
+            extract_material,
+            blur_material,
+            copy_material,
+            glow_material,
+        }
+    }
+
+    fn load_bloom_material(pipeline_params: PipelineParams) -> Material {
+        load_material(
+            ShaderSource::Glsl {
+                vertex: BLOOM_VERTEX_SHADER,
+                fragment: BLOOM_FRAGMENT_SHADER,
+            },
+            MaterialParams {
+                pipeline_params,
+                uniforms: vec![
+                    UniformDesc::new(TEXEL_DIRECTION_UNIFORM, UniformType::Float2),
+                    UniformDesc::new(THRESHOLD_UNIFORM, UniformType::Float1),
+                    UniformDesc::new(INTENSITY_UNIFORM, UniformType::Float1),
+                ],
+                ..Default::default()
+            },
+        )
+        .expect("Error initialising bloom shaders")
+    }
+
+    fn set_pass_uniforms(
+        material: &Material,
+        texel_direction: Vec2,
+        threshold: f32,
+        intensity: f32,
+    ) {
+        material.set_uniform(TEXEL_DIRECTION_UNIFORM, texel_direction);
+        material.set_uniform(THRESHOLD_UNIFORM, threshold);
+        material.set_uniform(INTENSITY_UNIFORM, intensity);
+    }
+
+    /// texel size is the uv size of a pixel in the blurred texture
+    pub fn set_extract_material(&self, texel_size: Vec2, threshold: f32) {
+        Self::set_pass_uniforms(
+            &self.extract_material,
+            vec2(texel_size.x, 0.0),
+            threshold,
+            1.0,
+        );
+        gl_use_material(&self.extract_material);
+    }
+
+    /// texel size is the uv size of a pixel in the blurred texture
+    pub fn set_blur_material(&self, texel_size: Vec2) {
+        self.blur_material
+            .set_uniform(TEXEL_DIRECTION_UNIFORM, vec2(0.0, texel_size.y));
+        gl_use_material(&self.blur_material);
+    }
+
+    pub fn set_copy_material(&self) {
+        gl_use_material(&self.copy_material);
+    }
+
+    pub fn set_glow_material(&self) {
+        gl_use_material(&self.glow_material);
+    }
+}
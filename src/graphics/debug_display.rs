@@ -1,8 +1,8 @@
 use macroquad::{
     camera::Camera3D,
-    color::WHITE,
-    math::vec3,
-    models::draw_cube_wires,
+    color::{Color, WHITE},
+    math::{Vec3, vec3},
+    models::{draw_cube_wires, draw_line_3d},
     prelude::info,
     shapes::draw_rectangle,
     text::Font,
@@ -11,24 +11,33 @@ use macroquad::{
 
 use crate::{
     interface::{
-        style::{CLEAR_SCREEN_COLOR, TEXT_COLOR},
+        style::{BUTTON_FONT_SIZE, CLEAR_SCREEN_COLOR, TEXT_COLOR},
         text::draw_game_text,
     },
     model::{
         area::{AREA_HEIGHT, AREA_SIZE, VOXELS_IN_AREA},
+        key_bindings::{KeyAction, KeyBindings},
+        location::{AreaLocation, InternalLocation, Location},
         voxel::Voxel,
         world::World,
     },
-    service::{camera_controller::CameraController, creatures::creature_manager::CreatureManager},
-    utils::vector_to_location,
+    service::{
+        area_generation::biome_type::BiomeType, camera_controller::CameraController,
+        creatures::creature_manager::CreatureManager,
+        physics::falling_voxel_simulator::MAX_FALLING_VOXELS,
+    },
 };
 
 use super::renderer::Renderer;
 
 const KILOBYTE: usize = 1024;
 const MS_IN_SECONDS: f32 = 1000.0;
-const FONT_SIZE: f32 = 30.0;
+const FONT_SIZE: f32 = BUTTON_FONT_SIZE;
 const LEFT_MARGIN: f32 = 10.0;
+const ALL_AREA_BORDERS_COLOR: Color = Color::new(1.0, 1.0, 0.0, 0.6);
+const CHANGED_AREA_BORDER_COLOR: Color = Color::new(1.0, 0.2, 0.2, 1.0);
+/// borders closer than this are drawn even if behind the camera look direction
+const MIN_BORDER_CULL_DISTANCE: f32 = AREA_SIZE as f32 * 2.0;
 
 pub struct DebugInfo<'a> {
     pub world: &'a World,
@@ -37,23 +46,62 @@ pub struct DebugInfo<'a> {
     pub rendered_areas_faces: (usize, usize),
     pub creature_manager: &'a CreatureManager,
     pub rendered_creatures: u32,
+    pub falling_voxels: usize,
+    pub is_time_frozen: bool,
+    pub player_location: Location,
+    /// biome of the column the player is in
+    pub biome: BiomeType,
+    /// shows the keys of the debug toggles
+    pub key_bindings: &'a KeyBindings,
 }
 
 pub struct DebugDisplay {
     should_display: bool,
+    x_ray: bool,
+    /// draws the borders of every area within the render distance, not only the current one
+    all_area_borders: bool,
+    /// draws the borders of the loaded areas that will be saved when unloaded
+    changed_areas: bool,
 }
 impl DebugDisplay {
     pub fn new() -> Self {
         Self {
             should_display: false,
+            x_ray: false,
+            all_area_borders: false,
+            changed_areas: false,
         }
     }
 
+    pub fn is_displayed(&self) -> bool {
+        self.should_display
+    }
+
     pub fn toggle_display(&mut self) {
         self.should_display = !self.should_display;
         info!("Debug display:{}", self.should_display);
     }
 
+    pub fn toggle_x_ray(&mut self) {
+        self.x_ray = !self.x_ray;
+        info!("X-ray:{}", self.x_ray);
+    }
+
+    pub fn toggle_all_area_borders(&mut self) {
+        self.all_area_borders = !self.all_area_borders;
+        info!("All area borders:{}", self.all_area_borders);
+    }
+
+    pub fn toggle_changed_areas(&mut self) {
+        self.changed_areas = !self.changed_areas;
+        info!("Changed areas:{}", self.changed_areas);
+    }
+
+    /// solid voxels are see-through, only active while the debug display is shown
+    pub fn is_x_ray(&self) -> bool {
+        self.should_display && self.x_ray
+    }
+
     pub fn draw_debug_display(&self, debug_info: DebugInfo, font: &Font) {
         if !self.should_display {
             return;
@@ -62,7 +110,6 @@ impl DebugDisplay {
         let fps = get_fps();
         let frame_time_ms = get_frame_time() * MS_IN_SECONDS;
         let meshes = debug_info.renderer.get_voxel_face_count();
-        let camera_location = vector_to_location(debug_info.camera.position);
         let look_target = debug_info.camera.target;
         let loaded_areas = debug_info.world.get_loaded_areas_count();
         let areas_max_height_bytes = loaded_areas * VOXELS_IN_AREA;
@@ -99,7 +146,10 @@ impl DebugDisplay {
             font,
         );
         draw_game_text(
-            &format!("Loaded areas:{loaded_areas}({areas_memory_kb}KB)"),
+            &format!(
+                "Loaded areas:{loaded_areas}/{}({areas_memory_kb}KB)",
+                debug_info.world.get_max_loaded_areas()
+            ),
             LEFT_MARGIN,
             4.0 * FONT_SIZE,
             FONT_SIZE,
@@ -122,7 +172,9 @@ impl DebugDisplay {
         draw_game_text(
             &format!(
                 "(X:{}, Y:{}, Z:{})",
-                camera_location.x, camera_location.y, camera_location.z
+                debug_info.player_location.x,
+                debug_info.player_location.y,
+                debug_info.player_location.z
             ),
             LEFT_MARGIN,
             6.0 * FONT_SIZE,
@@ -153,19 +205,122 @@ impl DebugDisplay {
             TEXT_COLOR,
             font,
         );
+        draw_game_text(
+            &format!(
+                "Falling voxels: {}/{MAX_FALLING_VOXELS}",
+                debug_info.falling_voxels
+            ),
+            LEFT_MARGIN,
+            9.0 * FONT_SIZE,
+            FONT_SIZE,
+            TEXT_COLOR,
+            font,
+        );
+        draw_game_text(
+            &format!(
+                "X-ray ({}): {}",
+                debug_info
+                    .key_bindings
+                    .get(KeyAction::ToggleXRay)
+                    .get_name(),
+                if self.x_ray { "ON" } else { "OFF" }
+            ),
+            LEFT_MARGIN,
+            10.0 * FONT_SIZE,
+            FONT_SIZE,
+            TEXT_COLOR,
+            font,
+        );
+        draw_game_text(
+            &format!(
+                "Area borders ({}): {}",
+                debug_info
+                    .key_bindings
+                    .get(KeyAction::ToggleAllAreaBorders)
+                    .get_name(),
+                if self.all_area_borders {
+                    "ALL"
+                } else {
+                    "CURRENT"
+                }
+            ),
+            LEFT_MARGIN,
+            11.0 * FONT_SIZE,
+            FONT_SIZE,
+            TEXT_COLOR,
+            font,
+        );
+        draw_game_text(
+            &format!(
+                "Changed areas ({}): {} ({} unsaved)",
+                debug_info
+                    .key_bindings
+                    .get(KeyAction::ToggleChangedAreas)
+                    .get_name(),
+                if self.changed_areas { "ON" } else { "OFF" },
+                debug_info.world.get_changed_area_locations().count()
+            ),
+            LEFT_MARGIN,
+            12.0 * FONT_SIZE,
+            FONT_SIZE,
+            TEXT_COLOR,
+            font,
+        );
+        draw_game_text(
+            &format!(
+                "Freeze time ({}): {}",
+                debug_info
+                    .key_bindings
+                    .get(KeyAction::ToggleTimeFreeze)
+                    .get_name(),
+                if debug_info.is_time_frozen {
+                    "ON"
+                } else {
+                    "OFF"
+                }
+            ),
+            LEFT_MARGIN,
+            13.0 * FONT_SIZE,
+            FONT_SIZE,
+            TEXT_COLOR,
+            font,
+        );
+        draw_game_text(
+            &format!("Biome: {}", debug_info.biome.get_name()),
+            LEFT_MARGIN,
+            14.0 * FONT_SIZE,
+            FONT_SIZE,
+            TEXT_COLOR,
+            font,
+        );
     }
 
-    pub fn draw_area_border(&self, camera_controller: &CameraController) {
+    pub fn draw_area_border(
+        &self,
+        world: &World,
+        camera_controller: &CameraController,
+        render_distance: u32,
+    ) {
         if !self.should_display {
             return;
         }
-
-        const AREA_SIZE_F32: f32 = AREA_SIZE as f32;
-        const AREA_SIZE_I32: i32 = AREA_SIZE as i32;
+        if self.all_area_borders {
+            Self::draw_all_area_borders(camera_controller, render_distance);
+        }
+        if self.changed_areas {
+            Self::draw_changed_area_borders(world, camera_controller);
+        }
 
         let camera_location = camera_controller.get_camera_voxel_location();
         let area_x = camera_location.x.div_euclid(AREA_SIZE as i32);
         let area_y = camera_location.y.div_euclid(AREA_SIZE as i32);
+        Self::draw_area_cube(camera_controller.get_position(), area_x, area_y, WHITE);
+    }
+
+    /// draws the area as a wireframe box, the area coordinates are in signed world space
+    fn draw_area_cube(camera_position: Vec3, area_x: i32, area_y: i32, color: Color) {
+        const AREA_SIZE_F32: f32 = AREA_SIZE as f32;
+        const AREA_SIZE_I32: i32 = AREA_SIZE as i32;
 
         let x = (area_x * AREA_SIZE_I32) as f32 + AREA_SIZE_F32 / 2.0 - 0.5;
         let y = (area_y * AREA_SIZE_I32) as f32 + AREA_SIZE_F32 / 2.0 - 0.5;
@@ -173,8 +328,62 @@ impl DebugDisplay {
         let size = AREA_SIZE_F32;
         let height = AREA_HEIGHT as f32;
 
-        let position = vec3(x, y, z - 0.5) - camera_controller.get_position();
-        draw_cube_wires(position, vec3(size, size, height), WHITE);
+        let position = vec3(x, y, z - 0.5) - camera_position;
+        draw_cube_wires(position, vec3(size, size, height), color);
+    }
+
+    /// marks the loaded areas with unsaved changes that are in front of the camera
+    fn draw_changed_area_borders(world: &World, camera_controller: &CameraController) {
+        const AREA_SIZE_I32: i32 = AREA_SIZE as i32;
+        let camera_position = camera_controller.get_position();
+        let camera = camera_controller.create_camera();
+        let look = (camera.target - camera.position).normalize_or_zero();
+
+        for area_location in world.get_changed_area_locations() {
+            let origin = get_area_origin(area_location);
+            let to_center = vec3(
+                (origin.x + AREA_SIZE_I32 / 2) as f32 - camera_position.x,
+                (origin.y + AREA_SIZE_I32 / 2) as f32 - camera_position.y,
+                0.0,
+            );
+            if to_center.length() > MIN_BORDER_CULL_DISTANCE && to_center.dot(look) < 0.0 {
+                continue;
+            }
+
+            Self::draw_area_cube(
+                camera_position,
+                origin.x.div_euclid(AREA_SIZE_I32),
+                origin.y.div_euclid(AREA_SIZE_I32),
+                CHANGED_AREA_BORDER_COLOR,
+            );
+        }
+    }
+
+    /// draws vertical lines at the area corners within the render distance that are in front of the camera
+    fn draw_all_area_borders(camera_controller: &CameraController, render_distance: u32) {
+        const AREA_SIZE_I32: i32 = AREA_SIZE as i32;
+        let camera_position = camera_controller.get_position();
+        let camera = camera_controller.create_camera();
+        let look = (camera.target - camera.position).normalize_or_zero();
+        let camera_location = camera_controller.get_camera_voxel_location();
+        let area_x = camera_location.x.div_euclid(AREA_SIZE_I32);
+        let area_y = camera_location.y.div_euclid(AREA_SIZE_I32);
+        let render_distance = render_distance as i32;
+
+        for corner_y in (area_y - render_distance)..=(area_y + render_distance + 1) {
+            for corner_x in (area_x - render_distance)..=(area_x + render_distance + 1) {
+                let x = (corner_x * AREA_SIZE_I32) as f32 - 0.5;
+                let y = (corner_y * AREA_SIZE_I32) as f32 - 0.5;
+                let to_corner = vec3(x - camera_position.x, y - camera_position.y, 0.0);
+                if to_corner.length() > MIN_BORDER_CULL_DISTANCE && to_corner.dot(look) < 0.0 {
+                    continue;
+                }
+
+                let top = vec3(x, y, -0.5) - camera_position;
+                let bottom = top + Vec3::Z * AREA_HEIGHT as f32;
+                draw_line_3d(top, bottom, ALL_AREA_BORDERS_COLOR);
+            }
+        }
     }
 
     pub fn draw_creature_bounding_boxes(
@@ -190,6 +399,11 @@ impl DebugDisplay {
     }
 
     fn draw_background() {
-        draw_rectangle(0.0, 0.0, 530.0, FONT_SIZE * 9.0, CLEAR_SCREEN_COLOR);
+        draw_rectangle(0.0, 0.0, 530.0, FONT_SIZE * 14.0, CLEAR_SCREEN_COLOR);
     }
 }
+
+/// world location of the lowest x and y corner of the area
+fn get_area_origin(area_location: AreaLocation) -> Location {
+    InternalLocation::new(area_location.x * AREA_SIZE, area_location.y * AREA_SIZE, 0).into()
+}
@@ -0,0 +1,195 @@
+use bincode::{Decode, Encode};
+use macroquad::{
+    math::{vec2, vec3, vec4},
+    models::{Mesh, Vertex},
+    texture::Texture2D,
+};
+
+use crate::{
+    graphics::mesh_generator::MeshGenerator,
+    model::{
+        location::{AreaLocation, InternalLocation, Location},
+        voxel::Voxel,
+        world::World,
+    },
+};
+
+/// changed when the meshing changes so the meshes cached by older versions are regenerated
+const MESH_CACHE_VERSION: u16 = 1;
+
+#[derive(Debug, Clone, Encode, Decode)]
+struct VertexDTO {
+    position: [f32; 3],
+    uv: [f32; 2],
+    color: [u8; 4],
+    normal: [f32; 4],
+}
+
+/// the mesh of a single voxel, the vertex positions are relative to the voxel
+/// so the cache doesn't depend on the render origin
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct VoxelMeshDTO {
+    location: InternalLocation,
+    face_count: u8,
+    voxel: Voxel,
+    vertices: Vec<VertexDTO>,
+    indices: Vec<u16>,
+}
+impl VoxelMeshDTO {
+    pub fn new(
+        location: InternalLocation,
+        face_count: u8,
+        voxel: Voxel,
+        mesh: &Mesh,
+        render_origin: Location,
+    ) -> Self {
+        let middle = MeshGenerator::get_relative_position(location, render_origin);
+        let vertices = mesh
+            .vertices
+            .iter()
+            .map(|vertex| VertexDTO {
+                position: (vertex.position - middle).to_array(),
+                uv: vertex.uv.to_array(),
+                color: vertex.color,
+                normal: vertex.normal.to_array(),
+            })
+            .collect();
+
+        Self {
+            location,
+            face_count,
+            voxel,
+            vertices,
+            indices: mesh.indices.clone(),
+        }
+    }
+
+    pub fn get_voxel(&self) -> Voxel {
+        self.voxel
+    }
+
+    /// returns the location, face count and the mesh with vertices relative to the render origin
+    pub fn into_mesh(
+        self,
+        render_origin: Location,
+        texture: Option<Texture2D>,
+    ) -> (InternalLocation, u8, Mesh) {
+        let middle = MeshGenerator::get_relative_position(self.location, render_origin);
+        let vertices = self
+            .vertices
+            .into_iter()
+            .map(|vertex| Vertex {
+                position: vec3(vertex.position[0], vertex.position[1], vertex.position[2]) + middle,
+                uv: vec2(vertex.uv[0], vertex.uv[1]),
+                color: vertex.color,
+                normal: vec4(
+                    vertex.normal[0],
+                    vertex.normal[1],
+                    vertex.normal[2],
+                    vertex.normal[3],
+                ),
+            })
+            .collect();
+
+        let mesh = Mesh {
+            vertices,
+            indices: self.indices,
+            texture,
+        };
+
+        (self.location, self.face_count, mesh)
+    }
+}
+
+/// the meshes of an area with the state they were generated from,
+/// they are only used if that state is unchanged
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct AreaMeshCacheDTO {
+    version: u16,
+    smooth_lighting: bool,
+    /// checksums of the area and its neighbours, see `calculate_checksums`
+    checksums: [u64; 5],
+    meshes: Vec<VoxelMeshDTO>,
+}
+impl AreaMeshCacheDTO {
+    pub fn new(smooth_lighting: bool, checksums: [u64; 5], meshes: Vec<VoxelMeshDTO>) -> Self {
+        Self {
+            version: MESH_CACHE_VERSION,
+            smooth_lighting,
+            checksums,
+            meshes,
+        }
+    }
+
+    pub fn is_valid(&self, smooth_lighting: bool, checksums: [u64; 5]) -> bool {
+        self.version == MESH_CACHE_VERSION
+            && self.smooth_lighting == smooth_lighting
+            && self.checksums == checksums
+    }
+
+    pub fn into_meshes(self) -> Vec<VoxelMeshDTO> {
+        self.meshes
+    }
+}
+
+/// checksums of the voxels of the area and its four neighbours,
+/// the faces at the border and the ambient occlusion depend on the neighbours
+pub fn calculate_checksums(world: &mut World, area_location: AreaLocation) -> [u64; 5] {
+    [
+        area_location,
+        AreaLocation::new(area_location.x + 1, area_location.y),
+        AreaLocation::new(area_location.x - 1, area_location.y),
+        AreaLocation::new(area_location.x, area_location.y + 1),
+        AreaLocation::new(area_location.x, area_location.y - 1),
+    ]
+    .map(|location| world.with_cached_area(location, |_world, area| area.calculate_checksum()))
+}
+
+#[cfg(test)]
+mod tests {
+    use macroquad::math::{Vec2, Vec4};
+
+    use super::*;
+
+    #[test]
+    fn test_voxel_mesh_moves_with_render_origin() {
+        let location = InternalLocation::from(Location::new(20, -5, 40));
+        let old_origin = Location::new(0, 0, 0);
+        let new_origin = Location::new(16, -16, 0);
+        let middle = MeshGenerator::get_relative_position(location, old_origin);
+        let mesh = Mesh {
+            vertices: vec![Vertex {
+                position: middle + vec3(0.5, -0.5, 0.5),
+                uv: Vec2::ONE,
+                color: [255, 210, 170, 255],
+                normal: Vec4::Z,
+            }],
+            indices: vec![0, 0, 0],
+            texture: None,
+        };
+
+        let dto = VoxelMeshDTO::new(location, 1, Voxel::Stone, &mesh, old_origin);
+        let (loaded_location, face_count, loaded_mesh) = dto.into_mesh(new_origin, None);
+
+        assert_eq!(loaded_location, location);
+        assert_eq!(face_count, 1);
+        assert_eq!(loaded_mesh.indices, mesh.indices);
+        let vertex = loaded_mesh.vertices[0];
+        assert_eq!(
+            vertex.position,
+            MeshGenerator::get_relative_position(location, new_origin) + vec3(0.5, -0.5, 0.5)
+        );
+        assert_eq!(vertex.color, mesh.vertices[0].color);
+        assert_eq!(vertex.normal, Vec4::Z);
+    }
+
+    #[test]
+    fn test_cache_invalid_if_state_changed() {
+        let checksums = [1, 2, 3, 4, 5];
+        let cache = AreaMeshCacheDTO::new(true, checksums, vec![]);
+
+        assert!(cache.is_valid(true, checksums));
+        assert!(!cache.is_valid(false, checksums));
+        assert!(!cache.is_valid(true, [1, 2, 3, 4, 6]));
+    }
+}
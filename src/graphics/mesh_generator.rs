@@ -7,6 +7,7 @@ use macroquad::{
     math::{Vec2, Vec3, Vec4, vec2, vec3},
     models::Mesh,
     rand::rand,
+    texture::Texture2D,
     ui::Vertex,
 };
 
@@ -14,6 +15,7 @@ use crate::{
     graphics::mesh_transformer,
     model::{
         location::{InternalLocation, Location},
+        orientation::Orientation,
         voxel::Voxel,
     },
     service::asset_manager::AssetManager,
@@ -21,7 +23,7 @@ use crate::{
 
 use super::texture_manager::TextureManager;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FaceDirection {
     /// z - 1
     Up,
@@ -42,6 +44,8 @@ pub struct MeshGenerator {
 }
 impl MeshGenerator {
     const COLOR: [u8; 4] = [255, 255, 255, 255];
+    /// the vertex alpha is unused for blending, zero marks faces reflecting the sky
+    const REFLECTIVE_COLOR: [u8; 4] = [255, 255, 255, 0];
     const INDECIES: [u16; 6] = [0, 1, 2, 0, 2, 3];
     const FRONT_NORMAL: Vec4 = Vec4::new(0.0, 1.0, 0.0, 0.0);
     const BACK_NORMAL: Vec4 = Vec4::new(0.0, -1.0, 0.0, 0.0);
@@ -58,6 +62,25 @@ impl MeshGenerator {
         FaceDirection::Down,
     ];
     const VERTICES_PER_FACE: usize = 4;
+    /// the faces of stairs going up towards y - 1, each face is on the side of a box
+    /// given by its min and max corner relative to the middle,
+    /// the faces inside the cell (marked true) are always generated
+    const STAIRS_FACES: [(FaceDirection, [Vec3; 2], bool); 11] = [
+        (FaceDirection::Back, Self::STAIRS_BACK, false),
+        (FaceDirection::Left, Self::STAIRS_BACK, false),
+        (FaceDirection::Right, Self::STAIRS_BACK, false),
+        (FaceDirection::Up, Self::STAIRS_BACK, false),
+        (FaceDirection::Down, Self::STAIRS_BACK, false),
+        (FaceDirection::Front, Self::STAIRS_RISER, true),
+        (FaceDirection::Front, Self::STAIRS_STEP, false),
+        (FaceDirection::Left, Self::STAIRS_STEP, false),
+        (FaceDirection::Right, Self::STAIRS_STEP, false),
+        (FaceDirection::Down, Self::STAIRS_STEP, false),
+        (FaceDirection::Up, Self::STAIRS_STEP, true),
+    ];
+    const STAIRS_BACK: [Vec3; 2] = [Vec3::new(-0.5, -0.5, -0.5), Vec3::new(0.5, 0.0, 0.5)];
+    const STAIRS_RISER: [Vec3; 2] = [Vec3::new(-0.5, -0.5, -0.5), Vec3::new(0.5, 0.0, 0.0)];
+    const STAIRS_STEP: [Vec3; 2] = [Vec3::new(-0.5, 0.0, 0.0), Vec3::new(0.5, 0.5, 0.5)];
 
     // UVs:
     const UV_REPEATING: [Vec2; 4] = [
@@ -99,17 +122,39 @@ impl MeshGenerator {
         Vec2::new(0.0, 0.0),
     ];
 
+    /// vertex brightness by the number of solid voxels around its corner
+    const AMBIENT_OCCLUSION_LEVELS: [u8; 4] = [255, 210, 170, 135];
+
     const PARTICLE_SIZE: f32 = 0.08;
+    pub const DROPPED_ITEM_SIZE: f32 = 0.3;
 
     pub fn new(asset_manager: Rc<AssetManager>) -> Self {
         Self { asset_manager }
     }
 
-    /// generates a mesh for the voxel only with the side faces from the diretions slice
+    pub fn get_texture(&self, voxel: Voxel) -> Texture2D {
+        self.asset_manager.texture_manager.get(voxel)
+    }
+
+    /// returns the position of the voxel relative to the render origin,
+    /// the subtraction is done on integers so the result is exact at any distance from (0,0,0)
+    pub fn get_relative_position(location: InternalLocation, origin: Location) -> Vec3 {
+        let location: Location = location.into();
+        vec3(
+            (location.x - origin.x) as f32,
+            (location.y - origin.y) as f32,
+            (location.z - origin.z) as f32,
+        )
+    }
+
+    /// generates a mesh for the voxel only with the side faces from the diretions slice,
+    /// the vertices are relative to the render origin
     pub fn generate_mesh(
         &self,
         voxel: Voxel,
+        orientation: Orientation,
         location: InternalLocation,
+        origin: Location,
         directions: &[FaceDirection],
     ) -> Mesh {
         debug_assert!(
@@ -117,18 +162,35 @@ impl MeshGenerator {
             "Need at least one face direction to generate mesh"
         );
 
-        let location: Location = location.into();
-        let middle_x = location.x as f32;
-        let middle_y = location.y as f32;
-        let middle_z = location.z as f32;
+        let middle = Self::get_relative_position(location, origin);
+        let (middle_x, middle_y, middle_z) = (middle.x, middle.y, middle.z);
 
         let mut vertices = Vec::with_capacity(Self::VERTICES_PER_FACE);
         let mut indices = Vec::with_capacity(Self::INDECIES.len());
         let mut index_offset = 0;
 
-        for direction in directions {
-            let face_verticies =
-                Self::get_verticies_for_voxel(voxel, *direction, middle_x, middle_y, middle_z);
+        let faces: Vec<_> = if voxel == Voxel::WoodStairs {
+            Self::get_verticies_for_stairs(voxel, orientation, directions, middle)
+        } else if orientation != Orientation::Up {
+            Self::get_verticies_for_rotated_voxel(voxel, orientation, directions, middle)
+        } else {
+            directions
+                .iter()
+                .map(|direction| {
+                    let mut face_verticies = Self::get_verticies_for_voxel(
+                        voxel, *direction, middle_x, middle_y, middle_z,
+                    );
+                    if matches!(direction, FaceDirection::Up) && Voxel::WATER.contains(&voxel) {
+                        for vertex in &mut face_verticies {
+                            vertex.color = Self::REFLECTIVE_COLOR;
+                        }
+                    }
+                    face_verticies
+                })
+                .collect()
+        };
+
+        for face_verticies in faces {
             let face_indecies: Vec<_> = Self::INDECIES
                 .iter()
                 .map(|ind| ind + index_offset)
@@ -174,6 +236,22 @@ impl MeshGenerator {
         }
     }
 
+    pub fn generate_mesh_for_dropped_item(&self, voxel: Voxel, position: Vec3) -> Mesh {
+        let vertices = Self::create_vertices_for_all_sides(voxel, Vec3::ZERO)
+            .map(|vertex| Vertex {
+                position: vertex.position * Self::DROPPED_ITEM_SIZE + position,
+                ..vertex
+            })
+            .collect();
+        let indices = Self::create_indeicies_for_all_sides();
+
+        Mesh {
+            vertices,
+            indices,
+            texture: Some(self.asset_manager.texture_manager.get(voxel)),
+        }
+    }
+
     fn sample_random_uv() -> Vec2 {
         let x = (rand() % 100) as f32 / 100.0;
         let y = (rand() % 100) as f32 / 100.0;
@@ -487,18 +565,170 @@ impl MeshGenerator {
         }
     }
 
+    /// generates the stairs faces from the directions and the faces inside the cell,
+    /// the stairs are turned to go up towards the orientation
+    fn get_verticies_for_stairs(
+        voxel: Voxel,
+        orientation: Orientation,
+        directions: &[FaceDirection],
+        middle: Vec3,
+    ) -> Vec<Vec<Vertex>> {
+        Self::STAIRS_FACES
+            .iter()
+            .filter(|(direction, _, is_inside)| {
+                *is_inside
+                    || directions.contains(&Self::rotate_direction(voxel, *direction, orientation))
+            })
+            .map(|(direction, [min, max], _)| {
+                let face_verticies = Self::get_verticies_for_box(voxel, *direction, *min, *max);
+                Self::rotate_face(voxel, face_verticies, orientation, middle)
+            })
+            .collect()
+    }
+
+    /// generates the faces from the directions of a voxel laid with its top towards the orientation
+    fn get_verticies_for_rotated_voxel(
+        voxel: Voxel,
+        orientation: Orientation,
+        directions: &[FaceDirection],
+        middle: Vec3,
+    ) -> Vec<Vec<Vertex>> {
+        Self::ALL_DIRECTIONS
+            .into_iter()
+            .filter(|direction| {
+                directions.contains(&Self::rotate_direction(voxel, *direction, orientation))
+            })
+            .map(|direction| {
+                let face_verticies = Self::get_verticies_for_voxel(voxel, direction, 0.0, 0.0, 0.0);
+                Self::rotate_face(voxel, face_verticies, orientation, middle)
+            })
+            .collect()
+    }
+
+    /// the face of a box inside the cell relative to the middle,
+    /// the uvs are the part of the full face covered by the box
+    fn get_verticies_for_box(
+        voxel: Voxel,
+        direction: FaceDirection,
+        min: Vec3,
+        max: Vec3,
+    ) -> Vec<Vertex> {
+        let face = Self::get_verticies_for_voxel(voxel, direction, 0.0, 0.0, 0.0);
+        let origin = face[0].position;
+        let u_axis = face[1].position - origin;
+        let v_axis = face[3].position - origin;
+        let (origin_uv, u_uv, v_uv) =
+            (face[0].uv, face[1].uv - face[0].uv, face[3].uv - face[0].uv);
+
+        face.iter()
+            .map(|vertex| {
+                let position = vec3(
+                    if vertex.position.x > 0.0 {
+                        max.x
+                    } else {
+                        min.x
+                    },
+                    if vertex.position.y > 0.0 {
+                        max.y
+                    } else {
+                        min.y
+                    },
+                    if vertex.position.z > 0.0 {
+                        max.z
+                    } else {
+                        min.z
+                    },
+                );
+                let u = (position - origin).dot(u_axis) / u_axis.length_squared();
+                let v = (position - origin).dot(v_axis) / v_axis.length_squared();
+
+                Vertex {
+                    position,
+                    uv: origin_uv + u_uv * u + v_uv * v,
+                    ..*vertex
+                }
+            })
+            .collect()
+    }
+
+    /// rotates a vector of the voxel from its default orientation, stairs are turned
+    /// around the z axis and other voxels are laid with their top towards the orientation
+    fn rotate(voxel: Voxel, vector: Vec3, orientation: Orientation) -> Vec3 {
+        if voxel == Voxel::WoodStairs {
+            // each quarter turn goes from y - 1 towards x + 1
+            let turns = match orientation {
+                Orientation::Up | Orientation::Down | Orientation::NegativeY => 0,
+                Orientation::PositiveX => 1,
+                Orientation::PositiveY => 2,
+                Orientation::NegativeX => 3,
+            };
+            return (0..turns).fold(vector, |vector, _| vec3(-vector.y, vector.x, vector.z));
+        }
+
+        match orientation {
+            Orientation::Up | Orientation::Down => vector,
+            Orientation::PositiveX | Orientation::NegativeX => vec3(-vector.z, vector.y, vector.x),
+            Orientation::PositiveY | Orientation::NegativeY => vec3(vector.x, -vector.z, vector.y),
+        }
+    }
+
+    /// the direction the face of the voxel in its default orientation is turned towards
+    fn rotate_direction(
+        voxel: Voxel,
+        direction: FaceDirection,
+        orientation: Orientation,
+    ) -> FaceDirection {
+        let normal = Self::rotate(voxel, Self::get_normal(direction).truncate(), orientation);
+        Self::ALL_DIRECTIONS
+            .into_iter()
+            .find(|rotated| Self::get_normal(*rotated).truncate() == normal)
+            .expect("rotated normal should match a face")
+    }
+
+    /// moves the face from the middle of the voxel in its default orientation
+    fn rotate_face(
+        voxel: Voxel,
+        face_verticies: Vec<Vertex>,
+        orientation: Orientation,
+        middle: Vec3,
+    ) -> Vec<Vertex> {
+        face_verticies
+            .into_iter()
+            .map(|vertex| Vertex {
+                position: Self::rotate(voxel, vertex.position, orientation) + middle,
+                normal: Self::rotate(voxel, vertex.normal.truncate(), orientation).extend(0.0),
+                ..vertex
+            })
+            .collect()
+    }
+
+    fn get_normal(direction: FaceDirection) -> Vec4 {
+        match direction {
+            FaceDirection::Up => Self::UP_NORMAL,
+            FaceDirection::Down => Self::DOWN_NORMAL,
+            FaceDirection::Left => Self::LEFT_NORMAL,
+            FaceDirection::Right => Self::RIGHT_NORMAL,
+            FaceDirection::Front => Self::FRONT_NORMAL,
+            FaceDirection::Back => Self::BACK_NORMAL,
+        }
+    }
+
     fn get_partial_height_offset(voxel: Voxel) -> f32 {
         match voxel {
             Voxel::Water1 => 1.0 / 5.0,
             Voxel::Water2 => 2.0 / 5.0,
             Voxel::Water3 => 3.0 / 5.0,
             Voxel::Water4 => 4.0 / 5.0,
+            Voxel::StoneSlab => 1.0 / 2.0,
             _ => 0.0,
         }
     }
 
     /// checks if the face should be generated based on the current voxel and its neighbour
     pub fn should_generate_face(current_voxel: Voxel, neighbour_voxel: Voxel) -> bool {
+        if Voxel::SHAPED.contains(&neighbour_voxel) {
+            return true;
+        }
         if current_voxel == neighbour_voxel {
             return false;
         }
@@ -580,6 +810,58 @@ impl MeshGenerator {
     }
 
     /// generates an untextured quad mesh at the origin (0,0,0)
+    /// darkens the mesh vertices based on the solid voxels surrounding each corner,
+    /// `surrounding_solid` is indexed with `MeshGenerator::get_surrounding_index`
+    pub fn apply_ambient_occlusion(
+        mesh: &mut Mesh,
+        location: InternalLocation,
+        origin: Location,
+        surrounding_solid: &[bool; 27],
+    ) {
+        let middle = Self::get_relative_position(location, origin);
+
+        for vertex in &mut mesh.vertices {
+            let normal = [
+                vertex.normal.x as i32,
+                vertex.normal.y as i32,
+                vertex.normal.z as i32,
+            ];
+            let corner_offset = vertex.position - middle;
+            let corner = [corner_offset.x, corner_offset.y, corner_offset.z]
+                .map(|offset| if offset > 0.0 { 1 } else { -1 });
+
+            let mut tangents = (0..3).filter(|axis| normal[*axis] == 0);
+            let (Some(first_axis), Some(second_axis)) = (tangents.next(), tangents.next()) else {
+                continue;
+            };
+            let mut first_side = normal;
+            first_side[first_axis] = corner[first_axis];
+            let mut second_side = normal;
+            second_side[second_axis] = corner[second_axis];
+            let mut diagonal = first_side;
+            diagonal[second_axis] = corner[second_axis];
+
+            let is_solid =
+                |offset: [i32; 3]| surrounding_solid[Self::get_surrounding_index(offset)];
+            let occlusion = if is_solid(first_side) && is_solid(second_side) {
+                3
+            } else {
+                [first_side, second_side, diagonal]
+                    .into_iter()
+                    .filter(|offset| is_solid(*offset))
+                    .count()
+            };
+
+            let brightness = Self::AMBIENT_OCCLUSION_LEVELS[occlusion];
+            vertex.color = [brightness, brightness, brightness, vertex.color[3]];
+        }
+    }
+
+    /// index of the offset (each axis in -1..=1) in the surrounding voxels array
+    pub fn get_surrounding_index(offset: [i32; 3]) -> usize {
+        ((offset[0] + 1) + (offset[1] + 1) * 3 + (offset[2] + 1) * 9) as usize
+    }
+
     pub fn generate_quad_mesh(size: f32) -> Mesh {
         let vertices = [
             Vertex {
@@ -630,6 +912,64 @@ impl MeshGenerator {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_apply_ambient_occlusion() {
+        let location = InternalLocation::from(Location::new(0, 0, 50));
+        let mut mesh = Mesh {
+            vertices: MeshGenerator::get_verticies_for_voxel(
+                Voxel::Stone,
+                FaceDirection::Up,
+                0.0,
+                0.0,
+                50.0,
+            ),
+            indices: vec![],
+            texture: None,
+        };
+        let mut surrounding_solid = [false; 27];
+        surrounding_solid[MeshGenerator::get_surrounding_index([1, 0, -1])] = true;
+
+        MeshGenerator::apply_ambient_occlusion(
+            &mut mesh,
+            location,
+            Location::new(0, 0, 0),
+            &surrounding_solid,
+        );
+
+        for vertex in &mesh.vertices {
+            let expected = if vertex.position.x > 0.0 {
+                MeshGenerator::AMBIENT_OCCLUSION_LEVELS[1]
+            } else {
+                MeshGenerator::AMBIENT_OCCLUSION_LEVELS[0]
+            };
+            assert_eq!(vertex.color[0], expected);
+        }
+    }
+
+    #[test]
+    fn test_relative_position_is_exact_far_from_origin() {
+        const FAR: i32 = 900_000;
+        let origin = Location::new(FAR - 5, -FAR - 3, 0);
+        let first = InternalLocation::from(Location::new(FAR, -FAR, 60));
+        let second = InternalLocation::from(Location::new(FAR + 1, -FAR, 60));
+
+        let first_position = MeshGenerator::get_relative_position(first, origin);
+        let second_position = MeshGenerator::get_relative_position(second, origin);
+        assert_eq!(first_position, vec3(5.0, 3.0, 60.0));
+        assert_eq!(second_position - first_position, vec3(1.0, 0.0, 0.0));
+
+        // absolute positions this far out lose small offsets, relative ones keep them
+        assert_eq!(FAR as f32 + 0.01, FAR as f32);
+        assert_ne!(first_position.x + 0.01, first_position.x);
+        let vertices =
+            MeshGenerator::get_verticies_for_voxel(Voxel::Stone, FaceDirection::Up, 5.0, 3.0, 60.0);
+        assert!(
+            vertices
+                .iter()
+                .all(|vertex| (vertex.position.x - 5.0).abs() == Voxel::HALF_SIZE)
+        );
+    }
+
     #[test]
     fn test_should_generate_face_solid() {
         assert!(!MeshGenerator::should_generate_face(
@@ -743,6 +1083,74 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_should_generate_face_shaped() {
+        assert!(MeshGenerator::should_generate_face(
+            Voxel::Stone,
+            Voxel::StoneSlab
+        ));
+        assert!(MeshGenerator::should_generate_face(
+            Voxel::WoodStairs,
+            Voxel::WoodStairs
+        ));
+        assert!(!MeshGenerator::should_generate_face(
+            Voxel::WoodStairs,
+            Voxel::Stone
+        ));
+        assert!(MeshGenerator::should_generate_top_face(
+            Voxel::StoneSlab,
+            Voxel::Stone
+        ));
+    }
+
+    #[test]
+    fn test_stairs_turned_towards_orientation() {
+        let faces = MeshGenerator::get_verticies_for_stairs(
+            Voxel::WoodStairs,
+            Orientation::PositiveX,
+            &[FaceDirection::Up],
+            Vec3::ZERO,
+        );
+        // the top of the tall part and the two faces inside the cell
+        assert_eq!(faces.len(), 3);
+        let top = &faces[0];
+        for vertex in top {
+            assert_eq!(vertex.position.z, -Voxel::HALF_SIZE);
+            assert!(vertex.position.x >= 0.0);
+            assert_eq!(vertex.normal, MeshGenerator::UP_NORMAL);
+        }
+        let riser = &faces[1];
+        for vertex in riser {
+            assert_eq!(vertex.position.x, 0.0);
+            assert!(vertex.position.z <= 0.0);
+            assert_eq!(vertex.normal, MeshGenerator::RIGHT_NORMAL);
+        }
+    }
+
+    #[test]
+    fn test_pillar_laid_along_orientation() {
+        let faces = MeshGenerator::get_verticies_for_rotated_voxel(
+            Voxel::StonePillar,
+            Orientation::PositiveY,
+            &[FaceDirection::Front, FaceDirection::Up],
+            Vec3::ZERO,
+        );
+        assert_eq!(faces.len(), 2);
+        let top_uv_face = faces
+            .iter()
+            .find(|face| face[0].normal == MeshGenerator::FRONT_NORMAL)
+            .unwrap();
+        for (vertex, uv) in top_uv_face.iter().zip(MeshGenerator::TOP_UV) {
+            assert_eq!(vertex.position.y, Voxel::HALF_SIZE);
+            assert_eq!(vertex.uv, uv);
+        }
+        assert!(
+            faces
+                .iter()
+                .any(|face| face[0].normal == MeshGenerator::UP_NORMAL)
+        );
+    }
+
     #[test]
     fn test_generate_quad_mesh() {
         let size = 10.0;
@@ -21,9 +21,11 @@ pub enum MeshId {
     ButterflyUp,
     Penguin,
     Explosion,
+    Fish,
+    Firefly,
 }
 impl MeshId {
-    pub const VARIANTS: usize = 5;
+    pub const VARIANTS: usize = 7;
 
     pub fn index(self) -> usize {
         let index = self as usize;
@@ -41,6 +43,8 @@ const MODEL_FILES: [(MeshId, &str); MeshId::VARIANTS] = [
     (MeshId::ButterflyUp, "butterfly2.obj"),
     (MeshId::Penguin, "penguin.obj"),
     (MeshId::Explosion, "explosion.obj"),
+    (MeshId::Fish, "fish.obj"),
+    (MeshId::Firefly, "firefly.obj"),
 ];
 
 const MAX_COORDINATES: f32 = 4.0;
@@ -172,7 +172,7 @@ mod tests {
         let mut mesh = MeshGenerator::generate_quad_mesh(1.0);
         let mut direction = vec3(1.0, 1.0, 1.0).normalize_or_zero();
         let mesh_copy = MeshGenerator::generate_quad_mesh(1.0);
-        let direction_copy = direction.clone();
+        let direction_copy = direction;
 
         rotate_around_z_with_direction(&mut mesh, &mut direction, vec3(0.0, 0.0, 0.0), 0.0);
 
@@ -185,7 +185,7 @@ mod tests {
         let mut mesh = MeshGenerator::generate_quad_mesh(1.0);
         let mut direction = vec3(1.0, 1.0, 1.0).normalize_or_zero();
         let mesh_copy = MeshGenerator::generate_quad_mesh(1.0);
-        let direction_copy = direction.clone();
+        let direction_copy = direction;
 
         rotate_around_z_with_direction(&mut mesh, &mut direction, vec3(0.0, 0.0, 0.0), TAU);
 
@@ -198,7 +198,7 @@ mod tests {
         let mut mesh = MeshGenerator::generate_quad_mesh(1.0);
         let mut direction = vec3(1.0, 1.0, 1.0).normalize_or_zero();
         let mesh_copy = MeshGenerator::generate_quad_mesh(1.0);
-        let direction_copy = direction.clone();
+        let direction_copy = direction;
 
         rotate_around_z_with_direction(&mut mesh, &mut direction, vec3(0.0, 0.0, 0.0), PI);
 
@@ -1,10 +1,15 @@
+pub mod bloom;
+pub mod bloom_shader;
 pub mod debug_display;
 pub mod flat_shader;
 pub mod height_map;
+pub mod mesh_cache;
 pub mod mesh_generator;
 pub mod mesh_manager;
 pub mod mesh_transformer;
+pub mod noise_overlay;
 pub mod rain_system;
+pub mod recent_edits;
 pub mod renderer;
 pub mod screen_effects;
 pub mod shader_manager;
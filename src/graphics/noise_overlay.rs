@@ -0,0 +1,236 @@
+use macroquad::{
+    color::{BLACK, Color, WHITE},
+    miniquad::window::screen_size,
+    shapes::{draw_rectangle, draw_rectangle_lines},
+    text::Font,
+};
+
+use crate::{
+    interface::{
+        style::{BUTTON_FONT_SIZE, TEXT_COLOR},
+        text::draw_game_text,
+    },
+    model::{
+        area::AREA_HEIGHT,
+        key_bindings::{KeyAction, KeyBindings},
+        location::{InternalLocation, Location},
+        world::World,
+        world_rules::WorldGenConfig,
+    },
+    service::area_generation::{cave_generator::CaveGenerator, terrain_type::TerrainTypeGenerator},
+};
+
+/// number of samples on each side of the overlay
+const SAMPLES_PER_SIDE: usize = 64;
+/// distance in voxels between neighbouring samples
+const SAMPLE_SPACING: i32 = 4;
+const OVERLAY_SCREEN_SIZE: f32 = 0.85;
+const FONT_SIZE: f32 = BUTTON_FONT_SIZE;
+
+const LOW_COLOR: Color = Color::new(0.1, 0.2, 0.9, 1.0);
+const MIDDLE_COLOR: Color = Color::new(0.1, 0.9, 0.2, 1.0);
+const HIGH_COLOR: Color = Color::new(0.9, 0.1, 0.1, 1.0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NoiseOverlayMode {
+    Off,
+    TerrainHeight,
+    CaveDensity,
+}
+impl NoiseOverlayMode {
+    fn next(self) -> Self {
+        match self {
+            Self::Off => Self::TerrainHeight,
+            Self::TerrainHeight => Self::CaveDensity,
+            Self::CaveDensity => Self::Off,
+        }
+    }
+
+    fn get_name(self) -> &'static str {
+        match self {
+            Self::Off => "Off",
+            Self::TerrainHeight => "Terrain height",
+            Self::CaveDensity => "Cave density",
+        }
+    }
+}
+
+/// debug view of the generation noise around the player as a top down heat map,
+/// sampled directly from the generators without loading areas
+pub struct NoiseOverlay {
+    mode: NoiseOverlayMode,
+    /// the sampled location, mode and values in 0..=1
+    samples: Option<(InternalLocation, NoiseOverlayMode, Vec<f32>)>,
+}
+impl NoiseOverlay {
+    pub fn new() -> Self {
+        Self {
+            mode: NoiseOverlayMode::Off,
+            samples: None,
+        }
+    }
+
+    pub fn cycle_mode(&mut self) {
+        self.mode = self.mode.next();
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.mode != NoiseOverlayMode::Off
+    }
+
+    pub fn draw(
+        &mut self,
+        world: &World,
+        camera_location: Location,
+        key_bindings: &KeyBindings,
+        font: &Font,
+    ) {
+        if !self.is_active() {
+            return;
+        }
+        let center = Self::snap_to_samples(camera_location.into());
+        let is_up_to_date = self
+            .samples
+            .as_ref()
+            .is_some_and(|(location, mode, _)| *location == center && *mode == self.mode);
+        if !is_up_to_date {
+            let values = Self::sample(self.mode, world.get_seed(), world.get_gen_config(), center);
+            self.samples = Some((center, self.mode, values));
+        }
+        let Some((_, _, values)) = &self.samples else {
+            return;
+        };
+
+        let (width, height) = screen_size();
+        let overlay_size = width.min(height) * OVERLAY_SCREEN_SIZE;
+        let cell_size = overlay_size / SAMPLES_PER_SIDE as f32;
+        let overlay_x = (width - overlay_size) * 0.5;
+        let overlay_y = (height - overlay_size) * 0.5;
+        draw_rectangle(0.0, 0.0, width, height, BLACK);
+
+        for (index, value) in values.iter().enumerate() {
+            draw_rectangle(
+                overlay_x + (index % SAMPLES_PER_SIDE) as f32 * cell_size,
+                overlay_y + (index / SAMPLES_PER_SIDE) as f32 * cell_size,
+                cell_size,
+                cell_size,
+                Self::get_heat_color(*value),
+            );
+        }
+        let middle = (SAMPLES_PER_SIDE / 2) as f32 * cell_size;
+        draw_rectangle_lines(
+            overlay_x + middle,
+            overlay_y + middle,
+            cell_size,
+            cell_size,
+            2.0,
+            WHITE,
+        );
+        draw_game_text(
+            &format!(
+                "Noise overlay: {} ({})",
+                self.mode.get_name(),
+                key_bindings.get(KeyAction::CycleNoiseOverlay).get_name()
+            ),
+            overlay_x,
+            overlay_y - FONT_SIZE * 0.3,
+            FONT_SIZE,
+            TEXT_COLOR,
+            font,
+        );
+    }
+
+    fn snap_to_samples(location: InternalLocation) -> InternalLocation {
+        let spacing = SAMPLE_SPACING as u32;
+        InternalLocation::new(
+            location.x / spacing * spacing,
+            location.y / spacing * spacing,
+            location.z,
+        )
+    }
+
+    /// returns the samples row by row, normalised to 0..=1
+    fn sample(
+        mode: NoiseOverlayMode,
+        seed: u64,
+        config: WorldGenConfig,
+        center: InternalLocation,
+    ) -> Vec<f32> {
+        let terrain_generator = TerrainTypeGenerator::new(seed, config.amplitude);
+        let cave_generator = CaveGenerator::new(seed, config.cave_density);
+        let half_side = (SAMPLES_PER_SIDE / 2) as i32;
+
+        let mut values = Vec::with_capacity(SAMPLES_PER_SIDE * SAMPLES_PER_SIDE);
+        for y in -half_side..half_side {
+            for x in -half_side..half_side {
+                let location = center
+                    .offset_x(x * SAMPLE_SPACING)
+                    .offset_y(y * SAMPLE_SPACING);
+                let (area_location, local) =
+                    World::convert_global_to_area_and_local_location(location);
+                let value = match mode {
+                    NoiseOverlayMode::Off => 0.0,
+                    NoiseOverlayMode::TerrainHeight => {
+                        let height = terrain_generator.sample(area_location, local.x, local.y);
+                        height as f32 / AREA_HEIGHT as f32
+                    }
+                    NoiseOverlayMode::CaveDensity => {
+                        cave_generator.sample_density(area_location, local.x, local.y, local.z)
+                            / 100.0
+                    }
+                };
+                values.push(value.clamp(0.0, 1.0));
+            }
+        }
+
+        values
+    }
+
+    /// blends from blue for low values, through green, to red for high values
+    fn get_heat_color(value: f32) -> Color {
+        let (from, to, amount) = if value < 0.5 {
+            (LOW_COLOR, MIDDLE_COLOR, value * 2.0)
+        } else {
+            (MIDDLE_COLOR, HIGH_COLOR, (value - 0.5) * 2.0)
+        };
+
+        Color::new(
+            from.r + (to.r - from.r) * amount,
+            from.g + (to.g - from.g) * amount,
+            from.b + (to.b - from.b) * amount,
+            1.0,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_is_deterministic() {
+        let center = NoiseOverlay::snap_to_samples(Location::new(13, -7, 60).into());
+        for mode in [
+            NoiseOverlayMode::TerrainHeight,
+            NoiseOverlayMode::CaveDensity,
+        ] {
+            let values = NoiseOverlay::sample(mode, 42, WorldGenConfig::default(), center);
+            assert_eq!(values.len(), SAMPLES_PER_SIDE * SAMPLES_PER_SIDE);
+            assert!(values.iter().all(|value| (0.0..=1.0).contains(value)));
+            assert_eq!(
+                values,
+                NoiseOverlay::sample(mode, 42, WorldGenConfig::default(), center)
+            );
+        }
+    }
+
+    #[test]
+    fn test_get_heat_color() {
+        assert_eq!(NoiseOverlay::get_heat_color(0.0), LOW_COLOR);
+        assert_eq!(NoiseOverlay::get_heat_color(0.5), MIDDLE_COLOR);
+        let high = NoiseOverlay::get_heat_color(1.0);
+        assert!((high.r - HIGH_COLOR.r).abs() < 0.001);
+        assert!((high.g - HIGH_COLOR.g).abs() < 0.001);
+        assert!((high.b - HIGH_COLOR.b).abs() < 0.001);
+    }
+}
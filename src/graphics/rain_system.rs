@@ -16,8 +16,8 @@ use crate::{
         texture_manager::{PlainTextureId, TextureManager},
     },
     model::{
-        area::AREA_SIZE, player_info::PlayerInfo, user_settings::UserSettings, voxel::Voxel,
-        world::World,
+        area::AREA_SIZE, location::Location, player_info::PlayerInfo, user_settings::UserSettings,
+        voxel::Voxel, world::World,
     },
     service::{
         activity_timer::ActivityTimer,
@@ -152,6 +152,7 @@ impl RainSystem {
         }
     }
 
+    /// returns the location of the voxel struck by lightning
     pub fn update(
         &mut self,
         delta: f32,
@@ -159,7 +160,7 @@ impl RainSystem {
         world: &mut World,
         user_settings: &UserSettings,
         sound_manager: &SoundManager,
-    ) {
+    ) -> Option<Location> {
         if self.change_state_activity.tick(delta) {
             self.update_change_raining_state();
         }
@@ -176,7 +177,7 @@ impl RainSystem {
             self.remove_fallen();
         }
 
-        self.update_lightning(delta, player_info, world, user_settings, sound_manager);
+        self.update_lightning(delta, player_info, world, user_settings, sound_manager)
     }
 
     /// draws rain drops as quads facing at the camera
@@ -214,7 +215,7 @@ impl RainSystem {
         }
     }
 
-    /// updates lightning - adds or removes
+    /// updates lightning - adds or removes, returns the struck location if one was added
     fn update_lightning(
         &mut self,
         delta: f32,
@@ -222,7 +223,7 @@ impl RainSystem {
         world: &mut World,
         user_settings: &UserSettings,
         sound_manager: &SoundManager,
-    ) {
+    ) -> Option<Location> {
         self.last_lightning_delta = (self.last_lightning_delta - delta).max(0.0);
         for lightning in &mut self.lightnings {
             lightning.life -= delta;
@@ -234,19 +235,18 @@ impl RainSystem {
             && self
                 .lightning_activity
                 .tick_change_cooldown(delta, random_lightning_cooldown);
-        if should_add_lightning {
-            self.add_lightning(player_info, world, user_settings, sound_manager);
-        }
+        should_add_lightning
+            .then(|| self.add_lightning(player_info, world, user_settings, sound_manager))
     }
 
-    /// creates a ligtning at a random position around the player
+    /// creates a ligtning at a random position around the player, returns the struck location
     fn add_lightning(
         &mut self,
         player_info: &PlayerInfo,
         world: &mut World,
         user_settings: &UserSettings,
         sound_manager: &SoundManager,
-    ) {
+    ) -> Location {
         let max_add_distance = ((user_settings.get_render_distance() - 1) * AREA_SIZE) as f32;
 
         let x_offset = gen_range(-max_add_distance, max_add_distance);
@@ -268,6 +268,11 @@ impl RainSystem {
 
         self.lightnings.push(lightning);
         self.last_lightning_delta = LIGHNING_FLASH_DURATION_S;
+
+        Location {
+            z: ground_z as i32,
+            ..sample_location
+        }
     }
 
     fn create_lightning_mesh(&self, lightning_position: Vec3, camera_position: Vec3) -> Mesh {
@@ -287,6 +292,19 @@ impl RainSystem {
         look.dot(look_towards_rain) >= VISIBLE_THRESHOLD
     }
 
+    /// starts or stops the rain immediately, the fallen drops and the sky fade out as usual
+    /// and the random weather changes are delayed by a full cooldown
+    pub fn set_raining(&mut self, is_raining: bool) {
+        info!("Setting rain to {}", is_raining);
+        self.is_raining = is_raining;
+        self.change_state_activity = ActivityTimer::new(0.0, CHANGE_STATE_ACTIVITY_COOLDOWN);
+        self.lightning_activity = ActivityTimer::new(0.0, random_lightning_cooldown());
+    }
+
+    pub fn is_raining(&self) -> bool {
+        self.is_raining
+    }
+
     fn update_change_raining_state(&mut self) {
         const START_RAINING_CHANCE: u32 = 20;
         const STOP_RAINING_CHANCE: u32 = 50;
@@ -393,3 +411,8 @@ pub struct RainSystemDTO {
     sky_modifier: f32,
     lightning_activity: ActivityTimer,
 }
+impl RainSystemDTO {
+    pub fn is_raining(&self) -> bool {
+        self.is_raining
+    }
+}
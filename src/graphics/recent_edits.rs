@@ -0,0 +1,106 @@
+use std::collections::VecDeque;
+
+use macroquad::{
+    camera::Camera3D,
+    color::Color,
+    math::{Vec3, vec3},
+    models::draw_cube_wires,
+};
+
+use crate::model::location::Location;
+
+/// time in seconds a modified voxel stays highlighted
+const HIGHLIGHT_TIME: f32 = 2.0;
+/// oldest edits are dropped past this, large batch edits only keep their last voxels
+const MAX_RECENT_EDITS: usize = 512;
+const HIGHLIGHT_COLOR: Color = Color::new(0.2, 1.0, 0.4, 1.0);
+/// slightly larger than a voxel so the outline isn't hidden by the voxel faces
+const HIGHLIGHT_SIZE: f32 = 1.02;
+
+/// voxels placed, destroyed or replaced by the player, highlighted for the debug display
+/// to show which locations were re-meshed
+pub struct RecentEdits {
+    /// locations with their age in seconds, oldest first
+    edits: VecDeque<(Location, f32)>,
+}
+impl RecentEdits {
+    pub fn new() -> Self {
+        Self {
+            edits: VecDeque::new(),
+        }
+    }
+
+    pub fn add(&mut self, location: Location) {
+        if self.edits.len() >= MAX_RECENT_EDITS {
+            self.edits.pop_front();
+        }
+        self.edits.push_back((location, 0.0));
+    }
+
+    pub fn add_all(&mut self, locations: &[Location]) {
+        for location in locations {
+            self.add(*location);
+        }
+    }
+
+    /// ages the edits and removes the ones that have faded out
+    pub fn update(&mut self, delta: f32) {
+        for (_, age) in &mut self.edits {
+            *age += delta;
+        }
+        while self
+            .edits
+            .front()
+            .is_some_and(|(_, age)| *age >= HIGHLIGHT_TIME)
+        {
+            self.edits.pop_front();
+        }
+    }
+
+    pub fn draw(&self, camera: &Camera3D) {
+        for (location, age) in &self.edits {
+            let position = Vec3::from(*location) - camera.position;
+            let color = Color {
+                a: Self::get_alpha(*age),
+                ..HIGHLIGHT_COLOR
+            };
+            draw_cube_wires(position, vec3(1.0, 1.0, 1.0) * HIGHLIGHT_SIZE, color);
+        }
+    }
+
+    fn get_alpha(age: f32) -> f32 {
+        (1.0 - age / HIGHLIGHT_TIME).clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edits_fade_out() {
+        let mut recent_edits = RecentEdits::new();
+        recent_edits.add(Location::new(0, 0, 10));
+        recent_edits.update(HIGHLIGHT_TIME * 0.5);
+        recent_edits.add_all(&[Location::new(1, 0, 10), Location::new(2, 0, 10)]);
+        assert_eq!(recent_edits.edits.len(), 3);
+
+        recent_edits.update(HIGHLIGHT_TIME * 0.6);
+        assert_eq!(recent_edits.edits.len(), 2);
+        assert!(RecentEdits::get_alpha(recent_edits.edits[0].1) < 1.0);
+
+        recent_edits.update(HIGHLIGHT_TIME);
+        assert!(recent_edits.edits.is_empty());
+    }
+
+    #[test]
+    fn test_oldest_edits_dropped_over_limit() {
+        let mut recent_edits = RecentEdits::new();
+        for x in 0..(MAX_RECENT_EDITS + 10) as i32 {
+            recent_edits.add(Location::new(x, 0, 10));
+        }
+
+        assert_eq!(recent_edits.edits.len(), MAX_RECENT_EDITS);
+        assert_eq!(recent_edits.edits[0].0, Location::new(10, 0, 10));
+    }
+}
@@ -1,19 +1,25 @@
 use std::{
     collections::{HashMap, HashSet},
+    f32::consts::FRAC_PI_2,
     rc::Rc,
+    time::Instant,
 };
 
 use macroquad::{
     camera::{Camera3D, set_camera},
-    math::{Vec3, vec3},
+    math::{Vec3, Vec4Swizzles, vec3},
+    miniquad::window::screen_size,
     models::{Mesh, draw_mesh},
-    prelude::debug,
+    prelude::{debug, info},
 };
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 
 use crate::{
     graphics::{
-        height_map::HeightMap, shader_manager::SHADER_MANAGER_INSTANCE,
+        height_map::HeightMap,
+        mesh_cache::{AreaMeshCacheDTO, VoxelMeshDTO, calculate_checksums},
+        shader_manager::SHADER_MANAGER_INSTANCE,
+        sky::Sky,
         voxel_shader::VoxelUniformParams,
     },
     model::{
@@ -21,22 +27,29 @@ use crate::{
         location::{AreaLocation, InternalLocation, LOCATION_OFFSET, Location},
         player_info::PlayerInfo,
         user_settings::UserSettings,
-        voxel::{MAX_VOXEL_VARIANTS, Voxel},
+        voxel::{LightColor, MAX_VOXEL_VARIANTS, Voxel},
         world::World,
     },
     service::{
-        asset_manager::AssetManager, camera_controller::CameraController, world_time::WorldTime,
+        asset_manager::AssetManager,
+        camera_controller::CameraController,
+        persistence::mesh_cache_persistence::{load_mesh_cache, store_mesh_cache},
+        world_time::WorldTime,
     },
     utils::StackVec,
 };
 
 use super::mesh_generator::{FaceDirection, MeshGenerator};
 
-const AREA_RENDER_THRESHOLD: f32 = 0.35;
+/// angles in radians outside the corners of the view where areas and voxels are still rendered
+const AREA_VIEW_ANGLE_MARGIN: f32 = 0.51;
+const VOXEL_VIEW_ANGLE_MARGIN: f32 = 0.08;
 const LOOK_DOWN_RENDER_MULTIPLIER: f32 = 0.5;
-const VOXEL_RENDER_THRESHOLD: f32 = 0.71;
 const VOXEL_PROXIMITY_THRESHOLD: f32 = 5.5;
 
+/// the render origin is moved once the camera is this far away from it
+const REBASE_DISTANCE: f32 = (AREA_SIZE * 64) as f32;
+
 const BACKLOG_THRESHOLD: usize = 100;
 const AREAS_TO_LOAD_PER_FRAME: usize = 2;
 const INCREASED_AREAS_TO_LOAD_PER_FRAME: usize = 5;
@@ -47,19 +60,19 @@ type Meshes = HashMap<AreaLocation, RenderArea>;
 
 pub struct RenderArea {
     mesh_map: HashMap<InternalLocation, MeshInfo>,
-    lights: HashSet<InternalLocation>,
+    lights: HashMap<InternalLocation, LightColor>,
 }
 impl RenderArea {
     pub fn new_empty() -> Self {
         Self {
             mesh_map: HashMap::new(),
-            lights: HashSet::new(),
+            lights: HashMap::new(),
         }
     }
 
     pub fn insert(&mut self, location: InternalLocation, mesh_info: MeshInfo) {
-        if mesh_info.1 == Voxel::Lamp {
-            self.lights.insert(location);
+        if let Some(light_color) = mesh_info.1.emits_light() {
+            self.lights.insert(location, light_color);
         } else {
             self.lights.remove(&location);
         }
@@ -90,14 +103,23 @@ impl GeneratedMeshResult {
 #[derive(Debug)]
 pub struct RendererParams {
     pub world_light_level: f32,
+    pub ambient_color: Vec3,
     pub should_show_map: bool,
     pub explosion_positions: Vec<Vec3>,
+    /// the position, color and radius of lights that aren't voxels
+    pub creature_lights: Vec<(Vec3, LightColor, f32)>,
+    pub x_ray: bool,
 }
 
 pub struct Renderer {
     meshes: Meshes,
     mesh_generator: MeshGenerator,
     render_set: HashSet<AreaLocation>,
+    smooth_lighting: bool,
+    /// stores the generated area meshes in the world directory to load them faster next time
+    use_mesh_cache: bool,
+    /// area meshes are stored relative to this location to keep their vertices precise
+    render_origin: Location,
 }
 impl Renderer {
     pub fn new(asset_manager: Rc<AssetManager>) -> Self {
@@ -105,6 +127,61 @@ impl Renderer {
             meshes: Meshes::new(),
             mesh_generator: MeshGenerator::new(asset_manager),
             render_set: HashSet::new(),
+            smooth_lighting: true,
+            use_mesh_cache: false,
+            render_origin: Location::new(0, 0, 0),
+        }
+    }
+
+    /// unloads all meshes if the lighting mode changes, so they get regenerated
+    pub fn set_smooth_lighting(&mut self, smooth_lighting: bool) {
+        if self.smooth_lighting == smooth_lighting {
+            return;
+        }
+        self.smooth_lighting = smooth_lighting;
+        self.reload_all_areas();
+    }
+
+    pub fn set_mesh_cache(&mut self, use_mesh_cache: bool) {
+        self.use_mesh_cache = use_mesh_cache;
+    }
+
+    /// moves the render origin to the camera once it gets too far away,
+    /// returns true if the meshes were unloaded to be regenerated
+    pub fn update_render_origin(&mut self, camera_position: Vec3) -> bool {
+        let Some(render_origin) =
+            Self::calculate_render_origin(camera_position, self.render_origin)
+        else {
+            return false;
+        };
+        debug!("Moved render origin to {:?}", render_origin);
+        self.render_origin = render_origin;
+        self.reload_all_areas();
+
+        true
+    }
+
+    /// returns the corner of the area with the camera if the camera is too far from the current origin
+    fn calculate_render_origin(camera_position: Vec3, render_origin: Location) -> Option<Location> {
+        let offset = camera_position - Into::<Vec3>::into(render_origin);
+        if offset.x.abs() <= REBASE_DISTANCE && offset.y.abs() <= REBASE_DISTANCE {
+            return None;
+        }
+        let camera_location: Location = camera_position.into();
+        let area_size = AREA_SIZE as i32;
+
+        Some(Location::new(
+            camera_location.x.div_euclid(area_size) * area_size,
+            camera_location.y.div_euclid(area_size) * area_size,
+            0,
+        ))
+    }
+
+    fn reload_all_areas(&mut self) {
+        let loaded_areas: Vec<_> = self.meshes.keys().copied().collect();
+        for area_location in loaded_areas {
+            self.unload_area(area_location);
+            self.add_area_to_load_queue(area_location);
         }
     }
 
@@ -116,6 +193,30 @@ impl Renderer {
         self.render_set.remove(&area_location);
     }
 
+    /// re-meshes the areas and their loaded neighbours so the faces at the borders match
+    pub fn reload_areas(&mut self, area_locations: &[AreaLocation]) {
+        let mut to_reload = HashSet::new();
+        for area_location in area_locations {
+            to_reload.insert(*area_location);
+            let neighbours = [
+                AreaLocation::new(area_location.x + 1, area_location.y),
+                AreaLocation::new(area_location.x - 1, area_location.y),
+                AreaLocation::new(area_location.x, area_location.y + 1),
+                AreaLocation::new(area_location.x, area_location.y - 1),
+            ];
+            to_reload.extend(
+                neighbours
+                    .into_iter()
+                    .filter(|neighbour| self.meshes.contains_key(neighbour)),
+            );
+        }
+
+        for area_location in to_reload {
+            self.unload_area(area_location);
+            self.add_area_to_load_queue(area_location);
+        }
+    }
+
     fn add_area_to_load_queue(&mut self, area_location: AreaLocation) {
         if self.meshes.contains_key(&area_location) {
             return;
@@ -183,9 +284,24 @@ impl Renderer {
             return GeneratedMeshResult::new_empty(area_location);
         }
 
-        let mesh = self
-            .mesh_generator
-            .generate_mesh(voxel, global_location, &face_directions);
+        let orientation = world.get_orientation_with_cache(global_location, cached_area);
+        let mut mesh = self.mesh_generator.generate_mesh(
+            voxel,
+            orientation,
+            global_location,
+            self.render_origin,
+            &face_directions,
+        );
+        if self.smooth_lighting {
+            let surrounding_solid =
+                Self::get_surrounding_solid(world, global_location, cached_area);
+            MeshGenerator::apply_ambient_occlusion(
+                &mut mesh,
+                global_location,
+                self.render_origin,
+                &surrounding_solid,
+            );
+        }
 
         GeneratedMeshResult {
             mesh: Some(mesh),
@@ -194,6 +310,53 @@ impl Renderer {
         }
     }
 
+    fn get_surrounding_solid(
+        world: &mut World,
+        global_location: InternalLocation,
+        cached_area: Option<&Area>,
+    ) -> [bool; 27] {
+        let mut surrounding_solid = [false; 27];
+        for z in -1..=1 {
+            let neighbour_z = global_location.z as i32 + z;
+            if neighbour_z < 0 || neighbour_z >= AREA_HEIGHT as i32 {
+                continue;
+            }
+            for y in -1..=1 {
+                for x in -1..=1 {
+                    let neighbour = global_location.offset_x(x).offset_y(y).offset_z(z);
+                    surrounding_solid[MeshGenerator::get_surrounding_index([x, y, z])] =
+                        world.get_with_cache(neighbour, cached_area).is_solid();
+                }
+            }
+        }
+
+        surrounding_solid
+    }
+
+    /// returns the locations whose meshes depend on the voxel at the location
+    fn get_affected_locations(&self, location: InternalLocation) -> Vec<InternalLocation> {
+        if !self.smooth_lighting {
+            return Self::get_neighbours(location).into_iter().collect();
+        }
+
+        let mut affected = Vec::with_capacity(26);
+        for z in -1..=1 {
+            let neighbour_z = location.z as i32 + z;
+            if neighbour_z < 0 || neighbour_z >= AREA_HEIGHT as i32 {
+                continue;
+            }
+            for y in -1..=1 {
+                for x in -1..=1 {
+                    if x != 0 || y != 0 || z != 0 {
+                        affected.push(location.offset_x(x).offset_y(y).offset_z(z));
+                    }
+                }
+            }
+        }
+
+        affected
+    }
+
     fn set_voxel_mesh(
         &mut self,
         area_location: AreaLocation,
@@ -236,12 +399,8 @@ impl Renderer {
         );
     }
 
-    pub fn update_location(&mut self, world: &mut World, location: impl Into<InternalLocation>) {
-        let internal_location = location.into();
-        if let Some(voxel) = world.get_without_loading(internal_location) {
-            self.update_meshes_for_voxel(world, internal_location, voxel, None);
-        }
-
+    /// returns the directly adjacent locations that are within the world height
+    pub fn get_neighbours(internal_location: InternalLocation) -> StackVec<InternalLocation, 6> {
         let mut neighbors = StackVec::<InternalLocation, 6>::new();
         neighbors.push(InternalLocation::new(
             internal_location.x + 1,
@@ -278,13 +437,38 @@ impl Renderer {
             ));
         }
 
-        for neighbor in neighbors {
+        neighbors
+    }
+
+    pub fn update_location(&mut self, world: &mut World, location: impl Into<InternalLocation>) {
+        let internal_location = location.into();
+        if let Some(voxel) = world.get_without_loading(internal_location) {
+            self.update_meshes_for_voxel(world, internal_location, voxel, None);
+        }
+
+        for neighbor in self.get_affected_locations(internal_location) {
             if let Some(neighbour_voxel) = world.get_without_loading(neighbor) {
                 self.update_meshes_for_voxel(world, neighbor, neighbour_voxel, None);
             }
         }
     }
 
+    /// updates the meshes for many changed locations, each affected voxel is meshed only once
+    pub fn update_locations(&mut self, world: &mut World, locations: &[Location]) {
+        let mut to_update = HashSet::with_capacity(locations.len() * 2);
+        for location in locations {
+            let internal_location: InternalLocation = (*location).into();
+            to_update.insert(internal_location);
+            to_update.extend(self.get_affected_locations(internal_location));
+        }
+
+        for location in to_update {
+            if let Some(voxel) = world.get_without_loading(location) {
+                self.update_meshes_for_voxel(world, location, voxel, None);
+            }
+        }
+    }
+
     /// loads the next areas in the load queue
     pub fn load_areas_in_queue(&mut self, world: &mut World) {
         let number_of_areas_to_load = if self.render_set.len() >= BACKLOG_THRESHOLD {
@@ -306,10 +490,20 @@ impl Renderer {
         }
     }
 
-    /// generates all the meshes for the area
-    fn load_full_area(&mut self, world: &mut World, area_location: AreaLocation) {
+    /// generates all the meshes for the area or loads them from the mesh cache if enabled,
+    /// returns true if the cached meshes were used
+    fn load_full_area(&mut self, world: &mut World, area_location: AreaLocation) -> bool {
         if self.meshes.contains_key(&area_location) {
-            return;
+            return false;
+        }
+
+        let checksums = self
+            .use_mesh_cache
+            .then(|| calculate_checksums(world, area_location));
+        if let Some(checksums) = checksums
+            && self.try_load_cached_meshes(world.get_world_name(), area_location, checksums)
+        {
+            return true;
         }
 
         let voxels = world.get_renderable_voxels_for_area(area_location);
@@ -319,6 +513,67 @@ impl Renderer {
                 self.update_meshes_for_voxel(world, location, voxel, Some(area));
             }
         });
+
+        if let Some(checksums) = checksums {
+            self.store_cached_meshes(world.get_world_name(), area_location, checksums);
+        }
+        false
+    }
+
+    /// returns false if there are no cached meshes or they were made from different voxels
+    fn try_load_cached_meshes(
+        &mut self,
+        world_name: &str,
+        area_location: AreaLocation,
+        checksums: [u64; 5],
+    ) -> bool {
+        let Some(cache) = load_mesh_cache(world_name, area_location) else {
+            return false;
+        };
+        if !cache.is_valid(self.smooth_lighting, checksums) {
+            return false;
+        }
+
+        let mut render_area = RenderArea::new_empty();
+        for voxel_mesh in cache.into_meshes() {
+            let voxel = voxel_mesh.get_voxel();
+            let texture = self.mesh_generator.get_texture(voxel);
+            let (location, face_count, mesh) =
+                voxel_mesh.into_mesh(self.render_origin, Some(texture));
+            render_area.insert(location, (face_count, voxel, mesh));
+        }
+        if !render_area.mesh_map.is_empty() {
+            self.meshes.insert(area_location, render_area);
+        }
+
+        true
+    }
+
+    fn store_cached_meshes(
+        &self,
+        world_name: &str,
+        area_location: AreaLocation,
+        checksums: [u64; 5],
+    ) {
+        let meshes = self
+            .meshes
+            .get(&area_location)
+            .map(|render_area| {
+                render_area
+                    .mesh_map
+                    .iter()
+                    .map(|(location, (face_count, voxel, mesh))| {
+                        VoxelMeshDTO::new(*location, *face_count, *voxel, mesh, self.render_origin)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        store_mesh_cache(
+            world_name,
+            area_location,
+            AreaMeshCacheDTO::new(self.smooth_lighting, checksums, meshes),
+        );
     }
 
     fn is_area_visible(
@@ -326,6 +581,7 @@ impl Renderer {
         camera: &Camera3D,
         look: Vec3,
         render_distance: f32,
+        render_threshold: f32,
     ) -> bool {
         let area_middle = [
             (area_location.x * AREA_SIZE + AREA_SIZE / 2) as i32 - LOCATION_OFFSET,
@@ -342,22 +598,35 @@ impl Renderer {
 
         let area_look = (area_vec - camera.position).normalize_or_zero();
 
-        area_look.dot(look) >= AREA_RENDER_THRESHOLD
+        area_look.dot(look) >= render_threshold
     }
 
-    /// returns an iterator of the voxel meshes to be rendered in an optimised order
+    /// returns an iterator of the voxel meshes to be rendered in an optimised order,
+    /// opaque voxels are grouped by type and the transparent ones are drawn last from back to front
     fn optimise_render_order<'a>(
         mesh_infos: &'a [(&'a InternalLocation, &'a MeshInfo)],
+        camera_position: Vec3,
     ) -> impl Iterator<Item = (&'a InternalLocation, &'a MeshInfo)> {
         let mut groups: Vec<Vec<(&'a InternalLocation, &'a MeshInfo)>> =
             vec![vec![]; MAX_VOXEL_VARIANTS];
+        let mut transparent = vec![];
         for pair in mesh_infos {
-            let (_location, (_faces, voxel, _mesh)) = pair;
-            let index = voxel.index();
-            groups[index].push(*pair);
+            let (location, (_faces, voxel, _mesh)) = pair;
+            if Voxel::TRANSPARENT.contains(voxel) {
+                let distance =
+                    Vec3::from(Location::from(**location)).distance_squared(camera_position);
+                transparent.push((distance, *pair));
+            } else {
+                groups[voxel.index()].push(*pair);
+            }
         }
+        transparent
+            .sort_unstable_by(|(distance1, _), (distance2, _)| distance2.total_cmp(distance1));
 
-        groups.into_iter().flatten()
+        groups
+            .into_iter()
+            .flatten()
+            .chain(transparent.into_iter().map(|(_distance, pair)| pair))
     }
 
     /// determies the visible areas and sets the default world shader,
@@ -392,10 +661,13 @@ impl Renderer {
             height_map.get_empty_height_map()
         };
         let lights = Self::prepare_lights(&visible_areas);
-        let light_level = if renderer_params.should_show_map {
-            WorldTime::MAX_LIGHT_LEVEL
+        let (light_level, ambient_color) = if renderer_params.should_show_map {
+            (WorldTime::MAX_LIGHT_LEVEL, Vec3::ONE)
         } else {
-            renderer_params.world_light_level
+            (
+                renderer_params.world_light_level,
+                renderer_params.ambient_color,
+            )
         };
 
         SHADER_MANAGER_INSTANCE
@@ -404,10 +676,17 @@ impl Renderer {
                 camera,
                 render_size,
                 light_level,
+                ambient_color,
+                fog_color: Sky::get_sky_color(light_level).to_vec().xyz(),
                 lights: &lights,
+                creature_lights: &renderer_params.creature_lights,
                 height_map,
                 has_dynamic_lighting: user_settings.has_dynamic_lighting(),
+                smooth_lighting: user_settings.smooth_lighting,
                 show_map: renderer_params.should_show_map,
+                x_ray: renderer_params.x_ray,
+                water_reflections: user_settings.water_reflections,
+                has_fog: user_settings.fog,
                 explosions: renderer_params.explosion_positions,
             });
 
@@ -435,19 +714,24 @@ impl Renderer {
             Self::filter_visible_voxels(
                 camera.position,
                 look,
+                Self::calculate_render_threshold(camera.fovy, VOXEL_VIEW_ANGLE_MARGIN),
                 visible_areas,
                 render_size,
                 player_info,
             )
         };
-        let optimised_voxel_meshes = Self::optimise_render_order(&visible_voxels);
+        let optimised_voxel_meshes = Self::optimise_render_order(&visible_voxels, camera.position);
 
+        // the area meshes are relative to the render origin, unlike the other meshes
+        let voxel_shader = &SHADER_MANAGER_INSTANCE.voxel_shader;
+        voxel_shader.set_camera_position(camera.position - Into::<Vec3>::into(self.render_origin));
         let mut faces_visible: usize = 0;
         for (_location, (face_count, _, mesh)) in optimised_voxel_meshes {
             debug_assert!(*face_count > 0, "Meshes map is storing empty voxels");
             faces_visible += *face_count as usize;
             draw_mesh(mesh);
         }
+        voxel_shader.set_camera_position(camera.position);
 
         (visible_areas.len(), faces_visible)
     }
@@ -466,9 +750,20 @@ impl Renderer {
 
     /// performs a blocking area loading operation
     pub fn load_all_blocking(&mut self, world: &mut World, areas: &[AreaLocation]) {
+        let start = Instant::now();
+        let mut cached_areas = 0;
         for area_location in areas {
-            self.load_full_area(world, *area_location);
+            if self.load_full_area(world, *area_location) {
+                cached_areas += 1;
+            }
         }
+        let end = start.elapsed();
+        info!(
+            "Meshed {} areas in {}ms, {} from the mesh cache",
+            areas.len(),
+            end.as_millis(),
+            cached_areas
+        );
     }
 
     pub fn update_loaded_areas(&mut self, areas: &[AreaLocation]) {
@@ -489,14 +784,29 @@ impl Renderer {
         }
     }
 
+    /// returns the locations of all light emitting voxels in the loaded areas
+    pub fn get_light_locations(&self) -> impl Iterator<Item = InternalLocation> {
+        self.meshes
+            .values()
+            .flat_map(|render_area| render_area.lights.keys().copied())
+    }
+
     pub fn get_mesh_generator(&self) -> &MeshGenerator {
         &self.mesh_generator
     }
 
-    fn prepare_lights(render_areas: &[(&AreaLocation, &RenderArea)]) -> Vec<InternalLocation> {
+    /// returns the location, color and radius of the lights in the areas
+    fn prepare_lights(
+        render_areas: &[(&AreaLocation, &RenderArea)],
+    ) -> Vec<(InternalLocation, LightColor, f32)> {
         render_areas
             .iter()
-            .flat_map(|(_, area)| area.lights.iter().copied())
+            .flat_map(|(_, area)| {
+                area.lights.iter().map(|(location, light_color)| {
+                    let (_, voxel, _) = &area.mesh_map[location];
+                    (*location, *light_color, voxel.light_radius())
+                })
+            })
             .collect()
     }
 
@@ -508,19 +818,34 @@ impl Renderer {
         render_size: u32,
     ) -> Vec<(&AreaLocation, &RenderArea)> {
         let area_render_distance = Self::calculate_area_render_distance(look, render_size);
+        let render_threshold =
+            Self::calculate_render_threshold(camera.fovy, AREA_VIEW_ANGLE_MARGIN);
 
         self.meshes
             .iter()
             .filter(|(area, _meshes)| {
-                Self::is_area_visible(**area, camera, look, area_render_distance)
+                Self::is_area_visible(**area, camera, look, area_render_distance, render_threshold)
             })
             .collect()
     }
 
+    /// the minimum dot product of the look direction and the direction to a rendered object,
+    /// widens with the field of view of the camera
+    fn calculate_render_threshold(fovy: f32, margin: f32) -> f32 {
+        let (width, height) = screen_size();
+        Self::calculate_render_threshold_for_aspect(fovy, width / height, margin)
+    }
+
+    fn calculate_render_threshold_for_aspect(fovy: f32, aspect: f32, margin: f32) -> f32 {
+        let corner_angle = ((fovy * 0.5).tan() * (1.0 + aspect * aspect).sqrt()).atan();
+        (corner_angle + margin).min(FRAC_PI_2).cos()
+    }
+
     /// filters the visible voxels based on the camera position and look direction
     fn filter_visible_voxels<'a>(
         camera_position: Vec3,
         look: Vec3,
+        render_threshold: f32,
         visible_areas: &'a Vec<(&'a AreaLocation, &'a RenderArea)>,
         render_size: u32,
         player_info: &PlayerInfo,
@@ -532,7 +857,13 @@ impl Renderer {
             .flat_map(|(_, y)| &y.mesh_map)
             .filter(|(location, (_face_count, voxel, _mesh))| {
                 !(player_info.is_head_in_water && Voxel::WATER.contains(voxel))
-                    && Self::is_voxel_visible(location, look, camera_position, render_distance)
+                    && Self::is_voxel_visible(
+                        location,
+                        look,
+                        camera_position,
+                        render_distance,
+                        render_threshold,
+                    )
             })
             .collect()
     }
@@ -554,6 +885,7 @@ impl Renderer {
         look: Vec3,
         camera_position: Vec3,
         max_distance: f32,
+        render_threshold: f32,
     ) -> bool {
         let voxel_location: Vec3 = Location::from(*internal_location).into();
         let direction_to_voxel = voxel_location - camera_position;
@@ -572,7 +904,132 @@ impl Renderer {
 
         let dot_product = direction_to_voxel.dot(look);
 
-        // look . direction_to_voxel > VT * |direction_to_voxel|
-        dot_product > VOXEL_RENDER_THRESHOLD * distance_to_voxel
+        // look . direction_to_voxel > threshold * |direction_to_voxel|
+        dot_product > render_threshold * distance_to_voxel
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_mesh_info(voxel: Voxel) -> MeshInfo {
+        let mesh = Mesh {
+            vertices: vec![],
+            indices: vec![],
+            texture: None,
+        };
+        (1, voxel, mesh)
+    }
+
+    #[test]
+    fn test_render_threshold_widens_with_fov() {
+        let aspect = 16.0 / 9.0;
+        let default_threshold = Renderer::calculate_render_threshold_for_aspect(
+            45.0_f32.to_radians(),
+            aspect,
+            VOXEL_VIEW_ANGLE_MARGIN,
+        );
+        let wide_threshold = Renderer::calculate_render_threshold_for_aspect(
+            90.0_f32.to_radians(),
+            aspect,
+            VOXEL_VIEW_ANGLE_MARGIN,
+        );
+
+        assert!((default_threshold - 0.71).abs() < 0.01);
+        assert!(wide_threshold < default_threshold);
+        assert!(wide_threshold >= 0.0);
+    }
+
+    #[test]
+    fn test_transparent_voxels_rendered_last_back_to_front() {
+        let camera_position: Vec3 = Location::new(0, 0, 50).into();
+        let locations = [
+            InternalLocation::from(Location::new(2, 0, 50)),
+            InternalLocation::from(Location::new(1, 0, 50)),
+            InternalLocation::from(Location::new(3, 0, 50)),
+            InternalLocation::from(Location::new(4, 0, 50)),
+            InternalLocation::from(Location::new(5, 0, 50)),
+        ];
+        let mesh_infos = [
+            create_mesh_info(Voxel::Glass),
+            create_mesh_info(Voxel::Stone),
+            create_mesh_info(Voxel::WaterSource),
+            create_mesh_info(Voxel::Glass),
+            create_mesh_info(Voxel::Dirt),
+        ];
+        let pairs: Vec<_> = locations.iter().zip(mesh_infos.iter()).collect();
+
+        let ordered: Vec<_> = Renderer::optimise_render_order(&pairs, camera_position)
+            .map(|(location, (_, voxel, _))| (Location::from(*location).x, *voxel))
+            .collect();
+
+        assert_eq!(
+            ordered[2..],
+            [
+                (4, Voxel::Glass),
+                (3, Voxel::WaterSource),
+                (2, Voxel::Glass)
+            ]
+        );
+        assert!(
+            ordered[..2]
+                .iter()
+                .all(|(_, voxel)| !Voxel::TRANSPARENT.contains(voxel))
+        );
+    }
+
+    #[test]
+    fn test_render_area_tracks_emissive_voxels() {
+        let location = InternalLocation::new(1, 2, 3);
+        let mut render_area = RenderArea::new_empty();
+
+        render_area.insert(location, create_mesh_info(Voxel::Lamp));
+        assert_eq!(
+            render_area.lights.get(&location).copied(),
+            Voxel::Lamp.emits_light()
+        );
+
+        render_area.insert(location, create_mesh_info(Voxel::Stone));
+        assert!(Voxel::Stone.emits_light().is_none());
+        assert!(!render_area.lights.contains_key(&location));
+
+        render_area.insert(location, create_mesh_info(Voxel::Lamp));
+        render_area.remove(&location);
+        assert!(render_area.lights.is_empty());
+    }
+
+    #[test]
+    fn test_prepare_lights_uses_voxel_radius() {
+        let lamp_location = InternalLocation::new(1, 2, 3);
+        let torch_location = InternalLocation::new(4, 2, 3);
+        let mut render_area = RenderArea::new_empty();
+        render_area.insert(lamp_location, create_mesh_info(Voxel::Lamp));
+        render_area.insert(torch_location, create_mesh_info(Voxel::Torch));
+        let area_location = AreaLocation::new(0, 0);
+
+        let lights = Renderer::prepare_lights(&[(&area_location, &render_area)]);
+        let radius_at = |location| {
+            lights
+                .iter()
+                .find(|(light_location, _, _)| *light_location == location)
+                .map(|(_, _, radius)| *radius)
+        };
+        assert_eq!(lights.len(), 2);
+        assert!(radius_at(torch_location) < radius_at(lamp_location));
+    }
+
+    #[test]
+    fn test_calculate_render_origin() {
+        let origin = Location::new(0, 0, 0);
+        assert!(Renderer::calculate_render_origin(vec3(100.0, -200.0, 50.0), origin).is_none());
+
+        let far_position = vec3(REBASE_DISTANCE + 20.0, -3.0, 50.0);
+        let new_origin = Renderer::calculate_render_origin(far_position, origin).unwrap();
+        assert_eq!(new_origin.x % AREA_SIZE as i32, 0);
+        assert_eq!(new_origin.y % AREA_SIZE as i32, 0);
+        assert_eq!(new_origin.z, 0);
+        assert_eq!(new_origin.y, -(AREA_SIZE as i32));
+        assert!(Renderer::calculate_render_origin(far_position, new_origin).is_none());
     }
 }
@@ -2,7 +2,10 @@ use std::sync::LazyLock;
 
 use macroquad::prelude::info;
 
-use crate::graphics::{flat_shader::FlatShader, sky_shader::SkyShader, voxel_shader::VoxelShader};
+use crate::graphics::{
+    bloom_shader::BloomShader, flat_shader::FlatShader, sky_shader::SkyShader,
+    voxel_shader::VoxelShader,
+};
 
 /// global shader singleton containing all game shaders
 pub static SHADER_MANAGER_INSTANCE: LazyLock<ShaderManager> = LazyLock::new(ShaderManager::new);
@@ -11,6 +14,7 @@ pub struct ShaderManager {
     pub voxel_shader: VoxelShader,
     pub sky_shader: SkyShader,
     pub flat_shader: FlatShader,
+    pub bloom_shader: BloomShader,
 }
 impl ShaderManager {
     pub fn initialise_global_instance() {
@@ -22,6 +26,7 @@ impl ShaderManager {
             voxel_shader: VoxelShader::new(),
             sky_shader: SkyShader::new(),
             flat_shader: FlatShader::new(),
+            bloom_shader: BloomShader::new(),
         };
         info!("Initialised shaders");
 
@@ -19,14 +19,15 @@ use crate::{
         shader_manager::SHADER_MANAGER_INSTANCE,
         texture_manager::{PlainTextureId, TextureManager},
     },
+    model::voxel::Voxel,
     service::{
         activity_timer::ActivityTimer, camera_controller::CameraController, world_time::WorldTime,
     },
     utils::{arr_to_vec3, vec3_to_arr},
 };
 
-pub const SKY_BRIGHT_COLOR: Color = Color::new(0.75, 0.96, 1.0, 1.0);
-pub const SKY_DARK_COLOR: Color = Color::new(0.12, 0.08, 0.36, 1.0);
+const SKY_BRIGHT_COLOR: Color = Color::new(0.75, 0.96, 1.0, 1.0);
+const SKY_DARK_COLOR: Color = Color::new(0.12, 0.08, 0.36, 1.0);
 
 const DISTANCE_TO_SKY: f32 = 3000.0;
 const SUN_AND_MOON_SIZE: f32 = 400.0;
@@ -71,23 +72,27 @@ impl Sky {
 
     pub fn draw_sky(&self, world_time: &WorldTime, rain_system: &RainSystem, camera: &Camera3D) {
         let light_level = world_time.get_light_level(rain_system.get_light_level_modifier());
-        let dark_level = 1.0 - light_level;
-        let sky_color = Color::new(
-            SKY_BRIGHT_COLOR.r * light_level + SKY_DARK_COLOR.r * dark_level,
-            SKY_BRIGHT_COLOR.g * light_level + SKY_DARK_COLOR.g * dark_level,
-            SKY_BRIGHT_COLOR.b * light_level + SKY_DARK_COLOR.b * dark_level,
-            1.0,
-        );
-        clear_background(sky_color);
-
+        let sky_color = Self::get_sky_color(light_level);
         let normalised_camera = CameraController::normalize_camera_3d(camera);
         set_camera(&normalised_camera);
+        clear_background(sky_color);
 
         SHADER_MANAGER_INSTANCE.sky_shader.set_sky_material();
         self.draw_sun_and_moon(world_time);
         self.clouds_manager.draw();
     }
 
+    /// the color of the sky for the light level, also used for the fog
+    pub fn get_sky_color(light_level: f32) -> Color {
+        let dark_level = 1.0 - light_level;
+        Color::new(
+            SKY_BRIGHT_COLOR.r * light_level + SKY_DARK_COLOR.r * dark_level,
+            SKY_BRIGHT_COLOR.g * light_level + SKY_DARK_COLOR.g * dark_level,
+            SKY_BRIGHT_COLOR.b * light_level + SKY_DARK_COLOR.b * dark_level,
+            1.0,
+        )
+    }
+
     pub fn update(&mut self, delta: f32) {
         self.clouds_manager.update(delta);
     }
@@ -97,7 +102,9 @@ impl Sky {
         let moon = self.create_moon(world_time);
 
         draw_mesh(&sun);
-        draw_mesh(&moon);
+        if let Some(moon) = moon {
+            draw_mesh(&moon);
+        }
     }
 
     fn create_sun(&self, world_time: &WorldTime) -> Mesh {
@@ -108,12 +115,29 @@ impl Sky {
         }
     }
 
-    fn create_moon(&self, world_time: &WorldTime) -> Mesh {
+    /// crops the moon to the lit part for the current phase, returns none during a new moon
+    fn create_moon(&self, world_time: &WorldTime) -> Option<Mesh> {
+        let (start, end) = get_lit_moon_range(
+            world_time.get_moon_phase(),
+            world_time.get_moon_illumination(),
+        );
+        if end - start <= f32::EPSILON {
+            return None;
+        }
+
         let angle = world_time.get_delta() * 2.0;
-        Mesh {
-            texture: Some(self.moon_texture.clone()),
-            ..Self::create_sun_or_moon_mesh(angle)
+        let mut mesh = Self::create_sun_or_moon_mesh(angle);
+        let left = -Voxel::HALF_SIZE * SUN_AND_MOON_SIZE;
+        let right = Voxel::HALF_SIZE * SUN_AND_MOON_SIZE;
+        for v in &mut mesh.vertices {
+            v.uv.x = v.uv.x.clamp(start, end);
+            v.position.x = left + (right - left) * v.uv.x;
         }
+
+        Some(Mesh {
+            texture: Some(self.moon_texture.clone()),
+            ..mesh
+        })
     }
 
     fn create_sun_or_moon_mesh(angle: f32) -> Mesh {
@@ -131,6 +155,16 @@ impl Sky {
     }
 }
 
+/// horizontal texture range of the lit part of the moon,
+/// waxing from the right side and waning towards the left side
+fn get_lit_moon_range(phase: f32, illumination: f32) -> (f32, f32) {
+    if phase < 0.5 {
+        (1.0 - illumination, 1.0)
+    } else {
+        (0.0, illumination)
+    }
+}
+
 #[derive(Debug, Clone, Encode, Decode)]
 pub struct SkyDTO {
     clouds_dto: CloudsDTO,
@@ -233,3 +267,16 @@ pub struct CloudsDTO {
     clouds_spawn_delta: f32,
     cloud_positions: Vec<[f32; 3]>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lit_moon_range() {
+        assert_eq!(get_lit_moon_range(0.0, 0.0), (1.0, 1.0));
+        assert_eq!(get_lit_moon_range(0.25, 0.5), (0.5, 1.0));
+        assert_eq!(get_lit_moon_range(0.5, 1.0), (0.0, 1.0));
+        assert_eq!(get_lit_moon_range(0.75, 0.5), (0.0, 0.5));
+    }
+}
@@ -14,12 +14,13 @@ use crate::{
 const BASE_MODEL_TEXTURES_PATH: &str = "assets/images/model_textures/";
 const BASE_VOXEL_TEXTURES_PATH: &str = "assets/images/voxels/";
 const BASE_ICON_TEXTURES_PATH: &str = "assets/images/icons/";
-const TEXTURES: [(Voxel, &str); 20] = [
+const TEXTURES: [(Voxel, &str); 35] = [
     (Voxel::Stone, "stone.png"),
     (Voxel::Sand, "sand.png"),
     (Voxel::Grass, "grass.png"),
     (Voxel::Wood, "wood.png"),
     (Voxel::Leaves, "leaves.png"),
+    (Voxel::SnowyLeaves, "snowy-leaves.png"),
     (Voxel::Brick, "brick.png"),
     (Voxel::Dirt, "dirt.png"),
     (Voxel::Boards, "boards.png"),
@@ -35,6 +36,20 @@ const TEXTURES: [(Voxel, &str); 20] = [
     (Voxel::StonePillar, "stone-pillar.png"),
     (Voxel::Bomb, "bomb.png"),
     (Voxel::ActiveBomb, "active_bomb.png"),
+    (Voxel::Compass, "compass.png"),
+    (Voxel::WeatherTotem, "weather-totem.png"),
+    (Voxel::CoalOre, "coal-ore.png"),
+    (Voxel::IronOre, "iron-ore.png"),
+    (Voxel::Fire, "fire.png"),
+    (Voxel::Torch, "torch.png"),
+    (Voxel::Furnace, "furnace.png"),
+    (Voxel::Iron, "iron.png"),
+    (Voxel::StoneSlab, "stone-slab.png"),
+    (Voxel::WoodStairs, "wood-stairs.png"),
+    (Voxel::Bucket, "bucket.png"),
+    (Voxel::WaterBucket, "water-bucket.png"),
+    (Voxel::Pickaxe, "pickaxe.png"),
+    (Voxel::ReachStaff, "reach-staff.png"),
 ];
 const WATER_TEXTURE: &str = "water.png";
 const ICON_TEXTURES: [(Voxel, &str); 7] = [
@@ -52,6 +67,8 @@ const MESH_TEXTURES: [(MeshId, &str); MeshId::VARIANTS] = [
     (MeshId::ButterflyUp, "butterfly_texture.png"),
     (MeshId::Penguin, "penguin_texture.png"),
     (MeshId::Explosion, "explosion_texture.png"),
+    (MeshId::Fish, "fish_texture.png"),
+    (MeshId::Firefly, "firefly_texture.png"),
 ];
 const MAX_TEXTURE_COUNT: usize = MAX_VOXEL_VARIANTS;
 
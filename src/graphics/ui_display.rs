@@ -1,30 +1,208 @@
 use bincode::{Decode, Encode};
 use macroquad::{
     camera::Camera3D,
-    color::{Color, WHITE},
-    math::{vec2, vec3},
+    color::{Color, RED, WHITE},
+    math::{Vec3, vec2, vec3},
     miniquad::window::screen_size,
     models::draw_cube_wires,
-    shapes::{draw_circle, draw_rectangle},
+    shapes::{draw_circle, draw_rectangle, draw_triangle},
     text::Font,
     texture::{DrawTextureParams, Texture2D, draw_texture_ex},
 };
-use std::fmt::Write;
+use std::{
+    f32::consts::{PI, TAU},
+    fmt::Write,
+};
 
 use crate::{
-    interface::{style::TEXT_COLOR, text::draw_game_text},
+    interface::{
+        style::{CLEAR_SCREEN_COLOR, MARGIN, SMALL_FONT_SIZE, TEXT_COLOR},
+        text::{draw_game_text, get_text_width},
+    },
     model::{
         inventory::{Inventory, Item},
         location::Location,
     },
-    service::asset_manager::AssetManager,
+    service::{asset_manager::AssetManager, raycast::RaycastResult},
     utils::use_str_buffer,
 };
 
 const BASE_COUNT_FONT_SIZE: f32 = 0.5;
+const BASE_CHARGES_FONT_SIZE: f32 = 0.3;
+const CHARGES_COLOR: Color = Color::new(1.0, 0.85, 0.35, 1.0);
+const CROSSHAIR_SIZE: f32 = 2.0;
+/// crosshair color when there is no voxel within reach
+const OUT_OF_REACH_CROSSHAIR_COLOR: Color = Color::new(1.0, 1.0, 1.0, 0.35);
+/// distance of the compass arrow above the crosshair
+const COMPASS_ARROW_OFFSET: f32 = 40.0;
+const COMPASS_ARROW_SIZE: f32 = 14.0;
+/// distance of the targeted voxel name below the crosshair
+const TARGET_NAME_OFFSET: f32 = 20.0;
+/// height of the health and stamina bars relative to a hotbar slot
+const STATUS_BAR_HEIGHT: f32 = 0.15;
+const HEALTH_COLOR: Color = Color::new(0.85, 0.15, 0.15, 1.0);
+const STAMINA_COLOR: Color = Color::new(0.95, 0.8, 0.2, 1.0);
+const OXYGEN_COLOR: Color = Color::new(0.45, 0.8, 1.0, 1.0);
+/// distance of the oxygen indicator to the right of the crosshair
+const OXYGEN_INDICATOR_OFFSET: f32 = 20.0;
+const OXYGEN_INDICATOR_WIDTH: f32 = 6.0;
+const OXYGEN_INDICATOR_HEIGHT: f32 = 40.0;
+const COMPASS_BAR_WIDTH: f32 = 360.0;
+const COMPASS_BAR_HEIGHT: f32 = 36.0;
+/// the range of headings visible on the compass bar
+const COMPASS_BAR_SPAN: f32 = PI;
+/// angle between the tick marks on the compass bar
+const COMPASS_TICK_STEP: f32 = PI / 12.0;
+const COMPASS_TICK_WIDTH: f32 = 2.0;
+const COMPASS_TICK_HEIGHT: f32 = 6.0;
+const COMPASS_DIRECTIONS: [&str; 4] = ["N", "E", "S", "W"];
+
+/// draws the crosshair, dimmed if no voxel is targeted
+pub fn draw_crosshair(width: f32, height: f32, raycast_result: RaycastResult) {
+    let color = match raycast_result {
+        RaycastResult::NoneHit => OUT_OF_REACH_CROSSHAIR_COLOR,
+        RaycastResult::Hit {
+            first_non_empty: _,
+            last_empty: _,
+        } => WHITE,
+    };
+
+    draw_circle(width / 2.0, height / 2.0, CROSSHAIR_SIZE, color);
+}
+
+/// draws a vertical bar to the right of the crosshair that empties as the oxygen runs out
+pub fn draw_oxygen_indicator(width: f32, height: f32, oxygen_fraction: f32) {
+    let x = width / 2.0 + OXYGEN_INDICATOR_OFFSET;
+    let y = (height - OXYGEN_INDICATOR_HEIGHT) / 2.0;
+    let filled_height = OXYGEN_INDICATOR_HEIGHT * oxygen_fraction.clamp(0.0, 1.0);
+
+    draw_rectangle(
+        x,
+        y,
+        OXYGEN_INDICATOR_WIDTH,
+        OXYGEN_INDICATOR_HEIGHT,
+        CLEAR_SCREEN_COLOR,
+    );
+    draw_rectangle(
+        x,
+        y + OXYGEN_INDICATOR_HEIGHT - filled_height,
+        OXYGEN_INDICATOR_WIDTH,
+        filled_height,
+        OXYGEN_COLOR,
+    );
+}
+
+/// draws the name of the targeted voxel below the crosshair
+pub fn draw_target_name(width: f32, height: f32, name: &str, font: &Font) {
+    let text_width = get_text_width(name, SMALL_FONT_SIZE, font);
+    let x = (width - text_width) / 2.0;
+    let y = height / 2.0 + TARGET_NAME_OFFSET;
+    draw_rectangle(
+        x - MARGIN,
+        y,
+        text_width + MARGIN * 2.0,
+        SMALL_FONT_SIZE + MARGIN,
+        CLEAR_SCREEN_COLOR,
+    );
+    draw_game_text(
+        name,
+        x,
+        y + SMALL_FONT_SIZE,
+        SMALL_FONT_SIZE,
+        TEXT_COLOR,
+        font,
+    );
+}
+
+/// returns the angle in radians from the look direction to the target,
+/// positive when the target is to the right
+pub fn calculate_bearing(position: Vec3, yaw: f32, target: Location) -> f32 {
+    let to_target_x = target.x as f32 - position.x;
+    let to_target_y = target.y as f32 - position.y;
+    let angle = to_target_y.atan2(to_target_x) - yaw;
+
+    (angle + PI).rem_euclid(TAU) - PI
+}
+
+/// draws an arrow above the crosshair, pointing up when the bearing is straight ahead
+pub fn draw_compass_arrow(width: f32, height: f32, bearing: f32) {
+    let center = vec2(width / 2.0, height / 2.0 - COMPASS_ARROW_OFFSET);
+    let direction = vec2(bearing.sin(), -bearing.cos());
+    let perpendicular = vec2(-direction.y, direction.x);
+
+    let tip = center + direction * COMPASS_ARROW_SIZE;
+    let back = center - direction * COMPASS_ARROW_SIZE * 0.6;
+    draw_triangle(
+        tip,
+        back + perpendicular * COMPASS_ARROW_SIZE * 0.5,
+        back - perpendicular * COMPASS_ARROW_SIZE * 0.5,
+        RED,
+    );
+}
+
+/// returns the heading clockwise from north (-y) in [0, TAU), east is +x
+pub fn calculate_heading(yaw: f32) -> f32 {
+    (yaw + PI / 2.0).rem_euclid(TAU)
+}
+
+/// draws a horizontal strip at the top of the screen with tick marks and the cardinal directions
+/// around the heading, the heading is in the middle
+pub fn draw_compass_bar(width: f32, heading: f32, font: &Font) {
+    let center_x = width / 2.0;
+    let left = center_x - COMPASS_BAR_WIDTH / 2.0;
+    let top = MARGIN;
+    draw_rectangle(
+        left,
+        top,
+        COMPASS_BAR_WIDTH,
+        COMPASS_BAR_HEIGHT,
+        CLEAR_SCREEN_COLOR,
+    );
+
+    let tick_count = (TAU / COMPASS_TICK_STEP).round() as usize;
+    let ticks_per_direction = tick_count / COMPASS_DIRECTIONS.len();
+    for tick in 0..tick_count {
+        let offset = (tick as f32 * COMPASS_TICK_STEP - heading + PI).rem_euclid(TAU) - PI;
+        if offset.abs() > COMPASS_BAR_SPAN / 2.0 {
+            continue;
+        }
+        let x = center_x + offset / COMPASS_BAR_SPAN * COMPASS_BAR_WIDTH;
+
+        if tick % ticks_per_direction != 0 {
+            let tick_height = if tick % (ticks_per_direction / 2) == 0 {
+                COMPASS_TICK_HEIGHT * 2.0
+            } else {
+                COMPASS_TICK_HEIGHT
+            };
+            draw_rectangle(
+                x - COMPASS_TICK_WIDTH / 2.0,
+                top + COMPASS_BAR_HEIGHT - tick_height,
+                COMPASS_TICK_WIDTH,
+                tick_height,
+                TEXT_COLOR,
+            );
+            continue;
+        }
+
+        let direction = COMPASS_DIRECTIONS[tick / ticks_per_direction];
+        let text_width = get_text_width(direction, SMALL_FONT_SIZE, font);
+        let color = if tick == 0 { RED } else { TEXT_COLOR };
+        draw_game_text(
+            direction,
+            x - text_width / 2.0,
+            top + SMALL_FONT_SIZE,
+            SMALL_FONT_SIZE,
+            color,
+            font,
+        );
+    }
 
-pub fn draw_crosshair(width: f32, height: f32) {
-    draw_circle(width / 2.0, height / 2.0, 2.0, WHITE);
+    draw_triangle(
+        vec2(center_x, top + COMPASS_BAR_HEIGHT - COMPASS_TICK_HEIGHT),
+        vec2(center_x - COMPASS_TICK_HEIGHT, top + COMPASS_BAR_HEIGHT),
+        vec2(center_x + COMPASS_TICK_HEIGHT, top + COMPASS_BAR_HEIGHT),
+        RED,
+    );
 }
 
 pub fn draw_selected_voxel(location: Location, camera: &Camera3D) {
@@ -85,10 +263,10 @@ impl ItemHotbar {
         let y = screen_height - border_size;
 
         for (index, item) in items_on_hotbar.iter().enumerate() {
-            let texture_with_count = item.as_ref().map(|non_empty| {
+            let texture_with_item = item.map(|non_empty| {
                 (
                     asset_manager.texture_manager.get_icon(non_empty.voxel),
-                    non_empty.count,
+                    non_empty,
                 )
             });
             let is_selected = self.selected == index;
@@ -97,7 +275,7 @@ impl ItemHotbar {
             Self::draw_voxel(
                 border_size,
                 picture_size,
-                texture_with_count,
+                texture_with_item,
                 x,
                 y,
                 is_selected,
@@ -106,10 +284,39 @@ impl ItemHotbar {
         }
     }
 
+    /// draws the health bar above the hotbar with the same width
+    pub fn draw_health_bar(&self, health_fraction: f32) {
+        self.draw_status_bar(health_fraction, 0, HEALTH_COLOR);
+    }
+
+    /// draws the stamina bar above the health bar
+    pub fn draw_stamina_bar(&self, stamina_fraction: f32) {
+        self.draw_status_bar(stamina_fraction, 1, STAMINA_COLOR);
+    }
+
+    /// the row counts up from the hotbar
+    fn draw_status_bar(&self, fraction: f32, row: usize, color: Color) {
+        let (screen_width, screen_height) = screen_size();
+        let border_size = screen_width * self.ui_size;
+        let total_width = border_size * Inventory::SELECTED_SIZE as f32;
+        let bar_height = border_size * STATUS_BAR_HEIGHT;
+        let x = (screen_width - total_width) / 2.0;
+        let y = screen_height - border_size - (bar_height + MARGIN) * (row + 1) as f32;
+
+        draw_rectangle(x, y, total_width, bar_height, CLEAR_SCREEN_COLOR);
+        draw_rectangle(
+            x,
+            y,
+            total_width * fraction.clamp(0.0, 1.0),
+            bar_height,
+            color,
+        );
+    }
+
     fn draw_voxel(
         border_size: f32,
         picture_size: f32,
-        texture_with_count: Option<(Texture2D, u8)>,
+        texture_with_item: Option<(Texture2D, Item)>,
         x: f32,
         y: f32,
         is_selected: bool,
@@ -123,7 +330,7 @@ impl ItemHotbar {
         let offset = (border_size - picture_size) / 2.0;
 
         draw_rectangle(x, y, border_size, border_size, border_color);
-        if let Some((texture, count)) = texture_with_count {
+        if let Some((texture, item)) = texture_with_item {
             draw_texture_ex(
                 &texture,
                 x + offset,
@@ -137,7 +344,7 @@ impl ItemHotbar {
 
             let font_size = BASE_COUNT_FONT_SIZE * border_size;
             use_str_buffer(|buffer| {
-                write!(buffer, "{count}").expect("error writing to text buffer");
+                write!(buffer, "{}", item.count).expect("error writing to text buffer");
                 draw_game_text(
                     buffer,
                     x + offset,
@@ -147,6 +354,9 @@ impl ItemHotbar {
                     font,
                 );
             });
+            if let Some(charges) = item.charges {
+                Self::draw_charges(charges, border_size, offset, x, y, font);
+            }
         } else {
             draw_rectangle(
                 x + offset,
@@ -157,4 +367,58 @@ impl ItemHotbar {
             );
         }
     }
+
+    /// draws the remaining charges in the bottom right corner of the slot
+    fn draw_charges(charges: u16, border_size: f32, offset: f32, x: f32, y: f32, font: &Font) {
+        let font_size = BASE_CHARGES_FONT_SIZE * border_size;
+        use_str_buffer(|buffer| {
+            write!(buffer, "{charges}").expect("error writing to text buffer");
+            let text_width = get_text_width(buffer, font_size, font);
+            draw_game_text(
+                buffer,
+                x + border_size - offset - text_width,
+                y + border_size - offset,
+                font_size,
+                CHARGES_COLOR,
+                font,
+            );
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calculate_bearing() {
+        let position = Vec3::ZERO;
+        let ahead = calculate_bearing(position, 0.0, Location::new(10, 0, 0));
+        assert!(ahead.abs() < 0.001);
+
+        // +y is to the right when looking towards +x
+        let right = calculate_bearing(position, 0.0, Location::new(0, 10, 0));
+        assert!((right - PI / 2.0).abs() < 0.001);
+
+        let behind = calculate_bearing(position, PI / 2.0, Location::new(0, -10, 0));
+        assert!((behind.abs() - PI).abs() < 0.001);
+
+        let wrapped = calculate_bearing(position, 3.0 * TAU, Location::new(0, -10, 0));
+        assert!((wrapped + PI / 2.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_calculate_heading() {
+        let north = calculate_heading(-PI / 2.0);
+        assert!(north.abs() < 0.001);
+
+        let east = calculate_heading(0.0);
+        assert!((east - PI / 2.0).abs() < 0.001);
+
+        let south = calculate_heading(PI / 2.0 + 2.0 * TAU);
+        assert!((south - PI).abs() < 0.001);
+
+        let west = calculate_heading(-PI);
+        assert!((west - 3.0 * PI / 2.0).abs() < 0.001);
+    }
 }
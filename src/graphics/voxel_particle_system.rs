@@ -15,6 +15,14 @@ const RANDOM_VELOCITY: f32 = 4.5;
 const RANDOM_DESTROYED_COUNT: u32 = 10;
 const MIN_DESTROYED_COUNT: u32 = 10;
 const LANDING_COUNT: u32 = 15;
+/// particles for each voxel removed by an explosion
+const EXPLODED_COUNT: u32 = 2;
+/// limits the particles of a single explosion
+const MAX_EXPLOSION_PARTICLES: usize = 200;
+const PLACED_COUNT: u32 = 4;
+const PLACED_PARTICLE_LIFE: f32 = 0.2;
+/// placing voxels doesn't add particles if there are more than this many
+const MAX_PARTICLES_FOR_PLACED: usize = 100;
 const LANDING_Z_OFFSET: f32 = 0.3;
 const PARTICLE_LIFE: f32 = 0.4;
 const GRAVITY: Vec3 = vec3(0.0, 0.0, 15.0);
@@ -23,10 +31,11 @@ struct VoxelParticle {
     position: Vec3,
     velocity: Vec3,
     delta: f32,
+    life: f32,
     mesh: Mesh,
 }
 impl VoxelParticle {
-    fn create_random(position: Vec3, mesh: Mesh) -> Self {
+    fn create_random(position: Vec3, mesh: Mesh, life: f32) -> Self {
         let x_velocity = gen_range(-RANDOM_VELOCITY, RANDOM_VELOCITY);
         let y_velocity = gen_range(-RANDOM_VELOCITY, RANDOM_VELOCITY);
         let z_velocity = gen_range(-RANDOM_VELOCITY, 0.0);
@@ -35,6 +44,7 @@ impl VoxelParticle {
             position,
             velocity: vec3(x_velocity, y_velocity, z_velocity),
             delta: 0.0,
+            life,
             mesh,
         }
     }
@@ -69,7 +79,13 @@ impl VoxelParticleSystem {
         if let CollisionType::Strong { voxel } = collision {
             let mut position = player_info.camera_controller.get_bottom_position();
             position.z += LANDING_Z_OFFSET;
-            self.add_particles(voxel, position, LANDING_COUNT, mesh_generator);
+            self.add_particles(
+                voxel,
+                position,
+                LANDING_COUNT,
+                PARTICLE_LIFE,
+                mesh_generator,
+            );
         }
     }
 
@@ -80,7 +96,48 @@ impl VoxelParticleSystem {
         mesh_generator: &MeshGenerator,
     ) {
         let count = rand() % RANDOM_DESTROYED_COUNT + MIN_DESTROYED_COUNT;
-        self.add_particles(voxel, location.into(), count, mesh_generator);
+        self.add_particles(voxel, location.into(), count, PARTICLE_LIFE, mesh_generator);
+    }
+
+    /// adds a few short lived particles on the face of the placed voxel opposite of the voxel
+    /// it was placed against, skipped if there are already many particles
+    pub fn add_particles_for_placed(
+        &mut self,
+        voxel: Voxel,
+        location: Location,
+        placed_against: Location,
+        mesh_generator: &MeshGenerator,
+    ) {
+        if self.particles.len() > MAX_PARTICLES_FOR_PLACED {
+            return;
+        }
+        let position: Vec3 = location.into();
+        let away_from_placed_against = position - Vec3::from(placed_against);
+        self.add_particles(
+            voxel,
+            position + away_from_placed_against * Voxel::HALF_SIZE,
+            PLACED_COUNT,
+            PLACED_PARTICLE_LIFE,
+            mesh_generator,
+        );
+    }
+
+    pub fn add_particles_for_exploded(
+        &mut self,
+        exploded: &[(Location, Voxel)],
+        mesh_generator: &MeshGenerator,
+    ) {
+        let max_voxels = MAX_EXPLOSION_PARTICLES / EXPLODED_COUNT as usize;
+        let step = exploded.len().div_ceil(max_voxels).max(1);
+        for (location, voxel) in exploded.iter().step_by(step) {
+            self.add_particles(
+                *voxel,
+                (*location).into(),
+                EXPLODED_COUNT,
+                PARTICLE_LIFE,
+                mesh_generator,
+            );
+        }
     }
 
     pub fn update(&mut self, delta: f32) {
@@ -88,7 +145,7 @@ impl VoxelParticleSystem {
             particle.update(delta);
         }
 
-        self.particles.retain(|p| p.delta <= PARTICLE_LIFE);
+        self.particles.retain(|p| p.delta <= p.life);
     }
 
     pub fn draw(&self) {
@@ -102,6 +159,7 @@ impl VoxelParticleSystem {
         voxel: Voxel,
         position: Vec3,
         count: u32,
+        life: f32,
         mesh_generator: &MeshGenerator,
     ) {
         for _ in 0..count {
@@ -110,7 +168,7 @@ impl VoxelParticleSystem {
             let z_offset = gen_range(-RANDOM_POSITION_OFFSET, RANDOM_POSITION_OFFSET);
             let particle_position = position + vec3(x_offset, y_offset, z_offset);
             let mesh = mesh_generator.generate_mesh_for_particle(voxel, particle_position);
-            let particle = VoxelParticle::create_random(particle_position, mesh);
+            let particle = VoxelParticle::create_random(particle_position, mesh, life);
 
             self.particles.push(particle);
         }
@@ -139,7 +197,7 @@ mod tests {
             indices: vec![],
             texture: None,
         };
-        let mut particle = VoxelParticle::create_random(Vec3::ZERO, point_mesh);
+        let mut particle = VoxelParticle::create_random(Vec3::ZERO, point_mesh, PARTICLE_LIFE);
         let starting_position = particle.position;
         let starting_mesh_position = particle.mesh.vertices[0].position;
 
@@ -1,20 +1,18 @@
 use macroquad::{
     camera::Camera3D,
-    math::{Vec3, Vec4Swizzles, vec3},
+    math::{Vec3, vec3},
     miniquad::{BlendFactor, BlendState, BlendValue, Equation},
     prelude::{
         Comparison, Material, MaterialParams, PipelineParams, ShaderSource, UniformDesc,
-        UniformType, gl_use_material, load_material,
+        UniformType, gl_use_material, load_material, warn,
     },
     texture::Texture2D,
 };
 
-use crate::{
-    graphics::sky::{SKY_BRIGHT_COLOR, SKY_DARK_COLOR},
-    model::{
-        area::AREA_SIZE,
-        location::{InternalLocation, Location},
-    },
+use crate::model::{
+    area::AREA_SIZE,
+    location::{InternalLocation, Location},
+    voxel::LightColor,
 };
 
 // values from voxel shader
@@ -26,6 +24,8 @@ const FALSE: i32 = 0;
 
 const VOXEL_VERTEX_SHADER: &str = include_str!("../../resources/shaders/voxel_vertex.glsl");
 const VOXEL_FRAGMENT_SHADER: &str = include_str!("../../resources/shaders/voxel_fragment.glsl");
+/// compiles the fragment shader without the light arrays and the height map
+const STATIC_LIGHTING_DEFINE: &str = "#define STATIC_LIGHTING";
 
 const HEIGHT_MAP_TEXTURE_NAME: &str = "heightMap";
 
@@ -34,29 +34,51 @@ const CAMERA_TARGET_UNIFORM: &str = "cameraTarget";
 const FOG_NEAR_UNIFORM: &str = "fogNear";
 const FOG_FAR_UNIFORM: &str = "fogFar";
 const LIGHT_LEVEL_UNIFORM: &str = "lightLevel";
-const FOG_BASE_COLOR_LIGHT_UNIFORM: &str = "fogBaseColorLight";
-const FOG_BASE_COLOR_DARK_UNIFORM: &str = "fogBaseColorDark";
+const FOG_COLOR_UNIFORM: &str = "fogColor";
+const HAS_FOG_UNIFORM: &str = "hasFog";
 const LIGHTS_COUNT_UNIFORM: &str = "lightsCount";
 const LIGHTS_UNIFORM: &str = "lights";
+const LIGHT_COLORS_UNIFORM: &str = "lightColors";
+const LIGHT_RADII_UNIFORM: &str = "lightRadii";
 const EXPLOSIONS_COUNT_UNIFORM: &str = "explosionsCount";
 const EXPLOSIONS_UNIFORM: &str = "explosions";
 const HAS_DYNAMIC_SHADOWS_UNIFORM: &str = "hasDynamicShadows";
 const SHOW_DROP_SHADOW_UNIFORM: &str = "showDropShadow";
+const SMOOTH_LIGHTING_UNIFORM: &str = "smoothLighting";
+const X_RAY_UNIFORM: &str = "xRay";
+const WATER_REFLECTIONS_UNIFORM: &str = "waterReflections";
+const AMBIENT_COLOR_UNIFORM: &str = "ambientColor";
 
 pub struct VoxelUniformParams<'a> {
     pub camera: &'a Camera3D,
     pub render_size: u32,
     pub light_level: f32,
-    pub lights: &'a [InternalLocation],
+    /// multiplies the lit color, neutral is white
+    pub ambient_color: Vec3,
+    /// the current sky color, distant voxels fade into it
+    pub fog_color: Vec3,
+    /// the location, color and radius of each light
+    pub lights: &'a [(InternalLocation, LightColor, f32)],
+    /// the position, color and radius of each creature light, used after the voxel lights
+    pub creature_lights: &'a [(Vec3, LightColor, f32)],
     pub explosions: Vec<Vec3>,
     pub height_map: Texture2D,
     pub has_dynamic_lighting: bool,
+    pub smooth_lighting: bool,
     pub show_map: bool,
+    /// debug mode for seeing through solid voxels
+    pub x_ray: bool,
+    pub water_reflections: bool,
+    pub has_fog: bool,
 }
 
 /// default 3D material shader for voxels
 pub struct VoxelShader {
     voxel_material: Material,
+    x_ray_material: Material,
+    /// false if the GPU couldn't load the dynamic lighting shader
+    /// and the static lighting fallback is used
+    has_dynamic_lighting_support: bool,
 }
 impl VoxelShader {
     pub fn new() -> Self {
@@ -64,26 +86,83 @@ impl VoxelShader {
             depth_write: true,
             depth_test: Comparison::LessOrEqual,
             cull_face: macroquad::miniquad::CullFace::Back,
-            color_blend: Some(BlendState::new(
-                Equation::Add,
-                BlendFactor::Value(BlendValue::SourceAlpha),
-                BlendFactor::OneMinusValue(BlendValue::SourceAlpha),
-            )),
+            color_blend: Some(Self::create_color_blend()),
+            ..Default::default()
+        };
+        // faces don't hide what is behind them, so both sides are drawn
+        let x_ray_pipeline_params = PipelineParams {
+            depth_write: false,
+            depth_test: Comparison::LessOrEqual,
+            cull_face: macroquad::miniquad::CullFace::Nothing,
+            color_blend: Some(Self::create_color_blend()),
             ..Default::default()
         };
 
+        let dynamic_materials =
+            Self::load_voxel_material(voxel_pipeline_params, true).and_then(|voxel_material| {
+                Self::load_voxel_material(x_ray_pipeline_params, true)
+                    .map(|x_ray_material| (voxel_material, x_ray_material))
+            });
+        match dynamic_materials {
+            Ok((voxel_material, x_ray_material)) => Self {
+                voxel_material,
+                x_ray_material,
+                has_dynamic_lighting_support: true,
+            },
+            Err(err) => {
+                warn!(
+                    "Dynamic lighting shader unavailable, using static lighting: {:?}",
+                    err
+                );
+                Self {
+                    voxel_material: Self::load_voxel_material(voxel_pipeline_params, false)
+                        .expect("Error initialising voxel shaders"),
+                    x_ray_material: Self::load_voxel_material(x_ray_pipeline_params, false)
+                        .expect("Error initialising voxel shaders"),
+                    has_dynamic_lighting_support: false,
+                }
+            }
+        }
+    }
+
+    pub fn has_dynamic_lighting_support(&self) -> bool {
+        self.has_dynamic_lighting_support
+    }
+
+    /// inserts the static lighting define after the version directive
+    fn create_static_lighting_source(source: &str) -> String {
+        match source.split_once('\n') {
+            Some((version, rest)) => format!("{version}\n{STATIC_LIGHTING_DEFINE}\n{rest}"),
+            None => format!("{STATIC_LIGHTING_DEFINE}\n{source}"),
+        }
+    }
+
+    fn create_color_blend() -> BlendState {
+        BlendState::new(
+            Equation::Add,
+            BlendFactor::Value(BlendValue::SourceAlpha),
+            BlendFactor::OneMinusValue(BlendValue::SourceAlpha),
+        )
+    }
+
+    fn load_voxel_material(
+        pipeline_params: PipelineParams,
+        has_dynamic_lighting: bool,
+    ) -> Result<Material, macroquad::Error> {
         let camera_uniform = UniformDesc::new(CAMERA_POSITION_UNIFORM, UniformType::Float3);
         let look_uniform = UniformDesc::new(CAMERA_TARGET_UNIFORM, UniformType::Float3);
         let fog_near_uniform = UniformDesc::new(FOG_NEAR_UNIFORM, UniformType::Float1);
         let fog_far_uniform = UniformDesc::new(FOG_FAR_UNIFORM, UniformType::Float1);
         let light_level_uniform = UniformDesc::new(LIGHT_LEVEL_UNIFORM, UniformType::Float1);
-        let fog_light_color_uniform =
-            UniformDesc::new(FOG_BASE_COLOR_LIGHT_UNIFORM, UniformType::Float3);
-        let fog_dark_color_uniform =
-            UniformDesc::new(FOG_BASE_COLOR_DARK_UNIFORM, UniformType::Float3);
+        let fog_color_uniform = UniformDesc::new(FOG_COLOR_UNIFORM, UniformType::Float3);
+        let has_fog_uniform = UniformDesc::new(HAS_FOG_UNIFORM, UniformType::Int1);
         let lights_count_uniform = UniformDesc::new(LIGHTS_COUNT_UNIFORM, UniformType::Int1);
         let lights_uniform =
             UniformDesc::new(LIGHTS_UNIFORM, UniformType::Float3).array(MAX_LIGHTS);
+        let light_colors_uniform =
+            UniformDesc::new(LIGHT_COLORS_UNIFORM, UniformType::Float3).array(MAX_LIGHTS);
+        let light_radii_uniform =
+            UniformDesc::new(LIGHT_RADII_UNIFORM, UniformType::Float1).array(MAX_LIGHTS);
         let explosions_count_uniform =
             UniformDesc::new(EXPLOSIONS_COUNT_UNIFORM, UniformType::Int1);
         let explosions_uniform =
@@ -92,114 +171,183 @@ impl VoxelShader {
             UniformDesc::new(HAS_DYNAMIC_SHADOWS_UNIFORM, UniformType::Int1);
         let show_drop_shadow_uniform =
             UniformDesc::new(SHOW_DROP_SHADOW_UNIFORM, UniformType::Int1);
+        let smooth_lighting_uniform = UniformDesc::new(SMOOTH_LIGHTING_UNIFORM, UniformType::Int1);
+        let x_ray_uniform = UniformDesc::new(X_RAY_UNIFORM, UniformType::Int1);
+        let water_reflections_uniform =
+            UniformDesc::new(WATER_REFLECTIONS_UNIFORM, UniformType::Int1);
+        let ambient_color_uniform = UniformDesc::new(AMBIENT_COLOR_UNIFORM, UniformType::Float3);
 
-        let voxel_material = load_material(
+        let mut uniforms = vec![
+            camera_uniform,
+            look_uniform,
+            fog_near_uniform,
+            fog_far_uniform,
+            light_level_uniform,
+            fog_color_uniform,
+            has_fog_uniform,
+            show_drop_shadow_uniform,
+            smooth_lighting_uniform,
+            x_ray_uniform,
+            water_reflections_uniform,
+            ambient_color_uniform,
+        ];
+        let (fragment, textures) = if has_dynamic_lighting {
+            uniforms.extend([
+                lights_count_uniform,
+                lights_uniform,
+                light_colors_uniform,
+                light_radii_uniform,
+                explosions_count_uniform,
+                explosions_uniform,
+                has_dynamic_shadows_uniform,
+            ]);
+            (
+                VOXEL_FRAGMENT_SHADER.to_owned(),
+                vec![HEIGHT_MAP_TEXTURE_NAME.to_owned()],
+            )
+        } else {
+            (
+                Self::create_static_lighting_source(VOXEL_FRAGMENT_SHADER),
+                vec![],
+            )
+        };
+
+        load_material(
             ShaderSource::Glsl {
                 vertex: VOXEL_VERTEX_SHADER,
-                fragment: VOXEL_FRAGMENT_SHADER,
+                fragment: &fragment,
             },
             MaterialParams {
-                pipeline_params: voxel_pipeline_params,
-                uniforms: vec![
-                    camera_uniform,
-                    look_uniform,
-                    fog_near_uniform,
-                    fog_far_uniform,
-                    light_level_uniform,
-                    fog_light_color_uniform,
-                    fog_dark_color_uniform,
-                    lights_count_uniform,
-                    lights_uniform,
-                    explosions_count_uniform,
-                    explosions_uniform,
-                    has_dynamic_shadows_uniform,
-                    show_drop_shadow_uniform,
-                ],
-                textures: vec![HEIGHT_MAP_TEXTURE_NAME.to_owned()],
+                pipeline_params,
+                uniforms,
+                textures,
             },
         )
-        .expect("Error initialising voxel shaders");
-
-        Self { voxel_material }
     }
 
     /// sets the current OpenGL shader to render the world voxels
     pub fn set_voxel_material(&self, uniform_params: VoxelUniformParams) {
         let camera = uniform_params.camera;
+        let material = if uniform_params.x_ray {
+            &self.x_ray_material
+        } else {
+            &self.voxel_material
+        };
 
-        self.voxel_material
-            .set_texture(HEIGHT_MAP_TEXTURE_NAME, uniform_params.height_map);
-
-        self.voxel_material.set_uniform(
+        material.set_uniform(
             CAMERA_POSITION_UNIFORM,
             [camera.position.x, camera.position.y, camera.position.z],
         );
 
-        self.voxel_material.set_uniform(
+        material.set_uniform(
             CAMERA_TARGET_UNIFORM,
             [camera.target.x, camera.target.y, camera.target.z],
         );
 
         let (fog_near, fog_far) = Self::calulate_fog_distances(uniform_params.render_size);
-        self.voxel_material.set_uniform(FOG_NEAR_UNIFORM, fog_near);
-        self.voxel_material.set_uniform(FOG_FAR_UNIFORM, fog_far);
+        material.set_uniform(FOG_NEAR_UNIFORM, fog_near);
+        material.set_uniform(FOG_FAR_UNIFORM, fog_far);
 
-        self.voxel_material
-            .set_uniform(LIGHT_LEVEL_UNIFORM, uniform_params.light_level);
+        material.set_uniform(LIGHT_LEVEL_UNIFORM, uniform_params.light_level);
+        material.set_uniform(AMBIENT_COLOR_UNIFORM, uniform_params.ambient_color);
 
-        self.voxel_material.set_uniform(
-            FOG_BASE_COLOR_LIGHT_UNIFORM,
-            SKY_BRIGHT_COLOR.to_vec().xyz(),
-        );
+        material.set_uniform(FOG_COLOR_UNIFORM, uniform_params.fog_color);
+        let has_fog = if uniform_params.has_fog { TRUE } else { FALSE };
+        material.set_uniform(HAS_FOG_UNIFORM, has_fog);
 
-        self.voxel_material
-            .set_uniform(FOG_BASE_COLOR_DARK_UNIFORM, SKY_DARK_COLOR.to_vec().xyz());
+        let show_drop_shadow = if uniform_params.show_map { FALSE } else { TRUE };
+        material.set_uniform(SHOW_DROP_SHADOW_UNIFORM, show_drop_shadow);
 
-        let has_dynamic_shadows = if uniform_params.has_dynamic_lighting {
+        let smooth_lighting = if uniform_params.smooth_lighting {
             TRUE
         } else {
             FALSE
         };
-        self.voxel_material
-            .set_uniform(HAS_DYNAMIC_SHADOWS_UNIFORM, has_dynamic_shadows);
+        material.set_uniform(SMOOTH_LIGHTING_UNIFORM, smooth_lighting);
 
-        let show_drop_shadow = if uniform_params.show_map { FALSE } else { TRUE };
-        self.voxel_material
-            .set_uniform(SHOW_DROP_SHADOW_UNIFORM, show_drop_shadow);
+        let x_ray = if uniform_params.x_ray { TRUE } else { FALSE };
+        material.set_uniform(X_RAY_UNIFORM, x_ray);
 
-        self.set_lights(uniform_params.lights, camera);
-        self.set_explosions(uniform_params.explosions, camera);
+        let water_reflections = if uniform_params.water_reflections && !uniform_params.show_map {
+            TRUE
+        } else {
+            FALSE
+        };
+        material.set_uniform(WATER_REFLECTIONS_UNIFORM, water_reflections);
 
-        gl_use_material(&self.voxel_material);
+        if self.has_dynamic_lighting_support {
+            material.set_texture(HEIGHT_MAP_TEXTURE_NAME, uniform_params.height_map);
+            let has_dynamic_shadows = if uniform_params.has_dynamic_lighting {
+                TRUE
+            } else {
+                FALSE
+            };
+            material.set_uniform(HAS_DYNAMIC_SHADOWS_UNIFORM, has_dynamic_shadows);
+            Self::set_lights(
+                material,
+                uniform_params.lights,
+                uniform_params.creature_lights,
+                camera,
+            );
+            Self::set_explosions(material, uniform_params.explosions, camera);
+        }
+
+        gl_use_material(material);
     }
 
-    fn set_lights(&self, lights: &[InternalLocation], camera: &Camera3D) {
+    /// changes the camera position for the following draws,
+    /// used for meshes that are relative to a different origin
+    pub fn set_camera_position(&self, position: Vec3) {
+        for material in [&self.voxel_material, &self.x_ray_material] {
+            material.set_uniform(
+                CAMERA_POSITION_UNIFORM,
+                [position.x, position.y, position.z],
+            );
+        }
+    }
+
+    fn set_lights(
+        material: &Material,
+        lights: &[(InternalLocation, LightColor, f32)],
+        creature_lights: &[(Vec3, LightColor, f32)],
+        camera: &Camera3D,
+    ) {
         let mut lights_array: [Vec3; MAX_LIGHTS] = [Vec3::ZERO; MAX_LIGHTS];
-        let lights_count = lights.len().min(MAX_LIGHTS);
-        let lights_iter = lights
+        let mut light_colors_array: [Vec3; MAX_LIGHTS] = [Vec3::ZERO; MAX_LIGHTS];
+        let mut light_radii_array: [f32; MAX_LIGHTS] = [0.0; MAX_LIGHTS];
+        let lights_count = (lights.len() + creature_lights.len()).min(MAX_LIGHTS);
+        let voxel_lights = lights
             .iter()
-            .take(MAX_LIGHTS)
-            .map(|internal_location| {
+            .map(|(internal_location, light_color, radius)| {
                 let location: Location = (*internal_location).into();
-                vec3(
-                    location.x as f32 - camera.position.x,
-                    location.y as f32 - camera.position.y,
-                    location.z as f32 - camera.position.z,
+                let position = vec3(location.x as f32, location.y as f32, location.z as f32);
+                (position, *light_color, *radius)
+            });
+        let lights_iter = voxel_lights
+            .chain(creature_lights.iter().copied())
+            .take(MAX_LIGHTS)
+            .map(|(position, light_color, radius)| {
+                (
+                    position - camera.position,
+                    vec3(light_color.r, light_color.g, light_color.b),
+                    radius,
                 )
             })
             .enumerate();
 
-        for (i, light_position) in lights_iter {
+        for (i, (light_position, light_color, radius)) in lights_iter {
             lights_array[i] = light_position;
+            light_colors_array[i] = light_color;
+            light_radii_array[i] = radius;
         }
 
-        self.voxel_material
-            .set_uniform_array(LIGHTS_UNIFORM, &lights_array);
-        self.voxel_material
-            .set_uniform(LIGHTS_COUNT_UNIFORM, lights_count as i32);
+        material.set_uniform_array(LIGHTS_UNIFORM, &lights_array);
+        material.set_uniform_array(LIGHT_COLORS_UNIFORM, &light_colors_array);
+        material.set_uniform_array(LIGHT_RADII_UNIFORM, &light_radii_array);
+        material.set_uniform(LIGHTS_COUNT_UNIFORM, lights_count as i32);
     }
 
-    fn set_explosions(&self, explosions: Vec<Vec3>, camera: &Camera3D) {
+    fn set_explosions(material: &Material, explosions: Vec<Vec3>, camera: &Camera3D) {
         let mut explosions_array = [Vec3::ZERO; MAX_EXPLOSIONS];
         let explosions_count = explosions.len().min(MAX_EXPLOSIONS);
         let explosions_iter = explosions
@@ -212,10 +360,8 @@ impl VoxelShader {
             explosions_array[i] = explosion_position;
         }
 
-        self.voxel_material
-            .set_uniform_array(EXPLOSIONS_UNIFORM, &explosions_array);
-        self.voxel_material
-            .set_uniform(EXPLOSIONS_COUNT_UNIFORM, explosions_count as i32);
+        material.set_uniform_array(EXPLOSIONS_UNIFORM, &explosions_array);
+        material.set_uniform(EXPLOSIONS_COUNT_UNIFORM, explosions_count as i32);
     }
 
     fn calulate_fog_distances(render_size: u32) -> (f32, f32) {
@@ -225,3 +371,19 @@ impl VoxelShader {
         (fog_near, fog_far)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_static_lighting_define_follows_version() {
+        let source = VoxelShader::create_static_lighting_source(VOXEL_FRAGMENT_SHADER);
+
+        assert!(source.starts_with("#version 100\n#define STATIC_LIGHTING\n"));
+        assert_eq!(
+            source.len(),
+            VOXEL_FRAGMENT_SHADER.len() + STATIC_LIGHTING_DEFINE.len() + 1
+        );
+    }
+}
@@ -186,7 +186,7 @@ pub mod tests {
         map.increase_up_down_angle(10.0);
         let angle3 = map.up_down_angle;
 
-        assert!((angle3 - MAX_UP_DOWN_ANGLE).abs() < std::f32::EPSILON);
+        assert!((angle3 - MAX_UP_DOWN_ANGLE).abs() < f32::EPSILON);
     }
 
     #[test]
@@ -203,7 +203,7 @@ pub mod tests {
         map.decrease_up_down_angle(10.0);
         let angle3 = map.up_down_angle;
 
-        assert!((angle3 - MIN_UP_DOWN_ANGLE).abs() < std::f32::EPSILON);
+        assert!((angle3 - MIN_UP_DOWN_ANGLE).abs() < f32::EPSILON);
     }
 
     #[test]
@@ -222,7 +222,7 @@ pub mod tests {
         let angle3 = map.left_right_angle;
 
         assert!(angle3 < 0.5);
-        assert!(angle3 > std::f32::EPSILON);
+        assert!(angle3 > f32::EPSILON);
     }
 
     #[test]
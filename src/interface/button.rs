@@ -5,7 +5,7 @@ use macroquad::{
 };
 
 use crate::{
-    interface::text::draw_game_text,
+    interface::text::{draw_game_text, fit_font_size},
     model::user_settings::UserSettings,
     service::{asset_manager::AssetManager, sound_manager::SoundId},
 };
@@ -16,14 +16,14 @@ use super::{
 };
 
 const BACK_BUTTON_SIZE: f32 = 60.0;
-const BACK_BUTTON_FONT_SIZE: u16 = 45;
+const BACK_BUTTON_FONT_SIZE: f32 = SYMBOL_FONT_SIZE;
 const BACK_BUTTON_LOCATION: Vec2 = vec2(10.0, 10.0);
 
-/// draws a button and returns if it is pressed
+/// draws a button and returns if it is pressed, the text is shrunk to fit the button
 pub fn draw_button(
     rect: Rect,
     text: &str,
-    text_size: u16,
+    text_size: impl Into<f32>,
     asset_manager: &AssetManager,
     user_settings: &UserSettings,
 ) -> bool {
@@ -36,10 +36,11 @@ pub fn draw_button(
     };
 
     draw_rect_with_shadow(rect.x, rect.y, rect.w, rect.h, button_color);
+    let text_size = fit_font_size(text, rect.w - MARGIN * 2.0, text_size, &asset_manager.font);
     draw_game_text(
         text,
         rect.x + MARGIN,
-        rect.y + rect.h * 0.5 + text_size as f32 * 0.5,
+        rect.y + rect.h * 0.5 + text_size * 0.5,
         text_size,
         BLACK,
         &asset_manager.font,
@@ -0,0 +1,163 @@
+use macroquad::{math::Rect, shapes::draw_rectangle};
+
+use crate::{
+    interface::style::{BORDER_COLOR, BUTTON_COLOR},
+    service::input::ScrollDirection,
+};
+
+const SCROLL_BAR_WIDTH: f32 = 8.0;
+
+/// the size and spacing of the buttons in a grid
+#[derive(Debug, Clone, Copy)]
+pub struct ButtonGridStyle {
+    pub button_width: f32,
+    pub button_height: f32,
+    /// distance between the tops of two rows
+    pub row_height: f32,
+    pub column_gap: f32,
+    pub max_columns: usize,
+}
+
+/// lays out buttons in rows between a top and a bottom edge,
+/// scrolls by whole rows when they don't all fit
+#[derive(Debug, Clone, Copy)]
+pub struct ButtonGrid {
+    style: ButtonGridStyle,
+    columns: usize,
+    x: f32,
+    y: f32,
+    first_row: usize,
+    visible_rows: usize,
+    total_rows: usize,
+}
+impl ButtonGrid {
+    /// fits as many columns as the width allows up to the style maximum,
+    /// the first row is the scroll position kept by the menu
+    pub fn new(
+        style: ButtonGridStyle,
+        width: f32,
+        top: f32,
+        bottom: f32,
+        button_count: usize,
+        first_row: usize,
+    ) -> Self {
+        let columns = (((width + style.column_gap) / (style.button_width + style.column_gap))
+            as usize)
+            .clamp(1, style.max_columns.max(1));
+        let total_rows = button_count.div_ceil(columns);
+        let visible_rows =
+            (((bottom - top - style.button_height) / style.row_height).max(0.0) as usize + 1)
+                .min(total_rows.max(1));
+        let total_width =
+            style.button_width * columns as f32 + style.column_gap * (columns - 1) as f32;
+
+        let mut grid = Self {
+            style,
+            columns,
+            x: (width - total_width) * 0.5,
+            y: top,
+            first_row,
+            visible_rows,
+            total_rows,
+        };
+        grid.scroll(ScrollDirection::None);
+        grid
+    }
+
+    /// moves the visible rows by one, stays within the grid
+    pub fn scroll(&mut self, direction: ScrollDirection) {
+        let last_first_row = self.total_rows.saturating_sub(self.visible_rows);
+        self.first_row = match direction {
+            ScrollDirection::Up => self.first_row.saturating_sub(1),
+            ScrollDirection::Down => self.first_row + 1,
+            ScrollDirection::None => self.first_row,
+        }
+        .min(last_first_row);
+    }
+
+    pub fn get_first_row(&self) -> usize {
+        self.first_row
+    }
+
+    /// returns None if the button is scrolled out of view
+    pub fn get_button_rect(&self, index: usize) -> Option<Rect> {
+        let row = index / self.columns;
+        if !(self.first_row..self.first_row + self.visible_rows).contains(&row) {
+            return None;
+        }
+        let column = (index % self.columns) as f32;
+        let visible_row = (row - self.first_row) as f32;
+
+        Some(Rect {
+            x: self.x + column * (self.style.button_width + self.style.column_gap),
+            y: self.y + visible_row * self.style.row_height,
+            w: self.style.button_width,
+            h: self.style.button_height,
+        })
+    }
+
+    /// draws a scroll bar to the right of the rows if some are out of view
+    pub fn draw_scroll_bar(&self) {
+        if self.visible_rows >= self.total_rows {
+            return;
+        }
+        let x = self.x + (self.style.button_width + self.style.column_gap) * self.columns as f32;
+        let height =
+            self.style.row_height * (self.visible_rows - 1) as f32 + self.style.button_height;
+        let thumb_height = height * self.visible_rows as f32 / self.total_rows as f32;
+        let thumb_y = self.y + height * self.first_row as f32 / self.total_rows as f32;
+
+        draw_rectangle(x, self.y, SCROLL_BAR_WIDTH, height, BORDER_COLOR);
+        draw_rectangle(x, thumb_y, SCROLL_BAR_WIDTH, thumb_height, BUTTON_COLOR);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STYLE: ButtonGridStyle = ButtonGridStyle {
+        button_width: 100.0,
+        button_height: 50.0,
+        row_height: 60.0,
+        column_gap: 20.0,
+        max_columns: 2,
+    };
+
+    #[test]
+    fn test_fits_columns_in_width() {
+        let grid = ButtonGrid::new(STYLE, 1000.0, 0.0, 1000.0, 5, 0);
+        assert_eq!(grid.columns, 2);
+
+        let narrow_grid = ButtonGrid::new(STYLE, 150.0, 0.0, 1000.0, 5, 0);
+        assert_eq!(narrow_grid.columns, 1);
+        assert_eq!(narrow_grid.total_rows, 5);
+    }
+
+    #[test]
+    fn test_hides_rows_below_bottom() {
+        // rows start at 0, 60 and 120, the third ends past the bottom
+        let grid = ButtonGrid::new(STYLE, 1000.0, 0.0, 160.0, 10, 0);
+        assert_eq!(grid.visible_rows, 2);
+        assert!(grid.get_button_rect(3).is_some());
+        assert!(grid.get_button_rect(4).is_none());
+    }
+
+    #[test]
+    fn test_scroll_stays_within_rows() {
+        let mut grid = ButtonGrid::new(STYLE, 1000.0, 0.0, 160.0, 10, 0);
+        grid.scroll(ScrollDirection::Up);
+        assert_eq!(grid.get_first_row(), 0);
+
+        for _ in 0..10 {
+            grid.scroll(ScrollDirection::Down);
+        }
+        assert_eq!(grid.get_first_row(), 3);
+        assert!(grid.get_button_rect(0).is_none());
+        let last = grid.get_button_rect(9).unwrap();
+        assert_eq!(last.y, STYLE.row_height);
+
+        let restored = ButtonGrid::new(STYLE, 1000.0, 0.0, 1000.0, 10, 3);
+        assert_eq!(restored.get_first_row(), 0);
+    }
+}
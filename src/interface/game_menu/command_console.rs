@@ -0,0 +1,94 @@
+use std::collections::VecDeque;
+
+use macroquad::{
+    camera::set_default_camera,
+    input::{KeyCode, clear_input_queue, is_key_released},
+    miniquad::window::screen_size,
+    shapes::draw_rectangle,
+    text::Font,
+};
+
+use crate::interface::{
+    style::{CLEAR_SCREEN_COLOR, MARGIN, SMALL_FONT_SIZE, TEXT_COLOR},
+    text::draw_game_text,
+    text_input::TextInput,
+};
+
+const MAX_INPUT_LENGTH: usize = 40;
+const MAX_HISTORY_LINES: usize = 6;
+const CONSOLE_WIDTH: f32 = 700.0;
+const CONSOLE_Y_COEF: f32 = 0.75;
+const INPUT_HEIGHT: f32 = 44.0;
+const FONT_SIZE: u16 = SMALL_FONT_SIZE as u16;
+const LINE_HEIGHT: f32 = FONT_SIZE as f32 * 1.3;
+
+/// text console for entering in game commands
+pub struct CommandConsole {
+    input: TextInput,
+    history: VecDeque<String>,
+}
+impl CommandConsole {
+    pub fn new() -> Self {
+        Self {
+            input: TextInput::new(MAX_INPUT_LENGTH),
+            history: VecDeque::with_capacity(MAX_HISTORY_LINES),
+        }
+    }
+
+    /// clears the input and focuses it
+    pub fn open(&mut self) {
+        clear_input_queue();
+        self.input.set_text("".to_owned());
+        self.input.set_selected(true);
+    }
+
+    pub fn add_message(&mut self, message: String) {
+        if self.history.len() >= MAX_HISTORY_LINES {
+            self.history.pop_front();
+        }
+        self.history.push_back(message);
+    }
+
+    /// draws the console and returns the entered text when submitted
+    pub fn draw(&mut self, font: &Font) -> Option<String> {
+        set_default_camera();
+        let (width, height) = screen_size();
+        let console_width = CONSOLE_WIDTH.min(width - MARGIN * 2.0);
+        let x = (width - console_width) * 0.5;
+        let input_y = height * CONSOLE_Y_COEF;
+
+        self.draw_history(x, input_y, console_width, font);
+        self.input.input_text();
+        self.input
+            .draw(x, input_y, console_width, INPUT_HEIGHT, FONT_SIZE, font);
+
+        if !is_key_released(KeyCode::Enter) || self.input.get_text().trim().is_empty() {
+            return None;
+        }
+        let entered = self.input.get_text().to_owned();
+        self.add_message(format!("> {entered}"));
+        self.input.set_text("".to_owned());
+
+        Some(entered)
+    }
+
+    fn draw_history(&self, x: f32, input_y: f32, width: f32, font: &Font) {
+        if self.history.is_empty() {
+            return;
+        }
+        let history_height = LINE_HEIGHT * self.history.len() as f32 + MARGIN * 2.0;
+        let history_y = input_y - history_height - MARGIN;
+        draw_rectangle(x, history_y, width, history_height, CLEAR_SCREEN_COLOR);
+
+        for (index, line) in self.history.iter().enumerate() {
+            draw_game_text(
+                line,
+                x + MARGIN,
+                history_y + MARGIN + LINE_HEIGHT * (index as f32 + 0.8),
+                FONT_SIZE as f32,
+                TEXT_COLOR,
+                font,
+            );
+        }
+    }
+}
@@ -2,9 +2,9 @@ use std::{cell::RefCell, fmt::Write, rc::Rc};
 
 use macroquad::{
     camera::set_default_camera,
-    color::WHITE,
+    color::{Color, GRAY, RED, WHITE},
     input::{MouseButton, is_mouse_button_released, mouse_position},
-    math::vec2,
+    math::{Rect, vec2},
     miniquad::window::screen_size,
     text::Font,
     texture::{DrawTextureParams, Texture2D, draw_texture_ex},
@@ -13,20 +13,23 @@ use macroquad::{
 use crate::{
     graphics::screen_effects::darken_background,
     interface::{
+        button::draw_button,
         game_menu::game_menu_context::MenuSelection,
         style::{
-            BACKGROUND_COLOR, BUTTON_HOVER_COLOR, SECONDARY_TEXT_COLOR, SELECTED_COLOR, TEXT_COLOR,
+            BACKGROUND_COLOR, BUTTON_FONT_SIZE, BUTTON_HOVER_COLOR, MARGIN, SECONDARY_TEXT_COLOR,
+            SELECTED_COLOR, TEXT_COLOR,
         },
         text::{draw_centered_multiline_text, draw_game_text, get_text_width},
+        text_input::TextInput,
         util::{draw_item_name_box, draw_rect_with_shadow, is_point_in_rect},
     },
     model::{
-        inventory::{AvailableItems, Inventory, Item},
+        inventory::{AvailableItems, HotbarLocks, Inventory, Item},
         user_settings::UserSettings,
     },
     service::{
         asset_manager::AssetManager,
-        crafting::{CraftingRecipe, craft_recipe, find_craftable},
+        crafting::{CraftingRecipe, all_recipes, craft_recipe, find_craftable},
         input::{ScrollDirection, get_scroll_direction},
         sound_manager::SoundId,
     },
@@ -58,6 +61,7 @@ const TOP_MENU_Y_MARGIN_PX: f32 = 6.0;
 const MULTILINE_Y_OFFSET_OF_FONT: f32 = 1.5;
 const MULTILINE_FONT_SIZE_OF_MENU_SIZE: f32 = 0.1;
 const NO_CRAFTABLE_MULTILINE_TEXT: [&str; 2] = ["No items", "can be crafted"];
+const NO_MATCHES_MULTILINE_TEXT: [&str; 2] = ["No recipes", "match the search"];
 
 const PAGES_COUNTER_OFFSET_X: f32 = 3.0;
 const PAGES_COUNTER_FONT_SIZE: f32 = 0.07;
@@ -65,24 +69,138 @@ const PAGES_COUNTER_OFFSET_Y: f32 = 8.0;
 
 const BULK_CRAFT_COUNT: u32 = 5;
 
+const TAB_WIDTH: f32 = 200.0;
+const TAB_HEIGHT: f32 = 40.0;
+const TAB_GAP_PX: f32 = 6.0;
+/// background of recipe book cards that can't be crafted
+const UNAFFORDABLE_CARD_COLOR: Color = GRAY;
+/// owned count of inputs that there aren't enough of
+const MISSING_INPUT_COLOR: Color = RED;
+
+const SEARCH_WIDTH: f32 = 250.0;
+const SEARCH_MAX_LENGTH: usize = 20;
+const SEARCH_FONT_SIZE: u16 = 25;
+
 pub type CraftingMenuHandle = Rc<RefCell<CraftingMenuContext>>;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CraftingTab {
+    /// only the recipes that can be crafted now
+    Craftable,
+    /// every recipe, the unaffordable ones are greyed out
+    RecipeBook,
+}
+impl CraftingTab {
+    const ALL: [Self; 2] = [Self::Craftable, Self::RecipeBook];
+
+    fn get_name(self) -> &'static str {
+        match self {
+            CraftingTab::Craftable => "Can craft",
+            CraftingTab::RecipeBook => "Recipe book",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct CraftingMenuContext {
     available_recipes: Vec<(CraftingRecipe, u32)>,
     all_items: AvailableItems,
     current_page: usize,
+    hotbar_locks: HotbarLocks,
+    tab: CraftingTab,
+    /// filters the recipes of both tabs by the voxel names
+    search_input: TextInput,
 }
 impl CraftingMenuContext {
-    pub fn new(inventory: &Inventory) -> CraftingMenuHandle {
-        let all_items = inventory.create_all_items_map();
+    pub fn new(inventory: &Inventory, hotbar_locks: HotbarLocks) -> CraftingMenuHandle {
+        let all_items = inventory.create_unlocked_items_map(hotbar_locks);
         Rc::new(RefCell::new(Self {
             available_recipes: find_craftable(&all_items),
             current_page: 0,
             all_items,
+            hotbar_locks,
+            tab: CraftingTab::Craftable,
+            search_input: TextInput::new(SEARCH_MAX_LENGTH),
         }))
     }
 
+    fn find_tab_recipes(&self) -> Vec<(CraftingRecipe, u32)> {
+        let recipes = match self.tab {
+            CraftingTab::Craftable => find_craftable(&self.all_items),
+            CraftingTab::RecipeBook => all_recipes(&self.all_items),
+        };
+        let search = self.search_input.get_text();
+
+        recipes
+            .into_iter()
+            .filter(|(recipe, _)| recipe.matches_search(search))
+            .collect()
+    }
+
+    /// draws the search input next to the tabs and filters the recipes when the text changes
+    fn handle_search(&mut self, font: &Font, menu_x: f32, menu_y: f32) {
+        let x = menu_x + CraftingTab::ALL.len() as f32 * (TAB_WIDTH + TAB_GAP_PX);
+        let y = menu_y - TAB_HEIGHT - TAB_GAP_PX;
+        let previous_search = self.search_input.get_text().to_owned();
+
+        let _set_selected = self
+            .search_input
+            .input_selection(x, y, SEARCH_WIDTH, TAB_HEIGHT);
+        self.search_input.input_text();
+        self.search_input
+            .draw(x, y, SEARCH_WIDTH, TAB_HEIGHT, SEARCH_FONT_SIZE, font);
+        if self.search_input.get_text().is_empty() {
+            draw_game_text(
+                "Search",
+                x + MARGIN * 2.0,
+                y + (TAB_HEIGHT + SEARCH_FONT_SIZE as f32) * 0.5,
+                SEARCH_FONT_SIZE,
+                UNAFFORDABLE_CARD_COLOR,
+                font,
+            );
+        }
+
+        if self.search_input.get_text() != previous_search {
+            self.available_recipes = self.find_tab_recipes();
+            self.current_page = 0;
+        }
+    }
+
+    /// draws the tab buttons above the menu and switches to the pressed one
+    fn handle_tabs(
+        &mut self,
+        asset_manager: &AssetManager,
+        user_settings: &UserSettings,
+        menu_x: f32,
+        menu_y: f32,
+    ) {
+        let tab_y = menu_y - TAB_HEIGHT - TAB_GAP_PX;
+        for (index, tab) in CraftingTab::ALL.into_iter().enumerate() {
+            let text = if tab == self.tab {
+                format!("[{}]", tab.get_name())
+            } else {
+                tab.get_name().to_owned()
+            };
+            let is_pressed = draw_button(
+                Rect {
+                    x: menu_x + index as f32 * (TAB_WIDTH + TAB_GAP_PX),
+                    y: tab_y,
+                    w: TAB_WIDTH,
+                    h: TAB_HEIGHT,
+                },
+                &text,
+                BUTTON_FONT_SIZE,
+                asset_manager,
+                user_settings,
+            );
+            if is_pressed && tab != self.tab {
+                self.tab = tab;
+                self.available_recipes = self.find_tab_recipes();
+                self.current_page = 0;
+            }
+        }
+    }
+
     pub fn draw_menu(
         &mut self,
         inventory: &mut Inventory,
@@ -97,6 +215,8 @@ impl CraftingMenuContext {
         let menu_x = (width - menu_width) / 2.0;
         let menu_y = height * CARD_Y_COEF - TOP_MENU_Y_MARGIN_PX;
         draw_rect_with_shadow(menu_x, menu_y, menu_width, menu_height, BACKGROUND_COLOR);
+        self.handle_tabs(asset_manager, user_settings, menu_x, menu_y);
+        self.handle_search(&asset_manager.font, menu_x, menu_y);
         self.handle_pages(menu_x, menu_y, menu_height, &asset_manager.font);
 
         let to_craft = self.handle_crafting_cards(
@@ -117,10 +237,10 @@ impl CraftingMenuContext {
 
     fn craft_item(&mut self, recipe: CraftingRecipe, inventory: &mut Inventory, count: u32) {
         debug_assert!(count <= BULK_CRAFT_COUNT);
-        craft_recipe(&recipe, inventory, count as u8);
+        craft_recipe(&recipe, inventory, self.hotbar_locks, count as u8);
 
-        self.all_items = inventory.create_all_items_map();
-        self.available_recipes = find_craftable(&self.all_items);
+        self.all_items = inventory.create_unlocked_items_map(self.hotbar_locks);
+        self.available_recipes = self.find_tab_recipes();
         self.current_page = self.current_page.min(self.calculate_max_page());
     }
 
@@ -152,8 +272,13 @@ impl CraftingMenuContext {
         if self.available_recipes.is_empty() {
             let font = menu_height.min(menu_width) * MULTILINE_FONT_SIZE_OF_MENU_SIZE;
             let text_y = menu_y + font * MULTILINE_Y_OFFSET_OF_FONT;
+            let text = if self.search_input.get_text().is_empty() {
+                &NO_CRAFTABLE_MULTILINE_TEXT
+            } else {
+                &NO_MATCHES_MULTILINE_TEXT
+            };
             draw_centered_multiline_text(
-                &NO_CRAFTABLE_MULTILINE_TEXT,
+                text,
                 text_y,
                 width,
                 font,
@@ -190,10 +315,12 @@ impl CraftingMenuContext {
         let card_x = (width - card_width) / 2.0;
         let card_y = height * CARD_Y_COEF + (index as f32) * (CARD_OFFSET_PX + card_height);
 
-        let is_hovered =
-            is_point_in_rect(card_x, card_y, card_width, card_height, mouse_x, mouse_y);
+        let is_hovered = max_craftable_count > 0
+            && is_point_in_rect(card_x, card_y, card_width, card_height, mouse_x, mouse_y);
         let background_color = if is_hovered {
             BUTTON_HOVER_COLOR
+        } else if max_craftable_count == 0 {
+            UNAFFORDABLE_CARD_COLOR
         } else {
             WHITE
         };
@@ -381,13 +508,25 @@ impl CraftingMenuContext {
             );
 
             buffer.clear();
-            write!(buffer, " (have {})", available).expect(BUFFER_ERROR);
+            let required = item.count as u32;
+            let have_color = if available < required {
+                write!(
+                    buffer,
+                    " (have {available}, need {} more)",
+                    required - available
+                )
+                .expect(BUFFER_ERROR);
+                MISSING_INPUT_COLOR
+            } else {
+                write!(buffer, " (have {available})").expect(BUFFER_ERROR);
+                SECONDARY_TEXT_COLOR
+            };
             draw_game_text(
                 buffer,
                 text_start_x + count_offset,
                 text_start_y,
                 font_size,
-                SECONDARY_TEXT_COLOR,
+                have_color,
                 font,
             );
         });
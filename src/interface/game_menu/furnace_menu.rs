@@ -0,0 +1,277 @@
+use std::fmt::Write;
+
+use macroquad::{
+    camera::set_default_camera,
+    color::{Color, GOLD, WHITE},
+    input::{MouseButton, is_mouse_button_released, mouse_position},
+    math::vec2,
+    miniquad::window::screen_size,
+    shapes::draw_rectangle,
+    text::Font,
+    texture::{DrawTextureParams, draw_texture_ex},
+};
+
+use crate::{
+    graphics::screen_effects::darken_background,
+    interface::{
+        game_menu::game_menu_context::MenuSelection,
+        style::{BACKGROUND_COLOR, SECONDARY_TEXT_COLOR, SHADOW_COLOR, TEXT_COLOR},
+        text::draw_game_text,
+        util::{draw_item_name_box, draw_rect_with_shadow, is_point_in_rect},
+    },
+    model::{player_info::PlayerInfo, user_settings::UserSettings, voxel::Voxel},
+    service::{
+        asset_manager::AssetManager,
+        smelting::{Furnace, FurnaceSlot, get_fuel_voxels, get_smeltable_voxels},
+        sound_manager::SoundId,
+    },
+    utils::use_str_buffer,
+};
+
+const BUFFER_ERROR: &str = "error writing to buffer in furnace menu";
+
+const SLOT_SIZE: f32 = 0.08;
+const INNER_SLOT_MULTIPLIER: f32 = 0.8;
+const MENU_WIDTH_OF_SLOT: f32 = 8.5;
+const MENU_HEIGHT_OF_SLOT: f32 = 7.0;
+const LABEL_FONT_SIZE_OF_SLOT: f32 = 0.4;
+const COUNT_FONT_SIZE_OF_SLOT: f32 = 0.4;
+const TITLE_FONT_SIZE_OF_SLOT: f32 = 0.6;
+const PROGRESS_COLOR: Color = GOLD;
+
+/// draws the furnace menu, clicking a furnace slot moves its items back to the inventory
+/// and clicking a listed voxel moves it from the inventory into the input or fuel slot
+pub fn draw_furnace_menu(
+    asset_manager: &AssetManager,
+    user_settings: &UserSettings,
+    player_info: &mut PlayerInfo,
+    furnace: &mut Furnace,
+) -> MenuSelection {
+    set_default_camera();
+    let (width, height) = screen_size();
+    darken_background(width, height);
+
+    let slot_size = SLOT_SIZE * width.min(height);
+    let menu_width = MENU_WIDTH_OF_SLOT * slot_size;
+    let menu_height = MENU_HEIGHT_OF_SLOT * slot_size;
+    let menu_x = (width - menu_width) * 0.5;
+    let menu_y = (height - menu_height) * 0.5;
+    draw_rect_with_shadow(menu_x, menu_y, menu_width, menu_height, BACKGROUND_COLOR);
+    draw_game_text(
+        "Furnace",
+        menu_x + slot_size * 0.5,
+        menu_y + slot_size * 0.8,
+        slot_size * TITLE_FONT_SIZE_OF_SLOT,
+        TEXT_COLOR,
+        &asset_manager.font,
+    );
+
+    let slots_y = menu_y + slot_size * 1.6;
+    let clicked_slot = draw_furnace_slots(asset_manager, furnace, slot_size, menu_x, slots_y);
+    draw_progress(
+        furnace,
+        slot_size,
+        menu_x + slot_size * 3.5,
+        slots_y,
+        &asset_manager.font,
+    );
+
+    let available_items = player_info
+        .inventory
+        .create_unlocked_items_map(player_info.hotbar_locks);
+    let list_x = menu_x + slot_size * 0.5;
+    let smeltable_y = slots_y + slot_size * 2.0;
+    let fuel_y = smeltable_y + slot_size * 2.0;
+    let clicked_smeltable = draw_voxel_list(
+        asset_manager,
+        "Smelt:",
+        get_smeltable_voxels().map(|voxel| (voxel, available_items.get(voxel))),
+        slot_size,
+        list_x,
+        smeltable_y,
+    );
+    let clicked_fuel = draw_voxel_list(
+        asset_manager,
+        "Fuel:",
+        get_fuel_voxels().map(|voxel| (voxel, available_items.get(voxel))),
+        slot_size,
+        list_x,
+        fuel_y,
+    );
+
+    let has_clicked =
+        clicked_slot.is_some() || clicked_smeltable.is_some() || clicked_fuel.is_some();
+    if let Some(slot) = clicked_slot {
+        furnace.empty_slot(slot, &mut player_info.inventory);
+    } else if let Some(voxel) = clicked_smeltable {
+        let hotbar_locks = player_info.hotbar_locks;
+        furnace.fill_slot(
+            FurnaceSlot::Input,
+            voxel,
+            &mut player_info.inventory,
+            hotbar_locks,
+        );
+    } else if let Some(voxel) = clicked_fuel {
+        let hotbar_locks = player_info.hotbar_locks;
+        furnace.fill_slot(
+            FurnaceSlot::Fuel,
+            voxel,
+            &mut player_info.inventory,
+            hotbar_locks,
+        );
+    }
+    if has_clicked {
+        asset_manager
+            .sound_manager
+            .play_sound(SoundId::Click, user_settings);
+    }
+
+    MenuSelection::None
+}
+
+/// draws the input, fuel and output slots and returns the clicked one
+fn draw_furnace_slots(
+    asset_manager: &AssetManager,
+    furnace: &Furnace,
+    slot_size: f32,
+    menu_x: f32,
+    y: f32,
+) -> Option<FurnaceSlot> {
+    let slots = [
+        (FurnaceSlot::Input, "Input", menu_x + slot_size * 0.5),
+        (FurnaceSlot::Fuel, "Fuel", menu_x + slot_size * 2.0),
+        (FurnaceSlot::Output, "Output", menu_x + slot_size * 7.0),
+    ];
+
+    let mut clicked = None;
+    for (slot, label, x) in slots {
+        draw_game_text(
+            label,
+            x,
+            y - slot_size * 0.1,
+            slot_size * LABEL_FONT_SIZE_OF_SLOT,
+            TEXT_COLOR,
+            &asset_manager.font,
+        );
+        let item = furnace
+            .get_slot(slot)
+            .map(|item| (item.voxel, item.count as u32));
+        if draw_slot(asset_manager, item, slot_size, x, y) {
+            clicked = Some(slot);
+        }
+    }
+
+    clicked
+}
+
+/// draws the progress of the smelted item between the fuel and output slots
+fn draw_progress(furnace: &Furnace, slot_size: f32, x: f32, y: f32, font: &Font) {
+    let bar_width = slot_size * 3.0;
+    let bar_height = slot_size * 0.3;
+    let bar_y = y + (slot_size - bar_height) * 0.5;
+    draw_rectangle(x, bar_y, bar_width, bar_height, SHADOW_COLOR);
+    draw_rectangle(
+        x,
+        bar_y,
+        bar_width * furnace.get_progress(),
+        bar_height,
+        PROGRESS_COLOR,
+    );
+
+    let text = if furnace.is_burning() {
+        "Burning"
+    } else {
+        "No fuel burning"
+    };
+    draw_game_text(
+        text,
+        x,
+        bar_y + bar_height + slot_size * 0.4,
+        slot_size * LABEL_FONT_SIZE_OF_SLOT,
+        SECONDARY_TEXT_COLOR,
+        font,
+    );
+}
+
+/// draws the voxels in the inventory that can be used and returns the clicked one
+fn draw_voxel_list(
+    asset_manager: &AssetManager,
+    label: &str,
+    voxels: impl Iterator<Item = (Voxel, u32)>,
+    slot_size: f32,
+    x: f32,
+    y: f32,
+) -> Option<Voxel> {
+    draw_game_text(
+        label,
+        x,
+        y - slot_size * 0.1,
+        slot_size * LABEL_FONT_SIZE_OF_SLOT,
+        TEXT_COLOR,
+        &asset_manager.font,
+    );
+
+    let mut clicked = None;
+    for (index, (voxel, count)) in voxels.filter(|(_, count)| *count > 0).enumerate() {
+        let slot_x = x + index as f32 * slot_size;
+        if draw_slot(asset_manager, Some((voxel, count)), slot_size, slot_x, y) {
+            clicked = Some(voxel);
+        }
+    }
+
+    clicked
+}
+
+/// draws a slot with the voxel and count and returns true if it was clicked
+fn draw_slot(
+    asset_manager: &AssetManager,
+    item: Option<(Voxel, u32)>,
+    slot_size: f32,
+    x: f32,
+    y: f32,
+) -> bool {
+    let inner_size = slot_size * INNER_SLOT_MULTIPLIER;
+    draw_rectangle(x, y, inner_size, inner_size, SHADOW_COLOR);
+    let Some((voxel, count)) = item else {
+        return false;
+    };
+
+    let texture = asset_manager.texture_manager.get_icon(voxel);
+    draw_texture_ex(
+        &texture,
+        x,
+        y,
+        WHITE,
+        DrawTextureParams {
+            dest_size: Some(vec2(inner_size, inner_size)),
+            ..Default::default()
+        },
+    );
+    let font_size = slot_size * COUNT_FONT_SIZE_OF_SLOT;
+    use_str_buffer(|buffer| {
+        write!(buffer, "{count}").expect(BUFFER_ERROR);
+        draw_game_text(
+            buffer,
+            x,
+            y + font_size * 1.5,
+            font_size,
+            TEXT_COLOR,
+            &asset_manager.font,
+        );
+    });
+
+    let (mouse_x, mouse_y) = mouse_position();
+    if !is_point_in_rect(x, y, inner_size, inner_size, mouse_x, mouse_y) {
+        return false;
+    }
+    draw_item_name_box(
+        mouse_x,
+        mouse_y,
+        voxel.display_name(),
+        count,
+        font_size,
+        &asset_manager.font,
+    );
+
+    is_mouse_button_released(MouseButton::Left)
+}
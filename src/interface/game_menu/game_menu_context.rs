@@ -12,18 +12,21 @@ use crate::{
     interface::{
         button::draw_button,
         game_menu::crafting_menu::CraftingMenuHandle,
-        style::{BACKGROUND_COLOR, BUTTON_COLOR},
+        style::{BACKGROUND_COLOR, BUTTON_COLOR, BUTTON_FONT_SIZE, SYMBOL_FONT_SIZE},
         text::draw_game_text,
     },
-    model::{inventory::Item, user_settings::UserSettings},
+    model::{inventory::Item, location::Location, user_settings::UserSettings},
     service::asset_manager::AssetManager,
 };
 
 const MENU_BOX_WIDTH: f32 = 400.0;
-const MENU_BOX_HEIGHT: f32 = 400.0;
+const MENU_BOX_HEIGHT: f32 = 490.0;
+const OPTIONS_MENU_BOX_HEIGHT: f32 = 570.0;
+const CONFIRM_DISCARD_MENU_BOX_HEIGHT: f32 = 310.0;
+const CONFIRM_DISCARD_TEXT: [&str; 2] = ["Quit without saving?", "Unsaved changes are lost"];
 const BUTTON_WIDTH: f32 = 250.0;
 const BUTTON_HEIGHT: f32 = 60.0;
-const BUTTON_TEXT_SIZE: u16 = 30;
+const BUTTON_TEXT_SIZE: f32 = BUTTON_FONT_SIZE;
 
 #[derive(Debug, Clone, Copy)]
 pub enum MenuSelection {
@@ -32,7 +35,12 @@ pub enum MenuSelection {
     ToWorldSelection,
     ToOptions,
     ToMainMenu,
+    ReplayTutorials,
     Exit,
+    /// asks to confirm quitting without saving
+    ToConfirmDiscard,
+    /// quits to the world selection without saving the world
+    DiscardAndQuit,
 }
 
 #[derive(Debug, Clone)]
@@ -40,10 +48,15 @@ pub enum MenuState {
     Hidden,
     Main,
     Options,
+    ConfirmDiscard,
     ItemSelection {
         currently_selected_item: Option<Item>,
+        is_trash_armed: bool,
     },
     Crafting(CraftingMenuHandle),
+    /// the menu of the placed furnace at the location
+    Furnace(Location),
+    Console,
 }
 impl MenuState {
     /// returns true if a menu is being displayed
@@ -59,7 +72,7 @@ pub fn draw_main_menu(asset_manager: &AssetManager, user_settings: &UserSettings
     darken_background(width, height);
 
     let (menu_x, menu_y) = calculate_menu_position(width, height);
-    draw_menu_background(menu_x, menu_y);
+    draw_menu_background(menu_x, menu_y, MENU_BOX_HEIGHT);
     let button_x = menu_x + (MENU_BOX_WIDTH - BUTTON_WIDTH) * 0.5;
     let button_y_start = menu_y + 30.0;
 
@@ -99,13 +112,25 @@ pub fn draw_main_menu(asset_manager: &AssetManager, user_settings: &UserSettings
         asset_manager,
         user_settings,
     );
-    let is_exit = draw_button(
+    let is_quit_without_saving = draw_button(
         Rect {
             x: button_x,
             y: button_y_start + BUTTON_HEIGHT * 4.5,
             w: BUTTON_WIDTH,
             h: BUTTON_HEIGHT,
         },
+        "Quit without saving",
+        BUTTON_TEXT_SIZE,
+        asset_manager,
+        user_settings,
+    );
+    let is_exit = draw_button(
+        Rect {
+            x: button_x,
+            y: button_y_start + BUTTON_HEIGHT * 6.0,
+            w: BUTTON_WIDTH,
+            h: BUTTON_HEIGHT,
+        },
         "Exit game",
         BUTTON_TEXT_SIZE,
         asset_manager,
@@ -114,6 +139,8 @@ pub fn draw_main_menu(asset_manager: &AssetManager, user_settings: &UserSettings
 
     if is_exit {
         MenuSelection::Exit
+    } else if is_quit_without_saving {
+        MenuSelection::ToConfirmDiscard
     } else if is_back_to_game {
         MenuSelection::BackToGame
     } else if is_to_world_selection {
@@ -125,8 +152,66 @@ pub fn draw_main_menu(asset_manager: &AssetManager, user_settings: &UserSettings
     }
 }
 
+/// draws the confirmation for quitting without saving
+pub fn draw_confirm_discard_menu(
+    asset_manager: &AssetManager,
+    user_settings: &UserSettings,
+) -> MenuSelection {
+    set_default_camera();
+    let (width, height) = screen_size();
+    darken_background(width, height);
+
+    let (menu_x, menu_y) = calculate_menu_position(width, height);
+    draw_menu_background(menu_x, menu_y, CONFIRM_DISCARD_MENU_BOX_HEIGHT);
+    let button_x = menu_x + (MENU_BOX_WIDTH - BUTTON_WIDTH) * 0.5;
+    let contents_y = menu_y + 30.0;
+
+    for (index, line) in CONFIRM_DISCARD_TEXT.iter().enumerate() {
+        draw_game_text(
+            line,
+            menu_x + 20.0,
+            contents_y + BUTTON_TEXT_SIZE * (index as f32 + 1.0),
+            BUTTON_TEXT_SIZE,
+            BUTTON_COLOR,
+            &asset_manager.font,
+        );
+    }
+    let is_discard = draw_button(
+        Rect {
+            x: button_x,
+            y: contents_y + BUTTON_HEIGHT * 1.5,
+            w: BUTTON_WIDTH,
+            h: BUTTON_HEIGHT,
+        },
+        "Discard and quit",
+        BUTTON_TEXT_SIZE,
+        asset_manager,
+        user_settings,
+    );
+    let is_cancel = draw_button(
+        Rect {
+            x: button_x,
+            y: contents_y + BUTTON_HEIGHT * 3.0,
+            w: BUTTON_WIDTH,
+            h: BUTTON_HEIGHT,
+        },
+        "Cancel",
+        BUTTON_TEXT_SIZE,
+        asset_manager,
+        user_settings,
+    );
+
+    if is_discard {
+        MenuSelection::DiscardAndQuit
+    } else if is_cancel {
+        MenuSelection::ToMainMenu
+    } else {
+        MenuSelection::None
+    }
+}
+
 /// draws the in game options menu
-/// callback forces blocking area mesh generation
+/// callback forces blocking area mesh generation after a render setting changes
 pub fn draw_options_menu<F: FnMut(&UserSettings)>(
     asset_manager: &AssetManager,
     user_settings: &mut UserSettings,
@@ -137,7 +222,7 @@ pub fn draw_options_menu<F: FnMut(&UserSettings)>(
     darken_background(width, height);
 
     let (menu_x, menu_y) = calculate_menu_position(width, height);
-    draw_menu_background(menu_x, menu_y);
+    draw_menu_background(menu_x, menu_y, OPTIONS_MENU_BOX_HEIGHT);
     let contents_x = menu_x + (MENU_BOX_WIDTH - BUTTON_WIDTH) * 0.5;
     let contents_y = menu_y + 30.0;
 
@@ -155,7 +240,7 @@ pub fn draw_options_menu<F: FnMut(&UserSettings)>(
             h: render_distance_button_height,
         },
         "-",
-        40,
+        SYMBOL_FONT_SIZE,
         asset_manager,
         user_settings,
     );
@@ -163,7 +248,7 @@ pub fn draw_options_menu<F: FnMut(&UserSettings)>(
         &format!("View distance {}", user_settings.get_render_distance()),
         render_distance_x + render_distance_button_width + 10.0,
         contents_y + render_distance_button_height * 0.8,
-        28.0,
+        BUTTON_FONT_SIZE,
         BUTTON_COLOR,
         &asset_manager.font,
     );
@@ -175,7 +260,7 @@ pub fn draw_options_menu<F: FnMut(&UserSettings)>(
             h: render_distance_button_height,
         },
         "+",
-        40,
+        SYMBOL_FONT_SIZE,
         asset_manager,
         user_settings,
     );
@@ -183,6 +268,10 @@ pub fn draw_options_menu<F: FnMut(&UserSettings)>(
         draw_toggle_sound_button(asset_manager, user_settings, contents_x, contents_y);
     let toggle_fullscreen =
         draw_toggle_fullscreen_button(asset_manager, user_settings, contents_x, contents_y);
+    let toggle_smooth_lighting =
+        draw_toggle_smooth_lighting_button(asset_manager, user_settings, contents_x, contents_y);
+    let should_replay_tutorials =
+        draw_replay_tutorials_button(asset_manager, user_settings, contents_x, contents_y);
     let should_go_back = draw_go_back_button(asset_manager, user_settings, contents_x, contents_y);
 
     if toggle_fullscreen {
@@ -191,9 +280,10 @@ pub fn draw_options_menu<F: FnMut(&UserSettings)>(
     }
     if toggle_sounds {
         user_settings.has_sound = !user_settings.has_sound;
-        asset_manager
-            .sound_manager
-            .start_or_stop_music(user_settings);
+    }
+    if toggle_smooth_lighting {
+        user_settings.smooth_lighting = !user_settings.smooth_lighting;
+        change_render_callback(user_settings);
     }
     if increase_render_distance {
         let _increased = user_settings.increase_render_distance();
@@ -206,6 +296,8 @@ pub fn draw_options_menu<F: FnMut(&UserSettings)>(
 
     if should_go_back {
         MenuSelection::ToMainMenu
+    } else if should_replay_tutorials {
+        MenuSelection::ReplayTutorials
     } else {
         MenuSelection::None
     }
@@ -262,7 +354,7 @@ fn draw_toggle_fullscreen_button(
 }
 
 /// returns true if pressed
-fn draw_go_back_button(
+fn draw_toggle_smooth_lighting_button(
     asset_manager: &AssetManager,
     user_settings: &mut UserSettings,
     contents_x: f32,
@@ -275,6 +367,52 @@ fn draw_go_back_button(
             w: BUTTON_WIDTH,
             h: BUTTON_HEIGHT,
         },
+        if user_settings.smooth_lighting {
+            "Lighting:Smooth"
+        } else {
+            "Lighting:Blocky"
+        },
+        BUTTON_TEXT_SIZE,
+        asset_manager,
+        user_settings,
+    )
+}
+
+/// returns true if pressed
+fn draw_replay_tutorials_button(
+    asset_manager: &AssetManager,
+    user_settings: &mut UserSettings,
+    contents_x: f32,
+    contents_y: f32,
+) -> bool {
+    draw_button(
+        Rect {
+            x: contents_x,
+            y: contents_y + BUTTON_HEIGHT * 6.0,
+            w: BUTTON_WIDTH,
+            h: BUTTON_HEIGHT,
+        },
+        "Replay tutorials",
+        BUTTON_TEXT_SIZE,
+        asset_manager,
+        user_settings,
+    )
+}
+
+/// returns true if pressed
+fn draw_go_back_button(
+    asset_manager: &AssetManager,
+    user_settings: &mut UserSettings,
+    contents_x: f32,
+    contents_y: f32,
+) -> bool {
+    draw_button(
+        Rect {
+            x: contents_x,
+            y: contents_y + BUTTON_HEIGHT * 7.5,
+            w: BUTTON_WIDTH,
+            h: BUTTON_HEIGHT,
+        },
         "Back",
         BUTTON_TEXT_SIZE,
         asset_manager,
@@ -288,20 +426,20 @@ fn calculate_menu_position(width: f32, height: f32) -> (f32, f32) {
     (menu_x, menu_y)
 }
 
-fn draw_menu_background(menu_x: f32, menu_y: f32) {
+fn draw_menu_background(menu_x: f32, menu_y: f32, menu_height: f32) {
     draw_rectangle(
         menu_x - 3.0,
         menu_y + 3.0,
         MENU_BOX_WIDTH,
-        MENU_BOX_HEIGHT,
+        menu_height,
         Color::from_rgba(0, 0, 0, 150),
     );
     draw_rectangle(
         menu_x,
         menu_y,
         MENU_BOX_WIDTH,
-        MENU_BOX_HEIGHT,
+        menu_height,
         BACKGROUND_COLOR,
     );
-    draw_rectangle_lines(menu_x, menu_y, MENU_BOX_WIDTH, MENU_BOX_HEIGHT, 3.0, BLACK);
+    draw_rectangle_lines(menu_x, menu_y, MENU_BOX_WIDTH, menu_height, 3.0, BLACK);
 }
@@ -1,3 +1,5 @@
+pub mod command_console;
 pub mod crafting_menu;
+pub mod furnace_menu;
 pub mod game_menu_context;
 pub mod voxel_selection_menu;
@@ -1,11 +1,11 @@
 use std::fmt::Write;
 
 use macroquad::{
-    color::WHITE,
+    color::{Color, GOLD, RED, WHITE},
     input::{MouseButton, is_mouse_button_released, mouse_position},
     math::vec2,
     miniquad::window::screen_size,
-    shapes::draw_rectangle,
+    shapes::{draw_line, draw_rectangle, draw_rectangle_lines},
     text::Font,
     texture::{DrawTextureParams, Texture2D, draw_texture_ex},
 };
@@ -14,16 +14,20 @@ use crate::{
     graphics::screen_effects::darken_background,
     interface::{
         game_menu::game_menu_context::MenuSelection,
-        style::{BACKGROUND_COLOR, SHADOW_COLOR, TEXT_COLOR},
-        text::draw_game_text,
+        style::{BACKGROUND_COLOR, SECONDARY_TEXT_COLOR, SHADOW_COLOR, TEXT_COLOR},
+        text::{draw_game_text, get_text_width},
         util::{draw_item_name_box, draw_rect_with_shadow},
     },
     model::{
         inventory::{Inventory, Item, MAX_ITEMS_PER_SLOT},
+        key_bindings::KeyBindings,
         player_info::PlayerInfo,
         voxel::Voxel,
     },
-    service::{asset_manager::AssetManager, input::exit_focus},
+    service::{
+        asset_manager::AssetManager,
+        input::{exit_focus, is_lock_modifier_down, is_quick_transfer_modifier_down},
+    },
     utils::use_str_buffer,
 };
 
@@ -34,6 +38,10 @@ const VOXELS_IN_ROW: usize = Inventory::SELECTED_SIZE;
 const VOXELS_IN_COLUMN: usize = Inventory::INVENTORY_SIZE / VOXELS_IN_ROW;
 const SELECTED_VOXELS_OFFSET: f32 = 0.6;
 const BASE_COUNT_FONT_SIZE: f32 = 0.5;
+const LOCK_ICON_SIZE: f32 = 0.3;
+const LOCK_COLOR: Color = GOLD;
+const TRASH_COLOR: Color = RED;
+const ARMED_TRASH_COLOR: Color = Color::new(0.6, 0.1, 0.1, 1.0);
 
 enum ItemSource {
     Inventory,
@@ -71,13 +79,32 @@ impl HoveredItem {
             ItemSource::Selection => inventory.selected[self.index],
         }
     }
+
+    /// moves the whole stack to the hotbar or back to the inventory grid
+    fn quick_transfer(&self, player_info: &mut PlayerInfo) {
+        let is_from_hotbar = matches!(self.source, ItemSource::Selection);
+        player_info
+            .inventory
+            .quick_transfer(is_from_hotbar, self.index, player_info.hotbar_locks);
+    }
+
+    fn get_hotbar_index(&self) -> Option<usize> {
+        match self.source {
+            ItemSource::Inventory => None,
+            ItemSource::Selection => Some(self.index),
+        }
+    }
 }
 
-/// returns the new menu state and voxel selection
+/// returns the new menu state and voxel selection,
+/// `is_trash_armed` is set after the first click on the trash slot and the held item
+/// is deleted on the second
 pub fn draw_voxel_selection_menu(
     asset_manager: &AssetManager,
     player_info: &mut PlayerInfo,
     mut selected: Option<Item>,
+    is_trash_armed: &mut bool,
+    key_bindings: &KeyBindings,
 ) -> (Option<Item>, MenuSelection) {
     debug_assert!(selected.is_none() || selected.unwrap().voxel != Voxel::None);
     let (width, height) = screen_size();
@@ -85,14 +112,19 @@ pub fn draw_voxel_selection_menu(
 
     let voxel_size = VOXEL_SIZE * width.min(height);
     let menu_width = VOXELS_IN_ROW as f32 * voxel_size;
-    let menu_height =
-        VOXELS_IN_COLUMN as f32 * voxel_size + SELECTED_VOXELS_OFFSET * voxel_size + voxel_size;
+    let menu_height = VOXELS_IN_COLUMN as f32 * voxel_size
+        + (SELECTED_VOXELS_OFFSET * voxel_size + voxel_size) * 2.0;
     let menu_x = (width - menu_width) * 0.5;
     let menu_y = (height - menu_height) * 0.5;
     draw_rect_with_shadow(menu_x, menu_y, menu_width, menu_height, BACKGROUND_COLOR);
 
     draw_inventory_voxels(asset_manager, player_info, voxel_size, menu_x, menu_y);
     draw_selected_voxels(asset_manager, player_info, voxel_size, menu_x, menu_y);
+    let is_trash_hovered = is_trash_hovered(menu_x, menu_y, voxel_size);
+    if !is_trash_hovered || selected.is_none() {
+        *is_trash_armed = false;
+    }
+    draw_trash_slot(asset_manager, voxel_size, menu_x, menu_y, *is_trash_armed);
     draw_hovered_item_name(player_info, voxel_size, menu_x, menu_y, &asset_manager.font);
 
     if let Some(selected_item) = selected {
@@ -100,10 +132,50 @@ pub fn draw_voxel_selection_menu(
     }
 
     if is_mouse_button_released(MouseButton::Left) {
-        if let Some(some_item) = selected {
+        let hovered = get_hovered_item(menu_x, menu_y, voxel_size, player_info);
+        let hovered_hotbar_index = hovered
+            .as_ref()
+            .and_then(|hovered| hovered.get_hotbar_index());
+        let is_hovered_locked =
+            hovered_hotbar_index.is_some_and(|index| player_info.hotbar_locks.is_locked(index));
+
+        if is_trash_hovered {
+            if selected.is_some() && *is_trash_armed {
+                selected = None;
+                *is_trash_armed = false;
+            } else {
+                *is_trash_armed = selected.is_some();
+            }
+        } else if let (None, Some(index), true) = (
+            selected,
+            hovered_hotbar_index,
+            is_lock_modifier_down(key_bindings),
+        ) {
+            player_info.hotbar_locks.toggle(index);
+        } else if is_hovered_locked {
+            // locked slots can't be moved or replaced
+        } else if let (None, Some(some_hovered), true) = (
+            selected,
+            &hovered,
+            is_quick_transfer_modifier_down(key_bindings),
+        ) {
+            some_hovered.quick_transfer(player_info);
+        } else if let Some(some_item) = selected {
             selected = set_voxel_in_selection(menu_x, menu_y, voxel_size, player_info, some_item);
         } else {
-            selected = get_item_from_menu(menu_x, menu_y, voxel_size, player_info);
+            selected = get_item_from_menu(menu_x, menu_y, voxel_size, player_info, false);
+        }
+    } else if is_mouse_button_released(MouseButton::Right) && !is_trash_hovered {
+        let is_hovered_locked = get_hovered_item(menu_x, menu_y, voxel_size, player_info)
+            .and_then(|hovered| hovered.get_hotbar_index())
+            .is_some_and(|index| player_info.hotbar_locks.is_locked(index));
+
+        if is_hovered_locked {
+            // locked slots can't be split or added to
+        } else if let Some(some_item) = selected {
+            selected = deposit_single_item(menu_x, menu_y, voxel_size, player_info, some_item);
+        } else {
+            selected = get_item_from_menu(menu_x, menu_y, voxel_size, player_info, true);
         }
     }
 
@@ -122,29 +194,100 @@ fn draw_hovered_item_name(
     font: &Font,
 ) {
     if let Some(hovered) = get_hovered_item(menu_x, menu_y, voxel_size, player_info) {
-        let (voxel_name, count) = if let Some(item) = hovered.get(&player_info.inventory) {
-            (item.voxel.display_name(), item.count)
-        } else {
+        let Some(item) = hovered.get(&player_info.inventory) else {
             return;
         };
 
         let (x, y) = mouse_position();
         let font_size = voxel_size * 0.5;
-        draw_item_name_box(x, y, voxel_name, count, font_size, font);
+        draw_item_name_box(x, y, item.voxel.display_name(), item.count, font_size, font);
+
+        let total = player_info.inventory.create_all_items_map().get(item.voxel);
+        draw_item_total_box(x, y + font_size, total, font_size, font);
     }
 }
 
-/// returns the currently hovered over item in the slot and sets it to empty
+/// draws the total count of the hovered voxel across the inventory and hotbar
+fn draw_item_total_box(x: f32, y: f32, total: u32, font_size: f32, font: &Font) {
+    const TEXT_BOX_X_OFFSET: f32 = 3.0;
+    const TEXT_BOX_Y_OFFSET: f32 = -5.0;
+    use_str_buffer(|buffer| {
+        write!(buffer, "Total: {total}").expect("error writing to text buffer");
+        draw_rectangle(
+            x,
+            y - font_size,
+            get_text_width(buffer, font_size, font) + TEXT_BOX_X_OFFSET,
+            font_size,
+            SHADOW_COLOR,
+        );
+        draw_game_text(
+            buffer,
+            x + TEXT_BOX_X_OFFSET,
+            y + TEXT_BOX_Y_OFFSET,
+            font_size,
+            SECONDARY_TEXT_COLOR,
+            font,
+        );
+    });
+}
+
+/// returns the currently hovered over item in the slot and sets it to empty,
+/// with `is_half` only half of the stack is taken and stacks of one are left in place
 fn get_item_from_menu(
     menu_x: f32,
     menu_y: f32,
     voxel_size: f32,
     player_info: &mut PlayerInfo,
+    is_half: bool,
 ) -> Option<Item> {
     get_hovered_item(menu_x, menu_y, voxel_size, player_info).and_then(|hovered| {
-        let item = hovered.get(&player_info.inventory);
-        hovered.set(&mut player_info.inventory, None);
-        item
+        let item = hovered.get(&player_info.inventory)?;
+        if !is_half {
+            hovered.set(&mut player_info.inventory, None);
+            return Some(item);
+        }
+
+        let (taken, remainder) = item.split_half()?;
+        hovered.set(&mut player_info.inventory, Some(remainder));
+        Some(taken)
+    })
+}
+
+/// puts one of the held voxels into the hovered slot if it is empty or has the same voxel,
+/// returns the rest of the held item
+fn deposit_single_item(
+    menu_x: f32,
+    menu_y: f32,
+    voxel_size: f32,
+    player_info: &mut PlayerInfo,
+    selected_item: Item,
+) -> Option<Item> {
+    debug_assert_ne!(selected_item.voxel, Voxel::None);
+    let Some(hovered) = get_hovered_item(menu_x, menu_y, voxel_size, player_info) else {
+        return Some(selected_item);
+    };
+
+    let deposited = match hovered.get(&player_info.inventory) {
+        None => Item {
+            count: 1,
+            ..selected_item
+        },
+        Some(item_in_slot)
+            if item_in_slot.can_stack_with(&selected_item)
+                && item_in_slot.count < MAX_ITEMS_PER_SLOT =>
+        {
+            Item {
+                count: item_in_slot.count + 1,
+                ..item_in_slot
+            }
+        }
+        Some(_) => return Some(selected_item),
+    };
+    hovered.set(&mut player_info.inventory, Some(deposited));
+
+    (selected_item.count > 1).then_some(Item {
+        count: selected_item.count - 1,
+        ..selected_item
     })
 }
 
@@ -196,7 +339,7 @@ fn set_voxel_in_selection(
             let previous = some_hovered
                 .get(&player_info.inventory)
                 .and_then(|item_in_slot| {
-                    if item_in_slot.voxel == selected_item.voxel {
+                    if item_in_slot.can_stack_with(&selected_item) {
                         let amount_to_transfer =
                             (MAX_ITEMS_PER_SLOT - selected_item.count).min(item_in_slot.count);
                         selected_item.count += amount_to_transfer;
@@ -278,14 +421,19 @@ fn draw_selected_voxels(
 
     for x in 0..VOXELS_IN_ROW {
         let option_item = player_info.inventory.selected[x];
+        let slot_x = x as f32 * voxel_size + menu_x + voxel_size * BORDER_VOXELS_MULTIPLIER;
+        let slot_y = y + voxel_size * BORDER_VOXELS_MULTIPLIER;
+        if player_info.hotbar_locks.is_locked(x) {
+            draw_lock_icon(voxel_size, slot_x, slot_y);
+        }
         if let Some(item) = option_item {
             let texture = asset_manager.texture_manager.get_icon(item.voxel);
             draw_item(
                 &texture,
                 item.count,
                 voxel_size,
-                x as f32 * voxel_size + menu_x + voxel_size * BORDER_VOXELS_MULTIPLIER,
-                y + voxel_size * BORDER_VOXELS_MULTIPLIER,
+                slot_x,
+                slot_y,
                 &asset_manager.font,
             );
         } else {
@@ -333,6 +481,96 @@ fn draw_inventory_voxels(
     }
 }
 
+fn get_trash_slot_position(menu_x: f32, menu_y: f32, voxel_size: f32) -> (f32, f32) {
+    let x = menu_x + (VOXELS_IN_ROW - 1) as f32 * voxel_size;
+    let y = menu_y
+        + VOXELS_IN_COLUMN as f32 * voxel_size
+        + SELECTED_VOXELS_OFFSET * voxel_size * 2.0
+        + voxel_size;
+    (x, y)
+}
+
+fn is_trash_hovered(menu_x: f32, menu_y: f32, voxel_size: f32) -> bool {
+    let (mouse_x, mouse_y) = mouse_position();
+    let (x, y) = get_trash_slot_position(menu_x, menu_y, voxel_size);
+    (x..x + voxel_size).contains(&mouse_x) && (y..y + voxel_size).contains(&mouse_y)
+}
+
+/// draws the slot that deletes the held item, asking for a second click once armed
+fn draw_trash_slot(
+    asset_manager: &AssetManager,
+    voxel_size: f32,
+    menu_x: f32,
+    menu_y: f32,
+    is_armed: bool,
+) {
+    let (x, y) = get_trash_slot_position(menu_x, menu_y, voxel_size);
+    let text_size = voxel_size * 0.5;
+    draw_game_text(
+        if is_armed {
+            "Click again to delete"
+        } else {
+            "Trash:"
+        },
+        menu_x,
+        y + (voxel_size + text_size) * 0.5,
+        text_size,
+        if is_armed { TRASH_COLOR } else { TEXT_COLOR },
+        &asset_manager.font,
+    );
+
+    let slot_x = x + voxel_size * BORDER_VOXELS_MULTIPLIER;
+    let slot_y = y + voxel_size * BORDER_VOXELS_MULTIPLIER;
+    let slot_size = voxel_size * INNER_VOXELS_MULTIPLIER;
+    let slot_color = if is_armed {
+        ARMED_TRASH_COLOR
+    } else {
+        SHADOW_COLOR
+    };
+    draw_rectangle(slot_x, slot_y, slot_size, slot_size, slot_color);
+
+    let cross_offset = slot_size * 0.25;
+    let thickness = voxel_size * 0.06;
+    draw_line(
+        slot_x + cross_offset,
+        slot_y + cross_offset,
+        slot_x + slot_size - cross_offset,
+        slot_y + slot_size - cross_offset,
+        thickness,
+        TRASH_COLOR,
+    );
+    draw_line(
+        slot_x + slot_size - cross_offset,
+        slot_y + cross_offset,
+        slot_x + cross_offset,
+        slot_y + slot_size - cross_offset,
+        thickness,
+        TRASH_COLOR,
+    );
+}
+
+/// draws a small padlock in the top right corner of the slot
+fn draw_lock_icon(voxel_size: f32, x_pos: f32, y_pos: f32) {
+    let icon_size = voxel_size * LOCK_ICON_SIZE;
+    let icon_x = x_pos + voxel_size * INNER_VOXELS_MULTIPLIER - icon_size * 0.5;
+    let icon_y = y_pos - icon_size * 0.5;
+    draw_rectangle_lines(
+        icon_x + icon_size * 0.2,
+        icon_y,
+        icon_size * 0.6,
+        icon_size * 0.7,
+        icon_size * 0.15,
+        LOCK_COLOR,
+    );
+    draw_rectangle(
+        icon_x,
+        icon_y + icon_size * 0.4,
+        icon_size,
+        icon_size * 0.6,
+        LOCK_COLOR,
+    );
+}
+
 fn draw_empty_slot(voxel_size: f32, x_pos: f32, y_pos: f32) {
     let empty_slot_size = voxel_size * INNER_VOXELS_MULTIPLIER;
     draw_rectangle(x_pos, y_pos, empty_slot_size, empty_slot_size, SHADOW_COLOR);
@@ -20,7 +20,7 @@ use crate::{
         },
         title_screen::TitleScreenContext,
     },
-    model::user_settings::UserSettings,
+    model::{key_bindings::KeyAction, user_settings::UserSettings},
     service::asset_manager::AssetManager,
 };
 
@@ -57,7 +57,7 @@ impl HelpMenuContext {
         draw_version_number(height, &asset_manager.font);
         self.draw_controls_image(width, height, asset_manager);
         Self::draw_help_title(width, height, &asset_manager.font);
-        Self::draw_help_text(width, height, asset_manager);
+        Self::draw_help_text(width, height, asset_manager, user_settings);
         let should_go_back = draw_back_button(asset_manager, user_settings);
 
         next_frame().await;
@@ -97,20 +97,39 @@ impl HelpMenuContext {
         );
     }
 
-    fn draw_help_text(width: f32, height: f32, asset_manager: &AssetManager) {
+    /// lists the controls with the currently bound keys
+    fn draw_help_text(
+        width: f32,
+        height: f32,
+        asset_manager: &AssetManager,
+        user_settings: &UserSettings,
+    ) {
         let y = height * HELP_TEXT_Y_COEF;
         let font_size = height * FONT_SIZE_COEF;
+        let key = |action| user_settings.key_bindings.get(action).get_name();
+        let move_text = format!(
+            "{}/{}/{}/{} - Move",
+            key(KeyAction::MoveForward),
+            key(KeyAction::MoveBack),
+            key(KeyAction::MoveLeft),
+            key(KeyAction::MoveRight)
+        );
+        let inventory_text = format!("{} - Inventory", key(KeyAction::Inventory));
+        let crafting_text = format!("{} - Crafting", key(KeyAction::Crafting));
+        let map_text = format!("{} - Toggles a map of the world", key(KeyAction::Map));
+        let console_text = format!("{} - Command console", key(KeyAction::Console));
         let help_text = [
             "Controls:",
-            "W/S/A/D - Move",
+            &move_text,
             "Left mouse - Break voxels",
-            "Right mouse - Place voxels",
+            "Right mouse - Place voxels, open furnaces",
             "Middle mouse button - Replace voxels",
             "Scroll/1-8 - Change selected voxel",
-            "E - Inventory",
-            "C - Crafting",
+            &inventory_text,
+            &crafting_text,
             "Escape - Game menu",
-            "M - Toggles a map of the world",
+            &map_text,
+            &console_text,
         ];
         draw_multiline_left_text(
             &help_text,
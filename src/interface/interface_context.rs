@@ -2,8 +2,9 @@ use std::rc::Rc;
 
 use crate::{
     interface::{
-        help_menu::HelpMenuContext, settings_menu::SettingsContext,
-        title_screen::TitleScreenContext, world_selection::WorldSelectionContext,
+        help_menu::HelpMenuContext, key_bindings_menu::KeyBindingsContext,
+        settings_menu::SettingsContext, title_screen::TitleScreenContext,
+        world_selection::WorldSelectionContext,
     },
     model::user_settings::UserSettings,
     service::asset_manager::AssetManager,
@@ -11,10 +12,11 @@ use crate::{
 };
 
 pub enum InterfaceScreen {
-    WorldSelection(WorldSelectionContext),
+    WorldSelection(Box<WorldSelectionContext>),
     TitleScreen(TitleScreenContext),
     Settings(SettingsContext),
     Help(HelpMenuContext),
+    KeyBindings(KeyBindingsContext),
 }
 
 pub struct InterfaceContext {
@@ -28,7 +30,7 @@ impl InterfaceContext {
         user_settings: UserSettings,
     ) -> Self {
         Self {
-            current_screen: InterfaceScreen::WorldSelection(WorldSelectionContext::new()),
+            current_screen: InterfaceScreen::WorldSelection(Box::new(WorldSelectionContext::new())),
             asset_manager,
             user_settings,
         }
@@ -83,6 +85,14 @@ impl InterfaceContext {
                     self.current_screen = new_screen;
                 }
             }
+            InterfaceScreen::KeyBindings(key_bindings_context) => {
+                if let Some(new_screen) = key_bindings_context
+                    .draw(&self.asset_manager, &mut self.user_settings)
+                    .await
+                {
+                    self.current_screen = new_screen;
+                }
+            }
         }
     }
 
@@ -0,0 +1,211 @@
+use macroquad::{
+    camera::set_default_camera,
+    color::{Color, RED},
+    input::{KeyCode, is_key_released},
+    miniquad::window::screen_size,
+    text::Font,
+    window::next_frame,
+};
+
+use crate::{
+    interface::{
+        background::draw_background,
+        button::{draw_back_button, draw_button},
+        button_grid::{ButtonGrid, ButtonGridStyle},
+        interface_context::InterfaceScreen,
+        settings_menu::SettingsContext,
+        style::{MEDIUM_FONT_SIZE, MENU_TITLE_FONT_SIZE, SMALL_FONT_SIZE, TEXT_COLOR},
+        text::{draw_centered_multiline_text, draw_game_text, draw_version_number, get_text_width},
+    },
+    model::{
+        key_bindings::{BindableKey, KeyAction, KeyBindings},
+        user_settings::UserSettings,
+    },
+    service::{
+        asset_manager::AssetManager, input::get_scroll_direction,
+        persistence::user_settings_persistence::write_user_settings,
+    },
+};
+
+const BUTTON_HEIGHT: f32 = 50.0;
+const BINDING_GRID_STYLE: ButtonGridStyle = ButtonGridStyle {
+    button_width: 360.0,
+    button_height: BUTTON_HEIGHT,
+    row_height: BUTTON_HEIGHT * 1.2,
+    column_gap: 20.0,
+    max_columns: 3,
+};
+/// a button for each action followed by the reset button
+const BINDING_BUTTON_COUNT: usize = KeyAction::ALL.len() + 1;
+const WARNING_FONT_SIZE: f32 = MEDIUM_FONT_SIZE;
+/// space at the bottom kept free for the conflict warning
+const FOOTER_HEIGHT: f32 = WARNING_FONT_SIZE * 2.0;
+const WARNING_COLOR: Color = RED;
+
+/// lists the key bindings, pressing a row and then a key binds the key to the action
+pub struct KeyBindingsContext {
+    /// the action waiting for a key press
+    selected_action: Option<KeyAction>,
+    /// the first visible row of binding buttons
+    first_row: usize,
+}
+impl KeyBindingsContext {
+    pub fn new() -> Self {
+        Self {
+            selected_action: None,
+            first_row: 0,
+        }
+    }
+
+    /// returns the new screen if changed
+    pub async fn draw(
+        &mut self,
+        asset_manager: &AssetManager,
+        user_settings: &mut UserSettings,
+    ) -> Option<InterfaceScreen> {
+        set_default_camera();
+        let (width, height) = screen_size();
+        draw_background(width, height, &asset_manager.texture_manager);
+        Self::draw_title(width, height, &asset_manager.font);
+
+        self.bind_pressed_key(&mut user_settings.key_bindings);
+        let mut grid = ButtonGrid::new(
+            BINDING_GRID_STYLE,
+            width,
+            height * 0.2,
+            height - FOOTER_HEIGHT,
+            BINDING_BUTTON_COUNT,
+            self.first_row,
+        );
+        grid.scroll(get_scroll_direction());
+        self.first_row = grid.get_first_row();
+        self.handle_binding_buttons(asset_manager, user_settings, &grid);
+        grid.draw_scroll_bar();
+        Self::draw_conflict_warning(&user_settings.key_bindings, width, height, asset_manager);
+        draw_version_number(height, &asset_manager.font);
+
+        let should_go_back = draw_back_button(asset_manager, user_settings);
+        next_frame().await;
+
+        if should_go_back {
+            write_user_settings(user_settings);
+            Some(InterfaceScreen::Settings(SettingsContext::new()))
+        } else {
+            None
+        }
+    }
+
+    fn draw_title(width: f32, height: f32, font: &Font) {
+        let text = "Key bindings";
+        let text_width = get_text_width(text, MENU_TITLE_FONT_SIZE, font);
+        let x = (width - text_width) * 0.5;
+        let y = height * 0.1;
+
+        draw_game_text(text, x, y, MENU_TITLE_FONT_SIZE, TEXT_COLOR, font);
+    }
+
+    /// binds the released key to the selected action, escape cancels the selection
+    fn bind_pressed_key(&mut self, key_bindings: &mut KeyBindings) {
+        let Some(action) = self.selected_action else {
+            return;
+        };
+        if is_key_released(KeyCode::Escape) {
+            self.selected_action = None;
+            return;
+        }
+
+        if let Some(key) = BindableKey::all().find(|key| is_key_released(key.to_key_code())) {
+            key_bindings.set(action, key);
+            self.selected_action = None;
+        }
+    }
+
+    /// draws a button for each action followed by a reset button
+    fn handle_binding_buttons(
+        &mut self,
+        asset_manager: &AssetManager,
+        user_settings: &mut UserSettings,
+        grid: &ButtonGrid,
+    ) {
+        for (index, action) in KeyAction::ALL.into_iter().enumerate() {
+            let key_bindings = &user_settings.key_bindings;
+            let text = if self.selected_action == Some(action) {
+                format!("{}: press a key", action.display_name())
+            } else if key_bindings.find_conflicts(action).next().is_some() {
+                format!(
+                    "{}: {} (!)",
+                    action.display_name(),
+                    key_bindings.get(action).get_name()
+                )
+            } else {
+                format!(
+                    "{}: {}",
+                    action.display_name(),
+                    key_bindings.get(action).get_name()
+                )
+            };
+            if Self::draw_binding_button(asset_manager, user_settings, grid, index, &text) {
+                self.selected_action = Some(action);
+            }
+        }
+
+        let should_reset = Self::draw_binding_button(
+            asset_manager,
+            user_settings,
+            grid,
+            KeyAction::ALL.len(),
+            "Reset to defaults",
+        );
+        if should_reset {
+            user_settings.key_bindings = KeyBindings::default();
+            self.selected_action = None;
+        }
+    }
+
+    /// returns true if pressed, buttons scrolled out of view can't be pressed
+    fn draw_binding_button(
+        asset_manager: &AssetManager,
+        user_settings: &UserSettings,
+        grid: &ButtonGrid,
+        index: usize,
+        text: &str,
+    ) -> bool {
+        let Some(rect) = grid.get_button_rect(index) else {
+            return false;
+        };
+
+        draw_button(rect, text, SMALL_FONT_SIZE, asset_manager, user_settings)
+    }
+
+    /// warns about the first key that is bound to more than one action
+    fn draw_conflict_warning(
+        key_bindings: &KeyBindings,
+        width: f32,
+        height: f32,
+        asset_manager: &AssetManager,
+    ) {
+        let Some((action, conflict)) = KeyAction::ALL.into_iter().find_map(|action| {
+            key_bindings
+                .find_conflicts(action)
+                .next()
+                .map(|conflict| (action, conflict))
+        }) else {
+            return;
+        };
+
+        let warning = format!(
+            "{} is already used by {} and {}",
+            key_bindings.get(action).get_name(),
+            action.display_name(),
+            conflict.display_name()
+        );
+        draw_centered_multiline_text(
+            &[&warning],
+            height - FOOTER_HEIGHT + WARNING_FONT_SIZE,
+            width,
+            WARNING_FONT_SIZE,
+            WARNING_COLOR,
+            &asset_manager.font,
+        );
+    }
+}
@@ -1,9 +1,11 @@
 pub mod background;
 pub mod button;
+pub mod button_grid;
 pub mod error_display;
 pub mod game_menu;
 pub mod help_menu;
 pub mod interface_context;
+pub mod key_bindings_menu;
 pub mod list_input;
 pub mod settings_menu;
 pub mod style;
@@ -8,43 +8,167 @@ use macroquad::{
 };
 
 use crate::{
+    graphics::shader_manager::SHADER_MANAGER_INSTANCE,
     interface::{
         background::draw_background,
         button::{draw_back_button, draw_button},
+        button_grid::{ButtonGrid, ButtonGridStyle},
         interface_context::InterfaceScreen,
-        style::{MENU_TITLE_FONT_SIZE, TEXT_COLOR},
+        key_bindings_menu::KeyBindingsContext,
+        style::{
+            BUTTON_FONT_SIZE, MEDIUM_FONT_SIZE, MENU_TITLE_FONT_SIZE, SYMBOL_FONT_SIZE, TEXT_COLOR,
+        },
         text::{draw_centered_multiline_text, draw_game_text, draw_version_number, get_text_width},
         title_screen::TitleScreenContext,
         util::is_point_in_rect,
     },
-    model::user_settings::{ShadowType, UserSettings},
+    model::user_settings::{ScrollAction, ShadowType, UserSettings},
     service::{
-        asset_manager::AssetManager, persistence::user_settings_persistence::write_user_settings,
+        asset_manager::AssetManager, input::get_scroll_direction,
+        persistence::user_settings_persistence::write_user_settings,
     },
 };
 
 const BUTTON_WIDTH: f32 = 380.0;
 const BUTTON_HEIGHT: f32 = 70.0;
 const BUTTON_HEIGHT_OFFSET: f32 = BUTTON_HEIGHT * 1.2;
-const BUTTON_TEXT_SIZE: f32 = 30.0;
+const BUTTON_TEXT_SIZE: f32 = BUTTON_FONT_SIZE;
+const SETTING_GRID_STYLE: ButtonGridStyle = ButtonGridStyle {
+    button_width: BUTTON_WIDTH,
+    button_height: BUTTON_HEIGHT,
+    row_height: BUTTON_HEIGHT_OFFSET,
+    column_gap: 20.0,
+    max_columns: 2,
+};
+/// the setting buttons drawn in the grid, the key bindings button is the last one
+const SETTING_BUTTON_COUNT: usize = 30;
 const RENDER_DISTANCE_TEXT_WIDTH: f32 = 320.0;
-const SMALL_BUTTON_TEXT_SIZE: u16 = 50;
-const DESCRIPTION_FONT_SIZE: f32 = 34.0;
+const SMALL_BUTTON_TEXT_SIZE: f32 = SYMBOL_FONT_SIZE;
+const DESCRIPTION_FONT_SIZE: f32 = MEDIUM_FONT_SIZE;
+/// space at the bottom kept free for the two description lines
+const FOOTER_HEIGHT: f32 = DESCRIPTION_FONT_SIZE * 3.0;
 
 const DECREASE_RENDER_DISTANCE_DESCRIPTION: [&str; 2] =
     ["Lower view distance,", "improves performance"];
 const INCREASE_RENDER_DISTANCE_DESCRIPTION: [&str; 2] =
     ["Increase view distance,", "lowers performance"];
+const DECREASE_SIMULATION_DISTANCE_DESCRIPTION: [&str; 2] = [
+    "Lower distance where the world is simulated,",
+    "can't be lower than the view distance",
+];
+const INCREASE_SIMULATION_DISTANCE_DESCRIPTION: [&str; 2] = [
+    "Simulate water, physics and creatures further,",
+    "uses more memory and processing",
+];
 const TOGGLE_SOUNDS_DESCRIPTION: [&str; 1] = ["Toggles game sounds"];
 const TOGGLE_FULLSCREEN_DESCRIPTION: [&str; 1] = ["Toggles fullscreen mode"];
 const TOGGLE_SHADOWS_DESCRIPTION: [&str; 2] = [
     "Changes the appearance of dynamic shadows,",
     "can be disabled",
 ];
+const TOGGLE_HUD_DESCRIPTION: [&str; 2] = [
+    "Shows or hides the hotbar and crosshair,",
+    "can also be toggled in game, F3 by default",
+];
+const TOGGLE_AUTO_STEP_DESCRIPTION: [&str; 1] = ["Automatically steps up one voxel high ledges"];
+const TOGGLE_TUTORIALS_DESCRIPTION: [&str; 2] = [
+    "Shows hints when discovering new mechanics,",
+    "they can be replayed from the in game options",
+];
+const TOGGLE_SMOOTH_LIGHTING_DESCRIPTION: [&str; 2] = [
+    "Darkens voxel corners for softer lighting,",
+    "disable for a flat look and better performance",
+];
+const TOGGLE_BLOOM_DESCRIPTION: [&str; 2] = [
+    "Makes lamps, lava and lightning glow,",
+    "disable for better performance",
+];
+const TOGGLE_WATER_REFLECTIONS_DESCRIPTION: [&str; 2] = [
+    "Reflects the sky on water surfaces,",
+    "disable for better performance",
+];
+const TOGGLE_TARGET_NAME_DESCRIPTION: [&str; 1] = ["Shows the name of the targeted voxel"];
+const CHANGE_CREATURE_SPAWN_RATE_DESCRIPTION: [&str; 2] = [
+    "Changes how many creatures spawn,",
+    "0 stops new creatures from spawning",
+];
+const TOGGLE_KEEP_SPAWN_LOADED_DESCRIPTION: [&str; 2] =
+    ["Keeps the areas around spawn loaded,", "uses more memory"];
+const CHANGE_MOUSE_BINDINGS_DESCRIPTION: [&str; 2] = [
+    "Changes the mouse buttons to break and place,",
+    "replacing uses the remaining button",
+];
+const CHANGE_BREAK_SPEED_DESCRIPTION: [&str; 2] = [
+    "Breaks voxels faster while holding,",
+    "the broken voxels are still collected",
+];
+const CHANGE_ITEM_MAGNET_DESCRIPTION: [&str; 2] = [
+    "Pulls nearby dropped items towards",
+    "the player when not blocked by voxels",
+];
+const TOGGLE_INVERT_Y_DESCRIPTION: [&str; 2] = [
+    "Moving the mouse up looks down,",
+    "the world map controls are not affected",
+];
 
-pub struct SettingsContext;
+const CHANGE_LOOK_LIMIT_DESCRIPTION: [&str; 2] =
+    ["Maximum angle in degrees for", "looking up or down"];
+const CHANGE_CREATURE_RENDER_DISTANCE_DESCRIPTION: [&str; 2] = [
+    "Distance in areas where creatures are shown,",
+    "can't be higher than the view distance",
+];
+const CHANGE_SCROLL_ACTION_DESCRIPTION: [&str; 2] = [
+    "Scrolling changes the hotbar slot or the reach,",
+    "the number keys always select slots",
+];
+const TOGGLE_MUSIC_DESCRIPTION: [&str; 2] =
+    ["Toggles the background music,", "requires sounds to be on"];
+
+const CHANGE_MUSIC_VOLUME_DESCRIPTION: [&str; 1] = ["Music volume, separate from sounds"];
+const CHANGE_AUTO_SAVE_DESCRIPTION: [&str; 2] = [
+    "Time between saving the world while playing,",
+    "the world is always saved on exit",
+];
+const TOGGLE_FOG_DESCRIPTION: [&str; 2] = [
+    "Fades distant voxels into the sky,",
+    "disable for better performance",
+];
+const TOGGLE_MESH_CACHE_DESCRIPTION: [&str; 2] = [
+    "Saves the voxel meshes with the world,",
+    "loads faster but uses more disk space",
+];
+const CHANGE_DAY_LENGTH_DESCRIPTION: [&str; 2] = [
+    "Time for a full day and night,",
+    "it can be frozen in debug mode, F8 by default",
+];
+const TOGGLE_COMPASS_BAR_DESCRIPTION: [&str; 1] = ["Shows the heading at the top of the screen"];
+const CHANGE_MOUSE_SENSITIVITY_DESCRIPTION: [&str; 2] = [
+    "How fast the camera turns with the mouse,",
+    "the world map controls are not affected",
+];
+const CHANGE_FOV_DESCRIPTION: [&str; 2] = [
+    "Vertical field of view in degrees,",
+    "wider shows more but lowers performance",
+];
+const CHANGE_CREATURE_UPDATES_DESCRIPTION: [&str; 2] = [
+    "Distant creatures updated each frame,",
+    "lower values improve performance with many creatures",
+];
+const CHANGE_KEY_BINDINGS_DESCRIPTION: [&str; 2] = [
+    "Changes the keys for moving and menus,",
+    "the mouse buttons are changed separately",
+];
+#[derive(Debug, Clone, Copy)]
+pub struct SettingsContext {
+    /// the first visible row of setting buttons
+    first_row: usize,
+}
 
 impl SettingsContext {
+    pub fn new() -> Self {
+        Self { first_row: 0 }
+    }
+
     /// returns the new screen
     pub async fn draw(
         &mut self,
@@ -54,14 +178,23 @@ impl SettingsContext {
         set_default_camera();
         let (width, height) = screen_size();
         draw_background(width, height, &asset_manager.texture_manager);
-        let x_start = (width - BUTTON_WIDTH) * 0.5;
-        let y_start = height * 0.3;
+        let y_start = height * 0.25;
 
         Self::draw_settings_title(width, height, &asset_manager.font);
-        Self::handle_render_distance(asset_manager, user_settings, width, y_start);
-        Self::handle_toggle_sound_button(asset_manager, user_settings, x_start, y_start);
-        Self::handle_toggle_fullscreen_button(asset_manager, user_settings, x_start, y_start);
-        Self::handle_toggle_dynamic_light(asset_manager, user_settings, x_start, y_start);
+        Self::handle_distances(asset_manager, user_settings, width, y_start);
+        let mut grid = ButtonGrid::new(
+            SETTING_GRID_STYLE,
+            width,
+            y_start + BUTTON_HEIGHT_OFFSET * 2.0,
+            height - FOOTER_HEIGHT,
+            SETTING_BUTTON_COUNT,
+            self.first_row,
+        );
+        grid.scroll(get_scroll_direction());
+        self.first_row = grid.get_first_row();
+        let should_change_key_bindings =
+            Self::handle_setting_buttons(asset_manager, user_settings, &grid);
+        grid.draw_scroll_bar();
         draw_version_number(height, &asset_manager.font);
 
         let should_exit = draw_back_button(asset_manager, user_settings);
@@ -70,8 +203,10 @@ impl SettingsContext {
         if should_exit {
             write_user_settings(user_settings);
             InterfaceScreen::TitleScreen(TitleScreenContext::new())
+        } else if should_change_key_bindings {
+            InterfaceScreen::KeyBindings(KeyBindingsContext::new())
         } else {
-            InterfaceScreen::Settings(SettingsContext)
+            InterfaceScreen::Settings(*self)
         }
     }
 
@@ -84,29 +219,73 @@ impl SettingsContext {
         draw_game_text(settings_text, x, y, MENU_TITLE_FONT_SIZE, TEXT_COLOR, font);
     }
 
-    fn handle_render_distance(
+    fn handle_distances(
         asset_manager: &AssetManager,
         user_settings: &mut UserSettings,
         width: f32,
         y_start: f32,
     ) {
+        let text = format!("View distance: {}", user_settings.get_render_distance());
+        let (decrease, increase) = Self::draw_distance_setting(
+            asset_manager,
+            user_settings,
+            width,
+            y_start,
+            &text,
+            &DECREASE_RENDER_DISTANCE_DESCRIPTION,
+            &INCREASE_RENDER_DISTANCE_DESCRIPTION,
+        );
+        if increase {
+            user_settings.increase_render_distance();
+        } else if decrease {
+            user_settings.decrease_render_distance();
+        }
+
+        let text = format!("Sim. distance: {}", user_settings.get_simulation_distance());
+        let (decrease, increase) = Self::draw_distance_setting(
+            asset_manager,
+            user_settings,
+            width,
+            y_start + BUTTON_HEIGHT_OFFSET,
+            &text,
+            &DECREASE_SIMULATION_DISTANCE_DESCRIPTION,
+            &INCREASE_SIMULATION_DISTANCE_DESCRIPTION,
+        );
+        if increase {
+            user_settings.increase_simulation_distance();
+        } else if decrease {
+            user_settings.decrease_simulation_distance();
+        }
+    }
+
+    /// draws a distance with decrease and increase buttons,
+    /// returns if they are pressed
+    fn draw_distance_setting(
+        asset_manager: &AssetManager,
+        user_settings: &UserSettings,
+        width: f32,
+        y: f32,
+        text: &str,
+        decrease_description: &[&str],
+        increase_description: &[&str],
+    ) -> (bool, bool) {
         let x = (width - BUTTON_HEIGHT_OFFSET - RENDER_DISTANCE_TEXT_WIDTH) * 0.5;
         let (width, height) = screen_size();
         let (mouse_x, mouse_y) = mouse_position();
         Self::draw_description(
             width,
             height,
-            &DECREASE_RENDER_DISTANCE_DESCRIPTION,
-            is_point_in_rect(x, y_start, BUTTON_HEIGHT, BUTTON_HEIGHT, mouse_x, mouse_y),
+            decrease_description,
+            is_point_in_rect(x, y, BUTTON_HEIGHT, BUTTON_HEIGHT, mouse_x, mouse_y),
             &asset_manager.font,
         );
         Self::draw_description(
             width,
             height,
-            &INCREASE_RENDER_DISTANCE_DESCRIPTION,
+            increase_description,
             is_point_in_rect(
                 x + RENDER_DISTANCE_TEXT_WIDTH,
-                y_start,
+                y,
                 BUTTON_HEIGHT,
                 BUTTON_HEIGHT,
                 mouse_x,
@@ -117,7 +296,7 @@ impl SettingsContext {
         let decrease = draw_button(
             Rect {
                 x,
-                y: y_start,
+                y,
                 w: BUTTON_HEIGHT,
                 h: BUTTON_HEIGHT,
             },
@@ -127,11 +306,10 @@ impl SettingsContext {
             user_settings,
         );
 
-        let text = format!("View distance: {}", user_settings.get_render_distance());
         draw_game_text(
-            &text,
+            text,
             x + BUTTON_HEIGHT_OFFSET,
-            y_start + BUTTON_TEXT_SIZE,
+            y + BUTTON_TEXT_SIZE,
             BUTTON_TEXT_SIZE,
             TEXT_COLOR,
             &asset_manager.font,
@@ -140,7 +318,7 @@ impl SettingsContext {
         let increase = draw_button(
             Rect {
                 x: x + RENDER_DISTANCE_TEXT_WIDTH,
-                y: y_start,
+                y,
                 w: BUTTON_HEIGHT,
                 h: BUTTON_HEIGHT,
             },
@@ -150,131 +328,512 @@ impl SettingsContext {
             user_settings,
         );
 
-        if increase {
-            user_settings.increase_render_distance();
-        } else if decrease {
-            user_settings.decrease_render_distance();
-        }
+        (decrease, increase)
     }
 
-    /// returns true if pressed
-    fn handle_toggle_sound_button(
+    /// draws the setting toggles in a scrolling grid below the distances,
+    /// returns true if the key bindings should be opened
+    fn handle_setting_buttons(
         asset_manager: &AssetManager,
         user_settings: &mut UserSettings,
-        x_start: f32,
-        y_start: f32,
-    ) {
-        let (width, height) = screen_size();
-        let (mouse_x, mouse_y) = mouse_position();
-        let y = y_start + BUTTON_HEIGHT_OFFSET;
-        Self::draw_description(
-            width,
-            height,
+        grid: &ButtonGrid,
+    ) -> bool {
+        let toggle_sound = Self::draw_setting_button(
+            asset_manager,
+            user_settings,
+            grid,
+            0,
+            if user_settings.has_sound {
+                "Sound:ON"
+            } else {
+                "Sound:OFF"
+            },
             &TOGGLE_SOUNDS_DESCRIPTION,
-            is_point_in_rect(x_start, y, BUTTON_WIDTH, BUTTON_HEIGHT, mouse_x, mouse_y),
-            &asset_manager.font,
         );
+        if toggle_sound {
+            user_settings.has_sound = !user_settings.has_sound;
+        }
 
-        let should_toggle = draw_button(
-            Rect {
-                x: x_start,
-                y,
-                w: BUTTON_WIDTH,
-                h: BUTTON_HEIGHT,
+        let toggle_fullscreen = Self::draw_setting_button(
+            asset_manager,
+            user_settings,
+            grid,
+            1,
+            if user_settings.is_fullscreen {
+                "Go windowed"
+            } else {
+                "Go fullscreen"
             },
-            if user_settings.has_sound {
-                "Sound:ON"
+            &TOGGLE_FULLSCREEN_DESCRIPTION,
+        );
+        if toggle_fullscreen {
+            user_settings.is_fullscreen = !user_settings.is_fullscreen;
+            set_fullscreen(user_settings.is_fullscreen);
+        }
+
+        let change_shadows = Self::draw_setting_button(
+            asset_manager,
+            user_settings,
+            grid,
+            2,
+            match user_settings.shadow_type {
+                ShadowType::Soft => "Soft shadows",
+                ShadowType::Hard => "Hard shadows",
+                ShadowType::None => "No shadows",
+            },
+            &TOGGLE_SHADOWS_DESCRIPTION,
+        );
+        if change_shadows {
+            Self::change_shadow_type(user_settings);
+        }
+
+        let toggle_hud = Self::draw_setting_button(
+            asset_manager,
+            user_settings,
+            grid,
+            3,
+            if user_settings.show_hud {
+                "HUD:ON"
             } else {
-                "Sound:OFF"
+                "HUD:OFF"
             },
-            BUTTON_TEXT_SIZE as u16,
+            &TOGGLE_HUD_DESCRIPTION,
+        );
+        if toggle_hud {
+            user_settings.show_hud = !user_settings.show_hud;
+        }
+
+        let toggle_auto_step = Self::draw_setting_button(
             asset_manager,
             user_settings,
+            grid,
+            4,
+            if user_settings.auto_step {
+                "Auto step:ON"
+            } else {
+                "Auto step:OFF"
+            },
+            &TOGGLE_AUTO_STEP_DESCRIPTION,
         );
+        if toggle_auto_step {
+            user_settings.auto_step = !user_settings.auto_step;
+        }
 
-        if should_toggle {
-            user_settings.has_sound = !user_settings.has_sound;
+        let toggle_tutorials = Self::draw_setting_button(
+            asset_manager,
+            user_settings,
+            grid,
+            5,
+            if user_settings.show_tutorials {
+                "Tutorials:ON"
+            } else {
+                "Tutorials:OFF"
+            },
+            &TOGGLE_TUTORIALS_DESCRIPTION,
+        );
+        if toggle_tutorials {
+            user_settings.show_tutorials = !user_settings.show_tutorials;
         }
-    }
 
-    fn handle_toggle_fullscreen_button(
-        asset_manager: &AssetManager,
-        user_settings: &mut UserSettings,
-        x_start: f32,
-        y_start: f32,
-    ) {
-        let (width, height) = screen_size();
-        let (mouse_x, mouse_y) = mouse_position();
-        let y = y_start + BUTTON_HEIGHT_OFFSET * 2.0;
-        Self::draw_description(
-            width,
-            height,
-            &TOGGLE_FULLSCREEN_DESCRIPTION,
-            is_point_in_rect(x_start, y, BUTTON_WIDTH, BUTTON_HEIGHT, mouse_x, mouse_y),
-            &asset_manager.font,
+        let toggle_smooth_lighting = Self::draw_setting_button(
+            asset_manager,
+            user_settings,
+            grid,
+            6,
+            if user_settings.smooth_lighting {
+                "Lighting:Smooth"
+            } else {
+                "Lighting:Blocky"
+            },
+            &TOGGLE_SMOOTH_LIGHTING_DESCRIPTION,
         );
+        if toggle_smooth_lighting {
+            user_settings.smooth_lighting = !user_settings.smooth_lighting;
+        }
 
-        let should_change = draw_button(
-            Rect {
-                x: x_start,
-                y,
-                w: BUTTON_WIDTH,
-                h: BUTTON_HEIGHT,
+        let toggle_bloom = Self::draw_setting_button(
+            asset_manager,
+            user_settings,
+            grid,
+            7,
+            if user_settings.bloom {
+                "Bloom:ON"
+            } else {
+                "Bloom:OFF"
             },
-            if user_settings.is_fullscreen {
-                "Go windowed"
+            &TOGGLE_BLOOM_DESCRIPTION,
+        );
+        if toggle_bloom {
+            user_settings.bloom = !user_settings.bloom;
+        }
+
+        let toggle_water_reflections = Self::draw_setting_button(
+            asset_manager,
+            user_settings,
+            grid,
+            8,
+            if user_settings.water_reflections {
+                "Reflections:ON"
             } else {
-                "Go fullscreen"
+                "Reflections:OFF"
             },
-            BUTTON_TEXT_SIZE as u16,
+            &TOGGLE_WATER_REFLECTIONS_DESCRIPTION,
+        );
+        if toggle_water_reflections {
+            user_settings.water_reflections = !user_settings.water_reflections;
+        }
+
+        let toggle_target_name = Self::draw_setting_button(
             asset_manager,
             user_settings,
+            grid,
+            9,
+            if user_settings.show_target_name {
+                "Target name:ON"
+            } else {
+                "Target name:OFF"
+            },
+            &TOGGLE_TARGET_NAME_DESCRIPTION,
         );
-        if should_change {
-            user_settings.is_fullscreen = !user_settings.is_fullscreen;
-            set_fullscreen(user_settings.is_fullscreen);
+        if toggle_target_name {
+            user_settings.show_target_name = !user_settings.show_target_name;
+        }
+
+        let change_creature_spawn_rate = Self::draw_setting_button(
+            asset_manager,
+            user_settings,
+            grid,
+            10,
+            &format!("Creatures: x{:.1}", user_settings.get_creature_spawn_rate()),
+            &CHANGE_CREATURE_SPAWN_RATE_DESCRIPTION,
+        );
+        if change_creature_spawn_rate {
+            user_settings.cycle_creature_spawn_rate();
         }
+
+        let toggle_keep_spawn_loaded = Self::draw_setting_button(
+            asset_manager,
+            user_settings,
+            grid,
+            11,
+            if user_settings.keep_spawn_loaded {
+                "Keep spawn:ON"
+            } else {
+                "Keep spawn:OFF"
+            },
+            &TOGGLE_KEEP_SPAWN_LOADED_DESCRIPTION,
+        );
+        if toggle_keep_spawn_loaded {
+            user_settings.keep_spawn_loaded = !user_settings.keep_spawn_loaded;
+        }
+
+        let change_mouse_bindings = Self::draw_setting_button(
+            asset_manager,
+            user_settings,
+            grid,
+            12,
+            &format!(
+                "Break:{} Place:{}",
+                user_settings.mouse_bindings.destroy.get_short_name(),
+                user_settings.mouse_bindings.place.get_short_name()
+            ),
+            &CHANGE_MOUSE_BINDINGS_DESCRIPTION,
+        );
+        if change_mouse_bindings {
+            user_settings.mouse_bindings.cycle();
+        }
+
+        let change_break_speed = Self::draw_setting_button(
+            asset_manager,
+            user_settings,
+            grid,
+            13,
+            &format!("Break speed: x{:.1}", user_settings.get_break_speed()),
+            &CHANGE_BREAK_SPEED_DESCRIPTION,
+        );
+        if change_break_speed {
+            user_settings.cycle_break_speed();
+        }
+
+        let item_magnet_radius = user_settings.get_item_magnet_radius();
+        let item_magnet_text = if item_magnet_radius > 0.0 {
+            format!("Item magnet: {item_magnet_radius:.0}")
+        } else {
+            "Item magnet: Off".to_owned()
+        };
+        let change_item_magnet = Self::draw_setting_button(
+            asset_manager,
+            user_settings,
+            grid,
+            14,
+            &item_magnet_text,
+            &CHANGE_ITEM_MAGNET_DESCRIPTION,
+        );
+        if change_item_magnet {
+            user_settings.cycle_item_magnet_radius();
+        }
+
+        let toggle_invert_y = Self::draw_setting_button(
+            asset_manager,
+            user_settings,
+            grid,
+            15,
+            if user_settings.invert_y {
+                "Invert Y:ON"
+            } else {
+                "Invert Y:OFF"
+            },
+            &TOGGLE_INVERT_Y_DESCRIPTION,
+        );
+        if toggle_invert_y {
+            user_settings.invert_y = !user_settings.invert_y;
+        }
+
+        let change_look_limit = Self::draw_setting_button(
+            asset_manager,
+            user_settings,
+            grid,
+            16,
+            &format!(
+                "Look limit: {:.0}",
+                user_settings.get_max_pitch().to_degrees()
+            ),
+            &CHANGE_LOOK_LIMIT_DESCRIPTION,
+        );
+        if change_look_limit {
+            user_settings.cycle_max_pitch();
+        }
+
+        let change_creature_render_distance = Self::draw_setting_button(
+            asset_manager,
+            user_settings,
+            grid,
+            17,
+            &format!(
+                "Creature dist.: {}",
+                user_settings.get_creature_render_distance()
+            ),
+            &CHANGE_CREATURE_RENDER_DISTANCE_DESCRIPTION,
+        );
+        if change_creature_render_distance {
+            user_settings.cycle_creature_render_distance();
+        }
+
+        let change_scroll_action = Self::draw_setting_button(
+            asset_manager,
+            user_settings,
+            grid,
+            18,
+            match user_settings.scroll_action {
+                ScrollAction::Hotbar => "Scroll: Hotbar",
+                ScrollAction::Reach => "Scroll: Reach",
+            },
+            &CHANGE_SCROLL_ACTION_DESCRIPTION,
+        );
+        if change_scroll_action {
+            user_settings.scroll_action = match user_settings.scroll_action {
+                ScrollAction::Hotbar => ScrollAction::Reach,
+                ScrollAction::Reach => ScrollAction::Hotbar,
+            };
+        }
+
+        let toggle_music = Self::draw_setting_button(
+            asset_manager,
+            user_settings,
+            grid,
+            19,
+            if user_settings.has_music {
+                "Music:ON"
+            } else {
+                "Music:OFF"
+            },
+            &TOGGLE_MUSIC_DESCRIPTION,
+        );
+        if toggle_music {
+            user_settings.has_music = !user_settings.has_music;
+        }
+
+        let change_music_volume = Self::draw_setting_button(
+            asset_manager,
+            user_settings,
+            grid,
+            20,
+            &format!(
+                "Music volume: {:.0}%",
+                user_settings.get_music_volume() * 100.0
+            ),
+            &CHANGE_MUSIC_VOLUME_DESCRIPTION,
+        );
+        if change_music_volume {
+            user_settings.cycle_music_volume();
+        }
+
+        let change_auto_save = Self::draw_setting_button(
+            asset_manager,
+            user_settings,
+            grid,
+            21,
+            &match user_settings.get_auto_save_interval() {
+                Some(interval) => format!("Auto save: {:.0}s", interval),
+                None => "Auto save:OFF".to_owned(),
+            },
+            &CHANGE_AUTO_SAVE_DESCRIPTION,
+        );
+        if change_auto_save {
+            user_settings.cycle_auto_save_interval();
+        }
+
+        let toggle_fog = Self::draw_setting_button(
+            asset_manager,
+            user_settings,
+            grid,
+            22,
+            if user_settings.fog {
+                "Fog:ON"
+            } else {
+                "Fog:OFF"
+            },
+            &TOGGLE_FOG_DESCRIPTION,
+        );
+        if toggle_fog {
+            user_settings.fog = !user_settings.fog;
+        }
+
+        let toggle_mesh_cache = Self::draw_setting_button(
+            asset_manager,
+            user_settings,
+            grid,
+            23,
+            if user_settings.mesh_cache {
+                "Mesh cache:ON"
+            } else {
+                "Mesh cache:OFF"
+            },
+            &TOGGLE_MESH_CACHE_DESCRIPTION,
+        );
+        if toggle_mesh_cache {
+            user_settings.mesh_cache = !user_settings.mesh_cache;
+        }
+
+        let change_day_length = Self::draw_setting_button(
+            asset_manager,
+            user_settings,
+            grid,
+            24,
+            &format!(
+                "Day length: {:.0}min",
+                user_settings.get_day_length() / 60.0
+            ),
+            &CHANGE_DAY_LENGTH_DESCRIPTION,
+        );
+        if change_day_length {
+            user_settings.cycle_day_length();
+        }
+
+        let toggle_compass_bar = Self::draw_setting_button(
+            asset_manager,
+            user_settings,
+            grid,
+            25,
+            if user_settings.show_compass_bar {
+                "Compass:ON"
+            } else {
+                "Compass:OFF"
+            },
+            &TOGGLE_COMPASS_BAR_DESCRIPTION,
+        );
+        if toggle_compass_bar {
+            user_settings.show_compass_bar = !user_settings.show_compass_bar;
+        }
+
+        let change_mouse_sensitivity = Self::draw_setting_button(
+            asset_manager,
+            user_settings,
+            grid,
+            26,
+            &format!("Sensitivity: x{:.2}", user_settings.get_mouse_sensitivity()),
+            &CHANGE_MOUSE_SENSITIVITY_DESCRIPTION,
+        );
+        if change_mouse_sensitivity {
+            user_settings.cycle_mouse_sensitivity();
+        }
+
+        let change_fov = Self::draw_setting_button(
+            asset_manager,
+            user_settings,
+            grid,
+            27,
+            &format!("FOV: {:.0}", user_settings.get_fov_degrees()),
+            &CHANGE_FOV_DESCRIPTION,
+        );
+        if change_fov {
+            user_settings.cycle_fov();
+        }
+
+        let change_creature_updates = Self::draw_setting_button(
+            asset_manager,
+            user_settings,
+            grid,
+            28,
+            &format!(
+                "Creature updates: {}",
+                user_settings.get_creature_update_budget()
+            ),
+            &CHANGE_CREATURE_UPDATES_DESCRIPTION,
+        );
+        if change_creature_updates {
+            user_settings.cycle_creature_update_budget();
+        }
+
+        Self::draw_setting_button(
+            asset_manager,
+            user_settings,
+            grid,
+            29,
+            "Key bindings",
+            &CHANGE_KEY_BINDINGS_DESCRIPTION,
+        )
     }
 
-    fn handle_toggle_dynamic_light(
+    /// draws a setting button with a description on hover at the grid index,
+    /// returns true if pressed
+    fn draw_setting_button(
         asset_manager: &AssetManager,
-        user_settings: &mut UserSettings,
-        x_start: f32,
-        y_start: f32,
-    ) {
+        user_settings: &UserSettings,
+        grid: &ButtonGrid,
+        index: usize,
+        text: &str,
+        description: &[&str],
+    ) -> bool {
+        let Some(rect) = grid.get_button_rect(index) else {
+            return false;
+        };
         let (width, height) = screen_size();
         let (mouse_x, mouse_y) = mouse_position();
-        let y = y_start + BUTTON_HEIGHT_OFFSET * 3.0;
         Self::draw_description(
             width,
             height,
-            &TOGGLE_SHADOWS_DESCRIPTION,
-            is_point_in_rect(x_start, y, BUTTON_WIDTH, BUTTON_HEIGHT, mouse_x, mouse_y),
+            description,
+            is_point_in_rect(rect.x, rect.y, rect.w, rect.h, mouse_x, mouse_y),
             &asset_manager.font,
         );
 
-        let should_change = draw_button(
-            Rect {
-                x: x_start,
-                y,
-                w: BUTTON_WIDTH,
-                h: BUTTON_HEIGHT,
-            },
-            match user_settings.shadow_type {
-                ShadowType::Soft => "Soft shadows",
-                ShadowType::Hard => "Hard shadows",
-                ShadowType::None => "No shadows",
-            },
+        draw_button(
+            rect,
+            text,
             BUTTON_TEXT_SIZE as u16,
             asset_manager,
             user_settings,
-        );
-        if should_change {
-            Self::change_shadow_type(user_settings);
-        }
+        )
     }
 
     fn change_shadow_type(user_settings: &mut UserSettings) {
+        if !SHADER_MANAGER_INSTANCE
+            .voxel_shader
+            .has_dynamic_lighting_support()
+        {
+            return;
+        }
         match user_settings.shadow_type {
             ShadowType::None => user_settings.shadow_type = ShadowType::Soft,
             ShadowType::Soft => user_settings.shadow_type = ShadowType::Hard,
@@ -295,7 +854,7 @@ impl SettingsContext {
 
         draw_centered_multiline_text(
             descriptions,
-            height * 0.92,
+            height - FOOTER_HEIGHT + DESCRIPTION_FONT_SIZE,
             width,
             DESCRIPTION_FONT_SIZE,
             TEXT_COLOR,
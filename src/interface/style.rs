@@ -1,4 +1,7 @@
-use macroquad::color::{BLACK, BLUE, Color, LIGHTGRAY, ORANGE, WHITE};
+use macroquad::{
+    color::{BLACK, BLUE, Color, LIGHTGRAY, ORANGE, WHITE},
+    texture::FilterMode,
+};
 
 pub const CLEAR_SCREEN_COLOR: Color = Color::from_rgba(0, 0, 0, 100);
 pub const BACKGROUND_COLOR: Color = ORANGE;
@@ -11,4 +14,15 @@ pub const SELECTED_COLOR: Color = BLUE;
 pub const SHADOW_COLOR: Color = CLEAR_SCREEN_COLOR;
 pub const SHADOW_OFFSET: f32 = 3.0;
 pub const MARGIN: f32 = 5.0;
+
+// font sizes shared by the menus and the hud, text is tuned here instead of per screen
 pub const MENU_TITLE_FONT_SIZE: f32 = 80.0;
+/// single character buttons like "+", "-" and "<"
+pub const SYMBOL_FONT_SIZE: f32 = 45.0;
+pub const LARGE_FONT_SIZE: f32 = 40.0;
+pub const MEDIUM_FONT_SIZE: f32 = 35.0;
+pub const BUTTON_FONT_SIZE: f32 = 30.0;
+pub const SMALL_FONT_SIZE: f32 = 25.0;
+pub const CAPTION_FONT_SIZE: f32 = 22.0;
+/// keeps the glyphs smooth when the text is drawn at sizes other than the rasterised one
+pub const FONT_FILTER: FilterMode = FilterMode::Linear;
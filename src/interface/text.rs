@@ -60,6 +60,17 @@ pub fn get_text_width(text: &str, font_size: impl Into<f32>, font: &Font) -> f32
     measure_text(text, Some(font), font_size.into() as u16, 1.0).width
 }
 
+/// returns the largest font size up to the given one that fits the text in the width
+pub fn fit_font_size(text: &str, max_width: f32, font_size: impl Into<f32>, font: &Font) -> f32 {
+    let font_size = font_size.into();
+    let text_width = get_text_width(text, font_size, font);
+    if text_width <= max_width || text_width <= 0.0 {
+        return font_size;
+    }
+
+    (font_size * max_width / text_width).floor().max(1.0)
+}
+
 pub fn draw_text_with_shadow(
     text: &str,
     pos: Vec2,
@@ -17,7 +17,7 @@ use super::{
     util::{draw_rect_with_shadow, is_point_in_rect},
 };
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TextInput {
     text: String,
     is_selected: bool,
@@ -47,6 +47,10 @@ impl TextInput {
         is_selected
     }
 
+    pub fn set_selected(&mut self, is_selected: bool) {
+        self.is_selected = is_selected;
+    }
+
     pub fn input_text(&mut self) {
         if !self.is_selected {
             return;
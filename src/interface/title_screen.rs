@@ -14,7 +14,7 @@ use crate::{
         help_menu::HelpMenuContext,
         interface_context::InterfaceScreen,
         settings_menu::SettingsContext,
-        style::TEXT_COLOR,
+        style::{LARGE_FONT_SIZE, TEXT_COLOR},
         text::{draw_text_with_shadow, draw_version_number, get_text_width},
         world_selection::WorldSelectionContext,
     },
@@ -24,7 +24,7 @@ use crate::{
 
 const TITLE_TEXT: &str = "Voxel World";
 const TITLE_SIZE: f32 = 120.0;
-const BUTTON_TEXT_SIZE: u16 = 40;
+const BUTTON_TEXT_SIZE: f32 = LARGE_FONT_SIZE;
 const BUTTON_WIDTH: f32 = 300.0;
 const BUTTON_HEIGHT: f32 = 70.0;
 const BUTTON_HEIGHT_OFFSET: f32 = BUTTON_HEIGHT * 1.2;
@@ -65,9 +65,9 @@ impl TitleScreenContext {
         next_frame().await;
 
         if should_enter_settings {
-            InterfaceScreen::Settings(SettingsContext)
+            InterfaceScreen::Settings(SettingsContext::new())
         } else if should_play {
-            InterfaceScreen::WorldSelection(WorldSelectionContext::new())
+            InterfaceScreen::WorldSelection(Box::new(WorldSelectionContext::new()))
         } else if should_enter_help_menu {
             InterfaceScreen::Help(HelpMenuContext::new(asset_manager))
         } else {
@@ -6,9 +6,13 @@ use macroquad::{
     text::{Font, TextDimensions, TextParams, draw_text_ex, measure_text},
 };
 
-use crate::{interface::style::SHADOW_COLOR, service::asset_manager::AssetManager};
+use crate::{
+    interface::style::SHADOW_COLOR, model::user_settings::UserSettings,
+    service::asset_manager::AssetManager,
+};
 
 const DISPLAY_MESSAGE_DURATION: f32 = 5.0;
+const NOTIFICATION_DURATION: f32 = 2.5;
 const FONT_COEF: f32 = 0.05;
 const MESSAGE_X: f32 = 20.0;
 const Y_COEF: f32 = 0.8;
@@ -50,16 +54,16 @@ impl TutorialMessage {
             TutorialMessage::Inventory => vec!["Press 'E' to enter the inventory menu"],
         };
 
-        CurrentMessage::new(texts)
+        CurrentMessage::new(texts.into_iter().map(String::from).collect())
     }
 }
 
 struct CurrentMessage {
-    texts: Vec<&'static str>,
+    texts: Vec<String>,
     delta: f32,
 }
 impl CurrentMessage {
-    fn new(texts: Vec<&'static str>) -> Self {
+    fn new(texts: Vec<String>) -> Self {
         Self {
             texts,
             delta: DISPLAY_MESSAGE_DURATION,
@@ -85,9 +89,9 @@ impl TutorialMessages {
         }
     }
 
-    /// shows the message if it wasn't shown before
-    pub fn show(&mut self, tutorial_message: TutorialMessage) {
-        if self.current_message.is_some() {
+    /// shows the message if it wasn't shown before and tutorials are enabled
+    pub fn show(&mut self, tutorial_message: TutorialMessage, user_settings: &UserSettings) {
+        if self.current_message.is_some() || !user_settings.show_tutorials {
             return;
         }
 
@@ -98,6 +102,21 @@ impl TutorialMessages {
         self.current_message = Some(tutorial_message.create_message_to_display());
     }
 
+    /// briefly shows the text before the current message, regardless of the tutorial settings
+    pub fn show_notification(&mut self, text: String) {
+        let current_message = self
+            .current_message
+            .get_or_insert_with(|| CurrentMessage::new(vec![]));
+        current_message.texts.insert(0, text);
+        current_message.delta = NOTIFICATION_DURATION;
+    }
+
+    /// forgets the seen messages so they can be shown again
+    pub fn reset(&mut self) {
+        self.seen_messages.clear();
+        self.current_message = None;
+    }
+
     pub fn update(&mut self, delta: f32) {
         if let Some(current_message) = &mut self.current_message {
             current_message.delta -= delta;
@@ -119,7 +138,7 @@ impl TutorialMessages {
             debug_assert!(!message.texts.is_empty());
             debug_assert!(message.delta > 0.0);
 
-            let text = message.texts[0];
+            let text = &message.texts[0];
             let font_size = (height * FONT_COEF) as u16;
             let font = Some(&asset_manager.font);
             let y = height * Y_COEF;
@@ -187,7 +206,7 @@ mod tests {
     #[test]
     fn test_show_should_add() {
         let mut tutorial = TutorialMessages::new();
-        tutorial.show(TutorialMessage::Initial);
+        tutorial.show(TutorialMessage::Initial, &UserSettings::default());
 
         assert!(tutorial.seen_messages.contains(&TutorialMessage::Initial));
         assert!(tutorial.current_message.is_some());
@@ -197,13 +216,13 @@ mod tests {
     fn test_show_should_not_add() {
         let mut tutorial = TutorialMessages::new();
 
-        tutorial.show(TutorialMessage::Initial);
+        tutorial.show(TutorialMessage::Initial, &UserSettings::default());
         assert!(tutorial.current_message.is_some());
 
         tutorial.current_message = None;
         let seen_before = tutorial.seen_messages.clone();
 
-        tutorial.show(TutorialMessage::Initial);
+        tutorial.show(TutorialMessage::Initial, &UserSettings::default());
 
         assert_eq!(tutorial.seen_messages, seen_before);
         assert!(tutorial.current_message.is_none());
@@ -213,10 +232,10 @@ mod tests {
     fn test_show_should_not_override_existing_message() {
         let mut tutorial = TutorialMessages::new();
 
-        tutorial.show(TutorialMessage::Initial);
+        tutorial.show(TutorialMessage::Initial, &UserSettings::default());
         assert!(tutorial.current_message.is_some());
 
-        tutorial.show(TutorialMessage::Map);
+        tutorial.show(TutorialMessage::Map, &UserSettings::default());
 
         assert!(tutorial.seen_messages.contains(&TutorialMessage::Initial));
         assert!(!tutorial.seen_messages.contains(&TutorialMessage::Map));
@@ -226,7 +245,7 @@ mod tests {
     #[test]
     fn test_update_removes_messages() {
         let mut tutorial = TutorialMessages::new();
-        tutorial.show(TutorialMessage::Initial);
+        tutorial.show(TutorialMessage::Initial, &UserSettings::default());
 
         assert!(tutorial.current_message.is_some());
         let initial_text_count = tutorial.current_message.as_ref().unwrap().texts.len();
@@ -244,10 +263,50 @@ mod tests {
         assert!(tutorial.current_message.is_none());
     }
 
+    #[test]
+    fn test_show_disabled() {
+        let mut tutorial = TutorialMessages::new();
+        let mut user_settings = UserSettings::default();
+        user_settings.show_tutorials = false;
+        tutorial.show(TutorialMessage::Initial, &user_settings);
+
+        assert!(tutorial.seen_messages.is_empty());
+        assert!(tutorial.current_message.is_none());
+    }
+
+    #[test]
+    fn test_notification_shown_before_current_message() {
+        let mut tutorial = TutorialMessages::new();
+        tutorial.show(TutorialMessage::Initial, &UserSettings::default());
+        let initial_text_count = tutorial.current_message.as_ref().unwrap().texts.len();
+
+        tutorial.show_notification("Saved".to_owned());
+        let message = tutorial.current_message.as_ref().unwrap();
+        assert_eq!(message.texts[0], "Saved");
+        assert_eq!(message.texts.len(), initial_text_count + 1);
+
+        tutorial.update(NOTIFICATION_DURATION);
+        let message = tutorial.current_message.as_ref().unwrap();
+        assert_eq!(message.texts.len(), initial_text_count);
+    }
+
+    #[test]
+    fn test_reset_allows_replay() {
+        let mut tutorial = TutorialMessages::new();
+        tutorial.show(TutorialMessage::Initial, &UserSettings::default());
+        tutorial.reset();
+
+        assert!(tutorial.seen_messages.is_empty());
+        assert!(tutorial.current_message.is_none());
+
+        tutorial.show(TutorialMessage::Initial, &UserSettings::default());
+        assert!(tutorial.current_message.is_some());
+    }
+
     #[test]
     fn test_create_and_convert_dto() {
         let mut tutorial = TutorialMessages::new();
-        tutorial.show(TutorialMessage::Initial);
+        tutorial.show(TutorialMessage::Initial, &UserSettings::default());
         tutorial.current_message = None;
 
         let dto = tutorial.create_dto();
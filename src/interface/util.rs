@@ -30,12 +30,13 @@ pub fn draw_item_name_box(
     x: f32,
     y: f32,
     voxel_name: &str,
-    count: u8,
+    count: impl Into<u32>,
     font_size: f32,
     font: &Font,
 ) {
     const TEXT_BOX_X_OFFSET: f32 = 3.0;
     const TEXT_BOX_Y_OFFSET: f32 = -5.0;
+    let count = count.into();
     use_str_buffer(|buffer| {
         write!(buffer, "{voxel_name} ({count})").expect("error writing to text buffer");
         draw_rectangle(
@@ -15,17 +15,26 @@ use crate::{
         background::draw_background,
         button::draw_back_button,
         interface_context::InterfaceScreen,
-        style::TEXT_COLOR,
+        style::{
+            BUTTON_COLOR, BUTTON_FONT_SIZE, CAPTION_FONT_SIZE, LARGE_FONT_SIZE, MARGIN,
+            MEDIUM_FONT_SIZE, SECONDARY_TEXT_COLOR, SMALL_FONT_SIZE, TEXT_COLOR,
+        },
         text::{draw_centered_multiline_text, draw_game_text, draw_version_number},
         title_screen::TitleScreenContext,
+        util::draw_rect_with_shadow,
+    },
+    model::{
+        user_settings::UserSettings,
+        world_rules::{WorldGenPreset, WorldRules},
     },
-    model::user_settings::UserSettings,
     service::{
         asset_manager::AssetManager,
         persistence::{
             world_list_persistence::{read_world_list, write_world_list},
+            world_metadata_persistence::load_world_metadata,
             world_persistence,
         },
+        world_time::WorldTime,
     },
     voxel_engine::VoxelEngine,
 };
@@ -34,36 +43,57 @@ use super::{
     button::draw_button, list_input::ListInput, text::get_text_width, text_input::TextInput,
 };
 
-const LABEL_FONT_SIZE: f32 = 40.0;
+const LABEL_FONT_SIZE: f32 = LARGE_FONT_SIZE;
 const TEXT_INPUT_SIZE: Vec2 = vec2(350.0, 50.0);
-const TEXT_INPUT_FONT_SIZE: u16 = 36;
+const TEXT_INPUT_FONT_SIZE: u16 = MEDIUM_FONT_SIZE as u16;
 const PLAY_BUTTON_SIZE: Vec2 = vec2(220.0, 50.0);
-const PLAY_BUTTON_FONT_SIZE: u16 = 40;
+const PLAY_BUTTON_FONT_SIZE: f32 = LARGE_FONT_SIZE;
 const PLAY_BUTTON_Y_COEF: f32 = 0.5;
-const NOTIFICATION_TEXT_SIZE: f32 = 35.0;
+const NOTIFICATION_TEXT_SIZE: f32 = MEDIUM_FONT_SIZE;
 const DELETE_BUTTON_SIZE: Vec2 = vec2(220.0, 50.0);
-const DELETE_BUTTON_FONT_SIZE: u16 = 35;
+const DELETE_BUTTON_FONT_SIZE: f32 = MEDIUM_FONT_SIZE;
 const DELETE_BUTTON_Y_COEF: f32 = 0.9;
 const WORLD_LIST_WIDTH: f32 = 450.0;
-const WORLD_LIST_FONT_SIZE: f32 = 25.0;
+const WORLD_LIST_FONT_SIZE: f32 = SMALL_FONT_SIZE;
 const WORLD_LIST_ROWS: usize = 5;
 const MIN_WORLD_NAME_LENGTH: usize = 3;
 const WORLD_NAME_INPUT_Y_COEF: f32 = 0.2;
+const SEED_INPUT_SIZE: Vec2 = vec2(260.0, 50.0);
+/// enough digits for any u64
+const MAX_SEED_LENGTH: usize = 20;
+const SEED_LABEL_FONT_SIZE: f32 = SMALL_FONT_SIZE;
+const WORLD_INFO_WIDTH: f32 = 260.0;
+const WORLD_INFO_FONT_SIZE: f32 = CAPTION_FONT_SIZE;
+const WORLD_INFO_X_OFFSET: f32 = 30.0;
+const WORLD_RULE_BUTTON_SIZE: Vec2 = vec2(260.0, 50.0);
+const WORLD_RULE_BUTTON_FONT_SIZE: f32 = BUTTON_FONT_SIZE;
 
 pub struct WorldSelectionContext {
     world_name_input: TextInput,
+    /// optional numeric seed for a new world
+    seed_input: TextInput,
     error: String,
     should_enter: bool,
     world_list: ListInput,
+    /// name of the selected world and the lines describing it
+    world_info: Option<(String, Vec<String>)>,
+    /// rules used if a new world is created
+    new_world_rules: WorldRules,
+    /// fills the generation config of the new world rules
+    new_world_preset: WorldGenPreset,
 }
 impl WorldSelectionContext {
     pub fn new() -> Self {
         clear_input_queue();
         Self {
             world_name_input: TextInput::new(20),
+            seed_input: TextInput::new(MAX_SEED_LENGTH),
             error: "".to_owned(),
             should_enter: false,
             world_list: ListInput::new(read_world_list(), WORLD_LIST_ROWS),
+            world_info: None,
+            new_world_rules: WorldRules::default(),
+            new_world_preset: WorldGenPreset::default(),
         }
     }
 
@@ -73,11 +103,15 @@ impl WorldSelectionContext {
         user_settings: &UserSettings,
     ) -> Option<Box<VoxelEngine>> {
         if self.should_enter {
+            // the rules and seed inputs only apply to worlds that don't exist yet
+            let is_new_world = self.is_new_world();
             self.store_world_names(true);
             let voxel_engine = Box::new(VoxelEngine::new(
                 self.world_name_input.get_text(),
                 asset_manager.clone(),
                 user_settings.clone(),
+                is_new_world.then_some(self.new_world_rules),
+                self.get_new_world_seed().filter(|_| is_new_world),
             ));
             Some(voxel_engine)
         } else {
@@ -96,8 +130,10 @@ impl WorldSelectionContext {
 
         Self::draw_input_label(width, height, &asset_manager.font);
         self.handle_world_name_input(width, height, &asset_manager.font);
+        self.handle_seed_input(width, height, &asset_manager.font);
         self.handle_world_list(width, height, &asset_manager.font);
         self.handle_play_button(asset_manager, user_settings, width, height);
+        self.handle_new_world_rules(asset_manager, user_settings, width, height);
 
         let should_go_back = draw_back_button(asset_manager, user_settings);
 
@@ -130,6 +166,127 @@ impl WorldSelectionContext {
         }
     }
 
+    /// draws the rule buttons next to the play button when a new world would be created
+    fn handle_new_world_rules(
+        &mut self,
+        asset_manager: &AssetManager,
+        user_settings: &UserSettings,
+        width: f32,
+        height: f32,
+    ) {
+        if !self.is_new_world() {
+            return;
+        }
+
+        let toggle_mob_griefing = draw_button(
+            Rect {
+                x: (width + PLAY_BUTTON_SIZE.x) / 2.0 + MARGIN,
+                y: height * PLAY_BUTTON_Y_COEF,
+                w: WORLD_RULE_BUTTON_SIZE.x,
+                h: WORLD_RULE_BUTTON_SIZE.y,
+            },
+            if self.new_world_rules.mob_griefing {
+                "Mob griefing:ON"
+            } else {
+                "Mob griefing:OFF"
+            },
+            WORLD_RULE_BUTTON_FONT_SIZE,
+            asset_manager,
+            user_settings,
+        );
+        if toggle_mob_griefing {
+            self.new_world_rules.mob_griefing = !self.new_world_rules.mob_griefing;
+        }
+
+        let change_tree_density = draw_button(
+            Rect {
+                x: (width - PLAY_BUTTON_SIZE.x) / 2.0 - MARGIN - WORLD_RULE_BUTTON_SIZE.x,
+                y: height * PLAY_BUTTON_Y_COEF,
+                w: WORLD_RULE_BUTTON_SIZE.x,
+                h: WORLD_RULE_BUTTON_SIZE.y,
+            },
+            &format!("Trees:{}", self.new_world_rules.tree_density.get_name()),
+            WORLD_RULE_BUTTON_FONT_SIZE,
+            asset_manager,
+            user_settings,
+        );
+        if change_tree_density {
+            self.new_world_rules.tree_density = self.new_world_rules.tree_density.next();
+        }
+
+        let change_preset = draw_button(
+            Rect {
+                x: (width - PLAY_BUTTON_SIZE.x) / 2.0 - MARGIN - WORLD_RULE_BUTTON_SIZE.x,
+                y: height * PLAY_BUTTON_Y_COEF + WORLD_RULE_BUTTON_SIZE.y + MARGIN,
+                w: WORLD_RULE_BUTTON_SIZE.x,
+                h: WORLD_RULE_BUTTON_SIZE.y,
+            },
+            &format!("Terrain:{}", self.new_world_preset.get_name()),
+            WORLD_RULE_BUTTON_FONT_SIZE,
+            asset_manager,
+            user_settings,
+        );
+        if change_preset {
+            self.new_world_preset = self.new_world_preset.next();
+            self.new_world_rules.world_gen = self.new_world_preset.get_config();
+        }
+    }
+
+    fn is_new_world(&self) -> bool {
+        let world_name = self.world_name_input.get_text();
+        !world_name.is_empty()
+            && !self
+                .world_list
+                .get_all_values()
+                .iter()
+                .any(|existing| existing == world_name)
+    }
+
+    /// the entered seed, none if left blank so the world name is hashed instead
+    fn get_new_world_seed(&self) -> Option<u64> {
+        let seed = self.seed_input.get_text().trim();
+        if seed.is_empty() {
+            None
+        } else {
+            seed.parse().ok()
+        }
+    }
+
+    /// draws the seed input next to the world name when a new world would be created
+    fn handle_seed_input(&mut self, width: f32, height: f32, font: &Font) {
+        if !self.is_new_world() {
+            self.seed_input.set_selected(false);
+            return;
+        }
+        let seed_input_x = (width + TEXT_INPUT_SIZE.x) / 2.0 + MARGIN;
+        let seed_input_y = height * WORLD_NAME_INPUT_Y_COEF;
+
+        let _set_selected = self.seed_input.input_selection(
+            seed_input_x,
+            seed_input_y,
+            SEED_INPUT_SIZE.x,
+            SEED_INPUT_SIZE.y,
+        );
+        self.seed_input.input_text();
+
+        draw_game_text(
+            "Seed (optional):",
+            seed_input_x,
+            seed_input_y - MARGIN,
+            SEED_LABEL_FONT_SIZE,
+            TEXT_COLOR,
+            font,
+        );
+        self.seed_input.draw(
+            seed_input_x,
+            seed_input_y,
+            SEED_INPUT_SIZE.x,
+            SEED_INPUT_SIZE.y,
+            TEXT_INPUT_FONT_SIZE,
+            font,
+        );
+    }
+
     fn handle_delete_button(
         &mut self,
         asset_manager: &AssetManager,
@@ -175,6 +332,95 @@ impl WorldSelectionContext {
         if let Some(selection) = possible_selection {
             self.world_name_input.set_text(selection);
         }
+
+        self.refresh_world_info();
+        self.draw_world_info(
+            world_list_x + WORLD_LIST_WIDTH + WORLD_INFO_X_OFFSET,
+            world_list_y,
+            font,
+        );
+    }
+
+    /// reads the metadata of the selected world only when the selection changes
+    fn refresh_world_info(&mut self) {
+        let Some(selected) = self.world_list.get_selected() else {
+            self.world_info = None;
+            return;
+        };
+        let is_up_to_date = self
+            .world_info
+            .as_ref()
+            .is_some_and(|(world_name, _lines)| *world_name == selected);
+        if is_up_to_date {
+            return;
+        }
+
+        let lines = Self::create_world_info_lines(&selected);
+        self.world_info = Some((selected, lines));
+    }
+
+    fn create_world_info_lines(world_name: &str) -> Vec<String> {
+        let Some(world_metadata) = load_world_metadata(world_name) else {
+            return vec!["No world info".to_owned()];
+        };
+        let time_of_day = if WorldTime::new(world_metadata.delta).is_day() {
+            "Day"
+        } else {
+            "Night"
+        };
+        let weather = if world_metadata.rain_system.is_raining() {
+            "Raining"
+        } else {
+            "Clear"
+        };
+
+        vec![
+            format!(
+                "Created: {}",
+                world_metadata.statistics.format_creation_date()
+            ),
+            format!(
+                "Play time: {}",
+                world_metadata.statistics.format_play_time()
+            ),
+            format!("Time: {time_of_day}"),
+            format!("Weather: {weather}"),
+            format!(
+                "Mob griefing: {}",
+                if world_metadata.rules.mob_griefing {
+                    "On"
+                } else {
+                    "Off"
+                }
+            ),
+            format!("Trees: {}", world_metadata.rules.tree_density.get_name()),
+            format!(
+                "Terrain: {}",
+                WorldGenPreset::from_config(world_metadata.rules.world_gen)
+                    .map_or("Custom", WorldGenPreset::get_name)
+            ),
+            format!("Seed: {}", world_metadata.get_seed(world_name)),
+        ]
+    }
+
+    fn draw_world_info(&self, x: f32, y: f32, font: &Font) {
+        let Some((_world_name, lines)) = &self.world_info else {
+            return;
+        };
+        let line_height = WORLD_INFO_FONT_SIZE * 1.25;
+        let height = line_height * lines.len() as f32 + MARGIN * 2.0;
+        draw_rect_with_shadow(x, y, WORLD_INFO_WIDTH, height, BUTTON_COLOR);
+
+        for (index, line) in lines.iter().enumerate() {
+            draw_game_text(
+                line,
+                x + MARGIN,
+                y + MARGIN + line_height * (index as f32 + 0.8),
+                WORLD_INFO_FONT_SIZE,
+                SECONDARY_TEXT_COLOR,
+                font,
+            );
+        }
     }
 
     fn draw_notification_text(&mut self, width: f32, height: f32, font: &Font) {
@@ -287,6 +533,11 @@ impl WorldSelectionContext {
                 format!("World name should be at least {MIN_WORLD_NAME_LENGTH} characters");
         } else if is_whitespace() {
             self.error = "World name cannot be blank".to_owned();
+        } else if self.is_new_world()
+            && !self.seed_input.get_text().trim().is_empty()
+            && self.get_new_world_seed().is_none()
+        {
+            self.error = "Seed should be a positive whole number".to_owned();
         } else {
             self.error = "".to_owned();
         }
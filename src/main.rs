@@ -7,7 +7,8 @@ use macroquad::{
 
 use crate::{
     game_state::GameState,
-    graphics::shader_manager::ShaderManager,
+    graphics::shader_manager::{SHADER_MANAGER_INSTANCE, ShaderManager},
+    model::user_settings::ShadowType,
     service::{
         asset_manager::AssetManager,
         persistence::{
@@ -44,7 +45,13 @@ async fn main() {
     ShaderManager::initialise_global_instance();
     initialise_save_directory();
     let asset_manager_result = AssetManager::new().await;
-    let user_settings = read_or_initialise_user_settings();
+    let mut user_settings = read_or_initialise_user_settings();
+    if !SHADER_MANAGER_INSTANCE
+        .voxel_shader
+        .has_dynamic_lighting_support()
+    {
+        user_settings.shadow_type = ShadowType::None;
+    }
 
     let mut state = match asset_manager_result {
         Ok(asset_manager) => {
@@ -1,8 +1,22 @@
-use bincode::{Decode, Encode};
-
-use crate::{graphics::mesh_generator::MeshGenerator, model::location::AreaLocation};
-
-use super::{location::InternalLocation, voxel::Voxel};
+use std::{
+    collections::BTreeMap,
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+use bincode::{
+    Decode, Encode,
+    de::Decoder,
+    enc::Encoder,
+    error::{DecodeError, EncodeError},
+    impl_borrow_decode,
+};
+
+use crate::{
+    graphics::mesh_generator::MeshGenerator, model::location::AreaLocation,
+    service::area_generation::generator::GENERATION_VERSION,
+};
+
+use super::{location::InternalLocation, orientation::Orientation, voxel::Voxel};
 
 pub const AREA_SIZE: u32 = 16;
 pub const AREA_HEIGHT: u32 = 128;
@@ -11,16 +25,25 @@ pub const VOXELS_IN_AREA: usize = (AREA_SIZE * AREA_SIZE * AREA_HEIGHT) as usize
 #[derive(Debug, Clone)]
 pub struct Area {
     pub has_changed: bool,
+    /// version of the generation algorithm that created the area, 0 if unknown
+    pub generation_version: u16,
+    /// set once the area is changed after it was generated, such areas are never regenerated
+    pub is_modified: bool,
     area_location: AreaLocation,
     voxels: Box<[Voxel]>,
+    /// orientations of the voxels that aren't turned up, by voxel index
+    orientations: BTreeMap<u32, Orientation>,
     max_height: Box<[u8]>,
 }
 impl Area {
     pub fn new(area_location: AreaLocation) -> Self {
         Self {
             has_changed: true,
+            generation_version: 0,
+            is_modified: false,
             area_location,
             voxels: vec![Voxel::None; VOXELS_IN_AREA].into_boxed_slice(),
+            orientations: BTreeMap::new(),
             max_height: vec![(AREA_HEIGHT - 1) as u8; (AREA_SIZE * AREA_SIZE) as usize]
                 .into_boxed_slice(),
         }
@@ -93,11 +116,15 @@ impl Area {
         local_location: InternalLocation,
         voxel: Voxel,
     ) {
-        self.voxels[Self::convert_to_index(local_location)] = voxel;
+        let index = Self::convert_to_index(local_location);
+        self.voxels[index] = voxel;
+        self.clear_orientation(index);
     }
 
     pub fn set(&mut self, local_location: InternalLocation, voxel: Voxel) {
-        self.voxels[Self::convert_to_index(local_location)] = voxel;
+        let index = Self::convert_to_index(local_location);
+        self.voxels[index] = voxel;
+        self.clear_orientation(index);
 
         if voxel == Voxel::None || Voxel::TRANSPARENT.contains(&voxel) {
             self.set_column_height(local_location);
@@ -110,10 +137,55 @@ impl Area {
         }
     }
 
+    pub fn get_orientation(&self, local_location: InternalLocation) -> Orientation {
+        if self.orientations.is_empty() {
+            return Orientation::Up;
+        }
+
+        self.orientations
+            .get(&(Self::convert_to_index(local_location) as u32))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// sets the voxel turned towards the orientation
+    pub fn set_with_orientation(
+        &mut self,
+        local_location: InternalLocation,
+        voxel: Voxel,
+        orientation: Orientation,
+    ) {
+        self.set(local_location, voxel);
+        if orientation != Orientation::Up {
+            self.orientations
+                .insert(Self::convert_to_index(local_location) as u32, orientation);
+        }
+    }
+
+    #[inline(always)]
+    fn clear_orientation(&mut self, index: usize) {
+        if !self.orientations.is_empty() {
+            self.orientations.remove(&(index as u32));
+        }
+    }
+
+    /// the area was generated by an older version and can be regenerated without losing changes
+    pub fn is_stale(&self) -> bool {
+        !self.has_changed && !self.is_modified && self.generation_version < GENERATION_VERSION
+    }
+
     pub fn get_area_location(&self) -> AreaLocation {
         self.area_location
     }
 
+    /// hash of the voxels, used to check if data made from the area is still up to date
+    pub fn calculate_checksum(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.voxels.hash(&mut hasher);
+        self.orientations.hash(&mut hasher);
+        hasher.finish()
+    }
+
     pub fn get_x(&self) -> u32 {
         self.area_location.x
     }
@@ -201,16 +273,22 @@ impl Area {
     }
 }
 
-#[derive(Debug, Encode, Decode)]
+#[derive(Debug)]
 pub struct AreaDTO {
     pub voxels: Box<[Voxel]>,
+    pub generation_version: u16,
+    pub is_modified: bool,
+    pub orientations: BTreeMap<u32, Orientation>,
 }
 impl AreaDTO {
     pub fn into_area(self, area_location: AreaLocation, has_changed: bool) -> Area {
         let mut area = Area {
             has_changed,
+            generation_version: self.generation_version,
+            is_modified: self.is_modified,
             area_location,
             voxels: self.voxels,
+            orientations: self.orientations,
             max_height: vec![255; (AREA_SIZE * AREA_SIZE) as usize].into_boxed_slice(),
         };
         area.update_all_column_heights();
@@ -222,9 +300,46 @@ impl From<Area> for AreaDTO {
     fn from(value: Area) -> Self {
         Self {
             voxels: value.voxels,
+            generation_version: value.generation_version,
+            is_modified: value.is_modified,
+            orientations: value.orientations,
         }
     }
 }
+impl Encode for AreaDTO {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        self.voxels.encode(encoder)?;
+        self.generation_version.encode(encoder)?;
+        self.is_modified.encode(encoder)?;
+        self.orientations.encode(encoder)
+    }
+}
+impl<Context> Decode<Context> for AreaDTO {
+    /// areas saved before the generation version existed end after the voxels,
+    /// they are treated as modified so they are never regenerated,
+    /// areas saved before the orientations existed have all voxels turned up
+    fn decode<D: Decoder<Context = Context>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        let voxels = Box::<[Voxel]>::decode(decoder)?;
+        let (generation_version, is_modified) = match u16::decode(decoder) {
+            Ok(generation_version) => (generation_version, bool::decode(decoder)?),
+            Err(DecodeError::UnexpectedEnd { .. }) => (0, true),
+            Err(err) => return Err(err),
+        };
+        let orientations = match BTreeMap::decode(decoder) {
+            Ok(orientations) => orientations,
+            Err(DecodeError::UnexpectedEnd { .. }) => BTreeMap::new(),
+            Err(err) => return Err(err),
+        };
+
+        Ok(Self {
+            voxels,
+            generation_version,
+            is_modified,
+            orientations,
+        })
+    }
+}
+impl_borrow_decode!(AreaDTO);
 
 #[cfg(test)]
 mod tests {
@@ -302,4 +417,20 @@ mod tests {
         assert_eq!(area.sample_height(0, 0), 5);
         assert_eq!(area.sample_height(1, 0), 1);
     }
+
+    #[test]
+    fn test_orientation_reset_when_replaced() {
+        let mut area = Area::new(AreaLocation::new(0, 0));
+        let location = InternalLocation::new(3, 4, 20);
+        area.set_with_orientation(location, Voxel::WoodStairs, Orientation::NegativeX);
+        assert_eq!(area.get(location), Voxel::WoodStairs);
+        assert_eq!(area.get_orientation(location), Orientation::NegativeX);
+        assert_eq!(
+            area.get_orientation(InternalLocation::new(3, 4, 21)),
+            Orientation::Up
+        );
+
+        area.set(location, Voxel::Stone);
+        assert_eq!(area.get_orientation(location), Orientation::Up);
+    }
 }
@@ -1,8 +1,18 @@
-use bincode::{Decode, Encode};
+use bincode::{
+    Decode, Encode,
+    de::Decoder,
+    enc::Encoder,
+    error::{DecodeError, EncodeError},
+    impl_borrow_decode,
+};
 
 use crate::model::voxel::{MAX_VOXEL_VARIANTS, Voxel};
 
 pub const MAX_ITEMS_PER_SLOT: u8 = 100;
+/// set on the encoded count when charges follow it, the count never reaches this bit
+/// so items saved before charges existed decode unchanged
+const HAS_CHARGES_FLAG: u8 = 0x80;
+const _COUNT_FITS_BELOW_FLAG: () = assert!(MAX_ITEMS_PER_SLOT < HAS_CHARGES_FLAG);
 
 /// stores the quantity of all inventory items
 #[derive(Debug, Clone)]
@@ -25,17 +35,93 @@ impl AvailableItems {
     }
 }
 
-#[derive(Debug, Clone, Copy, Encode, Decode)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Item {
     pub voxel: Voxel,
     pub count: u8,
+    /// remaining durability or uses, items with charges don't stack
+    pub charges: Option<u16>,
 }
 impl Item {
     pub fn new(voxel: Voxel, count: u8) -> Item {
         debug_assert!(voxel != Voxel::None);
         debug_assert!(count > 0);
         debug_assert!(count <= MAX_ITEMS_PER_SLOT);
-        Item { voxel, count }
+        Item {
+            voxel,
+            count,
+            charges: None,
+        }
+    }
+
+    pub fn can_stack_with(&self, other: &Item) -> bool {
+        self.voxel == other.voxel && self.charges.is_none() && other.charges.is_none()
+    }
+
+    /// returns the taken half rounded up and the remainder, None if there is only one item
+    pub fn split_half(self) -> Option<(Item, Item)> {
+        if self.count <= 1 {
+            return None;
+        }
+        let taken = self.count.div_ceil(2);
+
+        Some((
+            Item {
+                count: taken,
+                ..self
+            },
+            Item {
+                count: self.count - taken,
+                ..self
+            },
+        ))
+    }
+}
+impl Encode for Item {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        self.voxel.encode(encoder)?;
+        match self.charges {
+            Some(charges) => {
+                (self.count | HAS_CHARGES_FLAG).encode(encoder)?;
+                charges.encode(encoder)
+            }
+            None => self.count.encode(encoder),
+        }
+    }
+}
+impl<Context> Decode<Context> for Item {
+    fn decode<D: Decoder<Context = Context>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        let voxel = Voxel::decode(decoder)?;
+        let encoded_count = u8::decode(decoder)?;
+        let charges = if encoded_count & HAS_CHARGES_FLAG != 0 {
+            Some(u16::decode(decoder)?)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            voxel,
+            count: encoded_count & !HAS_CHARGES_FLAG,
+            charges,
+        })
+    }
+}
+impl_borrow_decode!(Item);
+
+/// bitset of the hotbar slots that can't be moved or used up by crafting
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Encode, Decode)]
+pub struct HotbarLocks(u8);
+impl HotbarLocks {
+    const _FITS_HOTBAR: () = assert!(Inventory::SELECTED_SIZE <= u8::BITS as usize);
+
+    pub fn is_locked(self, index: usize) -> bool {
+        debug_assert!(index < Inventory::SELECTED_SIZE);
+        self.0 & (1 << index) != 0
+    }
+
+    pub fn toggle(&mut self, index: usize) {
+        debug_assert!(index < Inventory::SELECTED_SIZE);
+        self.0 ^= 1 << index;
     }
 }
 
@@ -56,7 +142,7 @@ impl Inventory {
             .flatten();
 
         for inventory_item in items_iterator {
-            if inventory_item.voxel == item.voxel {
+            if inventory_item.can_stack_with(&item) {
                 let to_add = (MAX_ITEMS_PER_SLOT - inventory_item.count).min(item.count);
                 inventory_item.count += to_add;
                 item.count -= to_add;
@@ -75,10 +161,97 @@ impl Inventory {
         }
     }
 
+    /// moves the item between the inventory grid and the hotbar, partial stacks are filled first
+    /// and the remainder that doesn't fit stays in the slot, locked hotbar slots aren't filled
+    pub fn quick_transfer(
+        &mut self,
+        is_from_hotbar: bool,
+        index: usize,
+        hotbar_locks: HotbarLocks,
+    ) {
+        let slot = if is_from_hotbar {
+            self.selected[index]
+        } else {
+            self.items[index]
+        };
+        let Some(item) = slot else {
+            return;
+        };
+
+        let destination: Vec<_> = if is_from_hotbar {
+            self.items.iter_mut().collect()
+        } else {
+            self.selected
+                .iter_mut()
+                .enumerate()
+                .filter(|(index, _)| !hotbar_locks.is_locked(*index))
+                .map(|(_, slot)| slot)
+                .collect()
+        };
+        let remainder = Self::add_to_slots(item, destination);
+
+        if is_from_hotbar {
+            self.selected[index] = remainder;
+        } else {
+            self.items[index] = remainder;
+        }
+    }
+
+    /// returns the part of the item that didn't fit
+    fn add_to_slots(mut item: Item, mut slots: Vec<&mut Option<Item>>) -> Option<Item> {
+        for slot_item in slots.iter_mut().filter_map(|slot| slot.as_mut()) {
+            if slot_item.can_stack_with(&item) {
+                let to_add = (MAX_ITEMS_PER_SLOT - slot_item.count).min(item.count);
+                slot_item.count += to_add;
+                item.count -= to_add;
+                if item.count == 0 {
+                    return None;
+                }
+            }
+        }
+
+        match slots.into_iter().find(|slot| slot.is_none()) {
+            Some(empty_slot) => {
+                *empty_slot = Some(item);
+                None
+            }
+            None => Some(item),
+        }
+    }
+
+    /// returns true if the whole item can be added without losing any of it
+    pub fn can_add_item(&self, item: Item) -> bool {
+        let free_space: u32 = self
+            .selected
+            .iter()
+            .chain(self.items.iter())
+            .map(|slot| match slot {
+                Some(inventory_item) if inventory_item.can_stack_with(&item) => {
+                    (MAX_ITEMS_PER_SLOT - inventory_item.count) as u32
+                }
+                Some(_) => 0,
+                None => MAX_ITEMS_PER_SLOT as u32,
+            })
+            .sum();
+
+        free_space >= item.count as u32
+    }
+
     /// creates a table of all the voxels in the inventory and their count
     pub fn create_all_items_map(&self) -> AvailableItems {
+        self.create_unlocked_items_map(HotbarLocks::default())
+    }
+
+    /// creates a table of the voxels and their count, ignoring locked hotbar slots
+    pub fn create_unlocked_items_map(&self, hotbar_locks: HotbarLocks) -> AvailableItems {
         let mut map = AvailableItems::new_empty();
-        for item in self.selected.iter().chain(self.items.iter()).flatten() {
+        let unlocked_selected = self
+            .selected
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !hotbar_locks.is_locked(*index))
+            .map(|(_, slot)| slot);
+        for item in unlocked_selected.chain(self.items.iter()).flatten() {
             map.add(item.voxel, item.count);
         }
 
@@ -86,11 +259,22 @@ impl Inventory {
     }
 
     /// unchecked operation, may not remove desired amount
-    pub fn remove_item(&mut self, mut item: Item) {
+    pub fn remove_item(&mut self, item: Item) {
+        self.remove_unlocked_item(item, HotbarLocks::default());
+    }
+
+    /// unchecked operation, may not remove desired amount, locked hotbar slots are skipped
+    pub fn remove_unlocked_item(&mut self, mut item: Item, hotbar_locks: HotbarLocks) {
+        let unlocked_selected = self
+            .selected
+            .iter_mut()
+            .enumerate()
+            .filter(|(index, _)| !hotbar_locks.is_locked(*index))
+            .map(|(_, slot)| slot);
         let iterator = self
             .items
             .iter_mut()
-            .chain(self.selected.iter_mut())
+            .chain(unlocked_selected)
             .filter(|i| i.is_some() && i.unwrap().voxel == item.voxel);
 
         for slot in iterator {
@@ -119,6 +303,23 @@ impl Inventory {
         self.selected[index] = if item.count == 0 { None } else { Some(item) }
     }
 
+    /// returns true if one of the selected items can be replaced without losing the new item
+    pub fn can_replace_selected_one(&self, index: usize, new_item: Item) -> bool {
+        self.selected[index].is_some_and(|item| item.count == 1 || self.can_add_item(new_item))
+    }
+
+    /// unchecked operation, replaces one of the selected items with the new item,
+    /// if the stack has more items the new item is added to the inventory instead
+    pub fn replace_selected_one(&mut self, index: usize, new_item: Item) {
+        debug_assert!(self.can_replace_selected_one(index, new_item));
+        if self.selected[index].is_some_and(|item| item.count == 1) {
+            self.selected[index] = Some(new_item);
+        } else {
+            self.reduce_selected_at(index);
+            self.add_item(new_item);
+        }
+    }
+
     pub fn is_hotbar_full(&self) -> bool {
         self.selected.iter().all(|item| item.is_some())
     }
@@ -135,6 +336,10 @@ impl Default for Inventory {
 
 #[cfg(test)]
 mod tests {
+    use bincode::{decode_from_slice, encode_to_vec};
+
+    use crate::service::persistence::config::SERIALIZATION_CONFIG;
+
     use super::*;
 
     impl Item {
@@ -143,6 +348,93 @@ mod tests {
         }
     }
 
+    #[derive(Encode)]
+    struct LegacyItem {
+        voxel: Voxel,
+        count: u8,
+    }
+
+    #[test]
+    fn test_item_without_charges_keeps_legacy_encoding() {
+        let legacy = LegacyItem {
+            voxel: Voxel::Brick,
+            count: MAX_ITEMS_PER_SLOT,
+        };
+        let legacy_bytes = encode_to_vec(&legacy, SERIALIZATION_CONFIG).unwrap();
+        let item = Item::new(Voxel::Brick, MAX_ITEMS_PER_SLOT);
+
+        assert_eq!(
+            encode_to_vec(item, SERIALIZATION_CONFIG).unwrap(),
+            legacy_bytes
+        );
+        let (decoded, _): (Item, usize) =
+            decode_from_slice(&legacy_bytes, SERIALIZATION_CONFIG).unwrap();
+        assert_eq!(decoded, item);
+    }
+
+    #[test]
+    fn test_item_with_charges_round_trip() {
+        let item = Item {
+            charges: Some(1234),
+            ..Item::new(Voxel::Compass, 1)
+        };
+        let inventory_slots = [Some(item), None, Item::some(Voxel::Stone, 5)];
+
+        let bytes = encode_to_vec(inventory_slots, SERIALIZATION_CONFIG).unwrap();
+        let (decoded, _): ([Option<Item>; 3], usize) =
+            decode_from_slice(&bytes, SERIALIZATION_CONFIG).unwrap();
+
+        assert_eq!(decoded, inventory_slots);
+    }
+
+    #[test]
+    fn test_items_with_charges_do_not_stack() {
+        let mut inventory = Inventory::default();
+        let item = Item {
+            charges: Some(10),
+            ..Item::new(Voxel::Compass, 1)
+        };
+        inventory.add_item(item);
+        inventory.add_item(item);
+
+        assert_eq!(inventory.selected[0], Some(item));
+        assert_eq!(inventory.selected[1], Some(item));
+    }
+
+    #[test]
+    fn test_replace_selected_one() {
+        let mut inventory = Inventory::default();
+        let water_bucket = Item::new(Voxel::WaterBucket, 1);
+        inventory.selected[2] = Item::some(Voxel::Bucket, 1);
+        inventory.replace_selected_one(2, water_bucket);
+        assert_eq!(inventory.selected[2], Item::some(Voxel::WaterBucket, 1));
+
+        inventory.selected[2] = Item::some(Voxel::Bucket, 3);
+        inventory.replace_selected_one(2, water_bucket);
+        assert_eq!(inventory.selected[2], Item::some(Voxel::Bucket, 2));
+        assert_eq!(inventory.selected[0], Item::some(Voxel::WaterBucket, 1));
+
+        inventory.selected = [Item::some(Voxel::Stone, 1); Inventory::SELECTED_SIZE];
+        inventory.items = [Item::some(Voxel::Stone, 1); Inventory::INVENTORY_SIZE];
+        inventory.selected[2] = Item::some(Voxel::Bucket, 1);
+        assert!(inventory.can_replace_selected_one(2, water_bucket));
+        inventory.selected[2] = Item::some(Voxel::Bucket, 3);
+        assert!(!inventory.can_replace_selected_one(2, water_bucket));
+    }
+
+    #[test]
+    fn test_split_half() {
+        assert_eq!(
+            Item::new(Voxel::Stone, 7).split_half(),
+            Some((Item::new(Voxel::Stone, 4), Item::new(Voxel::Stone, 3)))
+        );
+        assert_eq!(
+            Item::new(Voxel::Stone, 2).split_half(),
+            Some((Item::new(Voxel::Stone, 1), Item::new(Voxel::Stone, 1)))
+        );
+        assert_eq!(Item::new(Voxel::Stone, 1).split_half(), None);
+    }
+
     #[test]
     fn test_add_item_into_empty() {
         let mut inventory = Inventory::default();
@@ -203,6 +495,41 @@ mod tests {
         assert!(inventory.selected[1].unwrap().voxel == Voxel::Brick);
     }
 
+    #[test]
+    fn test_locked_slots_not_removed() {
+        let mut inventory = Inventory::default();
+        inventory.selected[0] = Item::some(Voxel::Brick, 50);
+        inventory.selected[1] = Item::some(Voxel::Brick, 20);
+        inventory.items[0] = Item::some(Voxel::Brick, 10);
+        let mut hotbar_locks = HotbarLocks::default();
+        hotbar_locks.toggle(0);
+        assert!(hotbar_locks.is_locked(0));
+        assert!(!hotbar_locks.is_locked(1));
+
+        let map = inventory.create_unlocked_items_map(hotbar_locks);
+        assert_eq!(map.get(Voxel::Brick), 30);
+
+        inventory.remove_unlocked_item(Item::new(Voxel::Brick, 30), hotbar_locks);
+        assert_eq!(inventory.selected[0].unwrap().count, 50);
+        assert!(inventory.selected[1].is_none());
+        assert!(inventory.items[0].is_none());
+    }
+
+    #[test]
+    fn test_can_add_item() {
+        let mut inventory = Inventory::default();
+        inventory
+            .selected
+            .iter_mut()
+            .chain(inventory.items.iter_mut())
+            .for_each(|slot| *slot = Item::some(Voxel::Grass, 100));
+        inventory.selected[0] = Item::some(Voxel::Brick, 95);
+
+        assert!(inventory.can_add_item(Item::new(Voxel::Brick, 5)));
+        assert!(!inventory.can_add_item(Item::new(Voxel::Brick, 6)));
+        assert!(!inventory.can_add_item(Item::new(Voxel::Sand, 1)));
+    }
+
     #[test]
     fn test_create_all_items_map() {
         let mut inventory = Inventory::default();
@@ -233,6 +560,38 @@ mod tests {
         assert_eq!(other_items_count, 0);
     }
 
+    #[test]
+    fn test_quick_transfer() {
+        let mut inventory = Inventory::default();
+        inventory.items[5] = Item::some(Voxel::Brick, 30);
+        inventory.selected[0] = Item::some(Voxel::Brick, 90);
+        inventory.quick_transfer(false, 5, HotbarLocks::default());
+
+        assert_eq!(inventory.selected[0], Item::some(Voxel::Brick, 100));
+        assert_eq!(inventory.selected[1], Item::some(Voxel::Brick, 20));
+        assert!(inventory.items[5].is_none());
+
+        inventory.quick_transfer(true, 1, HotbarLocks::default());
+        assert!(inventory.selected[1].is_none());
+        assert_eq!(inventory.items[0], Item::some(Voxel::Brick, 20));
+    }
+
+    #[test]
+    fn test_quick_transfer_full_hotbar_keeps_remainder() {
+        let mut inventory = Inventory::default();
+        let mut hotbar_locks = HotbarLocks::default();
+        inventory.selected = [Item::some(Voxel::Stone, 10); Inventory::SELECTED_SIZE];
+        inventory.selected[2] = Item::some(Voxel::Brick, 95);
+        inventory.selected[3] = Item::some(Voxel::Brick, 10);
+        hotbar_locks.toggle(3);
+        inventory.items[0] = Item::some(Voxel::Brick, 20);
+        inventory.quick_transfer(false, 0, hotbar_locks);
+
+        assert_eq!(inventory.selected[2], Item::some(Voxel::Brick, 100));
+        assert_eq!(inventory.selected[3], Item::some(Voxel::Brick, 10));
+        assert_eq!(inventory.items[0], Item::some(Voxel::Brick, 15));
+    }
+
     #[test]
     fn test_remove_item() {
         let mut inventory = Inventory::default();
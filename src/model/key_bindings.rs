@@ -0,0 +1,409 @@
+use bincode::{
+    Decode, Encode,
+    de::Decoder,
+    enc::Encoder,
+    error::{DecodeError, EncodeError},
+    impl_borrow_decode,
+};
+use macroquad::input::KeyCode;
+
+/// the keys that actions can be bound to, the number keys always select hotbar slots
+/// and escape always opens the game menu, so neither can conflict with a binding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub enum BindableKey {
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+    I,
+    J,
+    K,
+    L,
+    M,
+    N,
+    O,
+    P,
+    Q,
+    R,
+    S,
+    T,
+    U,
+    V,
+    W,
+    X,
+    Y,
+    Z,
+    Space,
+    Tab,
+    LeftShift,
+    LeftControl,
+    LeftAlt,
+    CapsLock,
+    Up,
+    Down,
+    Left,
+    Right,
+    GraveAccent,
+    Slash,
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+}
+
+/// every bindable key with its key code and name
+const KEYS: [(BindableKey, KeyCode, &str); 50] = [
+    (BindableKey::A, KeyCode::A, "A"),
+    (BindableKey::B, KeyCode::B, "B"),
+    (BindableKey::C, KeyCode::C, "C"),
+    (BindableKey::D, KeyCode::D, "D"),
+    (BindableKey::E, KeyCode::E, "E"),
+    (BindableKey::F, KeyCode::F, "F"),
+    (BindableKey::G, KeyCode::G, "G"),
+    (BindableKey::H, KeyCode::H, "H"),
+    (BindableKey::I, KeyCode::I, "I"),
+    (BindableKey::J, KeyCode::J, "J"),
+    (BindableKey::K, KeyCode::K, "K"),
+    (BindableKey::L, KeyCode::L, "L"),
+    (BindableKey::M, KeyCode::M, "M"),
+    (BindableKey::N, KeyCode::N, "N"),
+    (BindableKey::O, KeyCode::O, "O"),
+    (BindableKey::P, KeyCode::P, "P"),
+    (BindableKey::Q, KeyCode::Q, "Q"),
+    (BindableKey::R, KeyCode::R, "R"),
+    (BindableKey::S, KeyCode::S, "S"),
+    (BindableKey::T, KeyCode::T, "T"),
+    (BindableKey::U, KeyCode::U, "U"),
+    (BindableKey::V, KeyCode::V, "V"),
+    (BindableKey::W, KeyCode::W, "W"),
+    (BindableKey::X, KeyCode::X, "X"),
+    (BindableKey::Y, KeyCode::Y, "Y"),
+    (BindableKey::Z, KeyCode::Z, "Z"),
+    (BindableKey::Space, KeyCode::Space, "Space"),
+    (BindableKey::Tab, KeyCode::Tab, "Tab"),
+    (BindableKey::LeftShift, KeyCode::LeftShift, "L Shift"),
+    (BindableKey::LeftControl, KeyCode::LeftControl, "L Ctrl"),
+    (BindableKey::LeftAlt, KeyCode::LeftAlt, "L Alt"),
+    (BindableKey::CapsLock, KeyCode::CapsLock, "Caps"),
+    (BindableKey::Up, KeyCode::Up, "Up"),
+    (BindableKey::Down, KeyCode::Down, "Down"),
+    (BindableKey::Left, KeyCode::Left, "Left"),
+    (BindableKey::Right, KeyCode::Right, "Right"),
+    (BindableKey::GraveAccent, KeyCode::GraveAccent, "`"),
+    (BindableKey::Slash, KeyCode::Slash, "/"),
+    (BindableKey::F1, KeyCode::F1, "F1"),
+    (BindableKey::F2, KeyCode::F2, "F2"),
+    (BindableKey::F3, KeyCode::F3, "F3"),
+    (BindableKey::F4, KeyCode::F4, "F4"),
+    (BindableKey::F5, KeyCode::F5, "F5"),
+    (BindableKey::F6, KeyCode::F6, "F6"),
+    (BindableKey::F7, KeyCode::F7, "F7"),
+    (BindableKey::F8, KeyCode::F8, "F8"),
+    (BindableKey::F9, KeyCode::F9, "F9"),
+    (BindableKey::F10, KeyCode::F10, "F10"),
+    (BindableKey::F11, KeyCode::F11, "F11"),
+    (BindableKey::F12, KeyCode::F12, "F12"),
+];
+
+impl BindableKey {
+    pub fn all() -> impl Iterator<Item = Self> {
+        KEYS.iter().map(|(key, _, _)| *key)
+    }
+
+    pub fn to_key_code(self) -> KeyCode {
+        Self::find(self).1
+    }
+
+    pub fn get_name(self) -> &'static str {
+        Self::find(self).2
+    }
+
+    fn find(key: Self) -> (Self, KeyCode, &'static str) {
+        *KEYS
+            .iter()
+            .find(|(bindable_key, _, _)| *bindable_key == key)
+            .expect("Missing key code for bindable key")
+    }
+}
+
+/// the actions bound to keys in the order they are shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyAction {
+    MoveForward,
+    MoveBack,
+    MoveLeft,
+    MoveRight,
+    Jump,
+    Sneak,
+    Sprint,
+    Inventory,
+    LockSlot,
+    QuickTransfer,
+    Crafting,
+    Map,
+    DropItem,
+    PlaceColumn,
+    Zoom,
+    SelectCorner,
+    CopySelection,
+    PasteSelection,
+    RotateClipboard,
+    ReplaceInSelection,
+    Console,
+    Debug,
+    DecreaseRenderDistance,
+    IncreaseRenderDistance,
+    ToggleHud,
+    Screenshot,
+    CycleNoiseOverlay,
+    ToggleXRay,
+    ToggleAllAreaBorders,
+    ToggleChangedAreas,
+    ToggleTimeFreeze,
+}
+impl KeyAction {
+    pub const ALL: [Self; 31] = [
+        Self::MoveForward,
+        Self::MoveBack,
+        Self::MoveLeft,
+        Self::MoveRight,
+        Self::Jump,
+        Self::Sneak,
+        Self::Sprint,
+        Self::Inventory,
+        Self::LockSlot,
+        Self::QuickTransfer,
+        Self::Crafting,
+        Self::Map,
+        Self::DropItem,
+        Self::PlaceColumn,
+        Self::Zoom,
+        Self::SelectCorner,
+        Self::CopySelection,
+        Self::PasteSelection,
+        Self::RotateClipboard,
+        Self::ReplaceInSelection,
+        Self::Console,
+        Self::Debug,
+        Self::DecreaseRenderDistance,
+        Self::IncreaseRenderDistance,
+        Self::ToggleHud,
+        Self::Screenshot,
+        Self::CycleNoiseOverlay,
+        Self::ToggleXRay,
+        Self::ToggleAllAreaBorders,
+        Self::ToggleChangedAreas,
+        Self::ToggleTimeFreeze,
+    ];
+
+    pub fn display_name(self) -> &'static str {
+        match self {
+            Self::MoveForward => "Forward",
+            Self::MoveBack => "Back",
+            Self::MoveLeft => "Left",
+            Self::MoveRight => "Right",
+            Self::Jump => "Jump/Swim",
+            Self::Sneak => "Sneak",
+            Self::Sprint => "Sprint",
+            Self::Inventory => "Inventory",
+            Self::LockSlot => "Lock slot",
+            Self::QuickTransfer => "Quick transfer",
+            Self::Crafting => "Crafting",
+            Self::Map => "Map",
+            Self::DropItem => "Drop item",
+            Self::PlaceColumn => "Place column",
+            Self::Zoom => "Zoom",
+            Self::SelectCorner => "Select corner",
+            Self::CopySelection => "Copy selection",
+            Self::PasteSelection => "Paste",
+            Self::RotateClipboard => "Rotate clipboard",
+            Self::ReplaceInSelection => "Replace in selection",
+            Self::Console => "Console",
+            Self::Debug => "Debug",
+            Self::DecreaseRenderDistance => "View distance -",
+            Self::IncreaseRenderDistance => "View distance +",
+            Self::ToggleHud => "Toggle HUD",
+            Self::Screenshot => "Screenshot",
+            Self::CycleNoiseOverlay => "Noise overlay",
+            Self::ToggleXRay => "X-ray",
+            Self::ToggleAllAreaBorders => "Area borders",
+            Self::ToggleChangedAreas => "Changed areas",
+            Self::ToggleTimeFreeze => "Freeze time",
+        }
+    }
+
+    /// the modifiers held while clicking in the inventory, they are only read while the
+    /// inventory is open so they can share keys with the actions used while playing
+    fn is_inventory_modifier(self) -> bool {
+        matches!(self, Self::LockSlot | Self::QuickTransfer)
+    }
+}
+
+/// the keys bound to each action, indexed by the action
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyBindings {
+    keys: [BindableKey; KeyAction::ALL.len()],
+}
+impl KeyBindings {
+    pub fn get(&self, action: KeyAction) -> BindableKey {
+        self.keys[action as usize]
+    }
+
+    pub fn set(&mut self, action: KeyAction, key: BindableKey) {
+        self.keys[action as usize] = key;
+    }
+
+    /// returns the other actions bound to the same key as the action that are read
+    /// at the same time
+    pub fn find_conflicts(&self, action: KeyAction) -> impl Iterator<Item = KeyAction> {
+        let key = self.get(action);
+        KeyAction::ALL.into_iter().filter(move |other| {
+            *other != action
+                && self.get(*other) == key
+                && other.is_inventory_modifier() == action.is_inventory_modifier()
+        })
+    }
+}
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            keys: [
+                BindableKey::W,
+                BindableKey::S,
+                BindableKey::A,
+                BindableKey::D,
+                BindableKey::Space,
+                BindableKey::LeftShift,
+                BindableKey::LeftControl,
+                BindableKey::E,
+                BindableKey::LeftControl,
+                BindableKey::LeftShift,
+                BindableKey::C,
+                BindableKey::M,
+                BindableKey::Q,
+                BindableKey::G,
+                BindableKey::F,
+                BindableKey::Z,
+                BindableKey::X,
+                BindableKey::V,
+                BindableKey::R,
+                BindableKey::B,
+                BindableKey::Slash,
+                BindableKey::GraveAccent,
+                BindableKey::F1,
+                BindableKey::F2,
+                BindableKey::F3,
+                BindableKey::F12,
+                BindableKey::F4,
+                BindableKey::F5,
+                BindableKey::F6,
+                BindableKey::F7,
+                BindableKey::F8,
+            ],
+        }
+    }
+}
+impl Encode for KeyBindings {
+    /// saved as a list so bindings saved before new actions existed can still be decoded
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        self.keys.as_slice().encode(encoder)
+    }
+}
+impl<Context> Decode<Context> for KeyBindings {
+    /// actions missing from older bindings get their default keys
+    fn decode<D: Decoder<Context = Context>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        let saved_keys: Vec<BindableKey> = Vec::decode(decoder)?;
+        let mut key_bindings = Self::default();
+        for (key, saved_key) in key_bindings.keys.iter_mut().zip(saved_keys) {
+            *key = saved_key;
+        }
+
+        Ok(key_bindings)
+    }
+}
+impl_borrow_decode!(KeyBindings);
+
+#[cfg(test)]
+mod tests {
+    use bincode::{decode_from_slice, encode_to_vec};
+
+    use crate::service::persistence::config::SERIALIZATION_CONFIG;
+
+    use super::*;
+
+    #[test]
+    fn test_decode_bindings_saved_before_new_actions() {
+        let saved_keys = vec![BindableKey::Up, BindableKey::Down];
+        let bytes = encode_to_vec(&saved_keys, SERIALIZATION_CONFIG).unwrap();
+
+        let (bindings, _): (KeyBindings, usize) =
+            decode_from_slice(&bytes, SERIALIZATION_CONFIG).unwrap();
+
+        assert_eq!(bindings.get(KeyAction::MoveForward), BindableKey::Up);
+        assert_eq!(bindings.get(KeyAction::MoveBack), BindableKey::Down);
+        assert_eq!(bindings.get(KeyAction::MoveLeft), BindableKey::A);
+        assert_eq!(bindings.get(KeyAction::Screenshot), BindableKey::F12);
+    }
+
+    #[test]
+    fn test_default_bindings_have_no_conflicts() {
+        let bindings = KeyBindings::default();
+        for action in KeyAction::ALL {
+            assert_eq!(bindings.find_conflicts(action).count(), 0);
+        }
+        assert_eq!(bindings.get(KeyAction::Inventory), BindableKey::E);
+    }
+
+    #[test]
+    fn test_find_conflicts() {
+        let mut bindings = KeyBindings::default();
+        bindings.set(KeyAction::Map, BindableKey::E);
+
+        assert_eq!(bindings.get(KeyAction::Map), BindableKey::E);
+        assert_eq!(
+            bindings.find_conflicts(KeyAction::Map).collect::<Vec<_>>(),
+            vec![KeyAction::Inventory]
+        );
+        assert_eq!(
+            bindings
+                .find_conflicts(KeyAction::Inventory)
+                .collect::<Vec<_>>(),
+            vec![KeyAction::Map]
+        );
+    }
+
+    #[test]
+    fn test_inventory_modifiers_only_conflict_with_each_other() {
+        let mut bindings = KeyBindings::default();
+        assert_eq!(bindings.get(KeyAction::Sprint), BindableKey::LeftControl);
+        assert_eq!(bindings.get(KeyAction::LockSlot), BindableKey::LeftControl);
+
+        bindings.set(KeyAction::QuickTransfer, BindableKey::LeftControl);
+        assert_eq!(
+            bindings
+                .find_conflicts(KeyAction::LockSlot)
+                .collect::<Vec<_>>(),
+            vec![KeyAction::QuickTransfer]
+        );
+
+        bindings.set(KeyAction::Jump, BindableKey::LeftControl);
+        assert_eq!(
+            bindings
+                .find_conflicts(KeyAction::Sprint)
+                .collect::<Vec<_>>(),
+            vec![KeyAction::Jump]
+        );
+    }
+}
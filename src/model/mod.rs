@@ -1,7 +1,11 @@
 pub mod area;
 pub mod inventory;
+pub mod key_bindings;
 pub mod location;
+pub mod mouse_bindings;
+pub mod orientation;
 pub mod player_info;
 pub mod user_settings;
 pub mod voxel;
 pub mod world;
+pub mod world_rules;
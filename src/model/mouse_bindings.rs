@@ -0,0 +1,133 @@
+use bincode::{Decode, Encode};
+use macroquad::input::MouseButton;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub enum BindableMouseButton {
+    Left,
+    Right,
+    Middle,
+}
+impl BindableMouseButton {
+    pub fn to_mouse_button(self) -> MouseButton {
+        match self {
+            Self::Left => MouseButton::Left,
+            Self::Right => MouseButton::Right,
+            Self::Middle => MouseButton::Middle,
+        }
+    }
+
+    pub fn get_short_name(self) -> &'static str {
+        match self {
+            Self::Left => "L",
+            Self::Right => "R",
+            Self::Middle => "M",
+        }
+    }
+}
+
+/// the mouse buttons used for the voxel actions, each action has a different button
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub struct MouseBindings {
+    pub destroy: BindableMouseButton,
+    pub place: BindableMouseButton,
+    pub replace: BindableMouseButton,
+}
+impl MouseBindings {
+    /// every assignment of the buttons to the actions in the order they are cycled through
+    const ALL: [Self; 6] = [
+        Self::new(
+            BindableMouseButton::Left,
+            BindableMouseButton::Right,
+            BindableMouseButton::Middle,
+        ),
+        Self::new(
+            BindableMouseButton::Right,
+            BindableMouseButton::Left,
+            BindableMouseButton::Middle,
+        ),
+        Self::new(
+            BindableMouseButton::Left,
+            BindableMouseButton::Middle,
+            BindableMouseButton::Right,
+        ),
+        Self::new(
+            BindableMouseButton::Middle,
+            BindableMouseButton::Left,
+            BindableMouseButton::Right,
+        ),
+        Self::new(
+            BindableMouseButton::Right,
+            BindableMouseButton::Middle,
+            BindableMouseButton::Left,
+        ),
+        Self::new(
+            BindableMouseButton::Middle,
+            BindableMouseButton::Right,
+            BindableMouseButton::Left,
+        ),
+    ];
+
+    const fn new(
+        destroy: BindableMouseButton,
+        place: BindableMouseButton,
+        replace: BindableMouseButton,
+    ) -> Self {
+        Self {
+            destroy,
+            place,
+            replace,
+        }
+    }
+
+    /// selects the next assignment of buttons
+    pub fn cycle(&mut self) {
+        let index = Self::ALL
+            .iter()
+            .position(|bindings| bindings == self)
+            .unwrap_or_default();
+        *self = Self::ALL[(index + 1) % Self::ALL.len()];
+    }
+
+    /// resets to the default if two actions share a button, used for bindings read from disk
+    pub fn validate(self) -> Self {
+        if self.destroy == self.place || self.destroy == self.replace || self.place == self.replace
+        {
+            Self::default()
+        } else {
+            self
+        }
+    }
+}
+impl Default for MouseBindings {
+    fn default() -> Self {
+        Self::ALL[0]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycle_visits_all_bindings() {
+        let mut bindings = MouseBindings::default();
+        for expected in MouseBindings::ALL.iter().skip(1) {
+            bindings.cycle();
+            assert_eq!(bindings, *expected);
+            assert_eq!(bindings.validate(), bindings);
+        }
+        bindings.cycle();
+        assert_eq!(bindings, MouseBindings::default());
+    }
+
+    #[test]
+    fn test_validate_rejects_shared_buttons() {
+        let bindings = MouseBindings::new(
+            BindableMouseButton::Right,
+            BindableMouseButton::Right,
+            BindableMouseButton::Middle,
+        );
+
+        assert_eq!(bindings.validate(), MouseBindings::default());
+    }
+}
@@ -0,0 +1,100 @@
+use bincode::{Decode, Encode};
+use macroquad::math::Vec3;
+
+use crate::model::location::Location;
+
+/// the direction a placed voxel is turned towards,
+/// voxels placed without a direction are always turned up
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Encode, Decode)]
+pub enum Orientation {
+    /// z - 1
+    #[default]
+    Up,
+    /// z + 1
+    Down,
+    /// x + 1
+    PositiveX,
+    /// x - 1
+    NegativeX,
+    /// y + 1
+    PositiveY,
+    /// y - 1
+    NegativeY,
+}
+impl Orientation {
+    /// the direction from the placed voxel towards the voxel it was placed against
+    pub fn from_placement(placed: Location, placed_against: Location) -> Self {
+        if placed_against.x > placed.x {
+            Self::PositiveX
+        } else if placed_against.x < placed.x {
+            Self::NegativeX
+        } else if placed_against.y > placed.y {
+            Self::PositiveY
+        } else if placed_against.y < placed.y {
+            Self::NegativeY
+        } else if placed_against.z > placed.z {
+            Self::Down
+        } else {
+            Self::Up
+        }
+    }
+
+    /// the horizontal direction closest to the vector
+    pub fn from_horizontal_direction(direction: Vec3) -> Self {
+        if direction.x.abs() >= direction.y.abs() {
+            if direction.x >= 0.0 {
+                Self::PositiveX
+            } else {
+                Self::NegativeX
+            }
+        } else if direction.y >= 0.0 {
+            Self::PositiveY
+        } else {
+            Self::NegativeY
+        }
+    }
+
+    pub fn is_horizontal(self) -> bool {
+        !matches!(self, Self::Up | Self::Down)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use macroquad::math::vec3;
+
+    use super::*;
+
+    #[test]
+    fn test_from_placement() {
+        let placed = Location::new(4, 5, 6);
+        assert_eq!(
+            Orientation::from_placement(placed, Location::new(5, 5, 6)),
+            Orientation::PositiveX
+        );
+        assert_eq!(
+            Orientation::from_placement(placed, Location::new(4, 4, 6)),
+            Orientation::NegativeY
+        );
+        assert_eq!(
+            Orientation::from_placement(placed, Location::new(4, 5, 7)),
+            Orientation::Down
+        );
+        assert_eq!(
+            Orientation::from_placement(placed, Location::new(4, 5, 5)),
+            Orientation::Up
+        );
+    }
+
+    #[test]
+    fn test_from_horizontal_direction() {
+        assert_eq!(
+            Orientation::from_horizontal_direction(vec3(-0.8, 0.3, 0.5)),
+            Orientation::NegativeX
+        );
+        assert_eq!(
+            Orientation::from_horizontal_direction(vec3(0.1, 0.6, -0.9)),
+            Orientation::PositiveY
+        );
+    }
+}
@@ -1,16 +1,40 @@
-use bincode::{Decode, Encode};
+use bincode::{Decode, Encode, de::Decoder, error::DecodeError, impl_borrow_decode};
 use macroquad::math::Vec3;
 
 use crate::{
     graphics::ui_display::ItemHotbar,
-    model::inventory::Inventory,
-    service::{activity_timer::ActivityTimer, camera_controller::CameraController},
+    model::{
+        inventory::{HotbarLocks, Inventory},
+        location::{InternalLocation, Location},
+        voxel::Voxel,
+    },
+    service::{
+        activity_timer::ActivityTimer, camera_controller::CameraController,
+        persistence::generic_persistence::decode_appended_field,
+    },
     utils::{arr_to_vec3, vec3_to_arr},
 };
 
 const DESTROY_VOXEL_DELAY: f32 = 0.25;
 const PLACE_VOXEL_DELAY: f32 = 0.2;
 const REPLACE_VOXEL_DELAY: f32 = 0.1;
+/// how much lower the view is while sneaking
+const SNEAK_VIEW_OFFSET: f32 = 0.3;
+const MIN_VOXEL_REACH: f32 = 2.0;
+/// reach change for each scroll step
+const VOXEL_REACH_STEP: f32 = 1.0;
+/// health lost for each unit of fall speed above the strong collision speed
+const FALL_DAMAGE_PER_SPEED: f32 = 5.0;
+/// stamina regained each second while not sprinting, sprinting uses one each second
+const STAMINA_REGENERATION: f32 = 0.5;
+/// stamina needed to sprint again after running out
+const MIN_SPRINT_STAMINA: f32 = PlayerInfo::MAX_STAMINA * 0.25;
+/// oxygen regained each second while the head is above the water
+const OXYGEN_REFILL_SPEED: f32 = 5.0;
+/// health lost each drowning interval once the oxygen runs out
+const DROWNING_DAMAGE: f32 = 10.0;
+/// seconds between the damage taken while drowning
+const DROWNING_INTERVAL: f32 = 1.0;
 
 #[derive(Debug)]
 pub struct PlayerInfo {
@@ -18,17 +42,39 @@ pub struct PlayerInfo {
     pub place_progress: ActivityTimer,
     pub replace_progress: ActivityTimer,
     pub inventory: Inventory,
+    pub hotbar_locks: HotbarLocks,
     pub camera_controller: CameraController,
     pub voxel_selector: ItemHotbar,
     pub velocity: Vec3,
     pub is_in_water: bool,
     pub is_head_in_water: bool,
+    pub is_sneaking: bool,
+    /// location the compass points to instead of the spawn
+    pub waypoint: Option<Location>,
+    /// how far away voxels can be targeted, changed with the scroll wheel if enabled
+    voxel_reach: f32,
+    health: f32,
+    stamina: f32,
+    is_sprinting: bool,
+    /// set when the stamina runs out, sprinting is blocked until enough is regained
+    is_exhausted: bool,
+    oxygen: f32,
+    drowning_timer: ActivityTimer,
 }
 impl PlayerInfo {
     pub const PLAYER_MOVE_SPEED: f32 = 9.0;
     pub const PLAYER_SIZE: f32 = 0.3;
     pub const VOXEL_REACH: f32 = 7.0;
     pub const JUMP_VELOCITY: f32 = -15.0;
+    pub const SNEAK_SPEED_MODIFIER: f32 = 0.3;
+    /// where players start in new worlds
+    pub const SPAWN_LOCATION: Location = Location::new(0, 0, 0);
+    pub const MAX_HEALTH: f32 = 100.0;
+    /// seconds of sprinting with full stamina
+    pub const MAX_STAMINA: f32 = 5.0;
+    pub const SPRINT_SPEED_MODIFIER: f32 = 1.6;
+    /// seconds the player can stay under water
+    pub const MAX_OXYGEN: f32 = 15.0;
 
     pub fn new(position: Vec3) -> Self {
         Self {
@@ -36,14 +82,133 @@ impl PlayerInfo {
             velocity: Vec3::ZERO,
             voxel_selector: ItemHotbar::new(),
             inventory: Inventory::default(),
+            hotbar_locks: HotbarLocks::default(),
             is_in_water: false,
             is_head_in_water: false,
+            is_sneaking: false,
+            waypoint: None,
+            voxel_reach: Self::VOXEL_REACH,
+            health: Self::MAX_HEALTH,
+            stamina: Self::MAX_STAMINA,
+            is_sprinting: false,
+            is_exhausted: false,
+            oxygen: Self::MAX_OXYGEN,
+            drowning_timer: ActivityTimer::new(0.0, DROWNING_INTERVAL),
             destroy_progress: ActivityTimer::new(0.0, DESTROY_VOXEL_DELAY),
             place_progress: ActivityTimer::new(0.0, PLACE_VOXEL_DELAY),
             replace_progress: ActivityTimer::new(0.0, REPLACE_VOXEL_DELAY),
         }
     }
 
+    pub fn get_health(&self) -> f32 {
+        self.health
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.health <= 0.0
+    }
+
+    /// the damage grows with the speed above the minimum speed that causes damage
+    pub fn apply_fall_damage(&mut self, fall_speed: f32, min_damage_speed: f32) {
+        let damage = (fall_speed - min_damage_speed).max(0.0) * FALL_DAMAGE_PER_SPEED;
+        self.health = (self.health - damage).max(0.0);
+    }
+
+    pub fn restore_health(&mut self) {
+        self.health = Self::MAX_HEALTH;
+    }
+
+    pub fn get_oxygen(&self) -> f32 {
+        self.oxygen
+    }
+
+    /// uses up oxygen while the head is under water and refills it at the surface,
+    /// the player takes damage at intervals once it runs out
+    pub fn update_oxygen(&mut self, delta: f32) {
+        if !self.is_head_in_water {
+            self.oxygen = (self.oxygen + OXYGEN_REFILL_SPEED * delta).min(Self::MAX_OXYGEN);
+            self.drowning_timer.reset();
+            return;
+        }
+
+        if self.oxygen > 0.0 {
+            self.oxygen = (self.oxygen - delta).max(0.0);
+        } else if self.drowning_timer.tick(delta) {
+            self.health = (self.health - DROWNING_DAMAGE).max(0.0);
+        }
+    }
+
+    pub fn restore_oxygen(&mut self) {
+        self.oxygen = Self::MAX_OXYGEN;
+    }
+
+    pub fn get_stamina(&self) -> f32 {
+        self.stamina
+    }
+
+    pub fn is_sprinting(&self) -> bool {
+        self.is_sprinting
+    }
+
+    /// drains the stamina while sprinting and regenerates it otherwise
+    pub fn update_sprint(&mut self, wants_to_sprint: bool, delta: f32) {
+        if self.stamina >= MIN_SPRINT_STAMINA {
+            self.is_exhausted = false;
+        }
+        self.is_sprinting = wants_to_sprint && !self.is_exhausted;
+
+        if self.is_sprinting {
+            self.stamina = (self.stamina - delta).max(0.0);
+            if self.stamina <= 0.0 {
+                self.is_exhausted = true;
+                self.is_sprinting = false;
+            }
+        } else {
+            self.stamina = (self.stamina + STAMINA_REGENERATION * delta).min(Self::MAX_STAMINA);
+        }
+    }
+
+    pub fn set_sneaking(&mut self, is_sneaking: bool) {
+        self.is_sneaking = is_sneaking;
+        let view_offset = if is_sneaking { SNEAK_VIEW_OFFSET } else { 0.0 };
+        self.camera_controller.set_view_offset(view_offset);
+    }
+
+    /// the chosen reach extended by the held tool
+    pub fn get_voxel_reach(&self) -> f32 {
+        self.voxel_reach + self.get_held_voxel().map_or(0.0, Voxel::reach_bonus)
+    }
+
+    /// multiplier of the speed the target is broken at with the held tool
+    pub fn get_break_speed_against(&self, target: Voxel) -> f32 {
+        self.get_held_voxel()
+            .map_or(1.0, |held| held.break_speed_against(target))
+    }
+
+    /// the reach can't go above the default reach
+    pub fn increase_voxel_reach(&mut self) {
+        self.voxel_reach = (self.voxel_reach + VOXEL_REACH_STEP).min(Self::VOXEL_REACH);
+    }
+
+    pub fn decrease_voxel_reach(&mut self) {
+        self.voxel_reach = (self.voxel_reach - VOXEL_REACH_STEP).max(MIN_VOXEL_REACH);
+    }
+
+    /// returns the voxel of the selected hotbar item
+    pub fn get_held_voxel(&self) -> Option<Voxel> {
+        let selected_index = self.voxel_selector.get_selected_index();
+        self.inventory.selected[selected_index].map(|item| item.voxel)
+    }
+
+    pub fn is_holding_compass(&self) -> bool {
+        self.get_held_voxel() == Some(Voxel::Compass)
+    }
+
+    /// returns the waypoint if set, otherwise the spawn
+    pub fn get_compass_target(&self) -> Location {
+        self.waypoint.unwrap_or(Self::SPAWN_LOCATION)
+    }
+
     pub fn create_dto(&self) -> PlayerInfoDTO {
         let position = self.camera_controller.get_position();
         PlayerInfoDTO {
@@ -54,6 +219,10 @@ impl PlayerInfo {
             voxel_selector: self.voxel_selector.clone(),
             current_selection: self.voxel_selector.get_selected_index(),
             inventory: self.inventory.clone(),
+            hotbar_locks: self.hotbar_locks,
+            waypoint: self.waypoint.map(InternalLocation::from),
+            health: self.health,
+            oxygen: self.oxygen,
         }
     }
 }
@@ -70,8 +239,27 @@ impl From<PlayerInfoDTO> for PlayerInfo {
             velocity,
             voxel_selector: value.voxel_selector,
             inventory: value.inventory,
+            hotbar_locks: value.hotbar_locks,
             is_in_water: false,
             is_head_in_water: false,
+            is_sneaking: false,
+            waypoint: value.waypoint.map(Location::from),
+            voxel_reach: Self::VOXEL_REACH,
+            // a player saved while dead starts with full health
+            health: if value.health > 0.0 && value.health <= Self::MAX_HEALTH {
+                value.health
+            } else {
+                Self::MAX_HEALTH
+            },
+            stamina: Self::MAX_STAMINA,
+            is_sprinting: false,
+            is_exhausted: false,
+            oxygen: if (0.0..=Self::MAX_OXYGEN).contains(&value.oxygen) {
+                value.oxygen
+            } else {
+                Self::MAX_OXYGEN
+            },
+            drowning_timer: ActivityTimer::new(0.0, DROWNING_INTERVAL),
             destroy_progress: ActivityTimer::new(0.0, DESTROY_VOXEL_DELAY),
             place_progress: ActivityTimer::new(0.0, PLACE_VOXEL_DELAY),
             replace_progress: ActivityTimer::new(0.0, REPLACE_VOXEL_DELAY),
@@ -79,7 +267,8 @@ impl From<PlayerInfoDTO> for PlayerInfo {
     }
 }
 
-#[derive(Debug, Clone, Encode, Decode)]
+/// new fields are appended at the end so older saves can still be decoded
+#[derive(Debug, Clone, Encode)]
 pub struct PlayerInfoDTO {
     inventory: Inventory,
     velocity: [f32; 3],
@@ -88,4 +277,203 @@ pub struct PlayerInfoDTO {
     current_selection: usize,
     yaw: f32,
     pitch: f32,
+    hotbar_locks: HotbarLocks,
+    waypoint: Option<InternalLocation>,
+    health: f32,
+    oxygen: f32,
+}
+impl<Context> Decode<Context> for PlayerInfoDTO {
+    /// players saved before the appended fields existed get the default values
+    fn decode<D: Decoder<Context = Context>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        Ok(Self {
+            inventory: Inventory::decode(decoder)?,
+            velocity: Decode::decode(decoder)?,
+            position: Decode::decode(decoder)?,
+            voxel_selector: ItemHotbar::decode(decoder)?,
+            current_selection: usize::decode(decoder)?,
+            yaw: f32::decode(decoder)?,
+            pitch: f32::decode(decoder)?,
+            hotbar_locks: decode_appended_field(decoder, HotbarLocks::default)?,
+            waypoint: decode_appended_field(decoder, || None)?,
+            health: decode_appended_field(decoder, || PlayerInfo::MAX_HEALTH)?,
+            oxygen: decode_appended_field(decoder, || PlayerInfo::MAX_OXYGEN)?,
+        })
+    }
+}
+impl_borrow_decode!(PlayerInfoDTO);
+
+#[cfg(test)]
+mod tests {
+    use bincode::{decode_from_slice, encode_to_vec};
+
+    use crate::{model::inventory::Item, service::persistence::config::SERIALIZATION_CONFIG};
+
+    use super::*;
+
+    /// the player layout before the hotbar locks, waypoint, health and oxygen were saved
+    #[derive(Encode)]
+    struct LegacyPlayerInfoDTO {
+        inventory: Inventory,
+        velocity: [f32; 3],
+        position: [f32; 3],
+        voxel_selector: ItemHotbar,
+        current_selection: usize,
+        yaw: f32,
+        pitch: f32,
+    }
+
+    #[test]
+    fn test_decode_legacy_player_info() {
+        let mut inventory = Inventory::default();
+        inventory.selected[1] = Item::some(Voxel::Brick, 12);
+        let legacy = LegacyPlayerInfoDTO {
+            inventory,
+            velocity: [0.0, 0.0, 1.0],
+            position: [10.0, -4.0, 20.0],
+            voxel_selector: ItemHotbar::new(),
+            current_selection: 1,
+            yaw: 0.5,
+            pitch: -0.25,
+        };
+        let bytes = encode_to_vec(&legacy, SERIALIZATION_CONFIG).unwrap();
+
+        let (dto, _): (PlayerInfoDTO, usize) =
+            decode_from_slice(&bytes, SERIALIZATION_CONFIG).unwrap();
+        let player_info = PlayerInfo::from(dto);
+
+        assert_eq!(
+            player_info.inventory.selected[1],
+            Item::some(Voxel::Brick, 12)
+        );
+        assert_eq!(
+            player_info.camera_controller.get_position(),
+            Vec3::new(10.0, -4.0, 20.0)
+        );
+        assert_eq!(player_info.camera_controller.pitch, -0.25);
+        assert_eq!(player_info.hotbar_locks, HotbarLocks::default());
+        assert_eq!(player_info.waypoint, None);
+        assert_eq!(player_info.get_health(), PlayerInfo::MAX_HEALTH);
+        assert_eq!(player_info.get_oxygen(), PlayerInfo::MAX_OXYGEN);
+    }
+
+    #[test]
+    fn test_player_info_round_trip() {
+        let mut player_info = PlayerInfo::new(Vec3::ZERO);
+        player_info.waypoint = Some(Location::new(3, 4, 5));
+        player_info.apply_fall_damage(14.0, 12.0);
+        let bytes = encode_to_vec(player_info.create_dto(), SERIALIZATION_CONFIG).unwrap();
+
+        let (dto, _): (PlayerInfoDTO, usize) =
+            decode_from_slice(&bytes, SERIALIZATION_CONFIG).unwrap();
+        let loaded = PlayerInfo::from(dto);
+
+        assert_eq!(loaded.waypoint, Some(Location::new(3, 4, 5)));
+        assert_eq!(loaded.get_health(), player_info.get_health());
+    }
+
+    #[test]
+    fn test_voxel_reach_limits() {
+        let mut player_info = PlayerInfo::new(Vec3::ZERO);
+        player_info.increase_voxel_reach();
+        assert_eq!(player_info.get_voxel_reach(), PlayerInfo::VOXEL_REACH);
+
+        for _ in 0..20 {
+            player_info.decrease_voxel_reach();
+        }
+        assert_eq!(player_info.get_voxel_reach(), MIN_VOXEL_REACH);
+
+        player_info.increase_voxel_reach();
+        assert_eq!(
+            player_info.get_voxel_reach(),
+            MIN_VOXEL_REACH + VOXEL_REACH_STEP
+        );
+    }
+
+    #[test]
+    fn test_held_tools_modify_reach_and_break_speed() {
+        let mut player_info = PlayerInfo::new(Vec3::ZERO);
+        let selected_index = player_info.voxel_selector.get_selected_index();
+        assert_eq!(player_info.get_break_speed_against(Voxel::Stone), 1.0);
+
+        player_info.inventory.selected[selected_index] = Item::some(Voxel::ReachStaff, 1);
+        assert_eq!(
+            player_info.get_voxel_reach(),
+            PlayerInfo::VOXEL_REACH + Voxel::ReachStaff.reach_bonus()
+        );
+        player_info.decrease_voxel_reach();
+        assert_eq!(
+            player_info.get_voxel_reach(),
+            PlayerInfo::VOXEL_REACH - VOXEL_REACH_STEP + Voxel::ReachStaff.reach_bonus()
+        );
+
+        player_info.inventory.selected[selected_index] = Item::some(Voxel::Pickaxe, 1);
+        assert_eq!(
+            player_info.get_voxel_reach(),
+            PlayerInfo::VOXEL_REACH - VOXEL_REACH_STEP
+        );
+        assert!(player_info.get_break_speed_against(Voxel::IronOre) > 1.0);
+        assert_eq!(player_info.get_break_speed_against(Voxel::Dirt), 1.0);
+    }
+
+    #[test]
+    fn test_sprint_stops_when_exhausted() {
+        let mut player_info = PlayerInfo::new(Vec3::ZERO);
+        player_info.update_sprint(true, 1.0);
+        assert!(player_info.is_sprinting());
+        assert_eq!(player_info.get_stamina(), PlayerInfo::MAX_STAMINA - 1.0);
+
+        player_info.update_sprint(true, PlayerInfo::MAX_STAMINA);
+        assert!(!player_info.is_sprinting());
+        assert_eq!(player_info.get_stamina(), 0.0);
+
+        player_info.update_sprint(true, 1.0);
+        assert!(!player_info.is_sprinting());
+        assert_eq!(player_info.get_stamina(), STAMINA_REGENERATION);
+
+        player_info.update_sprint(false, MIN_SPRINT_STAMINA / STAMINA_REGENERATION);
+        player_info.update_sprint(true, 0.1);
+        assert!(player_info.is_sprinting());
+    }
+
+    #[test]
+    fn test_fall_damage() {
+        let mut player_info = PlayerInfo::new(Vec3::ZERO);
+        player_info.apply_fall_damage(10.0, 12.0);
+        assert_eq!(player_info.get_health(), PlayerInfo::MAX_HEALTH);
+
+        player_info.apply_fall_damage(14.0, 12.0);
+        assert_eq!(
+            player_info.get_health(),
+            PlayerInfo::MAX_HEALTH - 2.0 * FALL_DAMAGE_PER_SPEED
+        );
+        assert!(!player_info.is_dead());
+
+        player_info.apply_fall_damage(100.0, 12.0);
+        assert_eq!(player_info.get_health(), 0.0);
+        assert!(player_info.is_dead());
+
+        let loaded = PlayerInfo::from(player_info.create_dto());
+        assert_eq!(loaded.get_health(), PlayerInfo::MAX_HEALTH);
+    }
+
+    #[test]
+    fn test_oxygen_runs_out_under_water() {
+        let mut player_info = PlayerInfo::new(Vec3::ZERO);
+        player_info.is_head_in_water = true;
+        player_info.update_oxygen(PlayerInfo::MAX_OXYGEN);
+        assert_eq!(player_info.get_oxygen(), 0.0);
+        assert_eq!(player_info.get_health(), PlayerInfo::MAX_HEALTH);
+
+        player_info.update_oxygen(DROWNING_INTERVAL);
+        assert_eq!(
+            player_info.get_health(),
+            PlayerInfo::MAX_HEALTH - DROWNING_DAMAGE
+        );
+
+        player_info.is_head_in_water = false;
+        player_info.update_oxygen(1.0);
+        assert_eq!(player_info.get_oxygen(), OXYGEN_REFILL_SPEED);
+        let loaded = PlayerInfo::from(player_info.create_dto());
+        assert_eq!(loaded.get_oxygen(), OXYGEN_REFILL_SPEED);
+    }
 }
@@ -1,8 +1,46 @@
-use bincode::{Decode, Encode};
+use bincode::{Decode, Encode, de::Decoder, error::DecodeError, impl_borrow_decode};
+
+use crate::{
+    model::{key_bindings::KeyBindings, mouse_bindings::MouseBindings},
+    service::{
+        camera_controller::DEFAULT_FOV_DEGREES,
+        persistence::generic_persistence::decode_appended_field, world_time::DEFAULT_DAY_LENGTH,
+    },
+};
 
 const MIN_RENDER_DISTANCE: u32 = 3;
 const MAX_RENDER_DISTANCE: u32 = 16;
 const DEFAULT_RENDER_DISTANCE: u32 = 8;
+const MAX_SIMULATION_DISTANCE: u32 = 20;
+const MIN_CREATURE_RENDER_DISTANCE: u32 = 1;
+/// creature spawn rate multipliers in the order they are cycled through
+const CREATURE_SPAWN_RATES: [f32; 5] = [0.0, 0.5, 1.0, 1.5, 2.0];
+const DEFAULT_CREATURE_SPAWN_RATE: f32 = 1.0;
+/// hold to break speed multipliers, never below 1 so breaking can't become instant
+const BREAK_SPEEDS: [f32; 4] = [1.0, 1.5, 2.0, 3.0];
+const DEFAULT_BREAK_SPEED: f32 = 1.0;
+/// distances from which dropped items are pulled towards the player, 0 disables it
+const ITEM_MAGNET_RADII: [f32; 4] = [0.0, 2.0, 3.0, 5.0];
+const DEFAULT_ITEM_MAGNET_RADIUS: f32 = 3.0;
+/// limits in radians for looking up or down, below 90 degrees so the camera can't flip
+const MAX_PITCHES: [f32; 4] = [1.2, 1.4, 1.5, 1.55];
+const DEFAULT_MAX_PITCH: f32 = 1.5;
+/// multipliers for the look speed, limited so the camera stays controllable
+const MOUSE_SENSITIVITIES: [f32; 6] = [0.25, 0.5, 0.75, 1.0, 1.5, 2.0];
+const DEFAULT_MOUSE_SENSITIVITY: f32 = 1.0;
+/// vertical fields of view in degrees
+const FOVS_DEGREES: [f32; 5] = [DEFAULT_FOV_DEGREES, 55.0, 65.0, 75.0, 90.0];
+const MUSIC_VOLUMES: [f32; 4] = [0.25, 0.5, 0.75, 1.0];
+const DEFAULT_MUSIC_VOLUME: f32 = 1.0;
+/// seconds between automatic saves, 0 disables them
+const AUTO_SAVE_INTERVALS: [f32; 4] = [0.0, 60.0, 120.0, 300.0];
+const DEFAULT_AUTO_SAVE_INTERVAL: f32 = 120.0;
+/// number of distant creatures updated per frame, the others are drawn moving smoothly
+/// until their turn comes
+const CREATURE_UPDATE_BUDGETS: [u32; 4] = [4, 8, 16, 32];
+const DEFAULT_CREATURE_UPDATE_BUDGET: u32 = 8;
+/// seconds for a full day and night cycle
+const DAY_LENGTHS: [f32; 4] = [300.0, DEFAULT_DAY_LENGTH, 1200.0, 2400.0];
 
 #[derive(Debug, Clone, Copy, Encode, Decode)]
 pub enum ShadowType {
@@ -11,21 +49,99 @@ pub enum ShadowType {
     Hard,
 }
 
-#[derive(Debug, Clone, Encode, Decode)]
+/// what the mouse wheel does outside the world map
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub enum ScrollAction {
+    /// selects the next or previous hotbar slot
+    Hotbar,
+    /// changes how far away voxels can be targeted
+    Reach,
+}
+
+/// new fields are appended at the end so older settings can still be decoded
+#[derive(Debug, Clone, Encode)]
 pub struct UserSettings {
     render_distance: u32,
     pub has_sound: bool,
     pub is_fullscreen: bool,
     pub shadow_type: ShadowType,
+    pub show_hud: bool,
+    pub auto_step: bool,
+    pub show_tutorials: bool,
+    pub smooth_lighting: bool,
+    /// always at least the render distance
+    simulation_distance: u32,
+    /// makes bright voxels and lightning glow
+    pub bloom: bool,
+    /// tints the water surfaces with the sky color
+    pub water_reflections: bool,
+    /// shows the name of the targeted voxel below the crosshair
+    pub show_target_name: bool,
+    /// scales how often and how many creatures spawn, 0 disables spawning
+    creature_spawn_rate: f32,
+    /// keeps the areas around the world spawn loaded wherever the player is
+    pub keep_spawn_loaded: bool,
+    pub mouse_bindings: MouseBindings,
+    /// divides the delay between voxels broken while holding the destroy button
+    break_speed: f32,
+    /// dropped items closer than this move towards the player
+    item_magnet_radius: f32,
+    /// moving the mouse up looks down
+    pub invert_y: bool,
+    /// how far up or down the player can look
+    max_pitch: f32,
+    /// areas within which creatures are drawn and spawned, never above the render distance
+    creature_render_distance: u32,
+    pub scroll_action: ScrollAction,
+    /// plays music when the sounds are also on
+    pub has_music: bool,
+    /// music volume separate from the sound effects
+    music_volume: f32,
+    /// seconds between saving the world while playing, 0 only saves on exit
+    auto_save_interval: f32,
+    /// fades distant voxels into the sky color
+    pub fog: bool,
+    /// stores the generated meshes with the world to load it faster, uses more disk space
+    pub mesh_cache: bool,
+    /// seconds for a full day and night cycle in the world
+    day_length_seconds: f32,
+    /// shows the heading at the top of the screen
+    pub show_compass_bar: bool,
+    pub key_bindings: KeyBindings,
+    /// scales how fast the camera turns with the mouse
+    mouse_sensitivity: f32,
+    /// vertical field of view of the player camera
+    fov_degrees: f32,
+    /// number of distant creatures updated per frame
+    creature_update_budget: u32,
 }
 impl UserSettings {
     pub fn get_render_distance(&self) -> u32 {
         self.render_distance
     }
 
+    pub fn get_simulation_distance(&self) -> u32 {
+        self.simulation_distance
+    }
+
+    pub fn get_creature_render_distance(&self) -> u32 {
+        self.creature_render_distance.min(self.render_distance)
+    }
+
+    /// increases the creature render distance, wrapping around after the render distance
+    pub fn cycle_creature_render_distance(&mut self) {
+        let current = self.get_creature_render_distance();
+        self.creature_render_distance = if current >= self.render_distance {
+            MIN_CREATURE_RENDER_DISTANCE
+        } else {
+            current + 1
+        };
+    }
+
     pub fn increase_render_distance(&mut self) -> bool {
         if self.render_distance < MAX_RENDER_DISTANCE {
             self.render_distance += 1;
+            self.simulation_distance = self.simulation_distance.max(self.render_distance);
             true
         } else {
             false
@@ -41,6 +157,177 @@ impl UserSettings {
         }
     }
 
+    pub fn increase_simulation_distance(&mut self) -> bool {
+        if self.simulation_distance < MAX_SIMULATION_DISTANCE {
+            self.simulation_distance += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// the simulation distance can't go below the render distance
+    pub fn decrease_simulation_distance(&mut self) -> bool {
+        if self.simulation_distance > self.render_distance {
+            self.simulation_distance -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// clamps the distances to their limits, used for settings read from disk
+    pub fn validate(mut self) -> Self {
+        self.render_distance = self
+            .render_distance
+            .clamp(MIN_RENDER_DISTANCE, MAX_RENDER_DISTANCE);
+        self.simulation_distance = self
+            .simulation_distance
+            .clamp(self.render_distance, MAX_SIMULATION_DISTANCE);
+        self.creature_spawn_rate = Self::validate_preset(
+            self.creature_spawn_rate,
+            &CREATURE_SPAWN_RATES,
+            DEFAULT_CREATURE_SPAWN_RATE,
+        );
+        self.break_speed =
+            Self::validate_preset(self.break_speed, &BREAK_SPEEDS, DEFAULT_BREAK_SPEED);
+        self.item_magnet_radius = Self::validate_preset(
+            self.item_magnet_radius,
+            &ITEM_MAGNET_RADII,
+            DEFAULT_ITEM_MAGNET_RADIUS,
+        );
+        self.creature_render_distance = self
+            .creature_render_distance
+            .clamp(MIN_CREATURE_RENDER_DISTANCE, MAX_RENDER_DISTANCE);
+        self.max_pitch = Self::validate_preset(self.max_pitch, &MAX_PITCHES, DEFAULT_MAX_PITCH);
+        self.mouse_sensitivity = Self::validate_preset(
+            self.mouse_sensitivity,
+            &MOUSE_SENSITIVITIES,
+            DEFAULT_MOUSE_SENSITIVITY,
+        );
+        self.fov_degrees =
+            Self::validate_preset(self.fov_degrees, &FOVS_DEGREES, DEFAULT_FOV_DEGREES);
+        self.music_volume =
+            Self::validate_preset(self.music_volume, &MUSIC_VOLUMES, DEFAULT_MUSIC_VOLUME);
+        self.auto_save_interval = Self::validate_preset(
+            self.auto_save_interval,
+            &AUTO_SAVE_INTERVALS,
+            DEFAULT_AUTO_SAVE_INTERVAL,
+        );
+        self.day_length_seconds =
+            Self::validate_preset(self.day_length_seconds, &DAY_LENGTHS, DEFAULT_DAY_LENGTH);
+        self.creature_update_budget = self.creature_update_budget.clamp(
+            CREATURE_UPDATE_BUDGETS[0],
+            CREATURE_UPDATE_BUDGETS[CREATURE_UPDATE_BUDGETS.len() - 1],
+        );
+        self.mouse_bindings = self.mouse_bindings.validate();
+        self
+    }
+
+    pub fn get_creature_spawn_rate(&self) -> f32 {
+        self.creature_spawn_rate
+    }
+
+    /// selects the next spawn rate, wrapping around to 0 after the highest
+    pub fn cycle_creature_spawn_rate(&mut self) {
+        self.creature_spawn_rate =
+            Self::next_preset(self.creature_spawn_rate, &CREATURE_SPAWN_RATES);
+    }
+
+    pub fn get_break_speed(&self) -> f32 {
+        self.break_speed
+    }
+
+    pub fn cycle_break_speed(&mut self) {
+        self.break_speed = Self::next_preset(self.break_speed, &BREAK_SPEEDS);
+    }
+
+    pub fn get_item_magnet_radius(&self) -> f32 {
+        self.item_magnet_radius
+    }
+
+    pub fn cycle_item_magnet_radius(&mut self) {
+        self.item_magnet_radius = Self::next_preset(self.item_magnet_radius, &ITEM_MAGNET_RADII);
+    }
+
+    pub fn get_max_pitch(&self) -> f32 {
+        self.max_pitch
+    }
+
+    pub fn cycle_max_pitch(&mut self) {
+        self.max_pitch = Self::next_preset(self.max_pitch, &MAX_PITCHES);
+    }
+
+    pub fn get_mouse_sensitivity(&self) -> f32 {
+        self.mouse_sensitivity
+    }
+
+    pub fn cycle_mouse_sensitivity(&mut self) {
+        self.mouse_sensitivity = Self::next_preset(self.mouse_sensitivity, &MOUSE_SENSITIVITIES);
+    }
+
+    pub fn get_fov_degrees(&self) -> f32 {
+        self.fov_degrees
+    }
+
+    pub fn cycle_fov(&mut self) {
+        self.fov_degrees = Self::next_preset(self.fov_degrees, &FOVS_DEGREES);
+    }
+
+    pub fn get_music_volume(&self) -> f32 {
+        self.music_volume
+    }
+
+    pub fn cycle_music_volume(&mut self) {
+        self.music_volume = Self::next_preset(self.music_volume, &MUSIC_VOLUMES);
+    }
+
+    /// returns none if auto saving is disabled
+    pub fn get_auto_save_interval(&self) -> Option<f32> {
+        (self.auto_save_interval > 0.0).then_some(self.auto_save_interval)
+    }
+
+    pub fn cycle_auto_save_interval(&mut self) {
+        self.auto_save_interval = Self::next_preset(self.auto_save_interval, &AUTO_SAVE_INTERVALS);
+    }
+
+    pub fn get_day_length(&self) -> f32 {
+        self.day_length_seconds
+    }
+
+    pub fn cycle_day_length(&mut self) {
+        self.day_length_seconds = Self::next_preset(self.day_length_seconds, &DAY_LENGTHS);
+    }
+
+    pub fn get_creature_update_budget(&self) -> usize {
+        self.creature_update_budget as usize
+    }
+
+    pub fn cycle_creature_update_budget(&mut self) {
+        self.creature_update_budget = CREATURE_UPDATE_BUDGETS
+            .into_iter()
+            .find(|budget| *budget > self.creature_update_budget)
+            .unwrap_or(CREATURE_UPDATE_BUDGETS[0]);
+    }
+
+    /// returns the first preset above the current value or the lowest one
+    fn next_preset(current: f32, presets: &[f32]) -> f32 {
+        presets
+            .iter()
+            .copied()
+            .find(|preset| *preset > current)
+            .unwrap_or(presets[0])
+    }
+
+    /// clamps a value read from disk to the preset range
+    fn validate_preset(value: f32, presets: &[f32], default: f32) -> f32 {
+        if value.is_finite() {
+            value.clamp(presets[0], presets[presets.len() - 1])
+        } else {
+            default
+        }
+    }
+
     pub fn has_dynamic_lighting(&self) -> bool {
         !matches!(self.shadow_type, ShadowType::None)
     }
@@ -49,9 +336,274 @@ impl Default for UserSettings {
     fn default() -> Self {
         Self {
             render_distance: DEFAULT_RENDER_DISTANCE,
+            simulation_distance: DEFAULT_RENDER_DISTANCE,
             has_sound: true,
             is_fullscreen: false,
             shadow_type: ShadowType::Soft,
+            show_hud: true,
+            auto_step: false,
+            show_tutorials: true,
+            smooth_lighting: true,
+            bloom: true,
+            water_reflections: true,
+            fog: true,
+            show_target_name: false,
+            show_compass_bar: true,
+            creature_spawn_rate: DEFAULT_CREATURE_SPAWN_RATE,
+            keep_spawn_loaded: false,
+            mouse_bindings: MouseBindings::default(),
+            break_speed: DEFAULT_BREAK_SPEED,
+            item_magnet_radius: DEFAULT_ITEM_MAGNET_RADIUS,
+            invert_y: false,
+            max_pitch: DEFAULT_MAX_PITCH,
+            creature_render_distance: MAX_RENDER_DISTANCE,
+            scroll_action: ScrollAction::Hotbar,
+            has_music: true,
+            music_volume: DEFAULT_MUSIC_VOLUME,
+            auto_save_interval: DEFAULT_AUTO_SAVE_INTERVAL,
+            mesh_cache: false,
+            day_length_seconds: DEFAULT_DAY_LENGTH,
+            key_bindings: KeyBindings::default(),
+            mouse_sensitivity: DEFAULT_MOUSE_SENSITIVITY,
+            fov_degrees: DEFAULT_FOV_DEGREES,
+            creature_update_budget: DEFAULT_CREATURE_UPDATE_BUDGET,
         }
     }
 }
+impl<Context> Decode<Context> for UserSettings {
+    /// settings saved before the appended fields existed keep their values
+    /// and get the defaults for the rest
+    fn decode<D: Decoder<Context = Context>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        let default = Self::default();
+        let render_distance = u32::decode(decoder)?;
+        Ok(Self {
+            render_distance,
+            has_sound: bool::decode(decoder)?,
+            is_fullscreen: bool::decode(decoder)?,
+            shadow_type: ShadowType::decode(decoder)?,
+            show_hud: decode_appended_field(decoder, || default.show_hud)?,
+            auto_step: decode_appended_field(decoder, || default.auto_step)?,
+            show_tutorials: decode_appended_field(decoder, || default.show_tutorials)?,
+            smooth_lighting: decode_appended_field(decoder, || default.smooth_lighting)?,
+            simulation_distance: decode_appended_field(decoder, || render_distance)?,
+            bloom: decode_appended_field(decoder, || default.bloom)?,
+            water_reflections: decode_appended_field(decoder, || default.water_reflections)?,
+            show_target_name: decode_appended_field(decoder, || default.show_target_name)?,
+            creature_spawn_rate: decode_appended_field(decoder, || default.creature_spawn_rate)?,
+            keep_spawn_loaded: decode_appended_field(decoder, || default.keep_spawn_loaded)?,
+            mouse_bindings: decode_appended_field(decoder, || default.mouse_bindings)?,
+            break_speed: decode_appended_field(decoder, || default.break_speed)?,
+            item_magnet_radius: decode_appended_field(decoder, || default.item_magnet_radius)?,
+            invert_y: decode_appended_field(decoder, || default.invert_y)?,
+            max_pitch: decode_appended_field(decoder, || default.max_pitch)?,
+            creature_render_distance: decode_appended_field(decoder, || {
+                default.creature_render_distance
+            })?,
+            scroll_action: decode_appended_field(decoder, || default.scroll_action)?,
+            has_music: decode_appended_field(decoder, || default.has_music)?,
+            music_volume: decode_appended_field(decoder, || default.music_volume)?,
+            auto_save_interval: decode_appended_field(decoder, || default.auto_save_interval)?,
+            fog: decode_appended_field(decoder, || default.fog)?,
+            mesh_cache: decode_appended_field(decoder, || default.mesh_cache)?,
+            day_length_seconds: decode_appended_field(decoder, || default.day_length_seconds)?,
+            show_compass_bar: decode_appended_field(decoder, || default.show_compass_bar)?,
+            key_bindings: decode_appended_field(decoder, || default.key_bindings)?,
+            mouse_sensitivity: decode_appended_field(decoder, || default.mouse_sensitivity)?,
+            fov_degrees: decode_appended_field(decoder, || default.fov_degrees)?,
+            creature_update_budget: decode_appended_field(decoder, || {
+                default.creature_update_budget
+            })?,
+        })
+    }
+}
+impl_borrow_decode!(UserSettings);
+
+#[cfg(test)]
+mod tests {
+    use bincode::{decode_from_slice, encode_to_vec};
+
+    use crate::service::persistence::config::SERIALIZATION_CONFIG;
+
+    use super::*;
+
+    #[test]
+    fn test_decode_legacy_user_settings() {
+        // the settings layout before the later fields were saved
+        let legacy_bytes = encode_to_vec(
+            (12_u32, false, true, ShadowType::Hard),
+            SERIALIZATION_CONFIG,
+        )
+        .unwrap();
+
+        let (user_settings, _): (UserSettings, usize) =
+            decode_from_slice(&legacy_bytes, SERIALIZATION_CONFIG).unwrap();
+        let user_settings = user_settings.validate();
+
+        assert_eq!(user_settings.get_render_distance(), 12);
+        assert_eq!(user_settings.get_simulation_distance(), 12);
+        assert!(!user_settings.has_sound);
+        assert!(user_settings.is_fullscreen);
+        assert!(matches!(user_settings.shadow_type, ShadowType::Hard));
+        assert!(user_settings.show_hud);
+        assert_eq!(user_settings.key_bindings, KeyBindings::default());
+        assert_eq!(
+            user_settings.get_creature_update_budget(),
+            DEFAULT_CREATURE_UPDATE_BUDGET as usize
+        );
+
+        let mut user_settings = user_settings;
+        user_settings.show_hud = false;
+        user_settings.cycle_fov();
+        let bytes = encode_to_vec(&user_settings, SERIALIZATION_CONFIG).unwrap();
+        let (decoded, _): (UserSettings, usize) =
+            decode_from_slice(&bytes, SERIALIZATION_CONFIG).unwrap();
+        assert!(!decoded.show_hud);
+        assert_eq!(decoded.get_fov_degrees(), user_settings.get_fov_degrees());
+    }
+
+    #[test]
+    fn test_simulation_distance_not_below_render_distance() {
+        let mut user_settings = UserSettings::default();
+        assert!(!user_settings.decrease_simulation_distance());
+
+        while user_settings.increase_render_distance() {}
+        assert_eq!(user_settings.get_render_distance(), MAX_RENDER_DISTANCE);
+        assert_eq!(user_settings.get_simulation_distance(), MAX_RENDER_DISTANCE);
+
+        while user_settings.increase_simulation_distance() {}
+        assert_eq!(
+            user_settings.get_simulation_distance(),
+            MAX_SIMULATION_DISTANCE
+        );
+        while user_settings.decrease_simulation_distance() {}
+        assert_eq!(user_settings.get_simulation_distance(), MAX_RENDER_DISTANCE);
+    }
+
+    #[test]
+    fn test_validate() {
+        let user_settings = UserSettings {
+            render_distance: MAX_RENDER_DISTANCE + 5,
+            simulation_distance: MIN_RENDER_DISTANCE,
+            ..Default::default()
+        }
+        .validate();
+        assert_eq!(user_settings.get_render_distance(), MAX_RENDER_DISTANCE);
+        assert_eq!(user_settings.get_simulation_distance(), MAX_RENDER_DISTANCE);
+    }
+
+    #[test]
+    fn test_cycle_creature_spawn_rate() {
+        let mut user_settings = UserSettings::default();
+        let mut rates = vec![];
+        for _ in 0..CREATURE_SPAWN_RATES.len() {
+            user_settings.cycle_creature_spawn_rate();
+            rates.push(user_settings.get_creature_spawn_rate());
+        }
+
+        assert_eq!(rates, [1.5, 2.0, 0.0, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn test_break_speed_is_never_below_default() {
+        let mut user_settings = UserSettings::default();
+        for _ in 0..BREAK_SPEEDS.len() * 2 {
+            user_settings.cycle_break_speed();
+            assert!(user_settings.get_break_speed() >= DEFAULT_BREAK_SPEED);
+        }
+
+        let user_settings = UserSettings {
+            break_speed: 0.0,
+            ..Default::default()
+        }
+        .validate();
+        assert_eq!(user_settings.get_break_speed(), BREAK_SPEEDS[0]);
+        let user_settings = UserSettings {
+            break_speed: f32::NAN,
+            ..Default::default()
+        }
+        .validate();
+        assert_eq!(user_settings.get_break_speed(), DEFAULT_BREAK_SPEED);
+    }
+
+    #[test]
+    fn test_max_pitch_below_vertical() {
+        let mut user_settings = UserSettings::default();
+        for _ in 0..MAX_PITCHES.len() {
+            user_settings.cycle_max_pitch();
+            assert!(user_settings.get_max_pitch() < std::f32::consts::FRAC_PI_2);
+        }
+    }
+
+    #[test]
+    fn test_mouse_sensitivity_in_range() {
+        let mut user_settings = UserSettings::default();
+        for _ in 0..MOUSE_SENSITIVITIES.len() {
+            user_settings.cycle_mouse_sensitivity();
+            assert!(user_settings.get_mouse_sensitivity() > 0.0);
+        }
+        assert_eq!(
+            user_settings.get_mouse_sensitivity(),
+            DEFAULT_MOUSE_SENSITIVITY
+        );
+
+        let user_settings = UserSettings {
+            mouse_sensitivity: 100.0,
+            ..Default::default()
+        }
+        .validate();
+        assert_eq!(
+            user_settings.get_mouse_sensitivity(),
+            MOUSE_SENSITIVITIES[MOUSE_SENSITIVITIES.len() - 1]
+        );
+    }
+
+    #[test]
+    fn test_creature_update_budget_in_range() {
+        let mut user_settings = UserSettings::default();
+        for _ in 0..CREATURE_UPDATE_BUDGETS.len() {
+            user_settings.cycle_creature_update_budget();
+            assert!(user_settings.get_creature_update_budget() > 0);
+        }
+        assert_eq!(
+            user_settings.get_creature_update_budget(),
+            DEFAULT_CREATURE_UPDATE_BUDGET as usize
+        );
+
+        let user_settings = UserSettings {
+            creature_update_budget: 0,
+            ..Default::default()
+        }
+        .validate();
+        assert_eq!(
+            user_settings.get_creature_update_budget(),
+            CREATURE_UPDATE_BUDGETS[0] as usize
+        );
+    }
+
+    #[test]
+    fn test_creature_render_distance_not_above_render_distance() {
+        let mut user_settings = UserSettings::default();
+        assert_eq!(
+            user_settings.get_creature_render_distance(),
+            DEFAULT_RENDER_DISTANCE
+        );
+
+        user_settings.cycle_creature_render_distance();
+        assert_eq!(
+            user_settings.get_creature_render_distance(),
+            MIN_CREATURE_RENDER_DISTANCE
+        );
+        user_settings.cycle_creature_render_distance();
+        assert_eq!(
+            user_settings.get_creature_render_distance(),
+            MIN_CREATURE_RENDER_DISTANCE + 1
+        );
+
+        user_settings.creature_render_distance = DEFAULT_RENDER_DISTANCE;
+        user_settings.decrease_render_distance();
+        assert_eq!(
+            user_settings.get_creature_render_distance(),
+            DEFAULT_RENDER_DISTANCE - 1
+        );
+    }
+}
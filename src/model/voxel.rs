@@ -2,10 +2,24 @@ use bincode::{Decode, Encode};
 
 /// the maximum number of variants the voxel enum can have,
 /// used for performance optimisations
-pub const MAX_VOXEL_VARIANTS: usize = 32;
+pub const MAX_VOXEL_VARIANTS: usize = 64;
 
-#[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq, Hash)]
+/// color of the light emitted by a voxel
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LightColor {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+}
+impl LightColor {
+    pub const fn new(r: f32, g: f32, b: f32) -> Self {
+        Self { r, g, b }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Encode, Decode, PartialEq, Eq, Hash)]
 pub enum Voxel {
+    #[default]
     None,
     Cobblestone,
     Sand,
@@ -33,6 +47,21 @@ pub enum Voxel {
     Bomb,
     ActiveBomb,
     Glass,
+    SnowyLeaves,
+    Compass,
+    WeatherTotem,
+    CoalOre,
+    IronOre,
+    Fire,
+    Torch,
+    Furnace,
+    Iron,
+    StoneSlab,
+    WoodStairs,
+    Bucket,
+    WaterBucket,
+    Pickaxe,
+    ReachStaff,
 }
 impl Voxel {
     /// voxels that are fully or partially transparent
@@ -68,7 +97,38 @@ impl Voxel {
         Self::Water4,
     ];
 
-    pub const PARTIAL_HEIGHT: [Self; 4] = [Self::Water1, Self::Water2, Self::Water3, Self::Water4];
+    /// voxels that can catch fire
+    pub const FLAMMABLE: [Self; 3] = [Self::Wood, Self::Leaves, Self::Boards];
+
+    pub const PARTIAL_HEIGHT: [Self; 5] = [
+        Self::Water1,
+        Self::Water2,
+        Self::Water3,
+        Self::Water4,
+        Self::StoneSlab,
+    ];
+
+    /// voxels that don't fill their whole cell, they never hide the faces of their neighbours
+    pub const SHAPED: [Self; 2] = [Self::StoneSlab, Self::WoodStairs];
+
+    /// voxels placed with their top towards the side they were placed against
+    pub const DIRECTIONAL: [Self; 2] = [Self::Wood, Self::StonePillar];
+
+    /// solid voxels that only fill the lower half of their cell
+    pub const SLABS: [Self; 1] = [Self::StoneSlab];
+
+    /// voxels that are broken faster with a pickaxe
+    pub const STONE_FAMILY: [Self; 8] = [
+        Self::Stone,
+        Self::Cobblestone,
+        Self::StoneBrick,
+        Self::StonePillar,
+        Self::StoneSlab,
+        Self::CoalOre,
+        Self::IronOre,
+        Self::Furnace,
+    ];
+
     pub const SIZE: f32 = 1.0;
     pub const HALF_SIZE: f32 = Self::SIZE / 2.0;
 
@@ -90,6 +150,21 @@ impl Voxel {
             Self::Grass => "Grass",
             Self::Wood => "Wood",
             Self::Leaves => "Leaves",
+            Self::SnowyLeaves => "Snowy Leaves",
+            Self::Compass => "Compass",
+            Self::WeatherTotem => "Weather Totem",
+            Self::CoalOre => "Coal Ore",
+            Self::IronOre => "Iron Ore",
+            Self::Fire => "Fire",
+            Self::Torch => "Torch",
+            Self::Furnace => "Furnace",
+            Self::Iron => "Iron",
+            Self::StoneSlab => "Stone Slab",
+            Self::WoodStairs => "Wooden Stairs",
+            Self::Bucket => "Bucket",
+            Self::WaterBucket => "Water Bucket",
+            Self::Pickaxe => "Pickaxe",
+            Self::ReachStaff => "Reach Staff",
             Self::Brick => "Brick",
             Self::Dirt => "Dirt",
             Self::Boards => "Wooden Boards",
@@ -126,9 +201,52 @@ impl Voxel {
                 | Voxel::Water4
         )
     }
-}
-impl Default for Voxel {
-    fn default() -> Self {
-        Self::None
+
+    /// items that are only held and can't be put into the world
+    pub fn is_placeable(self) -> bool {
+        !matches!(
+            self,
+            Voxel::Compass
+                | Voxel::WeatherTotem
+                | Voxel::Fire
+                | Voxel::Bucket
+                | Voxel::WaterBucket
+                | Voxel::Pickaxe
+                | Voxel::ReachStaff
+        )
+    }
+
+    /// extra distance in voxels that can be reached while the item is held
+    pub fn reach_bonus(self) -> f32 {
+        match self {
+            Self::ReachStaff => 4.0,
+            _ => 0.0,
+        }
+    }
+
+    /// multiplier of the speed the target is broken at while the item is held
+    pub fn break_speed_against(self, target: Voxel) -> f32 {
+        match self {
+            Self::Pickaxe if Self::STONE_FAMILY.contains(&target) => 3.0,
+            _ => 1.0,
+        }
+    }
+
+    /// returns the color of the emitted light if the voxel is a light source
+    pub fn emits_light(self) -> Option<LightColor> {
+        match self {
+            Self::Lamp => Some(LightColor::new(0.95, 1.0, 0.6)),
+            Self::Fire => Some(LightColor::new(1.0, 0.6, 0.25)),
+            Self::Torch => Some(LightColor::new(1.0, 0.8, 0.45)),
+            _ => None,
+        }
+    }
+
+    /// distance in voxels reached by the emitted light
+    pub fn light_radius(self) -> f32 {
+        match self {
+            Self::Torch => 4.0,
+            _ => 6.0,
+        }
     }
 }
@@ -1,4 +1,9 @@
-use std::{collections::HashMap, mem::take, time::Instant};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    mem::take,
+    time::Instant,
+};
 
 use macroquad::prelude::{error, info};
 
@@ -6,34 +11,88 @@ use crate::{
     model::{
         area::{AREA_HEIGHT, Area},
         location::AreaLocation,
+        orientation::Orientation,
         voxel::Voxel,
+        world_rules::{TreeDensity, WorldGenConfig},
+    },
+    service::{
+        area_generation::generator::{AreaGenerator, hash_world_name},
+        persistence::world_persistence::{self, AreaLoader, AreaSaver},
     },
-    service::persistence::world_persistence::{self, AreaLoader},
 };
 
 use super::{area::AREA_SIZE, location::InternalLocation};
 
+/// limits the number of areas written to disk by each unload
+const MAX_AREA_WRITES_PER_BATCH: usize = 8;
+/// memory guard, the areas farthest from the camera are unloaded above this count
+pub const MAX_LOADED_AREAS: usize = 1600;
+/// number of areas in each direction around the keepalive center that stay loaded
+const KEEPALIVE_RADIUS: u32 = 1;
+
 pub struct World {
     world_name: String,
+    seed: u64,
     areas: HashMap<AreaLocation, Area>,
     area_loader: AreaLoader,
+    area_saver: AreaSaver,
+    max_loaded_areas: usize,
     empty_area: Area,
+    /// used when generating areas that were never saved
+    tree_density: TreeDensity,
+    /// used when generating areas that were never saved
+    gen_config: WorldGenConfig,
+    keepalive_center: Option<AreaLocation>,
+    /// areas that are never unloaded by `retain_areas`
+    keepalive_areas: Vec<AreaLocation>,
 }
 impl World {
     pub fn new(world_name: impl Into<String>) -> Self {
+        let world_name = world_name.into();
         Self {
-            world_name: world_name.into(),
+            seed: hash_world_name(&world_name),
+            world_name,
             areas: HashMap::new(),
             area_loader: AreaLoader::new(),
+            area_saver: AreaSaver::new(MAX_AREA_WRITES_PER_BATCH),
+            max_loaded_areas: MAX_LOADED_AREAS,
             empty_area: Area::new(AreaLocation::new(0, 0)),
+            tree_density: TreeDensity::default(),
+            gen_config: WorldGenConfig::default(),
+            keepalive_center: None,
+            keepalive_areas: vec![],
         }
     }
 
+    /// the seed used to generate areas that were never saved, defaults to the world name hash
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn with_tree_density(mut self, tree_density: TreeDensity) -> Self {
+        self.tree_density = tree_density;
+        self
+    }
+
+    pub fn with_gen_config(mut self, gen_config: WorldGenConfig) -> Self {
+        self.gen_config = gen_config;
+        self
+    }
+
     pub fn load_area(&mut self, area_location: AreaLocation) {
         if self.areas.contains_key(&area_location) {
             return;
         }
-        let area = world_persistence::load_blocking(area_location, &self.world_name);
+        let area = self.area_saver.take(area_location).unwrap_or_else(|| {
+            world_persistence::load_blocking(
+                area_location,
+                &self.world_name,
+                self.seed,
+                self.tree_density,
+                self.gen_config,
+            )
+        });
         self.areas.insert(area_location, area);
     }
 
@@ -173,6 +232,25 @@ impl World {
         area.get(local_location)
     }
 
+    pub fn get_orientation_with_cache(
+        &mut self,
+        location: impl Into<InternalLocation>,
+        cached_area: Option<&Area>,
+    ) -> Orientation {
+        let (area_location, local_location) =
+            Self::convert_global_to_area_and_local_location(location.into());
+
+        if let Some(area) = cached_area
+            && area.get_area_location() == area_location
+        {
+            return area.get_orientation(local_location);
+        }
+
+        self.load_area(area_location);
+        let area = &self.areas[&area_location];
+        area.get_orientation(local_location)
+    }
+
     pub fn get_without_loading(&self, location: impl Into<InternalLocation>) -> Option<Voxel> {
         let (area_location, local_location) =
             Self::convert_global_to_area_and_local_location(location.into());
@@ -187,13 +265,58 @@ impl World {
         self.load_area(area_location);
         let area = self.areas.get_mut(&area_location).expect("Area not loaded");
         area.has_changed = true;
+        area.is_modified = true;
         area.set(local_location, voxel);
     }
 
+    pub fn set_with_orientation(
+        &mut self,
+        location: impl Into<InternalLocation>,
+        voxel: Voxel,
+        orientation: Orientation,
+    ) {
+        let (area_location, local_location) =
+            Self::convert_global_to_area_and_local_location(location.into());
+        self.load_area(area_location);
+        let area = self.areas.get_mut(&area_location).expect("Area not loaded");
+        area.has_changed = true;
+        area.is_modified = true;
+        area.set_with_orientation(local_location, voxel, orientation);
+    }
+
+    /// replaces the loaded areas generated by an older version that were never changed,
+    /// returns the regenerated locations
+    pub fn regenerate_stale_areas(&mut self) -> Vec<AreaLocation> {
+        let stale: Vec<_> = self
+            .areas
+            .values()
+            .filter(|area| area.is_stale())
+            .map(|area| area.get_area_location())
+            .collect();
+        for area_location in &stale {
+            let area = AreaGenerator::generate_area(
+                *area_location,
+                self.seed,
+                self.tree_density,
+                self.gen_config,
+            );
+            self.areas.insert(*area_location, area);
+        }
+        info!("Regenerated {} stale areas", stale.len());
+
+        stale
+    }
+
     /// loads all areas at the input locations asynchronously and unloads
     /// all areas not at the input locations asynchronously
-    /// moves any loaded areas into the main area map
-    pub fn retain_areas(&mut self, area_locations: &[AreaLocation]) {
+    /// moves any loaded areas into the main area map,
+    /// only the areas closest to the camera are kept above the loaded areas limit
+    pub fn retain_areas(&mut self, area_locations: &[AreaLocation], camera_area: AreaLocation) {
+        let area_locations =
+            Self::select_nearest_areas(area_locations, camera_area, self.max_loaded_areas);
+        let area_locations = self.add_keepalive_areas(area_locations);
+        let area_locations = area_locations.as_ref();
+
         let loaded = self.area_loader.get_loaded();
         for area in loaded {
             let area_location = area.get_area_location();
@@ -202,6 +325,7 @@ impl World {
             }
             self.areas.insert(area_location, area);
         }
+        self.take_unsaved_areas(area_locations);
 
         let area_locations_to_load = area_locations
             .iter()
@@ -209,19 +333,120 @@ impl World {
             .copied()
             .collect::<Vec<_>>();
 
-        self.area_loader
-            .batch_load(&area_locations_to_load, &self.world_name);
+        self.area_loader.batch_load(
+            &area_locations_to_load,
+            &self.world_name,
+            self.seed,
+            self.tree_density,
+            self.gen_config,
+        );
 
+        let areas_to_retain: HashSet<_> = area_locations.iter().collect();
         let areas_to_unload: Vec<_> = self
             .areas
             .keys()
-            .filter(|loaded| !area_locations.contains(loaded))
+            .filter(|loaded| !areas_to_retain.contains(loaded))
             .copied()
             .collect();
 
         self.unload_areas(&areas_to_unload);
     }
 
+    fn select_nearest_areas(
+        area_locations: &[AreaLocation],
+        center: AreaLocation,
+        max_count: usize,
+    ) -> Cow<'_, [AreaLocation]> {
+        if area_locations.len() <= max_count {
+            return Cow::Borrowed(area_locations);
+        }
+
+        let mut nearest = area_locations.to_vec();
+        nearest.sort_by_key(|area_location| {
+            let dx = area_location.x.abs_diff(center.x) as u64;
+            let dy = area_location.y.abs_diff(center.y) as u64;
+            dx * dx + dy * dy
+        });
+        nearest.truncate(max_count);
+
+        Cow::Owned(nearest)
+    }
+
+    /// keeps the areas around the center loaded regardless of the camera position,
+    /// the previous keepalive areas are unloaded by the next retain if the center changes
+    pub fn set_keepalive_center(&mut self, center: Option<AreaLocation>) {
+        if self.keepalive_center == center {
+            return;
+        }
+        self.keepalive_center = center;
+        self.keepalive_areas = center
+            .map(|center| {
+                let x_range = center.x.saturating_sub(KEEPALIVE_RADIUS)
+                    ..=center.x.saturating_add(KEEPALIVE_RADIUS);
+                x_range
+                    .flat_map(|x| {
+                        (center.y.saturating_sub(KEEPALIVE_RADIUS)
+                            ..=center.y.saturating_add(KEEPALIVE_RADIUS))
+                            .map(move |y| AreaLocation::new(x, y))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        info!("Keeping {} areas loaded", self.keepalive_areas.len());
+    }
+
+    fn add_keepalive_areas<'a>(
+        &self,
+        area_locations: Cow<'a, [AreaLocation]>,
+    ) -> Cow<'a, [AreaLocation]> {
+        let missing: Vec<_> = self
+            .keepalive_areas
+            .iter()
+            .filter(|area_location| !area_locations.contains(area_location))
+            .copied()
+            .collect();
+        if missing.is_empty() {
+            return area_locations;
+        }
+
+        let mut area_locations = area_locations.into_owned();
+        area_locations.extend(missing);
+        Cow::Owned(area_locations)
+    }
+
+    /// moves areas that are still waiting to be saved back into the main area map
+    fn take_unsaved_areas(&mut self, area_locations: &[AreaLocation]) {
+        for area_location in area_locations {
+            if self.areas.contains_key(area_location) {
+                continue;
+            }
+            if let Some(area) = self.area_saver.take(*area_location) {
+                self.areas.insert(*area_location, area);
+            }
+        }
+    }
+
+    /// queues copies of the changed areas to be written in the background while they stay loaded,
+    /// returns the number of queued areas
+    pub fn save_changed_areas(&mut self) -> usize {
+        let changed: Vec<_> = self
+            .areas
+            .values_mut()
+            .filter(|area| area.has_changed)
+            .map(|area| {
+                let copy = area.clone();
+                area.has_changed = false;
+                copy
+            })
+            .collect();
+        let count = changed.len();
+        self.area_saver.queue(changed);
+        let _dispatched = self.area_saver.flush(&self.world_name);
+
+        count
+    }
+
+    /// queues the changed areas to be saved and writes the next batch in the background
     fn unload_areas(&mut self, areas_to_unload: &[AreaLocation]) {
         let mut unloaded = Vec::with_capacity(32);
         for area_location in areas_to_unload {
@@ -232,17 +457,26 @@ impl World {
                 unloaded.push(area);
             }
         }
-        if unloaded.is_empty() {
-            return;
-        }
-
-        world_persistence::store(unloaded, self.world_name.clone());
+        self.area_saver.queue(unloaded);
+        let _dispatched = self.area_saver.flush(&self.world_name);
     }
 
     pub fn get_loaded_areas_count(&self) -> usize {
         self.areas.len()
     }
 
+    /// loaded areas that will be written to disk when unloaded
+    pub fn get_changed_area_locations(&self) -> impl Iterator<Item = AreaLocation> + '_ {
+        self.areas
+            .values()
+            .filter(|area| area.has_changed)
+            .map(|area| area.get_area_location())
+    }
+
+    pub fn get_max_loaded_areas(&self) -> usize {
+        self.max_loaded_areas
+    }
+
     /// loads all areas and blocks the main thread
     pub fn load_all_blocking(&mut self, areas_to_load: &[AreaLocation]) {
         let start = Instant::now();
@@ -251,10 +485,19 @@ impl World {
             .filter(|area_location| !self.areas.contains_key(area_location))
             .copied()
             .collect();
+        self.take_unsaved_areas(&filtered_unloaded);
+        let filtered_unloaded: Vec<_> = filtered_unloaded
+            .into_iter()
+            .filter(|area_location| !self.areas.contains_key(area_location))
+            .collect();
         info!("Loading {} areas", filtered_unloaded.len());
-        let areas = self
-            .area_loader
-            .load_all_blocking(&filtered_unloaded, &self.world_name);
+        let areas = self.area_loader.load_all_blocking(
+            &filtered_unloaded,
+            &self.world_name,
+            self.seed,
+            self.tree_density,
+            self.gen_config,
+        );
         for area in areas {
             self.areas.insert(area.get_area_location(), area);
         }
@@ -270,6 +513,8 @@ impl World {
             .into_values()
             .filter(|area| area.has_changed)
             .collect();
+        // the queued areas are written first so an older in flight copy can't overwrite newer data
+        self.area_saver.store_all_blocking(&self.world_name);
         world_persistence::store_all_blocking(areas, self.world_name.clone());
         let end = start.elapsed();
         info!("Saved in {}ms", end.as_millis());
@@ -278,6 +523,14 @@ impl World {
     pub fn get_world_name(&self) -> &str {
         &self.world_name
     }
+
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn get_gen_config(&self) -> WorldGenConfig {
+        self.gen_config
+    }
 }
 
 #[cfg(test)]
@@ -285,11 +538,27 @@ mod tests {
     use std::{fs, time::Duration};
 
     use crate::{
-        model::location::Location, service::persistence::world_persistence::get_world_directory,
+        model::location::Location,
+        service::{
+            area_generation::generator::GENERATION_VERSION,
+            persistence::world_persistence::get_world_directory,
+        },
     };
 
     use super::*;
 
+    #[test]
+    fn test_seed_defaults_to_world_name_hash() {
+        let world = World::new("test_world_test_seed_defaults_to_world_name_hash");
+        assert_eq!(
+            world.get_seed(),
+            hash_world_name("test_world_test_seed_defaults_to_world_name_hash")
+        );
+
+        let world = World::new("test_world_test_seed_defaults_to_world_name_hash").with_seed(7);
+        assert_eq!(world.get_seed(), 7);
+    }
+
     #[test]
     fn test_get_and_set() {
         let mut world = World::new("test_world_test_get_and_set");
@@ -305,6 +574,80 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_get_changed_area_locations() {
+        let mut world = World::new("test_world_test_get_changed_area_locations");
+        let location = InternalLocation::new(3 * AREA_SIZE, 4 * AREA_SIZE, 3);
+        world.set(location, Voxel::Brick);
+
+        assert!(
+            world
+                .get_changed_area_locations()
+                .any(|area_location| area_location == location.into())
+        );
+    }
+
+    #[test]
+    fn test_regenerate_stale_areas_keeps_modified_areas() {
+        let mut world = World::new("test_world_test_regenerate_stale_areas_keeps_modified_areas");
+        let create_old_area = |x, is_modified| {
+            let mut area = Area::new(AreaLocation::new(x, 0));
+            area.set(InternalLocation::new(0, 0, 3), Voxel::Brick);
+            area.has_changed = false;
+            area.is_modified = is_modified;
+            area
+        };
+        let unmodified = create_old_area(0, false);
+        let modified = create_old_area(1, true);
+        let mut unsaved = create_old_area(2, false);
+        unsaved.has_changed = true;
+        for area in [unmodified, modified, unsaved] {
+            world.areas.insert(area.get_area_location(), area);
+        }
+
+        let regenerated = world.regenerate_stale_areas();
+
+        assert_eq!(regenerated, vec![AreaLocation::new(0, 0)]);
+        let regenerated_area = &world.areas[&AreaLocation::new(0, 0)];
+        assert_eq!(regenerated_area.generation_version, GENERATION_VERSION);
+        assert!(regenerated_area.has_changed);
+        for x in [1, 2] {
+            let area = &world.areas[&AreaLocation::new(x, 0)];
+            assert_eq!(area.get(InternalLocation::new(0, 0, 3)), Voxel::Brick);
+            assert_eq!(area.generation_version, 0);
+        }
+        assert!(world.regenerate_stale_areas().is_empty());
+    }
+
+    #[test]
+    fn test_save_changed_areas_keeps_areas_loaded() {
+        let world_name = "test_world_test_save_changed_areas_keeps_areas_loaded";
+        let mut world = World::new(world_name);
+        let location = InternalLocation::new(3, 4, 5);
+        world.set(location, Voxel::Brick);
+        let area_location = World::convert_global_to_area_location(location);
+
+        assert!(world.save_changed_areas() > 0);
+        assert!(world.areas.contains_key(&area_location));
+        assert_eq!(world.get_changed_area_locations().count(), 0);
+        assert_eq!(world.save_changed_areas(), 0);
+
+        world.area_saver.store_all_blocking(world_name);
+        let loaded = world_persistence::load_blocking(
+            area_location,
+            world_name,
+            world.get_seed(),
+            TreeDensity::default(),
+            WorldGenConfig::default(),
+        );
+        assert_eq!(
+            loaded.get(World::convert_global_to_local_location(location)),
+            Voxel::Brick
+        );
+
+        fs::remove_dir_all(get_world_directory(world_name)).unwrap();
+    }
+
     #[test]
     fn test_get_same_location() {
         let mut world = World::new("test_world_test_get_same_location");
@@ -342,9 +685,9 @@ mod tests {
         let mut world = World::new(world_name);
         let initial_areas = [AreaLocation::new(0, 0), AreaLocation::new(1, 0)];
 
-        world.retain_areas(&initial_areas);
+        world.retain_areas(&initial_areas, initial_areas[0]);
         std::thread::sleep(Duration::from_millis(150));
-        world.retain_areas(&initial_areas);
+        world.retain_areas(&initial_areas, initial_areas[0]);
 
         assert_eq!(world.areas.len(), initial_areas.len());
         for area in initial_areas {
@@ -353,9 +696,9 @@ mod tests {
 
         let other_areas = [AreaLocation::new(0, 0), AreaLocation::new(2, 0)];
 
-        world.retain_areas(&other_areas);
+        world.retain_areas(&other_areas, other_areas[0]);
         std::thread::sleep(Duration::from_millis(100));
-        world.retain_areas(&other_areas);
+        world.retain_areas(&other_areas, other_areas[0]);
 
         assert_eq!(world.areas.len(), other_areas.len());
         for area in other_areas {
@@ -365,6 +708,105 @@ mod tests {
         fs::remove_dir_all(&remove_dir).unwrap();
     }
 
+    #[test]
+    fn test_keepalive_areas_survive_retain_far_away() {
+        let world_name = "test_world_test_keepalive_areas_survive_retain_far_away";
+        let mut world = World::new(world_name);
+        let spawn_area = AreaLocation::new(5, 5);
+        let far_areas = [AreaLocation::new(50, 50)];
+        world.set_keepalive_center(Some(spawn_area));
+        world.load_all_blocking(&[spawn_area]);
+        world.set(
+            InternalLocation::new(5 * AREA_SIZE, 5 * AREA_SIZE, 3),
+            Voxel::Brick,
+        );
+
+        for _ in 0..2 {
+            world.retain_areas(&far_areas, far_areas[0]);
+            std::thread::sleep(Duration::from_millis(150));
+        }
+        world.retain_areas(&far_areas, far_areas[0]);
+
+        assert_eq!(world.areas.len(), far_areas.len() + 9);
+        assert!(world.areas.contains_key(&far_areas[0]));
+        for x in 4..=6 {
+            for y in 4..=6 {
+                assert!(world.areas.contains_key(&AreaLocation::new(x, y)));
+            }
+        }
+        assert_eq!(
+            world.get(InternalLocation::new(5 * AREA_SIZE, 5 * AREA_SIZE, 3)),
+            Voxel::Brick
+        );
+
+        world.set_keepalive_center(None);
+        world.retain_areas(&far_areas, far_areas[0]);
+        assert_eq!(world.areas.len(), far_areas.len());
+
+        world.save_all_blocking();
+        let _ = fs::remove_dir_all(get_world_directory(world_name));
+    }
+
+    #[test]
+    fn test_rapid_unload_and_reload_keeps_edits() {
+        let world_name = "test_world_test_rapid_unload_and_reload_keeps_edits";
+        let remove_dir = get_world_directory(world_name);
+        let mut world = World::new(world_name);
+        let edited_areas: Vec<_> = (0..MAX_AREA_WRITES_PER_BATCH as u32 * 2)
+            .map(|x| AreaLocation::new(x, 0))
+            .collect();
+        let other_areas = [AreaLocation::new(0, 5)];
+        world.load_all_blocking(&edited_areas);
+        for area_location in &edited_areas {
+            let location = InternalLocation::new(area_location.x * AREA_SIZE, 0, 3);
+            world.set(location, Voxel::Brick);
+        }
+
+        for _ in 0..3 {
+            world.retain_areas(&other_areas, other_areas[0]);
+            world.retain_areas(&edited_areas, edited_areas[0]);
+            for area_location in &edited_areas[..MAX_AREA_WRITES_PER_BATCH] {
+                world.load_area(*area_location);
+            }
+        }
+        for area_location in &edited_areas {
+            let location = InternalLocation::new(area_location.x * AREA_SIZE, 0, 3);
+            assert_eq!(world.get(location), Voxel::Brick);
+        }
+
+        world.save_all_blocking();
+        for area_location in &edited_areas {
+            let location = InternalLocation::new(area_location.x * AREA_SIZE, 0, 3);
+            assert_eq!(world.get(location), Voxel::Brick);
+        }
+
+        fs::remove_dir_all(&remove_dir).unwrap();
+    }
+
+    #[test]
+    fn test_retain_areas_enforces_loaded_areas_cap() {
+        let world_name = "test_world_test_retain_areas_enforces_loaded_areas_cap";
+        let mut world = World::new(world_name);
+        world.max_loaded_areas = 4;
+        let camera_area = AreaLocation::new(10, 10);
+        let load_zone: Vec<_> = (8..=12)
+            .flat_map(|x| (8..=12).map(move |y| AreaLocation::new(x, y)))
+            .collect();
+
+        world.load_all_blocking(&load_zone);
+        assert_eq!(world.areas.len(), load_zone.len());
+        world.retain_areas(&load_zone, camera_area);
+
+        assert_eq!(world.areas.len(), 4);
+        assert!(world.areas.contains_key(&camera_area));
+        for area_location in world.areas.keys() {
+            assert!(area_location.x.abs_diff(10) + area_location.y.abs_diff(10) <= 1);
+        }
+
+        world.save_all_blocking();
+        let _ = fs::remove_dir_all(get_world_directory(world_name));
+    }
+
     #[test]
     fn test_load_all_blocking() {
         let world_name = "test_world_test_load_all_blocking";
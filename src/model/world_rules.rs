@@ -0,0 +1,138 @@
+use bincode::{Decode, Encode};
+
+use crate::model::area::AREA_HEIGHT;
+
+/// height of the lake surface in the default terrain
+const DEFAULT_SEA_LEVEL: u32 = (AREA_HEIGHT as f32 * 0.27) as u32;
+
+/// how many trees are generated compared to the default
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Encode, Decode)]
+pub enum TreeDensity {
+    None,
+    Sparse,
+    #[default]
+    Normal,
+    Dense,
+}
+impl TreeDensity {
+    pub fn get_multiplier(self) -> f32 {
+        match self {
+            Self::None => 0.0,
+            Self::Sparse => 0.5,
+            Self::Normal => 1.0,
+            Self::Dense => 2.0,
+        }
+    }
+
+    pub fn get_name(self) -> &'static str {
+        match self {
+            Self::None => "None",
+            Self::Sparse => "Sparse",
+            Self::Normal => "Normal",
+            Self::Dense => "Dense",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Self::None => Self::Sparse,
+            Self::Sparse => Self::Normal,
+            Self::Normal => Self::Dense,
+            Self::Dense => Self::None,
+        }
+    }
+}
+
+/// terrain generation parameters, stored with the world when it is created
+#[derive(Debug, Clone, Copy, PartialEq, Encode, Decode)]
+pub struct WorldGenConfig {
+    /// multiplier of the terrain height variation, near zero generates a level plain
+    pub amplitude: f32,
+    /// height of the lake surface
+    pub sea_level: u32,
+    /// multiplier of how much of the cave noise is carved out, zero disables caves
+    pub cave_density: f32,
+    /// multiplier applied on top of the tree density rule
+    pub tree_frequency: f32,
+}
+impl Default for WorldGenConfig {
+    fn default() -> Self {
+        WorldGenPreset::default().get_config()
+    }
+}
+
+/// the generation configs selectable when creating a world
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WorldGenPreset {
+    Flat,
+    #[default]
+    Normal,
+    Mountainous,
+}
+impl WorldGenPreset {
+    const ALL: [Self; 3] = [Self::Flat, Self::Normal, Self::Mountainous];
+
+    pub fn get_config(self) -> WorldGenConfig {
+        match self {
+            Self::Flat => WorldGenConfig {
+                amplitude: 0.01,
+                sea_level: DEFAULT_SEA_LEVEL,
+                cave_density: 0.5,
+                tree_frequency: 1.0,
+            },
+            Self::Normal => WorldGenConfig {
+                amplitude: 1.0,
+                sea_level: DEFAULT_SEA_LEVEL,
+                cave_density: 1.0,
+                tree_frequency: 1.0,
+            },
+            Self::Mountainous => WorldGenConfig {
+                amplitude: 1.35,
+                sea_level: DEFAULT_SEA_LEVEL + 4,
+                cave_density: 1.25,
+                tree_frequency: 0.75,
+            },
+        }
+    }
+
+    /// finds the preset a stored config was created from
+    pub fn from_config(config: WorldGenConfig) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|preset| preset.get_config() == config)
+    }
+
+    pub fn get_name(self) -> &'static str {
+        match self {
+            Self::Flat => "Flat",
+            Self::Normal => "Normal",
+            Self::Mountainous => "Mountainous",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Self::Flat => Self::Normal,
+            Self::Normal => Self::Mountainous,
+            Self::Mountainous => Self::Flat,
+        }
+    }
+}
+
+/// gameplay rules chosen when creating a world
+#[derive(Debug, Clone, Copy, Encode, Decode)]
+pub struct WorldRules {
+    /// allows creatures to modify voxels
+    pub mob_griefing: bool,
+    pub tree_density: TreeDensity,
+    pub world_gen: WorldGenConfig,
+}
+impl Default for WorldRules {
+    fn default() -> Self {
+        Self {
+            mob_griefing: true,
+            tree_density: TreeDensity::default(),
+            world_gen: WorldGenConfig::default(),
+        }
+    }
+}
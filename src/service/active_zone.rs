@@ -42,9 +42,14 @@ pub fn get_render_zone_on_world_load(
     get_render_zone(area_location, reduced_render_size)
 }
 
-/// returns a list of areas to be loaded from disk
-pub fn get_load_zone(area_location: AreaLocation, render_size: u32) -> Vec<AreaLocation> {
-    get_render_zone(area_location, render_size + LOAD_EXTRA)
+/// returns a list of areas to be loaded from disk and simulated,
+/// never smaller than the render zone
+pub fn get_load_zone(
+    area_location: AreaLocation,
+    render_size: u32,
+    simulation_size: u32,
+) -> Vec<AreaLocation> {
+    get_render_zone(area_location, render_size.max(simulation_size) + LOAD_EXTRA)
 }
 
 /// returns a list of areas to be loaded from disk upon entering the world
@@ -76,8 +81,12 @@ mod tests {
         const MINIMUM_AREAS: u32 = MINIMUM_AREAS_SIDE * MINIMUM_AREAS_SIDE;
         assert_eq!(render_zone.len() as u32, MINIMUM_AREAS);
         for location in render_zone {
-            assert!((location.x as i32 - 10).abs() as u32 <= INITIAL_LOAD_MINIMUM_RENDER_DISTANCE);
-            assert!((location.y as i32 - 10).abs() as u32 <= INITIAL_LOAD_MINIMUM_RENDER_DISTANCE);
+            assert!(
+                (location.x as i32 - 10).unsigned_abs() <= INITIAL_LOAD_MINIMUM_RENDER_DISTANCE
+            );
+            assert!(
+                (location.y as i32 - 10).unsigned_abs() <= INITIAL_LOAD_MINIMUM_RENDER_DISTANCE
+            );
         }
     }
 
@@ -88,14 +97,14 @@ mod tests {
         const AREAS_SIZE: u32 = AREAS_SIDE * AREAS_SIDE;
         assert_eq!(render_zone.len() as u32, AREAS_SIZE);
         for location in render_zone {
-            assert!((location.x as i32 - 10).abs() as u32 <= AREAS_SIDE);
-            assert!((location.y as i32 - 10).abs() as u32 <= AREAS_SIDE);
+            assert!((location.x as i32 - 10).unsigned_abs() <= AREAS_SIDE);
+            assert!((location.y as i32 - 10).unsigned_abs() <= AREAS_SIDE);
         }
     }
 
     #[test]
     fn test_get_load_zone() {
-        let load_zone = get_load_zone(AreaLocation::new(10, 10), 2);
+        let load_zone = get_load_zone(AreaLocation::new(10, 10), 2, 2);
         let side_of_zone = 1 + 2 * (2 + LOAD_EXTRA);
         let area_of_zone = side_of_zone * side_of_zone;
         assert_eq!(load_zone.len(), area_of_zone as usize);
@@ -105,6 +114,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_get_load_zone_larger_simulation_size() {
+        let base = AreaLocation::new(10, 10);
+        let render_zone = get_render_zone(base, 2);
+        let load_zone = get_load_zone(base, 2, 5);
+        let side_of_zone = 1 + 2 * (5 + LOAD_EXTRA);
+        assert_eq!(load_zone.len(), (side_of_zone * side_of_zone) as usize);
+        assert!(render_zone.iter().all(|area| load_zone.contains(area)));
+
+        let load_zone = get_load_zone(base, 5, 2);
+        assert_eq!(load_zone.len(), (side_of_zone * side_of_zone) as usize);
+    }
+
     #[test]
     fn test_get_load_zone_on_world_load() {
         let base = AreaLocation::new(10, 10);
@@ -119,11 +141,11 @@ mod tests {
 
         for location in load_zone {
             assert!(
-                (location.x as i32 - base.x as i32).abs() as u32
+                (location.x as i32 - base.x as i32).unsigned_abs()
                     <= render_size + INITIAL_LOAD_EXTRA
             );
             assert!(
-                (location.y as i32 - base.y as i32).abs() as u32
+                (location.y as i32 - base.y as i32).unsigned_abs()
                     <= render_size + INITIAL_LOAD_EXTRA
             );
         }
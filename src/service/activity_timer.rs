@@ -42,9 +42,33 @@ impl ActivityTimer {
         }
     }
 
+    /// returns the number of times the activity is triggered, at most `max_ticks`,
+    /// the remaining time is dropped if the limit is reached
+    pub fn tick_multiple(&mut self, delta: f32, max_ticks: usize) -> usize {
+        self.delta += delta;
+
+        let mut ticks = 0;
+        while self.delta >= self.cooldown {
+            if ticks >= max_ticks {
+                self.delta = 0.0;
+                break;
+            }
+            self.delta -= self.cooldown;
+            ticks += 1;
+        }
+
+        ticks
+    }
+
     pub fn get_delta(&self) -> f32 {
         self.delta
     }
+
+    /// changes the cooldown without resetting the elapsed time
+    pub fn set_cooldown(&mut self, cooldown: f32) {
+        debug_assert!(cooldown > 0.0);
+        self.cooldown = cooldown;
+    }
 }
 
 #[cfg(test)]
@@ -101,4 +125,16 @@ mod tests {
         assert_eq!(timer.get_delta(), 0.5);
         assert_eq!(timer.cooldown, 0.5);
     }
+
+    #[test]
+    fn test_tick_multiple() {
+        let mut timer = ActivityTimer::new(0.0, 1.0);
+
+        assert_eq!(timer.tick_multiple(0.5, 4), 0);
+        assert_eq!(timer.tick_multiple(2.0, 4), 2);
+        assert_eq!(timer.get_delta(), 0.5);
+
+        assert_eq!(timer.tick_multiple(10.0, 4), 4);
+        assert_eq!(timer.get_delta(), 0.0);
+    }
 }
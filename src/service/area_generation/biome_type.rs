@@ -1,7 +1,7 @@
 use libnoise::{Fbm, Generator, Simplex};
 
 use crate::{
-    model::location::AreaLocation,
+    model::{area::AREA_SIZE, location::AreaLocation},
     service::area_generation::algorithms::{get_point_on_noise_map, normalise_sample},
 };
 
@@ -11,6 +11,23 @@ pub enum BiomeType {
     Wet,
     Cold,
 }
+impl BiomeType {
+    pub const ALL: [Self; 3] = [Self::Dry, Self::Wet, Self::Cold];
+
+    pub fn get_name(self) -> &'static str {
+        match self {
+            BiomeType::Dry => "dry",
+            BiomeType::Wet => "wet",
+            BiomeType::Cold => "cold",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|biome_type| biome_type.get_name().eq_ignore_ascii_case(name))
+    }
+}
 
 pub struct BiomeTypeGenerator {
     noise: Fbm<2, Simplex<2>>,
@@ -32,4 +49,68 @@ impl BiomeTypeGenerator {
             _ => BiomeType::Cold,
         }
     }
+
+    /// searches outwards in square rings around the start for the closest area whose center
+    /// has the biome type, only samples noise so no areas are loaded
+    pub fn find_nearest(
+        &self,
+        start: AreaLocation,
+        biome_type: BiomeType,
+        max_radius: u32,
+    ) -> Option<AreaLocation> {
+        let center = AREA_SIZE / 2;
+        for radius in 0..=max_radius as i64 {
+            let closest = Self::get_ring(start, radius)
+                .filter(|area_location| self.sample(*area_location, center, center) == biome_type)
+                .min_by_key(|area_location| {
+                    let dx = area_location.x as i64 - start.x as i64;
+                    let dy = area_location.y as i64 - start.y as i64;
+                    dx * dx + dy * dy
+                });
+            if closest.is_some() {
+                return closest;
+            }
+        }
+
+        None
+    }
+
+    /// returns the area locations at exactly the radius from the start
+    fn get_ring(start: AreaLocation, radius: i64) -> impl Iterator<Item = AreaLocation> {
+        (-radius..=radius)
+            .flat_map(move |dx| (-radius..=radius).map(move |dy| (dx, dy)))
+            .filter(move |(dx, dy)| dx.abs() == radius || dy.abs() == radius)
+            .filter_map(move |(dx, dy)| {
+                let x = u32::try_from(start.x as i64 + dx).ok()?;
+                let y = u32::try_from(start.y as i64 + dy).ok()?;
+                Some(AreaLocation::new(x, y))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_name() {
+        assert_eq!(BiomeType::from_name("wet"), Some(BiomeType::Wet));
+        assert_eq!(BiomeType::from_name("COLD"), Some(BiomeType::Cold));
+        assert_eq!(BiomeType::from_name("lava"), None);
+    }
+
+    #[test]
+    fn test_find_nearest() {
+        let generator = BiomeTypeGenerator::new(42);
+        let start = AreaLocation::new(62_500, 62_500);
+        let center = AREA_SIZE / 2;
+        let start_biome = generator.sample(start, center, center);
+        assert_eq!(generator.find_nearest(start, start_biome, 0), Some(start));
+
+        for biome_type in BiomeType::ALL {
+            if let Some(found) = generator.find_nearest(start, biome_type, 64) {
+                assert_eq!(generator.sample(found, center, center), biome_type);
+            }
+        }
+    }
 }
@@ -7,8 +7,10 @@ use crate::{
 
 use super::algorithms::{get_point_on_noise_map, get_point_on_noise_map_3d};
 
-const MIN_CAVES_THRESHOLD: i32 = 70;
-const MAX_CAVES_THRESHOLD: i32 = 90;
+/// the cave noise values around the center are carved out
+const CAVES_THRESHOLD_CENTER: i32 = 80;
+/// half of the carved out range at the default cave density
+const CAVES_THRESHOLD_HALF_RANGE: f32 = 10.0;
 
 /// min height to NOT generate caves
 const MIN_HEIGHT: u32 = (AREA_HEIGHT as f32 * 0.2) as u32;
@@ -21,15 +23,20 @@ const SHOULD_CHECK_FOR_CAVES_THRESHOLD: i32 = 80;
 pub struct CaveGenerator {
     cave_noise: Max<3, Fbm<3, Simplex<3>>, Fbm<3, Simplex<3>>>,
     check_noise: Fbm<2, Simplex<2>>,
+    min_threshold: i32,
+    max_threshold: i32,
 }
 impl CaveGenerator {
-    pub fn new(seed: u64) -> Self {
+    pub fn new(seed: u64, cave_density: f32) -> Self {
         let noise1 = Simplex::new(seed).fbm(6, 0.08, 1.3, 0.45);
         let noise2 = Simplex::new(seed ^ u64::MAX).fbm(8, 0.04, 1.2, 0.35);
+        let half_range = (CAVES_THRESHOLD_HALF_RANGE * cave_density.max(0.0)).round() as i32;
 
         Self {
             cave_noise: noise1.max(noise2),
             check_noise: Simplex::new(seed).fbm(2, 0.003, 1.3, 0.3),
+            min_threshold: CAVES_THRESHOLD_CENTER - half_range,
+            max_threshold: CAVES_THRESHOLD_CENTER + half_range,
         }
     }
 
@@ -57,9 +64,14 @@ impl CaveGenerator {
             return false;
         }
 
-        let point3d = get_point_on_noise_map_3d(area_location, x, y, z);
-        let value = normalise_sample(self.cave_noise.sample(point3d)) as i32;
+        let value = self.sample_density(area_location, x, y, z) as i32;
+
+        (self.min_threshold..self.max_threshold).contains(&value)
+    }
 
-        (MIN_CAVES_THRESHOLD..MAX_CAVES_THRESHOLD).contains(&value)
+    /// returns the cave noise value in the range 0..=100
+    pub fn sample_density(&self, area_location: AreaLocation, x: u32, y: u32, z: u32) -> f32 {
+        let point3d = get_point_on_noise_map_3d(area_location, x, y, z);
+        normalise_sample(self.cave_noise.sample(point3d)) as f32
     }
 }
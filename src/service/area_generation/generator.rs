@@ -5,13 +5,17 @@ use macroquad::logging::info;
 use crate::{
     model::{
         area::{AREA_HEIGHT, AREA_SIZE, Area},
-        location::{AreaLocation, InternalLocation},
+        location::{AreaLocation, InternalLocation, Location},
         voxel::Voxel,
+        world::World,
+        world_rules::{TreeDensity, WorldGenConfig},
     },
     service::area_generation::{
         biome_type::{BiomeType, BiomeTypeGenerator},
         cave_generator::CaveGenerator,
         lake_generator::LakeGenerator,
+        ore_generator::generate_ores,
+        river_generator::{RiverGenerator, RiverSample},
         terrain_type::TerrainTypeGenerator,
         trees::{TreeType, generate_trees, should_generate_tree},
         voxel_type_generator::VoxelTypeGenerator,
@@ -20,8 +24,11 @@ use crate::{
 };
 
 const AREA_SURFACE: usize = (AREA_SIZE * AREA_SIZE) as usize;
+/// increased when the generated terrain changes so unmodified areas can be regenerated
+pub const GENERATION_VERSION: u16 = 3;
 
-fn hash_world_name(world_name: &str) -> u64 {
+/// the default world seed, derived from its name when no seed is entered
+pub fn hash_world_name(world_name: &str) -> u64 {
     let mut hasher = DefaultHasher::new();
     world_name.hash(&mut hasher);
     hasher.finish()
@@ -32,6 +39,7 @@ pub struct ColumnSamples {
     pub max_generated_height: u32,
     pub is_cave_zone: bool,
     pub lake_depth: u32,
+    pub river: RiverSample,
     pub biome_type: BiomeType,
 }
 
@@ -42,20 +50,30 @@ pub struct AreaGenerator {
     cave_noise: CaveGenerator,
     voxel_type_generator: VoxelTypeGenerator,
     lake_generator: LakeGenerator,
+    river_generator: RiverGenerator,
+    /// combined tree density rule and generation config multiplier
+    tree_multiplier: f32,
     tree_locations: StackVec<(InternalLocation, TreeType), AREA_SURFACE>,
 }
 impl AreaGenerator {
-    /// generates an area at a specific location with the world name as the seed
-    pub fn generate_area(area_location: AreaLocation, world_name: &str) -> Area {
+    /// generates an area at a specific location from the world seed
+    pub fn generate_area(
+        area_location: AreaLocation,
+        seed: u64,
+        tree_density: TreeDensity,
+        config: WorldGenConfig,
+    ) -> Area {
         info!("Generating area: {:?}", area_location);
-        let mut generator = AreaGenerator::new(world_name);
+        let mut generator = AreaGenerator::new(seed, tree_density, config);
 
         let mut area = Area::new(area_location);
+        area.generation_version = GENERATION_VERSION;
         for x in 0..AREA_SIZE {
             for y in 0..AREA_SIZE {
                 generator.generate_column(&mut area, area_location, x, y);
             }
         }
+        generate_ores(&mut area, seed, area_location);
         generate_trees(&mut area, &generator.tree_locations);
 
         area.update_all_column_heights();
@@ -66,6 +84,16 @@ impl AreaGenerator {
     /// generates a single column in an area and marks any potential tree locations
     fn generate_column(&mut self, area: &mut Area, area_location: AreaLocation, x: u32, y: u32) {
         let mut column_sample = self.sample_column_characteristics(area_location, x, y);
+        if column_sample.river != RiverSample::None {
+            let surface_voxel = self.voxel_type_generator.calculate_voxel_type(
+                area_location,
+                x,
+                y,
+                column_sample.terrain_height,
+                &column_sample,
+            );
+            column_sample.river = RiverGenerator::check_surface(column_sample.river, surface_voxel);
+        }
 
         for z_inverted in 1..=column_sample.terrain_height {
             let current_voxel = self.voxel_type_generator.calculate_voxel_type(
@@ -75,11 +103,14 @@ impl AreaGenerator {
                 z_inverted,
                 &column_sample,
             );
-            let lake_voxel =
-                LakeGenerator::generate_voxel(&column_sample, z_inverted).unwrap_or(current_voxel);
+            let voxel = self
+                .lake_generator
+                .generate_voxel(&column_sample, z_inverted)
+                .or_else(|| RiverGenerator::generate_voxel(&column_sample, z_inverted))
+                .unwrap_or(current_voxel);
             if self.cave_noise.should_be_cave(
                 &column_sample,
-                lake_voxel,
+                voxel,
                 area_location,
                 x,
                 y,
@@ -88,22 +119,37 @@ impl AreaGenerator {
                 continue;
             }
 
-            if lake_voxel != Voxel::None {
+            if voxel != Voxel::None {
                 column_sample.max_generated_height = z_inverted;
             }
             area.set_without_updating_max_height(
                 InternalLocation::new(x, y, AREA_HEIGHT - z_inverted),
-                lake_voxel,
+                voxel,
             );
         }
 
         let local = InternalLocation::new(x, y, AREA_HEIGHT - column_sample.max_generated_height);
-        let tree_type = should_generate_tree(area.get(local), self.seed, area_location, local);
+        let tree_type = should_generate_tree(
+            area.get(local),
+            column_sample.biome_type,
+            self.seed,
+            area_location,
+            local,
+            self.tree_multiplier,
+        );
         if tree_type != TreeType::None {
             self.tree_locations.push((local, tree_type));
         }
     }
 
+    /// samples only the biome of the column at the location, without generating the column
+    pub fn sample_biome(seed: u64, location: Location) -> BiomeType {
+        let (area_location, local_location) =
+            World::convert_global_to_area_and_local_location(location.into());
+
+        BiomeTypeGenerator::new(seed).sample(area_location, local_location.x, local_location.y)
+    }
+
     /// samples shared characteristics for the whole column
     fn sample_column_characteristics(
         &self,
@@ -120,27 +166,37 @@ impl AreaGenerator {
             x,
             y,
         );
+        let river = self.river_generator.sample(
+            is_cave_zone,
+            lake_depth,
+            terrain_height,
+            area_location,
+            x,
+            y,
+        );
         let biome_type = self.biome_type_noise.sample(area_location, x, y);
 
         ColumnSamples {
             terrain_height,
             is_cave_zone,
             lake_depth,
+            river,
             biome_type,
             max_generated_height: 0,
         }
     }
 
     /// private constructor
-    fn new(world_name: &str) -> Self {
-        let seed = hash_world_name(world_name);
+    fn new(seed: u64, tree_density: TreeDensity, config: WorldGenConfig) -> Self {
         Self {
             seed,
-            height_noise: TerrainTypeGenerator::new(seed),
+            height_noise: TerrainTypeGenerator::new(seed, config.amplitude),
             biome_type_noise: BiomeTypeGenerator::new(seed),
-            cave_noise: CaveGenerator::new(seed),
+            cave_noise: CaveGenerator::new(seed, config.cave_density),
             voxel_type_generator: VoxelTypeGenerator::new(seed),
-            lake_generator: LakeGenerator::new(seed),
+            lake_generator: LakeGenerator::new(seed, config.sea_level),
+            river_generator: RiverGenerator::new(seed, config.sea_level),
+            tree_multiplier: tree_density.get_multiplier() * config.tree_frequency,
             tree_locations: StackVec::new(),
         }
     }
@@ -148,13 +204,18 @@ impl AreaGenerator {
 
 #[cfg(test)]
 mod tests {
-    use crate::model::voxel::Voxel;
+    use crate::model::{voxel::Voxel, world_rules::WorldGenPreset};
 
     use super::*;
 
     #[test]
     fn test_generate_area() {
-        let area = AreaGenerator::generate_area(AreaLocation::new(123, 456), "test");
+        let area = AreaGenerator::generate_area(
+            AreaLocation::new(123, 456),
+            0,
+            TreeDensity::Normal,
+            WorldGenConfig::default(),
+        );
         assert!(area.has_changed);
         assert_eq!(area.get_x(), 123);
         assert_eq!(area.get_y(), 456);
@@ -178,32 +239,105 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_sample_biome_matches_column() {
+        let seed = 7;
+        let generator = AreaGenerator::new(seed, TreeDensity::Normal, WorldGenConfig::default());
+        for location in [
+            Location::new(0, 0, 40),
+            Location::new(-37, 1250, 10),
+            Location::new(9000, -4321, 0),
+        ] {
+            let (area_location, local_location) =
+                World::convert_global_to_area_and_local_location(location.into());
+            let column = generator.sample_column_characteristics(
+                area_location,
+                local_location.x,
+                local_location.y,
+            );
+            assert_eq!(
+                AreaGenerator::sample_biome(seed, location),
+                column.biome_type
+            );
+        }
+    }
+
+    #[test]
+    fn test_flat_preset_generates_level_terrain() {
+        let height_noise =
+            TerrainTypeGenerator::new(0, WorldGenPreset::Flat.get_config().amplitude);
+        let heights: Vec<_> = (0..10)
+            .flat_map(|area_x| {
+                let height_noise = &height_noise;
+                (0..AREA_SIZE).map(move |x| height_noise.sample(AreaLocation::new(area_x, 5), x, 3))
+            })
+            .collect();
+
+        assert!(heights.iter().all(|height| *height == heights[0]));
+    }
+
     #[test]
     fn test_generate_area_different_locations() {
-        let area1 = AreaGenerator::generate_area(AreaLocation::new(123, 456), "test");
-        let area2 = AreaGenerator::generate_area(AreaLocation::new(999, 400), "test");
+        let area1 = AreaGenerator::generate_area(
+            AreaLocation::new(123, 456),
+            0,
+            TreeDensity::Normal,
+            WorldGenConfig::default(),
+        );
+        let area2 = AreaGenerator::generate_area(
+            AreaLocation::new(999, 400),
+            0,
+            TreeDensity::Normal,
+            WorldGenConfig::default(),
+        );
         assert!(check_if_areas_are_different(&area1, &area2));
     }
 
     #[test]
     fn test_generate_area_different_seeds() {
-        let area1 = AreaGenerator::generate_area(AreaLocation::new(123, 456), "test1");
-        let area2 = AreaGenerator::generate_area(AreaLocation::new(123, 456), "test2");
+        let area1 = AreaGenerator::generate_area(
+            AreaLocation::new(123, 456),
+            1,
+            TreeDensity::Normal,
+            WorldGenConfig::default(),
+        );
+        let area2 = AreaGenerator::generate_area(
+            AreaLocation::new(123, 456),
+            2,
+            TreeDensity::Normal,
+            WorldGenConfig::default(),
+        );
         assert!(check_if_areas_are_different(&area1, &area2));
     }
 
     #[test]
     fn test_generate_same_area() {
-        let area1 = AreaGenerator::generate_area(AreaLocation::new(123, 456), "test");
-        let area2 = AreaGenerator::generate_area(AreaLocation::new(123, 456), "test");
+        let area1 = AreaGenerator::generate_area(
+            AreaLocation::new(123, 456),
+            0,
+            TreeDensity::Normal,
+            WorldGenConfig::default(),
+        );
+        let area2 = AreaGenerator::generate_area(
+            AreaLocation::new(123, 456),
+            0,
+            TreeDensity::Normal,
+            WorldGenConfig::default(),
+        );
         assert!(!check_if_areas_are_different(&area1, &area2));
     }
 
     #[test]
     fn test_genearate_area_heights_calculated_correctly() {
         let areas: Vec<_> = (0..10)
-            .into_iter()
-            .map(|x| AreaGenerator::generate_area(AreaLocation::new(x, 123), "test"))
+            .map(|x| {
+                AreaGenerator::generate_area(
+                    AreaLocation::new(x, 123),
+                    0,
+                    TreeDensity::Normal,
+                    WorldGenConfig::default(),
+                )
+            })
             .collect();
 
         let mut areas_calculated_heights = areas.clone();
@@ -240,6 +374,6 @@ mod tests {
             }
         }
 
-        return false;
+        false
     }
 }
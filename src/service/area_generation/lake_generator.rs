@@ -1,7 +1,7 @@
 use libnoise::{Fbm, Generator, Simplex};
 
 use crate::{
-    model::{area::AREA_HEIGHT, location::AreaLocation, voxel::Voxel},
+    model::{location::AreaLocation, voxel::Voxel},
     service::area_generation::{
         algorithms::{get_point_on_noise_map, normalise_sample},
         biome_type::BiomeType,
@@ -9,19 +9,24 @@ use crate::{
     },
 };
 
-const MAX_TERRAIN_HEIGHT: u32 = (AREA_HEIGHT as f32 * 0.32) as u32;
-const LAKE_MAX_Z_INVERTED: u32 = (AREA_HEIGHT as f32 * 0.27) as u32;
+/// lakes only form on terrain up to this height above the sea level
+const MAX_HEIGHT_ABOVE_SEA_LEVEL: u32 = 6;
 const MIN_LAKE_HEIGHT: i32 = 2;
 const MIN_LAKE_NOISE: i32 = 70;
 
 pub struct LakeGenerator {
     lake_noise: Fbm<2, Simplex<2>>,
+    /// the lake surface height
+    sea_level: u32,
 }
 impl LakeGenerator {
-    pub fn new(seed: u64) -> Self {
+    pub fn new(seed: u64, sea_level: u32) -> Self {
         let lake_noise = Simplex::new(seed.wrapping_add(10)).fbm(2, 0.004, 1.3, 0.35);
 
-        Self { lake_noise }
+        Self {
+            lake_noise,
+            sea_level,
+        }
     }
 
     pub fn sample_lake_depth(
@@ -33,8 +38,8 @@ impl LakeGenerator {
         y: u32,
     ) -> u32 {
         if is_cave_zone
-            || terrain_height > MAX_TERRAIN_HEIGHT
-            || terrain_height <= LAKE_MAX_Z_INVERTED
+            || terrain_height > self.sea_level + MAX_HEIGHT_ABOVE_SEA_LEVEL
+            || terrain_height <= self.sea_level
         {
             return 0;
         }
@@ -51,19 +56,19 @@ impl LakeGenerator {
         lake_depth as u32
     }
 
-    pub fn generate_voxel(column_samples: &ColumnSamples, z_inverted: u32) -> Option<Voxel> {
+    pub fn generate_voxel(&self, column_samples: &ColumnSamples, z_inverted: u32) -> Option<Voxel> {
         if column_samples.lake_depth == 0 {
             return None;
         }
 
-        if z_inverted > LAKE_MAX_Z_INVERTED {
+        if z_inverted > self.sea_level {
             return Some(Voxel::None);
         }
 
-        let min_water = LAKE_MAX_Z_INVERTED - column_samples.lake_depth;
+        let min_water = self.sea_level.saturating_sub(column_samples.lake_depth);
 
         if z_inverted >= min_water {
-            if column_samples.biome_type == BiomeType::Cold && z_inverted == LAKE_MAX_Z_INVERTED {
+            if column_samples.biome_type == BiomeType::Cold && z_inverted == self.sea_level {
                 Some(Voxel::Ice)
             } else {
                 Some(Voxel::WaterSource)
@@ -3,6 +3,8 @@ pub mod biome_type;
 pub mod cave_generator;
 pub mod generator;
 pub mod lake_generator;
+pub mod ore_generator;
+pub mod river_generator;
 pub mod terrain_type;
 pub mod trees;
 pub mod voxel_type_generator;
@@ -0,0 +1,158 @@
+use crate::{
+    model::{
+        area::{AREA_HEIGHT, AREA_SIZE, Area},
+        location::{AreaLocation, InternalLocation},
+        voxel::Voxel,
+    },
+    service::area_generation::algorithms::{combine_seed, split_mix64},
+};
+
+/// changes the seed so ore placement doesn't line up with trees
+const ORE_SEED_SALT: u64 = 0x6f72_6573;
+
+#[derive(Debug, Clone, Copy)]
+struct OreType {
+    voxel: Voxel,
+    /// number of clusters attempted in each area
+    clusters_per_area: u32,
+    /// clusters are only placed at or below this height
+    max_height: u32,
+    min_cluster_size: u32,
+    max_cluster_size: u32,
+}
+
+const ORE_TYPES: [OreType; 2] = [
+    OreType {
+        voxel: Voxel::CoalOre,
+        clusters_per_area: 8,
+        max_height: (AREA_HEIGHT as f32 * 0.5) as u32,
+        min_cluster_size: 4,
+        max_cluster_size: 8,
+    },
+    OreType {
+        voxel: Voxel::IronOre,
+        clusters_per_area: 4,
+        max_height: (AREA_HEIGHT as f32 * 0.3) as u32,
+        min_cluster_size: 3,
+        max_cluster_size: 6,
+    },
+];
+
+/// scatters small clusters of ore inside the stone of a generated area,
+/// clusters are cut off at the area borders
+pub fn generate_ores(area: &mut Area, seed: u64, area_location: AreaLocation) {
+    for (ore_index, ore_type) in ORE_TYPES.iter().enumerate() {
+        for cluster in 0..ore_type.clusters_per_area {
+            let cluster_seed = combine_seed(
+                seed ^ ORE_SEED_SALT,
+                area_location,
+                InternalLocation::new(cluster, ore_index as u32, 0),
+            );
+            generate_cluster(area, ore_type, split_mix64(cluster_seed));
+        }
+    }
+}
+
+/// places the ore along a random walk from a random start
+fn generate_cluster(area: &mut Area, ore_type: &OreType, random_value: u64) {
+    let mut x = (random_value % AREA_SIZE as u64) as i32;
+    let mut y = ((random_value >> 8) % AREA_SIZE as u64) as i32;
+    let z_inverted = 1 + ((random_value >> 16) % ore_type.max_height as u64) as u32;
+    let mut z = (AREA_HEIGHT - z_inverted) as i32;
+    let size_range = (ore_type.max_cluster_size - ore_type.min_cluster_size + 1) as u64;
+    let size = ore_type.min_cluster_size + ((random_value >> 32) % size_range) as u32;
+
+    let min_z = (AREA_HEIGHT - ore_type.max_height) as i32;
+    let mut step_value = random_value;
+    for _ in 0..size {
+        if is_in_area(x, y, z) && z >= min_z {
+            let location = InternalLocation::new(x as u32, y as u32, z as u32);
+            if area.get(location) == Voxel::Stone {
+                area.set_without_updating_max_height(location, ore_type.voxel);
+            }
+        }
+
+        step_value = split_mix64(step_value);
+        match step_value % 6 {
+            0 => x += 1,
+            1 => x -= 1,
+            2 => y += 1,
+            3 => y -= 1,
+            4 => z += 1,
+            _ => z -= 1,
+        }
+    }
+}
+
+fn is_in_area(x: i32, y: i32, z: i32) -> bool {
+    (0..AREA_SIZE as i32).contains(&x)
+        && (0..AREA_SIZE as i32).contains(&y)
+        && (0..AREA_HEIGHT as i32).contains(&z)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_stone_area(area_location: AreaLocation) -> Area {
+        let mut area = Area::new(area_location);
+        for x in 0..AREA_SIZE {
+            for y in 0..AREA_SIZE {
+                for z in 0..AREA_HEIGHT {
+                    area.set_without_updating_max_height(
+                        InternalLocation::new(x, y, z),
+                        Voxel::Stone,
+                    );
+                }
+            }
+        }
+        area
+    }
+
+    fn count_voxel(area: &Area, voxel: Voxel) -> usize {
+        (0..AREA_SIZE)
+            .flat_map(|x| {
+                (0..AREA_SIZE).flat_map(move |y| {
+                    (0..AREA_HEIGHT).map(move |z| InternalLocation::new(x, y, z))
+                })
+            })
+            .filter(|location| area.get(*location) == voxel)
+            .count()
+    }
+
+    #[test]
+    fn test_generate_ores_is_deterministic_and_below_max_height() {
+        let area_location = AreaLocation::new(40, 17);
+        let mut area1 = create_stone_area(area_location);
+        let mut area2 = create_stone_area(area_location);
+        generate_ores(&mut area1, 5, area_location);
+        generate_ores(&mut area2, 5, area_location);
+
+        for ore_type in ORE_TYPES {
+            assert!(count_voxel(&area1, ore_type.voxel) > 0);
+            assert_eq!(
+                count_voxel(&area1, ore_type.voxel),
+                count_voxel(&area2, ore_type.voxel)
+            );
+            for x in 0..AREA_SIZE {
+                for y in 0..AREA_SIZE {
+                    for z in 0..(AREA_HEIGHT - ore_type.max_height) {
+                        assert_ne!(area1.get(InternalLocation::new(x, y, z)), ore_type.voxel);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_generate_ores_only_replaces_stone() {
+        let area_location = AreaLocation::new(3, 9);
+        let mut area = Area::new(area_location);
+        generate_ores(&mut area, 5, area_location);
+
+        assert_eq!(
+            count_voxel(&area, Voxel::None),
+            (AREA_SIZE * AREA_SIZE * AREA_HEIGHT) as usize
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use libnoise::{Fbm, Generator, Simplex};
+
+use crate::{
+    model::{location::AreaLocation, voxel::Voxel},
+    service::area_generation::{algorithms::get_point_on_noise_map, generator::ColumnSamples},
+};
+
+/// columns up to this distance from the channel are the banks
+const BANK_WIDTH: f64 = 2.0;
+/// rivers only flow through terrain up to this height above the sea level
+const MAX_HEIGHT_ABOVE_SEA_LEVEL: u32 = 16;
+/// depth of the water below the cut out surface voxel
+const RIVER_DEPTH: u32 = 2;
+/// surface voxels a river can cut through, other surfaces stop the river
+const CARVABLE_SURFACES: [Voxel; 5] = [
+    Voxel::Grass,
+    Voxel::Dirt,
+    Voxel::Sand,
+    Voxel::Clay,
+    Voxel::Snow,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RiverSample {
+    None,
+    Bank,
+    Channel,
+}
+
+/// carves winding river channels, sampled per global column so rivers line up across areas
+pub struct RiverGenerator {
+    river_noise: Fbm<2, Simplex<2>>,
+    sea_level: u32,
+}
+impl RiverGenerator {
+    pub fn new(seed: u64, sea_level: u32) -> Self {
+        let river_noise = Simplex::new(seed.wrapping_add(20)).fbm(2, 0.003, 2.0, 0.3);
+
+        Self {
+            river_noise,
+            sea_level,
+        }
+    }
+
+    /// rivers stay in the lowlands above the sea level, so they flow down towards the lakes
+    pub fn sample(
+        &self,
+        is_cave_zone: bool,
+        lake_depth: u32,
+        terrain_height: u32,
+        area_location: AreaLocation,
+        x: u32,
+        y: u32,
+    ) -> RiverSample {
+        if is_cave_zone
+            || lake_depth > 0
+            || terrain_height <= self.sea_level
+            || terrain_height > self.sea_level + MAX_HEIGHT_ABOVE_SEA_LEVEL
+        {
+            return RiverSample::None;
+        }
+
+        let [global_x, global_y] = get_point_on_noise_map(area_location, x, y);
+        let is_positive = self.is_positive(global_x, global_y);
+        let crosses = |offsets: &[(f64, f64)]| {
+            offsets
+                .iter()
+                .any(|(dx, dy)| self.is_positive(global_x + dx, global_y + dy) != is_positive)
+        };
+
+        if crosses(&[(1.0, 0.0), (0.0, 1.0)]) {
+            RiverSample::Channel
+        } else if crosses(&[
+            (-BANK_WIDTH, 0.0),
+            (BANK_WIDTH, 0.0),
+            (0.0, -BANK_WIDTH),
+            (0.0, BANK_WIDTH),
+        ]) {
+            RiverSample::Bank
+        } else {
+            RiverSample::None
+        }
+    }
+
+    /// rivers follow the zero crossing of the noise, the channel is on the columns where
+    /// the sign changes towards the next column so it is continuous and one or two voxels wide
+    fn is_positive(&self, global_x: f64, global_y: f64) -> bool {
+        self.river_noise.sample([global_x, global_y]) >= 0.0
+    }
+
+    /// removes the river from columns with a surface it can't cut through
+    pub fn check_surface(river: RiverSample, surface_voxel: Voxel) -> RiverSample {
+        if CARVABLE_SURFACES.contains(&surface_voxel) {
+            river
+        } else {
+            RiverSample::None
+        }
+    }
+
+    pub fn generate_voxel(column_samples: &ColumnSamples, z_inverted: u32) -> Option<Voxel> {
+        let surface = column_samples.terrain_height;
+        match column_samples.river {
+            RiverSample::None => None,
+            RiverSample::Bank if z_inverted == surface => Some(Voxel::Sand),
+            RiverSample::Bank => None,
+            RiverSample::Channel if z_inverted == surface => Some(Voxel::None),
+            RiverSample::Channel if z_inverted + RIVER_DEPTH >= surface => Some(Voxel::WaterSource),
+            RiverSample::Channel if z_inverted + RIVER_DEPTH + 1 == surface => Some(Voxel::Sand),
+            RiverSample::Channel => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::area::AREA_SIZE;
+
+    use super::*;
+
+    const SEA_LEVEL: u32 = 34;
+
+    #[test]
+    fn test_rivers_are_continuous_across_areas() {
+        let river_generator = RiverGenerator::new(3, SEA_LEVEL);
+        let height = SEA_LEVEL + 4;
+        let mut channels = 0;
+        for area_x in 0..32 {
+            for x in 0..AREA_SIZE {
+                let area_location = AreaLocation::new(area_x, 7);
+                let sample = river_generator.sample(false, 0, height, area_location, x, 3);
+                let [global_x, global_y] = get_point_on_noise_map(area_location, x, 3);
+                // includes the last column of an area and the first column of the next
+                let crosses = river_generator.is_positive(global_x, global_y)
+                    != river_generator.is_positive(global_x + 1.0, global_y);
+                if crosses {
+                    assert_eq!(sample, RiverSample::Channel);
+                }
+                if sample == RiverSample::Channel {
+                    channels += 1;
+                }
+            }
+        }
+
+        assert!(channels > 0);
+    }
+
+    #[test]
+    fn test_no_river_outside_lowlands() {
+        let river_generator = RiverGenerator::new(3, SEA_LEVEL);
+        for height in [SEA_LEVEL, SEA_LEVEL + MAX_HEIGHT_ABOVE_SEA_LEVEL + 1] {
+            for x in 0..AREA_SIZE {
+                let sample =
+                    river_generator.sample(false, 0, height, AreaLocation::new(5, 5), x, 0);
+                assert_eq!(sample, RiverSample::None);
+            }
+        }
+        assert_eq!(
+            RiverGenerator::check_surface(RiverSample::Channel, Voxel::Stone),
+            RiverSample::None
+        );
+    }
+}
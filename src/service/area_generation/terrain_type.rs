@@ -8,11 +8,14 @@ use crate::{
 pub struct TerrainTypeGenerator {
     noise: Max<2, Fbm<2, Simplex<2>>, Fbm<2, Simplex<2>>>,
     height_modifier: Fbm<2, Simplex<2>>,
+    amplitude: f64,
 }
 impl TerrainTypeGenerator {
     const HEIGHT_SCALE: f64 = 0.55;
+    /// leaves room above the highest terrain for trees
+    const MAX_HEIGHT: u32 = AREA_HEIGHT - 8;
 
-    pub fn new(seed: u64) -> Self {
+    pub fn new(seed: u64, amplitude: f32) -> Self {
         let noise1 = Simplex::new(seed).fbm(6, 0.008, 1.8, 0.45);
         let noise2 = Simplex::new(seed ^ u64::MAX).fbm(4, 0.004, 1.8, 0.45);
         let height_modifier = Simplex::new(seed.wrapping_add(1)).fbm(2, 0.004, 0.004, 0.2);
@@ -20,6 +23,7 @@ impl TerrainTypeGenerator {
         Self {
             noise: noise1.max(noise2),
             height_modifier,
+            amplitude: amplitude as f64,
         }
     }
 
@@ -28,6 +32,9 @@ impl TerrainTypeGenerator {
         let value = self.noise.sample(point).clamp(0.1, 1.0);
         let height_mod = 1.0 - (self.height_modifier.sample(point) + 1.0) / 2.0;
 
-        ((height_mod * value * Self::HEIGHT_SCALE * AREA_HEIGHT as f64) as u32) + AREA_HEIGHT / 4
+        let height =
+            (height_mod * value * Self::HEIGHT_SCALE * self.amplitude * AREA_HEIGHT as f64) as u32;
+
+        (height + AREA_HEIGHT / 4).min(Self::MAX_HEIGHT)
     }
 }
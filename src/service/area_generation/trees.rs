@@ -4,13 +4,19 @@ use crate::{
         location::{AreaLocation, InternalLocation, Location},
         voxel::Voxel,
     },
-    service::area_generation::algorithms::sample_probability,
+    service::area_generation::{algorithms::sample_probability, biome_type::BiomeType},
     utils::StackVec,
 };
 
 use super::algorithms::{combine_seed, split_mix64};
 
-const ALLOWED_TREE_BASES: [Voxel; 4] = [Voxel::Grass, Voxel::Dirt, Voxel::Clay, Voxel::Sand];
+const ALLOWED_TREE_BASES: [Voxel; 5] = [
+    Voxel::Grass,
+    Voxel::Dirt,
+    Voxel::Clay,
+    Voxel::Sand,
+    Voxel::Snow,
+];
 const BASE_PROBABILITY: u64 = 60;
 const SHORT_TREE_LOCATIONS: [(Location, Voxel); 8] = [
     (Location::new(0, 0, -1), Voxel::Wood),
@@ -62,6 +68,8 @@ const HUGE_TREE_LOCATIONS: [(Location, Voxel); 27] = [
     (Location::new(-1, 0, -6), Voxel::Leaves),
     (Location::new(0, 0, -7), Voxel::Leaves),
 ];
+const SNOWY_SHORT_TREE_LOCATIONS: [(Location, Voxel); 8] = with_snowy_leaves(SHORT_TREE_LOCATIONS);
+const SNOWY_TALL_TREE_LOCATIONS: [(Location, Voxel); 9] = with_snowy_leaves(TALL_TREE_LOCATIONS);
 const TALL_CACTUS_LOCATIONS: [(Location, Voxel); 3] = [
     (Location::new(0, 0, -1), Voxel::Cactus),
     (Location::new(0, 0, -2), Voxel::Cactus),
@@ -74,6 +82,20 @@ const DEAD_TREE_LOCATIONS: [(Location, Voxel); 2] = [
 const SHORT_CACTUS_LOCATIONS: [(Location, Voxel); 1] = [(Location::new(0, 0, -1), Voxel::Cactus)];
 const BUSH_LOCATIONS: [(Location, Voxel); 1] = [(Location::new(0, 0, -1), Voxel::Leaves)];
 
+/// replaces the leaves of a tree with their snow covered variant
+const fn with_snowy_leaves<const N: usize>(
+    mut locations: [(Location, Voxel); N],
+) -> [(Location, Voxel); N] {
+    let mut i = 0;
+    while i < N {
+        if matches!(locations[i].1, Voxel::Leaves) {
+            locations[i].1 = Voxel::SnowyLeaves;
+        }
+        i += 1;
+    }
+    locations
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TreeType {
     None,
@@ -84,9 +106,11 @@ pub enum TreeType {
     HugeTree,
     ShortCactus,
     Bush,
+    SnowyShort,
+    SnowyTall,
 }
 impl TreeType {
-    const ALL_TYPES_COUNT: usize = 7;
+    const ALL_TYPES_COUNT: usize = 9;
     const ALL_TYPES: [TreeType; Self::ALL_TYPES_COUNT] = [
         Self::Short,
         Self::Tall,
@@ -95,6 +119,8 @@ impl TreeType {
         Self::DeadTree,
         Self::HugeTree,
         Self::Bush,
+        Self::SnowyShort,
+        Self::SnowyTall,
     ];
 
     fn get_voxels_for_tree_type(self) -> &'static [(Location, Voxel)] {
@@ -109,6 +135,8 @@ impl TreeType {
             TreeType::DeadTree => DEAD_TREE_LOCATIONS.as_slice(),
             TreeType::HugeTree => HUGE_TREE_LOCATIONS.as_slice(),
             TreeType::Bush => BUSH_LOCATIONS.as_slice(),
+            TreeType::SnowyShort => SNOWY_SHORT_TREE_LOCATIONS.as_slice(),
+            TreeType::SnowyTall => SNOWY_TALL_TREE_LOCATIONS.as_slice(),
         }
     }
 
@@ -120,6 +148,20 @@ impl TreeType {
             TreeType::DeadTree => &[Voxel::Grass, Voxel::Clay, Voxel::Dirt, Voxel::Sand],
             TreeType::HugeTree => &[Voxel::Grass],
             TreeType::Bush => &[Voxel::Grass, Voxel::Sand],
+            TreeType::SnowyShort | TreeType::SnowyTall => &[Voxel::Snow, Voxel::Dirt],
+        }
+    }
+
+    fn get_allowed_biomes(self) -> &'static [BiomeType] {
+        match self {
+            TreeType::None => unreachable!(),
+            TreeType::Short | TreeType::Tall | TreeType::HugeTree | TreeType::Bush => {
+                &[BiomeType::Wet]
+            }
+            TreeType::TallCactus | TreeType::ShortCactus => &[BiomeType::Dry],
+            TreeType::DeadTree => &[BiomeType::Dry, BiomeType::Cold],
+            // snowy trees also grow on the snow covered mountains of wet biomes
+            TreeType::SnowyShort | TreeType::SnowyTall => &[BiomeType::Cold, BiomeType::Wet],
         }
     }
 
@@ -135,29 +177,45 @@ impl TreeType {
             TreeType::Bush => 100,
             TreeType::DeadTree => 10,
             TreeType::HugeTree => 300,
+            TreeType::SnowyShort => 800,
+            TreeType::SnowyTall => 600,
         }
     }
 }
 
+/// returns the one in n chance of a tree for the density multiplier, none if trees are disabled
+fn get_tree_probability(multiplier: f32) -> Option<u64> {
+    if multiplier <= 0.0 {
+        return None;
+    }
+
+    Some(((BASE_PROBABILITY as f32 / multiplier).round() as u64).max(1))
+}
+
 pub fn should_generate_tree(
     voxel: Voxel,
+    biome_type: BiomeType,
     seed: u64,
     area_location: AreaLocation,
     local: InternalLocation,
+    tree_multiplier: f32,
 ) -> TreeType {
     if !ALLOWED_TREE_BASES.contains(&voxel) {
         return TreeType::None;
     }
+    let Some(probability) = get_tree_probability(tree_multiplier) else {
+        return TreeType::None;
+    };
 
     let combined_seed = combine_seed(seed, area_location, local);
     let random_value = split_mix64(combined_seed);
 
-    if !sample_probability(random_value >> 4, BASE_PROBABILITY) {
+    if !sample_probability(random_value >> 4, probability) {
         return TreeType::None;
     }
 
     let mut possible_trees = StackVec::new();
-    get_possible_trees(voxel, &mut possible_trees);
+    get_possible_trees(voxel, biome_type, &mut possible_trees);
     if possible_trees.is_empty() {
         return TreeType::None;
     }
@@ -187,9 +245,15 @@ fn select_weighted_random_tree(
     TreeType::None
 }
 
-fn get_possible_trees(voxel: Voxel, vec: &mut StackVec<TreeType, { TreeType::ALL_TYPES_COUNT }>) {
+fn get_possible_trees(
+    voxel: Voxel,
+    biome_type: BiomeType,
+    vec: &mut StackVec<TreeType, { TreeType::ALL_TYPES_COUNT }>,
+) {
     for tree_type in TreeType::ALL_TYPES {
-        if tree_type.get_allowed_base().contains(&voxel) {
+        if tree_type.get_allowed_base().contains(&voxel)
+            && tree_type.get_allowed_biomes().contains(&biome_type)
+        {
             vec.push(tree_type);
         }
     }
@@ -242,6 +306,8 @@ fn generate_tree(area: &mut Area, local: InternalLocation, tree_type: TreeType)
 
 #[cfg(test)]
 mod tests {
+    use crate::model::world_rules::TreeDensity;
+
     use super::*;
 
     #[test]
@@ -272,6 +338,94 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_possible_trees_depend_on_biome() {
+        let mut dry_trees = StackVec::new();
+        get_possible_trees(Voxel::Sand, BiomeType::Dry, &mut dry_trees);
+        assert!(!dry_trees.is_empty());
+        assert!(dry_trees.iter().all(|tree| matches!(
+            tree,
+            TreeType::TallCactus | TreeType::ShortCactus | TreeType::DeadTree
+        )));
+
+        let mut cold_trees = StackVec::new();
+        get_possible_trees(Voxel::Snow, BiomeType::Cold, &mut cold_trees);
+        assert!(cold_trees.contains(&TreeType::SnowyTall));
+        assert!(
+            cold_trees
+                .iter()
+                .flat_map(|tree| tree.get_voxels_for_tree_type())
+                .all(|(_, voxel)| *voxel != Voxel::Leaves)
+        );
+
+        let mut wet_trees = StackVec::new();
+        get_possible_trees(Voxel::Grass, BiomeType::Wet, &mut wet_trees);
+        assert!(wet_trees.contains(&TreeType::Tall));
+        assert!(!wet_trees.contains(&TreeType::TallCactus));
+    }
+
+    #[test]
+    fn test_should_generate_tree_is_deterministic() {
+        for x in 0..AREA_SIZE {
+            let local = InternalLocation::new(x, 3, 60);
+            let area_location = AreaLocation::new(2, 5);
+            let tree = should_generate_tree(
+                Voxel::Snow,
+                BiomeType::Cold,
+                7,
+                area_location,
+                local,
+                TreeDensity::Normal.get_multiplier(),
+            );
+            assert_eq!(
+                tree,
+                should_generate_tree(
+                    Voxel::Snow,
+                    BiomeType::Cold,
+                    7,
+                    area_location,
+                    local,
+                    TreeDensity::Normal.get_multiplier()
+                )
+            );
+            assert!(matches!(
+                tree,
+                TreeType::None | TreeType::SnowyShort | TreeType::SnowyTall | TreeType::DeadTree
+            ));
+        }
+    }
+
+    #[test]
+    fn test_tree_density_changes_tree_count() {
+        let count_trees = |tree_density: TreeDensity| {
+            (0..64)
+                .flat_map(|area_x| {
+                    (0..AREA_SIZE).flat_map(move |x| {
+                        (0..AREA_SIZE).map(move |y| {
+                            should_generate_tree(
+                                Voxel::Grass,
+                                BiomeType::Wet,
+                                11,
+                                AreaLocation::new(area_x, 3),
+                                InternalLocation::new(x, y, 60),
+                                tree_density.get_multiplier(),
+                            )
+                        })
+                    })
+                })
+                .filter(|tree| *tree != TreeType::None)
+                .count()
+        };
+
+        let sparse = count_trees(TreeDensity::Sparse);
+        let normal = count_trees(TreeDensity::Normal);
+        let dense = count_trees(TreeDensity::Dense);
+        assert_eq!(count_trees(TreeDensity::None), 0);
+        assert!(sparse > 0);
+        assert!(sparse < normal);
+        assert!(normal < dense);
+    }
+
     #[test]
     fn test_generate_tree() {
         for tree in TreeType::ALL_TYPES {
@@ -4,6 +4,7 @@ use macroquad::text::{Font, load_ttf_font_from_bytes};
 
 use crate::{
     graphics::{mesh_manager::MeshManager, texture_manager::TextureManager},
+    interface::style::FONT_FILTER,
     service::sound_manager::SoundManager,
 };
 
@@ -38,7 +39,8 @@ impl AssetManager {
     pub async fn new() -> Result<Rc<Self>, AssetLoadingErrors> {
         let mut errors = vec![];
 
-        let font = load_ttf_font_from_bytes(FONT).expect("Error loading font");
+        let mut font = load_ttf_font_from_bytes(FONT).expect("Error loading font");
+        font.set_filter(FONT_FILTER);
         let texture_manager_result = TextureManager::new().await;
         let sound_manager_result = SoundManager::new().await;
         if let Err(image_errors) = &texture_manager_result {
@@ -12,6 +12,12 @@ use crate::{
 };
 
 const LOOK_SPEED: f32 = 0.1;
+/// vertical field of view in degrees used until the user setting is applied
+pub const DEFAULT_FOV_DEGREES: f32 = 45.0;
+/// the field of view when fully zoomed in relative to the base field of view
+const ZOOM_FOV_MULTIPLIER: f32 = 0.25;
+/// change in zoom per second
+const ZOOM_SPEED: f32 = 5.0;
 
 #[derive(Debug)]
 pub struct CameraController {
@@ -24,6 +30,12 @@ pub struct CameraController {
     world_up: Vec3,
     last_mouse_position: Vec2,
     position: Vec3,
+    /// lowers the view without moving the player
+    view_offset: f32,
+    /// base vertical field of view in radians
+    fovy: f32,
+    /// from 0 (base field of view) to 1 (fully zoomed in)
+    zoom: f32,
 }
 impl CameraController {
     pub fn new(position: Vec3) -> Self {
@@ -38,8 +50,6 @@ impl CameraController {
         .normalize();
         let right = front.cross(world_up).normalize();
         let up = right.cross(front).normalize();
-        let last_mouse_position: Vec2 = mouse_position().into();
-
         Self {
             is_focused: false,
             yaw,
@@ -48,19 +58,25 @@ impl CameraController {
             right,
             up,
             world_up,
-            last_mouse_position,
+            last_mouse_position: Vec2::ZERO,
             position,
+            view_offset: 0.0,
+            fovy: DEFAULT_FOV_DEGREES.to_radians(),
+            zoom: 0.0,
         }
     }
 
     /// sets window focus
     pub fn set_focus(&mut self, is_focused: bool) {
         self.is_focused = is_focused;
+        self.last_mouse_position = mouse_position().into();
         set_cursor_grab(is_focused);
         show_mouse(!is_focused);
     }
 
-    pub fn update_look(&mut self, delta: f32) {
+    /// rotates the camera with the mouse movement scaled by the sensitivity and zoom,
+    /// the pitch is kept within the max pitch
+    pub fn update_look(&mut self, delta: f32, invert_y: bool, max_pitch: f32, sensitivity: f32) {
         let delta = delta.min(0.03);
         let mouse_position: Vec2 = mouse_position().into();
         let mouse_delta = mouse_position - self.last_mouse_position;
@@ -70,11 +86,11 @@ impl CameraController {
             return;
         }
 
-        self.yaw += mouse_delta.x * delta * LOOK_SPEED;
-        self.pitch += mouse_delta.y * delta * LOOK_SPEED;
-
-        self.pitch = if self.pitch > 1.5 { 1.5 } else { self.pitch };
-        self.pitch = if self.pitch < -1.5 { -1.5 } else { self.pitch };
+        let look_speed = LOOK_SPEED * sensitivity * self.get_zoom_fov_multiplier();
+        self.yaw += mouse_delta.x * delta * look_speed;
+        let pitch_direction = if invert_y { -1.0 } else { 1.0 };
+        self.pitch += mouse_delta.y * delta * look_speed * pitch_direction;
+        self.pitch = self.pitch.clamp(-max_pitch, max_pitch);
 
         self.front = vec3(
             self.yaw.cos() * self.pitch.cos(),
@@ -115,11 +131,37 @@ impl CameraController {
         self.position = position;
     }
 
+    pub fn set_view_offset(&mut self, view_offset: f32) {
+        self.view_offset = view_offset;
+    }
+
+    pub fn set_fov(&mut self, fov_degrees: f32) {
+        self.fovy = fov_degrees.to_radians();
+    }
+
+    /// moves the zoom towards fully zoomed in while zooming and back otherwise
+    pub fn update_zoom(&mut self, delta: f32, is_zooming: bool) {
+        let change = ZOOM_SPEED * delta;
+        self.zoom = if is_zooming {
+            (self.zoom + change).min(1.0)
+        } else {
+            (self.zoom - change).max(0.0)
+        };
+    }
+
+    /// eases in and out of the zoomed field of view
+    fn get_zoom_fov_multiplier(&self) -> f32 {
+        let eased_zoom = self.zoom * self.zoom * (3.0 - 2.0 * self.zoom);
+        1.0 + (ZOOM_FOV_MULTIPLIER - 1.0) * eased_zoom
+    }
+
     pub fn create_camera(&self) -> Camera3D {
+        let position = self.position + vec3(0.0, 0.0, self.view_offset);
         Camera3D {
-            position: self.position,
+            position,
             up: self.up,
-            target: self.position + self.front,
+            target: position + self.front,
+            fovy: self.fovy * self.get_zoom_fov_multiplier(),
             ..Default::default()
         }
     }
@@ -164,3 +206,28 @@ impl CameraController {
         vec3(vec.x, vec.y, 0.0).normalize_or_zero()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zoom_narrows_fov_relative_to_base() {
+        let mut camera_controller = CameraController::new(Vec3::ZERO);
+        camera_controller.set_fov(80.0);
+        let base_fovy = camera_controller.create_camera().fovy;
+        assert!((base_fovy - 80.0_f32.to_radians()).abs() < 0.0001);
+
+        camera_controller.update_zoom(0.1, true);
+        let zooming_fovy = camera_controller.create_camera().fovy;
+        assert!(zooming_fovy < base_fovy);
+
+        camera_controller.update_zoom(1.0, true);
+        let zoomed_fovy = camera_controller.create_camera().fovy;
+        assert!((zoomed_fovy - base_fovy * ZOOM_FOV_MULTIPLIER).abs() < 0.0001);
+        assert!(zoomed_fovy < zooming_fovy);
+
+        camera_controller.update_zoom(1.0, false);
+        assert_eq!(camera_controller.create_camera().fovy, base_fovy);
+    }
+}
@@ -0,0 +1,255 @@
+use crate::{
+    graphics::{mesh_manager::MeshManager, renderer::Renderer},
+    model::{
+        area::AREA_SIZE,
+        location::{InternalLocation, Location},
+        player_info::PlayerInfo,
+        world::World,
+    },
+    service::{
+        area_generation::biome_type::{BiomeType, BiomeTypeGenerator},
+        creatures::creature_manager::{CreatureId, CreatureManager},
+        performance_log::PerformanceLog,
+        raycast::{RaycastResult, cast_ray},
+    },
+};
+
+/// maximum number of areas searched outwards from the player when locating a biome
+const MAX_LOCATE_RADIUS: u32 = 64;
+const WAYPOINT_USAGE: &str = "Usage: waypoint [<x> <y>|clear]";
+
+/// the state the commands can read and modify
+pub struct CommandContext<'a> {
+    pub world: &'a mut World,
+    pub renderer: &'a mut Renderer,
+    pub player_info: &'a mut PlayerInfo,
+    pub creature_manager: &'a mut CreatureManager,
+    pub mesh_manager: &'a MeshManager,
+    pub performance_log: &'a mut PerformanceLog,
+    /// debug commands are only allowed while the debug display is shown
+    pub is_debug_enabled: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Locate(BiomeType),
+    /// sets the compass waypoint to the given x and y or the player location if none
+    SetWaypoint(Option<(i32, i32)>),
+    ClearWaypoint,
+    /// debug command, spawns the creature at the targeted voxel
+    Spawn(CreatureId),
+    /// starts or stops writing performance metrics to a csv file
+    TogglePerformanceLog,
+    /// regenerates the loaded areas from older generation versions that were never modified
+    RegenerateStaleAreas,
+}
+impl Command {
+    /// parses a command entered in the command console
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut words = input.split_whitespace();
+        let Some(name) = words.next() else {
+            return Err("Enter a command".to_owned());
+        };
+        let arguments: Vec<&str> = words.collect();
+
+        match (name.to_ascii_lowercase().as_str(), arguments.as_slice()) {
+            ("locate", [biome_name]) => BiomeType::from_name(biome_name)
+                .map(Command::Locate)
+                .ok_or_else(|| format!("Unknown biome '{biome_name}', use {}", biome_names())),
+            ("locate", _) => Err(format!("Usage: locate <{}>", biome_names())),
+            ("waypoint", []) => Ok(Command::SetWaypoint(None)),
+            ("waypoint", [argument]) if argument.eq_ignore_ascii_case("clear") => {
+                Ok(Command::ClearWaypoint)
+            }
+            ("waypoint", [x, y]) => match (x.parse(), y.parse()) {
+                (Ok(x), Ok(y)) => Ok(Command::SetWaypoint(Some((x, y)))),
+                _ => Err(WAYPOINT_USAGE.to_owned()),
+            },
+            ("waypoint", _) => Err(WAYPOINT_USAGE.to_owned()),
+            ("spawn", [creature_name]) => CreatureId::from_name(creature_name)
+                .map(Command::Spawn)
+                .ok_or_else(|| {
+                    format!(
+                        "Unknown creature '{creature_name}', use {}",
+                        creature_names()
+                    )
+                }),
+            ("spawn", _) => Err(format!("Usage: spawn <{}>", creature_names())),
+            ("perflog", []) => Ok(Command::TogglePerformanceLog),
+            ("perflog", _) => Err("Usage: perflog".to_owned()),
+            ("regenerate", []) => Ok(Command::RegenerateStaleAreas),
+            ("regenerate", _) => Err("Usage: regenerate".to_owned()),
+            _ => Err(format!("Unknown command '{name}'")),
+        }
+    }
+
+    /// executes the command and returns the message to display
+    pub fn execute(self, context: CommandContext) -> String {
+        match self {
+            Command::Locate(biome_type) => {
+                locate_biome(biome_type, context.world, context.player_info)
+            }
+            Command::SetWaypoint(coordinates) => set_waypoint(coordinates, context.player_info),
+            Command::ClearWaypoint => {
+                context.player_info.waypoint = None;
+                "Waypoint cleared, the compass points to spawn".to_owned()
+            }
+            Command::Spawn(id) => spawn_creature(id, context),
+            Command::TogglePerformanceLog => context.performance_log.toggle(),
+            Command::RegenerateStaleAreas => regenerate_stale_areas(context),
+        }
+    }
+}
+
+fn regenerate_stale_areas(context: CommandContext) -> String {
+    let regenerated = context.world.regenerate_stale_areas();
+    if regenerated.is_empty() {
+        return "No unmodified areas from older versions are loaded".to_owned();
+    }
+    context.renderer.reload_areas(&regenerated);
+
+    format!("Regenerated {} unmodified areas", regenerated.len())
+}
+
+fn biome_names() -> String {
+    BiomeType::ALL
+        .map(|biome_type| biome_type.get_name())
+        .join("|")
+}
+
+fn creature_names() -> String {
+    CreatureId::ALL.map(|id| id.get_name()).join("|")
+}
+
+fn locate_biome(biome_type: BiomeType, world: &World, player_info: &PlayerInfo) -> String {
+    let player_location = player_info.camera_controller.get_camera_voxel_location();
+    let generator = BiomeTypeGenerator::new(world.get_seed());
+    let Some(area_location) =
+        generator.find_nearest(player_location.into(), biome_type, MAX_LOCATE_RADIUS)
+    else {
+        return format!("No {} biome found nearby", biome_type.get_name());
+    };
+
+    let target: Location = InternalLocation::new(
+        area_location.x * AREA_SIZE + AREA_SIZE / 2,
+        area_location.y * AREA_SIZE + AREA_SIZE / 2,
+        0,
+    )
+    .into();
+    let dx = target.x - player_location.x;
+    let dy = target.y - player_location.y;
+    let distance = ((dx * dx + dy * dy) as f32).sqrt().round();
+
+    format!(
+        "Nearest {} biome at x:{} y:{} ({} voxels away)",
+        biome_type.get_name(),
+        target.x,
+        target.y,
+        distance
+    )
+}
+
+fn set_waypoint(coordinates: Option<(i32, i32)>, player_info: &mut PlayerInfo) -> String {
+    let player_location = player_info.camera_controller.get_camera_voxel_location();
+    let (x, y) = coordinates.unwrap_or((player_location.x, player_location.y));
+    player_info.waypoint = Some(Location::new(x, y, player_location.z));
+
+    format!("Waypoint set at x:{x} y:{y}")
+}
+
+fn spawn_creature(id: CreatureId, context: CommandContext) -> String {
+    if !context.is_debug_enabled {
+        return "Spawning creatures requires the debug display".to_owned();
+    }
+    let camera = context.player_info.camera_controller.create_camera();
+    let RaycastResult::Hit { last_empty, .. } = cast_ray(
+        context.world,
+        camera.position,
+        camera.target,
+        context.player_info.get_voxel_reach(),
+    ) else {
+        return "Look at a voxel to spawn a creature".to_owned();
+    };
+
+    let has_spawned = context.creature_manager.spawn_creature_at(
+        id,
+        last_empty,
+        context.world,
+        context.mesh_manager,
+    );
+    if has_spawned {
+        format!(
+            "Spawned {} at x:{} y:{} z:{}",
+            id.get_name(),
+            last_empty.x,
+            last_empty.y,
+            last_empty.z
+        )
+    } else if id.is_aquatic() {
+        format!("Can't spawn {} outside of water", id.get_name())
+    } else {
+        format!("Can't spawn {} without ground below", id.get_name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_locate() {
+        assert_eq!(
+            Command::parse("locate cold"),
+            Ok(Command::Locate(BiomeType::Cold))
+        );
+        assert_eq!(
+            Command::parse("  LOCATE   Dry "),
+            Ok(Command::Locate(BiomeType::Dry))
+        );
+    }
+
+    #[test]
+    fn test_parse_waypoint() {
+        assert_eq!(Command::parse("waypoint"), Ok(Command::SetWaypoint(None)));
+        assert_eq!(
+            Command::parse("waypoint 12 -40"),
+            Ok(Command::SetWaypoint(Some((12, -40))))
+        );
+        assert_eq!(Command::parse("Waypoint CLEAR"), Ok(Command::ClearWaypoint));
+        assert!(Command::parse("waypoint 12").is_err());
+        assert!(Command::parse("waypoint a b").is_err());
+    }
+
+    #[test]
+    fn test_parse_spawn() {
+        assert_eq!(
+            Command::parse("spawn Penguin"),
+            Ok(Command::Spawn(CreatureId::Penguin))
+        );
+        assert!(Command::parse("spawn").is_err());
+        assert!(Command::parse("spawn dragon").is_err());
+    }
+
+    #[test]
+    fn test_parse_perflog() {
+        assert_eq!(Command::parse("perflog"), Ok(Command::TogglePerformanceLog));
+        assert!(Command::parse("perflog on").is_err());
+    }
+
+    #[test]
+    fn test_parse_regenerate() {
+        assert_eq!(
+            Command::parse("regenerate"),
+            Ok(Command::RegenerateStaleAreas)
+        );
+        assert!(Command::parse("regenerate all").is_err());
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(Command::parse("").is_err());
+        assert!(Command::parse("locate").is_err());
+        assert!(Command::parse("locate lava").is_err());
+        assert!(Command::parse("teleport").is_err());
+    }
+}
@@ -1,16 +1,20 @@
 use crate::model::{
-    inventory::{AvailableItems, Inventory, Item},
+    inventory::{AvailableItems, HotbarLocks, Inventory, Item},
     voxel::Voxel,
 };
 
 impl Item {
     /// const constructor
-    const fn new_c(voxel: Voxel, count: u8) -> Self {
-        Self { voxel, count }
+    pub const fn new_c(voxel: Voxel, count: u8) -> Self {
+        Self {
+            voxel,
+            count,
+            charges: None,
+        }
     }
 }
 
-const RECEPES: [CraftingRecipe; 11] = [
+const RECEPES: [CraftingRecipe; 23] = [
     CraftingRecipe::new1(Item::new_c(Voxel::Boards, 3), Item::new_c(Voxel::Wood, 1)),
     CraftingRecipe::new1(Item::new_c(Voxel::Glass, 1), Item::new_c(Voxel::Sand, 4)),
     CraftingRecipe::new1(
@@ -53,6 +57,61 @@ const RECEPES: [CraftingRecipe; 11] = [
         Item::new_c(Voxel::Wood, 2),
         Item::new_c(Voxel::Stone, 2),
     ),
+    CraftingRecipe::new2(
+        Item::new_c(Voxel::Compass, 1),
+        Item::new_c(Voxel::Glass, 1),
+        Item::new_c(Voxel::Cobblestone, 2),
+    ),
+    CraftingRecipe::new3(
+        Item::new_c(Voxel::WeatherTotem, 1),
+        Item::new_c(Voxel::Wood, 4),
+        Item::new_c(Voxel::Ice, 2),
+        Item::new_c(Voxel::Lamp, 1),
+    ),
+    CraftingRecipe::new2(
+        Item::new_c(Voxel::Lamp, 2),
+        Item::new_c(Voxel::Glass, 1),
+        Item::new_c(Voxel::CoalOre, 1),
+    ),
+    CraftingRecipe::new2(
+        Item::new_c(Voxel::Trampoline, 1),
+        Item::new_c(Voxel::Wood, 4),
+        Item::new_c(Voxel::IronOre, 2),
+    ),
+    CraftingRecipe::new2(
+        Item::new_c(Voxel::Compass, 1),
+        Item::new_c(Voxel::Glass, 1),
+        Item::new_c(Voxel::IronOre, 1),
+    ),
+    CraftingRecipe::new2(
+        Item::new_c(Voxel::Torch, 4),
+        Item::new_c(Voxel::Wood, 1),
+        Item::new_c(Voxel::CoalOre, 1),
+    ),
+    CraftingRecipe::new1(
+        Item::new_c(Voxel::Furnace, 1),
+        Item::new_c(Voxel::Cobblestone, 8),
+    ),
+    CraftingRecipe::new1(
+        Item::new_c(Voxel::StoneSlab, 2),
+        Item::new_c(Voxel::Stone, 1),
+    ),
+    CraftingRecipe::new1(
+        Item::new_c(Voxel::WoodStairs, 4),
+        Item::new_c(Voxel::Boards, 3),
+    ),
+    CraftingRecipe::new1(Item::new_c(Voxel::Bucket, 1), Item::new_c(Voxel::Iron, 3)),
+    CraftingRecipe::new2(
+        Item::new_c(Voxel::Pickaxe, 1),
+        Item::new_c(Voxel::Iron, 3),
+        Item::new_c(Voxel::Wood, 2),
+    ),
+    CraftingRecipe::new3(
+        Item::new_c(Voxel::ReachStaff, 1),
+        Item::new_c(Voxel::Wood, 4),
+        Item::new_c(Voxel::Glass, 1),
+        Item::new_c(Voxel::Iron, 1),
+    ),
 ];
 
 #[derive(Debug, Clone, Copy)]
@@ -87,21 +146,45 @@ impl CraftingRecipe {
     pub fn get_inputs(&self) -> impl Iterator<Item = &Item> {
         self.inputs.iter().flatten()
     }
+
+    /// checks if the output or any of the inputs contain the search text, ignoring case
+    pub fn matches_search(&self, search: &str) -> bool {
+        let search = search.trim().to_lowercase();
+        [&self.output]
+            .into_iter()
+            .chain(self.get_inputs())
+            .any(|item| item.voxel.display_name().to_lowercase().contains(&search))
+    }
 }
 
-/// returns the craftable recipies and the number of times they can be crafted
-pub fn find_craftable(available_items: &AvailableItems) -> Vec<(CraftingRecipe, u32)> {
+/// returns every recipe and the number of times it can be crafted, including unaffordable ones
+pub fn all_recipes(available_items: &AvailableItems) -> Vec<(CraftingRecipe, u32)> {
     RECEPES
         .into_iter()
         .map(|recipe| (recipe, find_max_times_craftable(&recipe, available_items)))
+        .collect()
+}
+
+/// returns the craftable recipies and the number of times they can be crafted
+pub fn find_craftable(available_items: &AvailableItems) -> Vec<(CraftingRecipe, u32)> {
+    all_recipes(available_items)
+        .into_iter()
         .filter(|(_, count)| *count > 0)
         .collect()
 }
 
-/// crafts the recipe a number of times
-pub fn craft_recipe(recipe: &CraftingRecipe, inventory: &mut Inventory, craft_count: u8) {
+/// crafts the recipe a number of times, without using items from locked hotbar slots
+pub fn craft_recipe(
+    recipe: &CraftingRecipe,
+    inventory: &mut Inventory,
+    hotbar_locks: HotbarLocks,
+    craft_count: u8,
+) {
     for input in recipe.get_inputs() {
-        inventory.remove_item(Item::new(input.voxel, input.count * craft_count));
+        inventory.remove_unlocked_item(
+            Item::new(input.voxel, input.count * craft_count),
+            hotbar_locks,
+        );
     }
     let output = Item::new(recipe.output.voxel, recipe.output.count * craft_count);
     inventory.add_item(output);
@@ -128,7 +211,7 @@ mod tests {
         available.add(Voxel::Glass, 1u32);
 
         let craftable = find_craftable(&available);
-        assert_eq!(craftable.len(), 7);
+        assert_eq!(craftable.len(), 8);
         assert_eq!(craftable[0].0.output.voxel, Voxel::Boards);
         assert_eq!(craftable[0].1, 10);
         assert_eq!(craftable[1].0.output.voxel, Voxel::Cobblestone);
@@ -143,12 +226,25 @@ mod tests {
         assert_eq!(craftable[5].1, 1);
         assert_eq!(craftable[6].0.output.voxel, Voxel::Trampoline);
         assert_eq!(craftable[6].1, 1);
+        assert_eq!(craftable[7].0.output.voxel, Voxel::StoneSlab);
+        assert_eq!(craftable[7].1, 10);
+    }
+
+    #[test]
+    fn test_matches_search() {
+        let recipe = RECEPES[0];
+        assert_eq!(recipe.output.voxel, Voxel::Boards);
+
+        assert!(recipe.matches_search(""));
+        assert!(recipe.matches_search("boa"));
+        assert!(recipe.matches_search("WOOD "));
+        assert!(!recipe.matches_search("stone"));
     }
 
     #[test]
     fn test_find_craftable_none() {
         let mut available = AvailableItems::new_empty();
-        available.add(Voxel::Boards, 10u32);
+        available.add(Voxel::Boards, 2u32);
         available.add(Voxel::Glass, 220u32);
         available.add(Voxel::Clay, 10u32);
         available.add(Voxel::Grass, 50u32);
@@ -156,6 +252,18 @@ mod tests {
         assert_eq!(craftable.len(), 0);
     }
 
+    #[test]
+    fn test_all_recipes_includes_unaffordable() {
+        let mut available = AvailableItems::new_empty();
+        available.add(Voxel::Wood, 1u32);
+
+        let recipes = all_recipes(&available);
+        assert_eq!(recipes.len(), RECEPES.len());
+        assert_eq!(recipes[0].0.output.voxel, Voxel::Boards);
+        assert_eq!(recipes[0].1, 1);
+        assert!(recipes[1..].iter().all(|(_, count)| *count == 0));
+    }
+
     #[test]
     fn test_craft_recipe_once() {
         craft_recipe_with_count(1);
@@ -170,7 +278,7 @@ mod tests {
     #[should_panic]
     fn test_craft_recipe_insufficient_inputs() {
         let mut inventory = Inventory::default();
-        craft_recipe(&RECEPES[0], &mut inventory, 1);
+        craft_recipe(&RECEPES[0], &mut inventory, HotbarLocks::default(), 1);
     }
 
     fn craft_recipe_with_count(count: u8) {
@@ -183,7 +291,7 @@ mod tests {
                 });
             }
             inventory.add_item(Item::new(recipe.output.voxel, 1));
-            craft_recipe(&recipe, &mut inventory, count);
+            craft_recipe(&recipe, &mut inventory, HotbarLocks::default(), count);
 
             let items = inventory.create_all_items_map();
             for input in recipe.get_inputs() {
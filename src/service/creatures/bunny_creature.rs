@@ -12,13 +12,15 @@ use crate::{
         mesh_manager::{MeshId, MeshManager},
         mesh_transformer::{move_mesh, rotate_around_z_with_direction},
     },
-    model::{area::AREA_HEIGHT, player_info::PlayerInfo, voxel::Voxel, world::World},
+    model::{
+        area::AREA_HEIGHT, location::Location, player_info::PlayerInfo, voxel::Voxel, world::World,
+    },
     service::{
         activity_timer::ActivityTimer,
         creatures::{
             creature::{
-                Creature, collides, collides_with_ground, collides_with_player,
-                perform_static_collisions, push_away_from,
+                Creature, collides, collides_with_ground, collides_with_player, create_scaled_mesh,
+                perform_static_collisions, push_away_from, random_model_scale, turn_angle_towards,
             },
             creature_manager::{CreatureDTO, CreatureId, CreatureManager},
         },
@@ -36,6 +38,14 @@ const MOVE_ACTIVITY_MAX: f32 = 14.0;
 const TURN_ACTIVITY: f32 = 1.5;
 const MIN_ACTIVITY: f32 = 0.5;
 
+/// chance per second to eat the grass below while idle
+const EAT_GRASS_CHANCE: f32 = 0.05;
+
+/// the bunny runs away if the player gets this close
+const FLEE_DISTANCE: f32 = 4.0;
+const FLEE_TIME: f32 = 3.0;
+const FLEE_SPEED_MODIFIER: f32 = 1.8;
+
 const SWIM_SPEED: f32 = -30.0;
 const MAX_SWIM: f32 = -8.0;
 
@@ -46,6 +56,7 @@ enum Activity {
     Idle,
     Move,
     Turn(bool),
+    Flee,
 }
 
 pub struct BunnyCreature {
@@ -55,15 +66,19 @@ pub struct BunnyCreature {
     activity: Activity,
     direction: Vec3,
     rotation: f32,
+    /// size multiplier of the model and collision box
+    scale: f32,
     mesh: Mesh,
 }
 impl BunnyCreature {
-    /// creates a new bunny creature at position with a random rotation
+    /// creates a new bunny creature at position with a random rotation and scale
     pub fn new(position: Vec3, mesh_manager: &MeshManager) -> Self {
-        let mesh = mesh_manager.create_at(MeshId::Bunny, position);
+        let scale = random_model_scale();
+        let mesh = create_scaled_mesh(mesh_manager, MeshId::Bunny, position, scale);
         let random_rotation = gen_range(0.0, TAU);
         let mut bunny = Self {
             position,
+            scale,
             velocity: 0.0,
             mesh,
             activity_timer: ActivityTimer::new(0.0, gen_range(MIN_ACTIVITY, WAIT_ACTIVITY_MAX)),
@@ -97,8 +112,8 @@ impl BunnyCreature {
         is_on_ground
     }
 
-    fn handle_move(&mut self, delta: f32, world: &mut World, on_ground: bool) {
-        let move_distance = delta * SPEED;
+    fn handle_move(&mut self, delta: f32, world: &mut World, on_ground: bool, speed: f32) {
+        let move_distance = delta * speed;
 
         if on_ground {
             self.velocity = JUMP;
@@ -134,6 +149,36 @@ impl BunnyCreature {
         );
     }
 
+    /// turns away from the player and starts fleeing if the player is too close
+    fn flee_from_player(&mut self, player_info: &PlayerInfo) {
+        if matches!(self.activity, Activity::Flee) {
+            return;
+        }
+
+        let player_position = player_info.camera_controller.get_position();
+        let away_from_player = vec3(
+            self.position.x - player_position.x,
+            self.position.y - player_position.y,
+            0.0,
+        )
+        .normalize_or_zero();
+        if away_from_player == Vec3::ZERO || self.position.distance(player_position) > FLEE_DISTANCE
+        {
+            return;
+        }
+
+        let turn_amount = turn_angle_towards(self.direction, away_from_player);
+        self.rotation = (self.rotation + turn_amount).rem_euclid(TAU);
+        rotate_around_z_with_direction(
+            &mut self.mesh,
+            &mut self.direction,
+            self.position,
+            turn_amount,
+        );
+        self.activity = Activity::Flee;
+        self.activity_timer = ActivityTimer::new(0.0, FLEE_TIME);
+    }
+
     fn swim_if_in_water(&mut self, delta: f32, world: &mut World) {
         let voxel = world.get(vector_to_location(self.position));
         if !Voxel::WATER.contains(&voxel) {
@@ -176,15 +221,23 @@ impl Creature for BunnyCreature {
                     Activity::Move,
                     ActivityTimer::new(0.0, gen_range(MIN_ACTIVITY, MOVE_ACTIVITY_MAX)),
                 ),
+                Activity::Flee => (
+                    Activity::Idle,
+                    ActivityTimer::new(0.0, gen_range(MIN_ACTIVITY, WAIT_ACTIVITY_MAX)),
+                ),
             }
         }
+        self.flee_from_player(player_info);
         let on_ground = self.handle_gravity(delta, world);
         self.swim_if_in_water(delta, world);
 
         match self.activity {
             Activity::Idle => {}
             Activity::Move => {
-                self.handle_move(delta, world, on_ground);
+                self.handle_move(delta, world, on_ground, SPEED);
+            }
+            Activity::Flee => {
+                self.handle_move(delta, world, on_ground, SPEED * FLEE_SPEED_MODIFIER);
             }
             Activity::Turn(clockwise) => {
                 self.handle_turn(delta, clockwise);
@@ -199,6 +252,26 @@ impl Creature for BunnyCreature {
         }
     }
 
+    fn edit_world(&mut self, delta: f32, world: &mut World) -> Option<Location> {
+        if !matches!(self.activity, Activity::Idle)
+            || gen_range(0.0, 1.0) >= EAT_GRASS_CHANCE * delta
+        {
+            return None;
+        }
+
+        let below = vector_to_location(self.position + vec3(0.0, 0.0, SIZE.z));
+        if world.get(below) != Voxel::Grass {
+            return None;
+        }
+        world.set(below, Voxel::Dirt);
+
+        Some(below)
+    }
+
+    fn get_id(&self) -> CreatureId {
+        CreatureId::Bunny
+    }
+
     fn get_mesh_with_index(&self) -> (&Mesh, usize) {
         (&self.mesh, MeshId::Bunny.index())
     }
@@ -207,8 +280,17 @@ impl Creature for BunnyCreature {
         self.position
     }
 
+    fn move_by(&mut self, displacement: Vec3) {
+        self.position += displacement;
+        move_mesh(&mut self.mesh, displacement);
+    }
+
+    fn move_model_by(&mut self, displacement: Vec3) {
+        move_mesh(&mut self.mesh, displacement);
+    }
+
     fn get_size(&self) -> Vec3 {
-        SIZE
+        SIZE * self.scale
     }
 
     fn create_dto(&self) -> Option<CreatureDTO> {
@@ -218,6 +300,7 @@ impl Creature for BunnyCreature {
             velocity: self.velocity,
             activity: self.activity,
             rotation: self.rotation,
+            scale: self.scale,
         };
 
         CreatureManager::encode_creature_dto(&dto, CreatureId::Bunny)
@@ -231,7 +314,7 @@ impl Creature for BunnyCreature {
             CreatureManager::decode_creature_dto(creature_dto, CreatureId::Bunny)?;
 
         let position = arr_to_vec3(bunny_dto.position);
-        let mut mesh = mesh_manager.create_at(MeshId::Bunny, position);
+        let mut mesh = create_scaled_mesh(mesh_manager, MeshId::Bunny, position, bunny_dto.scale);
         let mut direction = FORWAD_DIRECTION;
         rotate_around_z_with_direction(&mut mesh, &mut direction, position, bunny_dto.rotation);
 
@@ -243,6 +326,7 @@ impl Creature for BunnyCreature {
             direction,
             mesh,
             rotation: bunny_dto.rotation,
+            scale: bunny_dto.scale,
         }))
     }
 
@@ -261,4 +345,81 @@ struct BunnyDTO {
     position: [f32; 3],
     velocity: f32,
     activity: Activity,
+    scale: f32,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::{
+        area::{AREA_SIZE, Area},
+        location::InternalLocation,
+    };
+
+    use super::*;
+
+    fn create_bunny(position: Vec3) -> BunnyCreature {
+        BunnyCreature {
+            activity_timer: ActivityTimer::new(0.0, WAIT_ACTIVITY_MAX),
+            position,
+            velocity: 0.0,
+            activity: Activity::Idle,
+            direction: FORWAD_DIRECTION,
+            rotation: 0.0,
+            scale: 1.0,
+            mesh: Mesh {
+                vertices: vec![],
+                indices: vec![],
+                texture: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_size_matches_scale() {
+        let scale = random_model_scale();
+        let bunny = BunnyCreature {
+            activity_timer: ActivityTimer::new(0.0, MIN_ACTIVITY),
+            position: Vec3::ZERO,
+            velocity: 0.0,
+            activity: Activity::Idle,
+            direction: FORWAD_DIRECTION,
+            rotation: 0.0,
+            scale,
+            mesh: Mesh {
+                vertices: vec![],
+                indices: vec![],
+                texture: None,
+            },
+        };
+
+        assert!((0.9..=1.1).contains(&scale));
+        assert!((bunny.get_size() - SIZE * scale).length() < 0.0001);
+        assert!(bunny.get_size().z < Voxel::SIZE);
+    }
+
+    #[test]
+    fn test_bunny_flees_from_player() {
+        let bunny_location = Location::new(3, 3, 50);
+        let mut world = World::new("test_world_test_bunny_flees_from_player");
+        let mut area = Area::new(bunny_location.into());
+        for x in 0..AREA_SIZE {
+            area.set(InternalLocation::new(x, 3, 51), Voxel::Stone);
+        }
+        world.return_area(area);
+
+        let mut bunny = create_bunny(bunny_location.into());
+        let far_player = PlayerInfo::new(vec3(3.0 + FLEE_DISTANCE + 1.0, 3.0, 50.0));
+        bunny.update(0.01, &mut world, &far_player);
+        assert!(matches!(bunny.activity, Activity::Idle));
+
+        let near_player = PlayerInfo::new(vec3(4.0, 3.0, 50.0));
+        bunny.update(0.01, &mut world, &near_player);
+        assert!(matches!(bunny.activity, Activity::Flee));
+        assert!((bunny.direction - vec3(-1.0, 0.0, 0.0)).length() < 0.001);
+
+        for _ in 0..50 {
+            bunny.update(0.02, &mut world, &near_player);
+        }
+        assert!(bunny.position.x < 3.0 - SPEED * 0.5);
+    }
 }
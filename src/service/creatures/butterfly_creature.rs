@@ -175,6 +175,10 @@ impl Creature for ButterflyCreature {
         }
     }
 
+    fn get_id(&self) -> CreatureId {
+        CreatureId::Butterfly
+    }
+
     fn get_mesh_with_index(&self) -> (&Mesh, usize) {
         let (mesh, id) = &self.mesh_arr[self.current_mesh];
         (mesh, id.index())
@@ -184,6 +188,17 @@ impl Creature for ButterflyCreature {
         self.position
     }
 
+    fn move_by(&mut self, displacement: Vec3) {
+        self.position += displacement;
+        self.move_model_by(displacement);
+    }
+
+    fn move_model_by(&mut self, displacement: Vec3) {
+        for (mesh, _id) in &mut self.mesh_arr {
+            move_mesh(mesh, displacement);
+        }
+    }
+
     fn get_size(&self) -> Vec3 {
         SIZE
     }
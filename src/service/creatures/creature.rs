@@ -1,22 +1,51 @@
+use std::f32::consts::TAU;
+
 use macroquad::{
     math::{Vec3, Vec3Swizzles, vec3},
     models::Mesh,
+    rand::gen_range,
 };
 
 use crate::{
-    graphics::mesh_manager::MeshManager,
-    model::{location::Location, player_info::PlayerInfo, voxel::Voxel, world::World},
-    service::creatures::creature_manager::CreatureDTO,
+    graphics::{
+        mesh_manager::{MeshId, MeshManager},
+        mesh_transformer::scale_mesh,
+    },
+    model::{
+        location::Location,
+        player_info::PlayerInfo,
+        voxel::{LightColor, Voxel},
+        world::World,
+    },
+    service::creatures::creature_manager::{CreatureDTO, CreatureId},
     utils::vector_to_location,
 };
 
 const PUSH_FROM_POINT_SPEED: f32 = 2.0;
+/// creature models are randomly scaled within this range,
+/// kept small so the size still fits the collision checks
+const MIN_MODEL_SCALE: f32 = 0.9;
+const MAX_MODEL_SCALE: f32 = 1.1;
 
 pub trait Creature {
     fn update(&mut self, delta: f32, world: &mut World, player_info: &PlayerInfo);
+    /// changes voxels in the world, only called if mob griefing is enabled,
+    /// returns the modified location
+    fn edit_world(&mut self, _delta: f32, _world: &mut World) -> Option<Location> {
+        None
+    }
+    fn get_id(&self) -> CreatureId;
     fn get_mesh_with_index(&self) -> (&Mesh, usize);
     fn get_position(&self) -> Vec3;
+    /// moves the creature and its model without any checks
+    fn move_by(&mut self, displacement: Vec3);
+    /// moves only the model, used to draw the creature between its updates
+    fn move_model_by(&mut self, displacement: Vec3);
     fn get_size(&self) -> Vec3;
+    /// returns the color and radius of the light emitted by the creature
+    fn get_light(&self) -> Option<(LightColor, f32)> {
+        None
+    }
     fn get_allowed_spawn_voxels() -> &'static [Voxel]
     where
         Self: Sized;
@@ -26,8 +55,24 @@ pub trait Creature {
         Self: Sized;
 }
 
+pub fn random_model_scale() -> f32 {
+    gen_range(MIN_MODEL_SCALE, MAX_MODEL_SCALE)
+}
+
+/// creates the creature model at the position, scaled around it
+pub fn create_scaled_mesh(
+    mesh_manager: &MeshManager,
+    mesh_id: MeshId,
+    position: Vec3,
+    scale: f32,
+) -> Mesh {
+    let mut mesh = mesh_manager.create_at(mesh_id, position);
+    scale_mesh(&mut mesh, position, scale);
+    mesh
+}
+
 /// returns the position of collision
-pub fn collides(creature: &impl Creature, world: &mut World) -> Option<Vec3> {
+pub fn collides(creature: &(impl Creature + ?Sized), world: &mut World) -> Option<Vec3> {
     let pos = creature.get_position();
     let size = creature.get_size();
     let creature_location: Location = pos.into();
@@ -83,6 +128,14 @@ pub fn collides_with_player(creature: &impl Creature, player_info: &PlayerInfo)
     !no_collision
 }
 
+/// returns the angle (0.0 .. TAU) to rotate the direction by around z to face the target direction
+pub fn turn_angle_towards(direction: Vec3, target_direction: Vec3) -> f32 {
+    let cross = direction.x * target_direction.y - direction.y * target_direction.x;
+    let dot = direction.x * target_direction.x + direction.y * target_direction.y;
+
+    cross.atan2(dot).rem_euclid(TAU)
+}
+
 /// returns the change in position
 pub fn push_away_from(creature: &impl Creature, point: Vec3, delta: f32) -> Vec3 {
     let position = creature.get_position();
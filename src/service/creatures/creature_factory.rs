@@ -10,6 +10,8 @@ use crate::{
         butterfly_creature::ButterflyCreature,
         creature::Creature,
         creature_manager::{CreatureDTO, CreatureId},
+        firefly_creature::FireflyCreature,
+        fish_creature::FishCreature,
         penguin_creature::PenguinCreature,
     },
 };
@@ -23,6 +25,8 @@ pub fn create_creature(
         CreatureId::Bunny => Box::new(BunnyCreature::new(position, mesh_manager)),
         CreatureId::Butterfly => Box::new(ButterflyCreature::new(position, mesh_manager)),
         CreatureId::Penguin => Box::new(PenguinCreature::new(position, mesh_manager)),
+        CreatureId::Fish => Box::new(FishCreature::new(position, mesh_manager)),
+        CreatureId::Firefly => Box::new(FireflyCreature::new(position, mesh_manager)),
     }
 }
 
@@ -35,6 +39,8 @@ pub fn create_creature_from_dto(
         CreatureId::Bunny => BunnyCreature::from_dto(dto, mesh_manager),
         CreatureId::Butterfly => ButterflyCreature::from_dto(dto, mesh_manager),
         CreatureId::Penguin => PenguinCreature::from_dto(dto, mesh_manager),
+        CreatureId::Fish => FishCreature::from_dto(dto, mesh_manager),
+        CreatureId::Firefly => FireflyCreature::from_dto(dto, mesh_manager),
     }
 }
 
@@ -45,6 +51,8 @@ thread_local! {
         add_allowed_voxels(&mut map, CreatureId::Bunny, BunnyCreature::get_allowed_spawn_voxels());
         add_allowed_voxels(&mut map, CreatureId::Butterfly, ButterflyCreature::get_allowed_spawn_voxels());
         add_allowed_voxels(&mut map, CreatureId::Penguin, PenguinCreature::get_allowed_spawn_voxels());
+        add_allowed_voxels(&mut map, CreatureId::Fish, FishCreature::get_allowed_spawn_voxels());
+        add_allowed_voxels(&mut map, CreatureId::Firefly, FireflyCreature::get_allowed_spawn_voxels());
 
         map
     };
@@ -82,17 +90,11 @@ mod tests {
 
     use super::*;
 
-    const ALL_CREATURE_IDS: [CreatureId; 3] = [
-        CreatureId::Bunny,
-        CreatureId::Butterfly,
-        CreatureId::Penguin,
-    ];
-
     #[test]
     fn test_allowed_spawn_voxel_map() {
         ALLOWED_SPAWN_VOXEL_MAP.with(|map| {
             let mut all_creature_ids = HashSet::new();
-            for id in ALL_CREATURE_IDS {
+            for id in CreatureId::ALL {
                 all_creature_ids.insert(id);
             }
 
@@ -110,8 +112,8 @@ mod tests {
                     seen_ids.insert(*id);
                     all_creature_ids.remove(id);
                     assert!(
-                        ALL_CREATURE_IDS.contains(id),
-                        "'ALL_CREATURE_IDS' constant is outdated"
+                        CreatureId::ALL.contains(id),
+                        "'CreatureId::ALL' constant is outdated"
                     )
                 }
             }
@@ -1,8 +1,10 @@
+use std::collections::HashMap;
+
 use bincode::{Decode, Encode, decode_from_slice, encode_to_vec};
 use macroquad::{
     camera::Camera3D,
     color::WHITE,
-    math::{Vec3, vec3},
+    math::{IVec3, Vec3, vec3},
     models::{Mesh, draw_cube_wires, draw_mesh},
     prelude::{error, info},
     rand::gen_range,
@@ -11,13 +13,18 @@ use macroquad::{
 use crate::{
     graphics::mesh_manager::{MeshId, MeshManager},
     model::{
-        area::AREA_SIZE, location::Location, player_info::PlayerInfo, user_settings::UserSettings,
+        area::{AREA_HEIGHT, AREA_SIZE},
+        location::Location,
+        player_info::PlayerInfo,
+        user_settings::UserSettings,
+        voxel::{LightColor, Voxel},
         world::World,
+        world_rules::WorldRules,
     },
     service::{
         activity_timer::ActivityTimer,
         creatures::{
-            creature::Creature,
+            creature::{Creature, collides},
             creature_factory::{
                 create_creature, create_creature_from_dto, random_creature_id_for_voxel,
             },
@@ -29,15 +36,125 @@ use crate::{
 
 const CHECK_UPDATES_TIME: f32 = 2.0;
 const MAX_CREATURES: usize = 10;
+/// number of random locations tried each time a creature is spawned
+const SPAWN_ATTEMPTS: usize = 4;
+/// caps the number of creatures written to the world metadata
+const MAX_SAVED_CREATURES: usize = 32;
 const SPAWN_SIZE_EXTRA_RANGE: f32 = AREA_SIZE as f32 * 0.75;
+/// creatures are despawned further away than they can be spawned
+const DESPAWN_DISTANCE_MULTIPLIER: f32 = 1.5;
+/// time a creature can spend outside the despawn distance before being removed
+const DESPAWN_DELAY: f32 = 5.0;
 const MIN_CULL_DISTANCE: f32 = 3.0;
 const SPAWN_CREATURES_MAX_FOV: f32 = 0.15;
+/// creatures closer than this are updated every step to keep their movement smooth
+const ALWAYS_UPDATE_DISTANCE: f32 = AREA_SIZE as f32 * 2.0;
+/// the accumulated delta of a distant creature is split into steps no longer than this
+const MAX_CREATURE_STEP: f32 = 0.05;
+/// the cell of a creature and the cells around it, overlapping creatures are never further apart
+const NEIGHBOUR_CELL_OFFSETS: [IVec3; 27] = {
+    let mut offsets = [IVec3::ZERO; 27];
+    let mut index = 0;
+    while index < 27 {
+        offsets[index] = IVec3::new(
+            index as i32 % 3 - 1,
+            index as i32 / 3 % 3 - 1,
+            index as i32 / 9 - 1,
+        );
+        index += 1;
+    }
+    offsets
+};
+/// caps the number of creature lights sent to the voxel shader
+const MAX_CREATURE_LIGHTS: usize = 16;
+const MAX_CREATURE_LIGHT_DISTANCE: f32 = AREA_SIZE as f32 * 2.0;
+/// horizontal distance from the spawn where creatures don't spawn naturally
+const SPAWN_SAFE_ZONE_RADIUS: f32 = 24.0;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Encode, Decode)]
 pub enum CreatureId {
     Bunny,
     Butterfly,
     Penguin,
+    Fish,
+    Firefly,
+}
+impl CreatureId {
+    pub const ALL: [Self; 5] = [
+        Self::Bunny,
+        Self::Butterfly,
+        Self::Penguin,
+        Self::Fish,
+        Self::Firefly,
+    ];
+
+    pub fn get_name(self) -> &'static str {
+        match self {
+            CreatureId::Bunny => "bunny",
+            CreatureId::Butterfly => "butterfly",
+            CreatureId::Penguin => "penguin",
+            CreatureId::Fish => "fish",
+            CreatureId::Firefly => "firefly",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|id| id.get_name().eq_ignore_ascii_case(name))
+    }
+
+    /// aquatic creatures spawn inside the water instead of on the ground
+    pub fn is_aquatic(self) -> bool {
+        self == CreatureId::Fish
+    }
+
+    /// nocturnal creatures only spawn at night and are removed during the day
+    pub fn is_nocturnal(self) -> bool {
+        self == CreatureId::Firefly
+    }
+
+    /// the number of creatures of this species that can be spawned naturally at the default spawn rate,
+    /// so one species doesn't take up the whole `MAX_CREATURES`
+    fn get_max_count(self) -> usize {
+        match self {
+            CreatureId::Bunny => 5,
+            CreatureId::Butterfly => 4,
+            CreatureId::Penguin => 4,
+            CreatureId::Fish => 4,
+            CreatureId::Firefly => 6,
+        }
+    }
+
+    /// speed at which the creature is pushed away from the creatures it overlaps
+    fn get_separation_speed(self) -> f32 {
+        match self {
+            CreatureId::Bunny => 1.0,
+            CreatureId::Butterfly => 2.0,
+            CreatureId::Penguin => 0.5,
+            CreatureId::Fish => 1.5,
+            CreatureId::Firefly => 2.0,
+        }
+    }
+
+    /// scales the space the creature keeps around itself, penguins huddle and fish school
+    /// closer together while flying insects spread out
+    fn get_separation_spacing(self) -> f32 {
+        match self {
+            CreatureId::Bunny => 1.0,
+            CreatureId::Butterfly => 1.5,
+            CreatureId::Penguin => 0.8,
+            CreatureId::Fish => 0.7,
+            CreatureId::Firefly => 1.5,
+        }
+    }
+}
+
+pub struct CreatureUpdateParams<'a> {
+    pub user_settings: &'a UserSettings,
+    pub world_rules: &'a WorldRules,
+    /// nocturnal creatures are only spawned and kept at night
+    pub is_night: bool,
 }
 
 #[derive(Debug, Clone, Encode, Decode)]
@@ -46,15 +163,89 @@ pub struct CreatureDTO {
     pub bytes: Vec<u8>,
 }
 
+/// a creature with the time it has spent outside the despawn distance
+/// and the time that has passed since it was last updated
+struct TrackedCreature {
+    creature: Box<dyn Creature>,
+    out_of_range_time: f32,
+    pending_delta: f32,
+    /// offset of the drawn model from the creature, the model trails behind after an update
+    /// that caught up on a long delta and moves towards the creature until the next one
+    model_offset: Vec3,
+    /// speed in voxels per second at which the model catches up with the creature
+    model_speed: f32,
+}
+impl TrackedCreature {
+    fn new(creature: Box<dyn Creature>) -> Self {
+        Self {
+            creature,
+            out_of_range_time: 0.0,
+            pending_delta: 0.0,
+            model_offset: Vec3::ZERO,
+            model_speed: 0.0,
+        }
+    }
+
+    /// catches up on the accumulated delta in small steps, collecting the modified locations
+    fn update(
+        &mut self,
+        world: &mut World,
+        player_info: &PlayerInfo,
+        world_rules: &WorldRules,
+        edited_locations: &mut Vec<Location>,
+    ) {
+        // the creature is updated with its model in place so turning rotates it correctly
+        self.creature.move_model_by(-self.model_offset);
+        let old_position = self.creature.get_position();
+        let caught_up_delta = self.pending_delta;
+
+        let mut remaining_delta = std::mem::take(&mut self.pending_delta);
+        while remaining_delta > 0.0 {
+            let step = remaining_delta.min(MAX_CREATURE_STEP);
+            remaining_delta -= step;
+            self.creature.update(step, world, player_info);
+            if world_rules.mob_griefing
+                && let Some(location) = self.creature.edit_world(step, world)
+            {
+                edited_locations.push(location);
+            }
+        }
+
+        self.model_offset -= self.creature.get_position() - old_position;
+        self.model_speed = if caught_up_delta > 0.0 {
+            self.model_offset.length() / caught_up_delta
+        } else {
+            0.0
+        };
+        self.creature.move_model_by(self.model_offset);
+    }
+
+    /// moves the model towards the creature, reaching it when the next update is expected
+    fn move_model(&mut self, delta: f32) {
+        let distance = self.model_offset.length();
+        if distance == 0.0 {
+            return;
+        }
+
+        let step = (self.model_speed * delta).min(distance);
+        let displacement = -self.model_offset / distance * step;
+        self.model_offset += displacement;
+        self.creature.move_model_by(displacement);
+    }
+}
+
 pub struct CreatureManager {
-    creatures: Vec<Box<dyn Creature>>,
+    creatures: Vec<TrackedCreature>,
     activity_timer: ActivityTimer,
+    /// index of the next distant creature to update
+    update_cursor: usize,
 }
 impl CreatureManager {
     pub fn new() -> Self {
         Self {
             creatures: vec![],
             activity_timer: ActivityTimer::new(0.0, CHECK_UPDATES_TIME),
+            update_cursor: 0,
         }
     }
 
@@ -63,56 +254,194 @@ impl CreatureManager {
             .creatures
             .into_iter()
             .flat_map(|creature_dto| create_creature_from_dto(creature_dto, mesh_manager))
+            .map(TrackedCreature::new)
             .collect();
 
         Self {
             creatures,
             activity_timer: ActivityTimer::new(dto.activity_delta, CHECK_UPDATES_TIME),
+            update_cursor: 0,
         }
     }
 
+    /// returns the locations modified by creatures
     pub fn update(
         &mut self,
         delta: f32,
         mesh_manager: &MeshManager,
         player_info: &PlayerInfo,
         world: &mut World,
-        user_settings: &UserSettings,
-    ) {
-        let creature_spawn_distance =
-            user_settings.get_render_distance() as f32 * AREA_SIZE as f32 + SPAWN_SIZE_EXTRA_RANGE;
-        for creature in &mut self.creatures {
-            creature.update(delta, world, player_info);
-        }
-        self.remove_distant_creatures(
+        params: CreatureUpdateParams,
+    ) -> Vec<Location> {
+        let CreatureUpdateParams {
+            user_settings,
+            world_rules,
+            is_night,
+        } = params;
+        let creature_spawn_distance = user_settings.get_creature_render_distance() as f32
+            * AREA_SIZE as f32
+            + SPAWN_SIZE_EXTRA_RANGE;
+        let edited_locations = self.update_creatures(
+            delta,
+            world,
+            player_info,
+            world_rules,
+            user_settings.get_creature_update_budget(),
+        );
+        self.separate_creatures(delta, world);
+        self.remove_unwanted_creatures(
             player_info.camera_controller.get_position(),
-            creature_spawn_distance,
+            Self::get_despawn_distance(creature_spawn_distance),
+            is_night,
+            delta,
         );
 
-        if self.activity_timer.tick(delta) && self.creatures.len() < MAX_CREATURES {
-            let camera = player_info.camera_controller.create_camera();
-            let camera_look = (camera.target - camera.position).normalize_or_zero();
+        let spawn_rate = user_settings.get_creature_spawn_rate();
+        if self.activity_timer.tick(delta * spawn_rate)
+            && self.creatures.len() < Self::get_max_creatures(spawn_rate)
+        {
             self.add_creature(
                 mesh_manager,
                 world,
-                &camera,
-                camera_look,
+                player_info,
                 creature_spawn_distance,
+                spawn_rate,
+                is_night,
             );
         }
+
+        edited_locations
+    }
+
+    /// updates the creatures near the player every step and staggers the distant ones,
+    /// updating at most `budget` of them per step with their accumulated delta,
+    /// the models of the waiting creatures keep moving so they don't jump on their turn
+    fn update_creatures(
+        &mut self,
+        delta: f32,
+        world: &mut World,
+        player_info: &PlayerInfo,
+        world_rules: &WorldRules,
+        budget: usize,
+    ) -> Vec<Location> {
+        let player_position = player_info.camera_controller.get_position();
+        let creature_count = self.creatures.len();
+        let start_index = self.update_cursor;
+        let mut remaining_budget = budget;
+        let mut edited_locations = vec![];
+        for offset in 0..creature_count {
+            let index = (start_index + offset) % creature_count;
+            let tracked = &mut self.creatures[index];
+            tracked.pending_delta += delta;
+            let is_near =
+                tracked.creature.get_position().distance(player_position) <= ALWAYS_UPDATE_DISTANCE;
+            let has_budget = is_near || remaining_budget > 0;
+            if !is_near && has_budget {
+                remaining_budget -= 1;
+                self.update_cursor = index + 1;
+            }
+            if has_budget {
+                tracked.update(world, player_info, world_rules, &mut edited_locations);
+            }
+            tracked.move_model(delta);
+        }
+
+        edited_locations
+    }
+
+    /// gently pushes apart overlapping creatures, a push into the terrain is undone
+    fn separate_creatures(&mut self, delta: f32, world: &mut World) {
+        let bodies: Vec<_> = self
+            .creatures
+            .iter()
+            .map(|tracked| {
+                (
+                    tracked.creature.get_position(),
+                    tracked.creature.get_size(),
+                    tracked.creature.get_id(),
+                )
+            })
+            .collect();
+        let separations = Self::calculate_separations(&bodies, delta);
+        for (tracked, displacement) in self.creatures.iter_mut().zip(separations) {
+            if displacement == Vec3::ZERO {
+                continue;
+            }
+            tracked.creature.move_by(displacement);
+            if collides(tracked.creature.as_ref(), world).is_some() {
+                tracked.creature.move_by(-displacement);
+            }
+        }
+    }
+
+    /// returns the displacement of each body (position, size, creature) pushing it away from the
+    /// ones it overlaps, the bodies are put in a grid of cells as large as the largest body so
+    /// only the bodies in neighbouring cells are compared
+    fn calculate_separations(bodies: &[(Vec3, Vec3, CreatureId)], delta: f32) -> Vec<Vec3> {
+        let mut separations = vec![Vec3::ZERO; bodies.len()];
+        let extents: Vec<Vec3> = bodies
+            .iter()
+            .map(|(_, size, id)| {
+                let horizontal_extent = size.x.max(size.y) * id.get_separation_spacing();
+                vec3(horizontal_extent, horizontal_extent, size.z)
+            })
+            .collect();
+        let cell_size = extents
+            .iter()
+            .fold(0.0_f32, |max, extent| max.max(extent.max_element()));
+        if cell_size <= 0.0 {
+            return separations;
+        }
+
+        let get_cell = |position: Vec3| (position / cell_size).floor().as_ivec3();
+        let mut grid: HashMap<IVec3, Vec<usize>> = HashMap::new();
+        for (index, (position, _, _)) in bodies.iter().enumerate() {
+            grid.entry(get_cell(*position)).or_default().push(index);
+        }
+
+        for (first_index, (first_position, _, first_id)) in bodies.iter().enumerate() {
+            let cell = get_cell(*first_position);
+            let neighbour_indices = NEIGHBOUR_CELL_OFFSETS
+                .iter()
+                .filter_map(|offset| grid.get(&(cell + *offset)))
+                .flatten()
+                .filter(|second_index| **second_index > first_index);
+            for second_index in neighbour_indices {
+                let (second_position, _, second_id) = &bodies[*second_index];
+                let offset = *first_position - *second_position;
+                let min_distance = (extents[first_index] + extents[*second_index]) * 0.5;
+                if offset.x.abs() >= min_distance.x
+                    || offset.y.abs() >= min_distance.y
+                    || offset.z.abs() >= min_distance.z
+                {
+                    continue;
+                }
+
+                // creatures in the exact same spot are split along the x axis
+                let direction = vec3(offset.x, offset.y, 0.0)
+                    .try_normalize()
+                    .unwrap_or(vec3(1.0, 0.0, 0.0));
+                separations[first_index] += direction * delta * first_id.get_separation_speed();
+                separations[*second_index] -= direction * delta * second_id.get_separation_speed();
+            }
+        }
+
+        separations
     }
 
     pub fn check_can_place_voxel(&self, location: Location) -> bool {
         let voxel_position: Vec3 = location.into();
-        self.creatures.iter().all(|creature| {
-            let creature_position = creature.get_position();
-            let size = creature.get_size();
-            let offset = creature_position - voxel_position;
+        self.creatures
+            .iter()
+            .all(|TrackedCreature { creature, .. }| {
+                let creature_position = creature.get_position();
+                let size = creature.get_size();
+                let offset = creature_position - voxel_position;
 
-            !(-size.x / 2.0..size.x / 2.0).contains(&offset.x)
-                || !(-size.y / 2.0..size.y / 2.0).contains(&offset.y)
-                || !(-size.z / 2.0..size.z / 2.0).contains(&offset.z)
-        })
+                !(-size.x / 2.0..size.x / 2.0).contains(&offset.x)
+                    || !(-size.y / 2.0..size.y / 2.0).contains(&offset.y)
+                    || !(-size.z / 2.0..size.z / 2.0).contains(&offset.z)
+            })
     }
 
     /// draws all visible creatures and returns the number drawn
@@ -121,20 +450,17 @@ impl CreatureManager {
             return 0;
         }
         let camera_look = camera.target - camera.position;
-        let draw_cull_range = (user_settings.get_render_distance() * AREA_SIZE) as f32;
+        let draw_cull_range = (user_settings.get_creature_render_distance() * AREA_SIZE) as f32;
 
         let mut drew = 0;
         let mut mesh_array = vec![vec![]; MeshId::VARIANTS];
-        for creature in &self.creatures {
-            let creature_pos = creature.get_position();
-            let vec_to_creature = creature_pos - camera.position;
-            let distance_to_creature = vec_to_creature.length();
-            if distance_to_creature > draw_cull_range {
-                continue;
-            }
-            if distance_to_creature > MIN_CULL_DISTANCE
-                && vec_to_creature.normalize_or_zero().dot(camera_look) < 0.0
-            {
+        for TrackedCreature { creature, .. } in &self.creatures {
+            if !Self::is_in_draw_range(
+                camera.position,
+                camera_look,
+                creature.get_position(),
+                draw_cull_range,
+            ) {
                 continue;
             }
 
@@ -148,8 +474,25 @@ impl CreatureManager {
         drew
     }
 
+    /// creatures are drawn within the cull range unless they are behind the camera
+    fn is_in_draw_range(
+        camera_position: Vec3,
+        camera_look: Vec3,
+        creature_position: Vec3,
+        draw_cull_range: f32,
+    ) -> bool {
+        let vec_to_creature = creature_position - camera_position;
+        let distance_to_creature = vec_to_creature.length();
+        if distance_to_creature > draw_cull_range {
+            return false;
+        }
+
+        distance_to_creature <= MIN_CULL_DISTANCE
+            || vec_to_creature.normalize_or_zero().dot(camera_look) >= 0.0
+    }
+
     pub fn draw_bounding_boxes(&self, camera: &Camera3D) {
-        for creature in &self.creatures {
+        for TrackedCreature { creature, .. } in &self.creatures {
             let position = creature.get_position() - camera.position;
             let size = creature.get_size();
 
@@ -157,6 +500,32 @@ impl CreatureManager {
         }
     }
 
+    /// returns the position, color and radius of the lights emitted by creatures near the camera,
+    /// closest first
+    pub fn get_lights(&self, camera_position: Vec3) -> Vec<(Vec3, LightColor, f32)> {
+        let mut lights: Vec<_> = self
+            .creatures
+            .iter()
+            .filter_map(|TrackedCreature { creature, .. }| {
+                let position = creature.get_position();
+                if position.distance(camera_position) > MAX_CREATURE_LIGHT_DISTANCE {
+                    return None;
+                }
+
+                creature
+                    .get_light()
+                    .map(|(light_color, radius)| (position, light_color, radius))
+            })
+            .collect();
+        lights.sort_by(|(a, _, _), (b, _, _)| {
+            a.distance_squared(camera_position)
+                .total_cmp(&b.distance_squared(camera_position))
+        });
+        lights.truncate(MAX_CREATURE_LIGHTS);
+
+        lights
+    }
+
     fn draw_mesh_array(mesh_array: Vec<Vec<&Mesh>>) {
         let ordered_meshes = mesh_array.into_iter().flatten();
         for mesh in ordered_meshes {
@@ -168,7 +537,8 @@ impl CreatureManager {
         let creatures = self
             .creatures
             .iter()
-            .flat_map(|creature| creature.create_dto())
+            .take(MAX_SAVED_CREATURES)
+            .flat_map(|tracked| tracked.creature.create_dto())
             .collect();
 
         CreatureManagerDTO {
@@ -215,12 +585,72 @@ impl CreatureManager {
         }
     }
 
-    fn remove_distant_creatures(&mut self, camera_pos: Vec3, creature_spawn_distance: f32) {
+    /// spawns the creature in the empty location bypassing the spawn rules,
+    /// returns false if the location is not empty or has no ground below it
+    pub fn spawn_creature_at(
+        &mut self,
+        id: CreatureId,
+        location: Location,
+        world: &mut World,
+        mesh_manager: &MeshManager,
+    ) -> bool {
+        let ground_location = Location::new(location.x, location.y, location.z + 1);
+        if location.z < 0 || ground_location.z >= AREA_HEIGHT as i32 {
+            return false;
+        }
+        let can_spawn = if id.is_aquatic() {
+            Voxel::WATER.contains(&world.get(location))
+        } else {
+            world.get(location) == Voxel::None && Self::is_ground(world.get(ground_location))
+        };
+        if !can_spawn {
+            return false;
+        }
+
+        let creature_position = vec3(location.x as f32, location.y as f32, location.z as f32);
+        let creature = create_creature(id, creature_position, mesh_manager);
+        self.creatures.push(TrackedCreature::new(creature));
+        info!("Spawned creature '{:?}' at {}", id, creature_position);
+
+        true
+    }
+
+    fn is_ground(voxel: Voxel) -> bool {
+        voxel != Voxel::None && !Voxel::WATER.contains(&voxel)
+    }
+
+    fn is_in_safe_zone(location: Location) -> bool {
+        let dx = (location.x - PlayerInfo::SPAWN_LOCATION.x) as f32;
+        let dy = (location.y - PlayerInfo::SPAWN_LOCATION.y) as f32;
+
+        dx * dx + dy * dy < SPAWN_SAFE_ZONE_RADIUS * SPAWN_SAFE_ZONE_RADIUS
+    }
+
+    fn get_despawn_distance(creature_spawn_distance: f32) -> f32 {
+        creature_spawn_distance * DESPAWN_DISTANCE_MULTIPLIER
+    }
+
+    /// removes creatures that have been outside the despawn distance
+    /// or out of their time of day for longer than the delay
+    fn remove_unwanted_creatures(
+        &mut self,
+        camera_pos: Vec3,
+        despawn_distance: f32,
+        is_night: bool,
+        delta: f32,
+    ) {
         let creature_count = self.creatures.len();
-        self.creatures.retain(|creature| {
-            let creature_pos = creature.get_position();
+        self.creatures.retain_mut(|tracked| {
+            let creature_pos = tracked.creature.get_position();
             let distance_to_creature = camera_pos.distance(creature_pos);
-            distance_to_creature <= creature_spawn_distance
+            let is_time_of_day_suitable = is_night || !tracked.creature.get_id().is_nocturnal();
+            if distance_to_creature <= despawn_distance && is_time_of_day_suitable {
+                tracked.out_of_range_time = 0.0;
+                return true;
+            }
+
+            tracked.out_of_range_time += delta;
+            tracked.out_of_range_time < DESPAWN_DELAY
         });
         let removed_creatures = creature_count as i32 - self.creatures.len() as i32;
         if removed_creatures != 0 {
@@ -228,14 +658,81 @@ impl CreatureManager {
         }
     }
 
+    /// the creature cap scaled by the spawn rate
+    fn get_max_creatures(spawn_rate: f32) -> usize {
+        (MAX_CREATURES as f32 * spawn_rate).round() as usize
+    }
+
+    /// the species cap scaled by the spawn rate
+    fn get_max_species_creatures(id: CreatureId, spawn_rate: f32) -> usize {
+        (id.get_max_count() as f32 * spawn_rate).round() as usize
+    }
+
+    fn count_species(&self, id: CreatureId) -> usize {
+        self.creatures
+            .iter()
+            .filter(|tracked| tracked.creature.get_id() == id)
+            .count()
+    }
+
+    /// tries a few random locations and spawns a creature allowed on the voxel there,
+    /// species that have reached their cap are skipped
     fn add_creature(
         &mut self,
         mesh_manager: &MeshManager,
+        world: &mut World,
+        player_info: &PlayerInfo,
+        render_distance: f32,
+        spawn_rate: f32,
+        is_night: bool,
+    ) {
+        let camera = player_info.camera_controller.create_camera();
+        let camera_look = (camera.target - camera.position).normalize_or_zero();
+        for _ in 0..SPAWN_ATTEMPTS {
+            let Some((creature_id, creature_position)) =
+                Self::find_spawn(world, &camera, camera_look, render_distance)
+            else {
+                continue;
+            };
+
+            if creature_id.is_nocturnal() && !is_night {
+                info!(
+                    "Skipped creature '{:?}', it only spawns at night",
+                    creature_id
+                );
+                continue;
+            }
+
+            let species_count = self.count_species(creature_id);
+            let max_species_count = Self::get_max_species_creatures(creature_id, spawn_rate);
+            if species_count >= max_species_count {
+                info!(
+                    "Skipped creature '{:?}', {} of the maximum {} are already spawned",
+                    creature_id, species_count, max_species_count
+                );
+                continue;
+            }
+
+            let creature = create_creature(creature_id, creature_position, mesh_manager);
+            self.creatures.push(TrackedCreature::new(creature));
+            info!(
+                "Added creature '{:?}' at {}",
+                creature_id, creature_position
+            );
+            return;
+        }
+
+        info!("No creatures added");
+    }
+
+    /// picks a random location and a creature allowed to spawn on the voxel there,
+    /// returns None if the location isn't suitable
+    fn find_spawn(
         world: &mut World,
         camera: &Camera3D,
         camera_look: Vec3,
         render_distance: f32,
-    ) {
+    ) -> Option<(CreatureId, Vec3)> {
         let random_x = gen_range(-render_distance, render_distance);
         let random_y = gen_range(-render_distance, render_distance);
         let location = vector_to_location(vec3(
@@ -248,33 +745,33 @@ impl CreatureManager {
             z: height as i32,
             ..location
         };
+        if Self::is_in_safe_zone(spawn_location) {
+            return None;
+        }
         let camera_to_location = Into::<Vec3>::into(spawn_location) - camera.position;
         if camera_to_location.normalize().dot(camera_look) > SPAWN_CREATURES_MAX_FOV {
-            info!("No creatures added");
-            return;
-        }
-
-        let spawn_voxel = world.get(spawn_location);
-        let option_creature_id = random_creature_id_for_voxel(spawn_voxel);
-        if option_creature_id.is_none() {
-            info!("No creatures added");
-            return;
+            return None;
         }
 
+        // the height skips over water, aquatic creatures spawn in the water above the ground
+        let water_location = Location {
+            z: (spawn_location.z - 1).max(0),
+            ..spawn_location
+        };
+        let water_voxel = world.get(water_location);
+        let spawn_voxel = if Voxel::WATER.contains(&water_voxel) {
+            water_voxel
+        } else {
+            world.get(spawn_location)
+        };
+        let creature_id = random_creature_id_for_voxel(spawn_voxel)?;
         let creature_position = vec3(
             spawn_location.x as f32,
             spawn_location.y as f32,
             (height as f32 - 1.0).max(0.0),
         );
 
-        let creature =
-            create_creature(option_creature_id.unwrap(), creature_position, mesh_manager);
-        self.creatures.push(creature);
-        info!(
-            "Added creature '{:?}' at {}",
-            option_creature_id.unwrap(),
-            creature_position
-        );
+        Some((creature_id, creature_position))
     }
 }
 
@@ -283,3 +780,420 @@ pub struct CreatureManagerDTO {
     creatures: Vec<CreatureDTO>,
     activity_delta: f32,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// number of distant creatures updated per step in the tests
+    const UPDATE_BUDGET: usize = 8;
+
+    struct TestCreature {
+        id: CreatureId,
+        position: Vec3,
+        model_position: Vec3,
+        mesh: Mesh,
+    }
+    impl TestCreature {
+        fn new_boxed(position: Vec3) -> Box<dyn Creature> {
+            Self::new_boxed_with_id(position, CreatureId::Bunny)
+        }
+
+        fn new_boxed_with_id(position: Vec3, id: CreatureId) -> Box<dyn Creature> {
+            Box::new(Self {
+                id,
+                position,
+                model_position: position,
+                mesh: Mesh {
+                    vertices: vec![],
+                    indices: vec![],
+                    texture: None,
+                },
+            })
+        }
+    }
+    impl Creature for TestCreature {
+        fn update(&mut self, delta: f32, _world: &mut World, _player_info: &PlayerInfo) {
+            self.move_by(vec3(0.0, 0.0, delta));
+        }
+
+        fn get_id(&self) -> CreatureId {
+            self.id
+        }
+
+        fn get_mesh_with_index(&self) -> (&Mesh, usize) {
+            (&self.mesh, 0)
+        }
+
+        fn get_position(&self) -> Vec3 {
+            self.position
+        }
+
+        fn move_by(&mut self, displacement: Vec3) {
+            self.position += displacement;
+            self.model_position += displacement;
+        }
+
+        fn move_model_by(&mut self, displacement: Vec3) {
+            self.model_position += displacement;
+        }
+
+        fn get_size(&self) -> Vec3 {
+            Vec3::ONE
+        }
+
+        fn get_allowed_spawn_voxels() -> &'static [crate::model::voxel::Voxel] {
+            &[]
+        }
+
+        fn create_dto(&self) -> Option<CreatureDTO> {
+            None
+        }
+
+        fn from_dto(
+            _creature_dto: CreatureDTO,
+            _mesh_manager: &MeshManager,
+        ) -> Option<Box<dyn Creature>> {
+            None
+        }
+    }
+
+    #[test]
+    fn test_despawn_distance_is_larger_than_spawn_distance() {
+        let spawn_distance = 3.0 * AREA_SIZE as f32 + SPAWN_SIZE_EXTRA_RANGE;
+        let despawn_distance = CreatureManager::get_despawn_distance(spawn_distance);
+        assert!(despawn_distance > spawn_distance);
+
+        let mut creature_manager = CreatureManager::new();
+        let between_radii = (spawn_distance + despawn_distance) / 2.0;
+        creature_manager
+            .creatures
+            .push(TrackedCreature::new(TestCreature::new_boxed(vec3(
+                between_radii,
+                0.0,
+                0.0,
+            ))));
+        creature_manager.remove_unwanted_creatures(
+            Vec3::ZERO,
+            despawn_distance,
+            true,
+            DESPAWN_DELAY,
+        );
+
+        assert_eq!(creature_manager.creature_count(), 1);
+    }
+
+    #[test]
+    fn test_overlapping_creatures_are_separated() {
+        let bodies = [
+            (vec3(0.0, 0.0, 10.0), Vec3::ONE, CreatureId::Bunny),
+            (vec3(0.5, 0.0, 10.0), Vec3::ONE, CreatureId::Bunny),
+            (vec3(0.5, 0.0, 10.0), Vec3::ONE, CreatureId::Bunny),
+            (vec3(5.0, 5.0, 10.0), Vec3::ONE, CreatureId::Bunny),
+            (vec3(0.0, 0.0, 12.0), Vec3::ONE, CreatureId::Bunny),
+        ];
+        let separations = CreatureManager::calculate_separations(&bodies, 1.0);
+
+        assert!(separations[0].x < 0.0);
+        assert_eq!(separations[0].y, 0.0);
+        assert_eq!(separations[0].z, 0.0);
+        assert!(separations[1].x != separations[2].x);
+        assert_eq!(separations[3], Vec3::ZERO);
+        assert_eq!(separations[4], Vec3::ZERO);
+        let total = separations.iter().fold(Vec3::ZERO, |sum, s| sum + *s);
+        assert!(total.length() < 0.0001);
+    }
+
+    #[test]
+    fn test_separation_uses_creature_spacing() {
+        let bodies = [
+            (vec3(0.0, 0.0, 10.0), Vec3::ONE, CreatureId::Fish),
+            (vec3(0.8, 0.0, 10.0), Vec3::ONE, CreatureId::Fish),
+            (vec3(20.0, 0.0, 10.0), Vec3::ONE, CreatureId::Butterfly),
+            (vec3(21.4, 0.0, 10.0), Vec3::ONE, CreatureId::Butterfly),
+        ];
+        let separations = CreatureManager::calculate_separations(&bodies, 1.0);
+
+        assert_eq!(separations[0], Vec3::ZERO);
+        assert_eq!(separations[1], Vec3::ZERO);
+        assert_eq!(
+            separations[2],
+            vec3(-CreatureId::Butterfly.get_separation_speed(), 0.0, 0.0)
+        );
+        assert_eq!(separations[3], -separations[2]);
+    }
+
+    #[test]
+    fn test_max_creatures_scale_with_spawn_rate() {
+        assert_eq!(CreatureManager::get_max_creatures(0.0), 0);
+        assert_eq!(CreatureManager::get_max_creatures(1.0), MAX_CREATURES);
+        assert!(CreatureManager::get_max_creatures(2.0) > MAX_CREATURES);
+    }
+
+    #[test]
+    fn test_species_are_capped() {
+        let mut creature_manager = CreatureManager::new();
+        let max_bunnies = CreatureManager::get_max_species_creatures(CreatureId::Bunny, 1.0);
+        for _ in 0..max_bunnies {
+            creature_manager
+                .creatures
+                .push(TrackedCreature::new(TestCreature::new_boxed(Vec3::ZERO)));
+        }
+
+        assert_eq!(
+            creature_manager.count_species(CreatureId::Bunny),
+            max_bunnies
+        );
+        assert_eq!(creature_manager.count_species(CreatureId::Penguin), 0);
+        for id in CreatureId::ALL {
+            assert!(CreatureManager::get_max_species_creatures(id, 1.0) > 0);
+            assert!(CreatureManager::get_max_species_creatures(id, 1.0) < MAX_CREATURES);
+            assert!(
+                CreatureManager::get_max_species_creatures(id, 2.0)
+                    > CreatureManager::get_max_species_creatures(id, 1.0)
+            );
+        }
+    }
+
+    #[test]
+    fn test_no_spawns_in_safe_zone() {
+        let radius = SPAWN_SAFE_ZONE_RADIUS as i32;
+        assert!(CreatureManager::is_in_safe_zone(PlayerInfo::SPAWN_LOCATION));
+        assert!(CreatureManager::is_in_safe_zone(Location::new(
+            radius - 1,
+            0,
+            40
+        )));
+        assert!(CreatureManager::is_in_safe_zone(Location::new(
+            -radius / 2,
+            radius / 2,
+            0
+        )));
+        assert!(!CreatureManager::is_in_safe_zone(Location::new(
+            radius, 0, 0
+        )));
+        assert!(!CreatureManager::is_in_safe_zone(Location::new(
+            -radius, radius, 0
+        )));
+    }
+
+    #[test]
+    fn test_remove_distant_creatures_after_delay() {
+        let despawn_distance = 10.0;
+        let mut creature_manager = CreatureManager::new();
+        creature_manager
+            .creatures
+            .push(TrackedCreature::new(TestCreature::new_boxed(vec3(
+                20.0, 0.0, 0.0,
+            ))));
+
+        creature_manager.remove_unwanted_creatures(
+            Vec3::ZERO,
+            despawn_distance,
+            true,
+            DESPAWN_DELAY / 2.0,
+        );
+        assert_eq!(creature_manager.creature_count(), 1);
+
+        creature_manager.remove_unwanted_creatures(
+            Vec3::ZERO,
+            despawn_distance,
+            true,
+            DESPAWN_DELAY,
+        );
+        assert_eq!(creature_manager.creature_count(), 0);
+    }
+
+    #[test]
+    fn test_nocturnal_creatures_removed_during_day() {
+        let despawn_distance = 10.0;
+        let mut creature_manager = CreatureManager::new();
+        creature_manager
+            .creatures
+            .push(TrackedCreature::new(TestCreature::new_boxed(Vec3::ZERO)));
+        creature_manager
+            .creatures
+            .push(TrackedCreature::new(TestCreature::new_boxed_with_id(
+                Vec3::ZERO,
+                CreatureId::Firefly,
+            )));
+
+        creature_manager.remove_unwanted_creatures(
+            Vec3::ZERO,
+            despawn_distance,
+            true,
+            DESPAWN_DELAY,
+        );
+        assert_eq!(creature_manager.creature_count(), 2);
+
+        creature_manager.remove_unwanted_creatures(
+            Vec3::ZERO,
+            despawn_distance,
+            false,
+            DESPAWN_DELAY,
+        );
+        assert_eq!(creature_manager.creature_count(), 1);
+        assert_eq!(
+            creature_manager.creatures[0].creature.get_id(),
+            CreatureId::Bunny
+        );
+    }
+
+    #[test]
+    fn test_out_of_range_time_resets_when_back_in_range() {
+        let despawn_distance = 10.0;
+        let mut creature_manager = CreatureManager::new();
+        creature_manager
+            .creatures
+            .push(TrackedCreature::new(TestCreature::new_boxed(vec3(
+                20.0, 0.0, 0.0,
+            ))));
+
+        creature_manager.remove_unwanted_creatures(
+            Vec3::ZERO,
+            despawn_distance,
+            true,
+            DESPAWN_DELAY * 0.9,
+        );
+        creature_manager.remove_unwanted_creatures(Vec3::ZERO, 30.0, true, 0.0);
+        creature_manager.remove_unwanted_creatures(
+            Vec3::ZERO,
+            despawn_distance,
+            true,
+            DESPAWN_DELAY * 0.9,
+        );
+
+        assert_eq!(creature_manager.creature_count(), 1);
+    }
+
+    #[test]
+    fn test_distant_creature_updates_are_staggered() {
+        const DISTANT_CREATURES: usize = 50;
+        const STEPS: usize = DISTANT_CREATURES / UPDATE_BUDGET + 1;
+        const DELTA: f32 = 0.01;
+        let mut world = World::new("test_world_test_distant_creature_updates_are_staggered");
+        let player_info = PlayerInfo::new(Vec3::ZERO);
+        let mut creature_manager = CreatureManager::new();
+        creature_manager
+            .creatures
+            .push(TrackedCreature::new(TestCreature::new_boxed(Vec3::ZERO)));
+        for i in 0..DISTANT_CREATURES {
+            creature_manager
+                .creatures
+                .push(TrackedCreature::new(TestCreature::new_boxed(vec3(
+                    ALWAYS_UPDATE_DISTANCE * 2.0 + i as f32,
+                    0.0,
+                    0.0,
+                ))));
+        }
+
+        creature_manager.update_creatures(
+            DELTA,
+            &mut world,
+            &player_info,
+            &WorldRules::default(),
+            UPDATE_BUDGET,
+        );
+        let updated = creature_manager
+            .creatures
+            .iter()
+            .filter(|tracked| tracked.pending_delta == 0.0)
+            .count();
+        assert_eq!(updated, UPDATE_BUDGET + 1);
+
+        for _ in 1..STEPS {
+            creature_manager.update_creatures(
+                DELTA,
+                &mut world,
+                &player_info,
+                &WorldRules::default(),
+                UPDATE_BUDGET,
+            );
+        }
+        assert!(
+            (creature_manager.creatures[0].creature.get_position().z - DELTA * STEPS as f32).abs()
+                < 0.0001
+        );
+        for tracked in &creature_manager.creatures {
+            let elapsed = tracked.creature.get_position().z + tracked.pending_delta;
+            assert!((elapsed - DELTA * STEPS as f32).abs() < 0.0001);
+            assert!(tracked.creature.get_position().z > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_distant_creature_models_move_between_updates() {
+        const DELTA: f32 = 0.01;
+        let mut world = World::new("test_world_test_distant_creature_models_move");
+        let player_info = PlayerInfo::new(Vec3::ZERO);
+        let mut creature_manager = CreatureManager::new();
+        for i in 0..2 {
+            creature_manager
+                .creatures
+                .push(TrackedCreature::new(TestCreature::new_boxed(vec3(
+                    ALWAYS_UPDATE_DISTANCE * 2.0 + i as f32,
+                    0.0,
+                    0.0,
+                ))));
+        }
+        let update = |creature_manager: &mut CreatureManager, world: &mut World| {
+            creature_manager.update_creatures(
+                DELTA,
+                world,
+                &player_info,
+                &WorldRules::default(),
+                1,
+            );
+        };
+        let get_model_z = |creature_manager: &CreatureManager| {
+            let tracked = &creature_manager.creatures[1];
+            tracked.creature.get_position().z + tracked.model_offset.z
+        };
+
+        update(&mut creature_manager, &mut world);
+        update(&mut creature_manager, &mut world);
+        let position_z = creature_manager.creatures[1].creature.get_position().z;
+        assert!((position_z - DELTA * 2.0).abs() < 0.0001);
+        assert!((get_model_z(&creature_manager) - DELTA).abs() < 0.0001);
+
+        update(&mut creature_manager, &mut world);
+        assert_eq!(
+            creature_manager.creatures[1].creature.get_position().z,
+            position_z
+        );
+        assert!((get_model_z(&creature_manager) - DELTA * 2.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_creatures_beyond_creature_render_distance_not_drawn() {
+        let mut user_settings = UserSettings::default();
+        while user_settings.get_creature_render_distance() != 2 {
+            user_settings.cycle_creature_render_distance();
+        }
+        let draw_cull_range = (user_settings.get_creature_render_distance() * AREA_SIZE) as f32;
+        let voxel_render_range = (user_settings.get_render_distance() * AREA_SIZE) as f32;
+        let look = vec3(1.0, 0.0, 0.0);
+
+        let near = vec3(draw_cull_range - 1.0, 0.0, 0.0);
+        let far = vec3(draw_cull_range + 1.0, 0.0, 0.0);
+        assert!(far.x < voxel_render_range);
+        assert!(CreatureManager::is_in_draw_range(
+            Vec3::ZERO,
+            look,
+            near,
+            draw_cull_range
+        ));
+        assert!(!CreatureManager::is_in_draw_range(
+            Vec3::ZERO,
+            look,
+            far,
+            draw_cull_range
+        ));
+        assert!(!CreatureManager::is_in_draw_range(
+            Vec3::ZERO,
+            look,
+            -near,
+            draw_cull_range
+        ));
+    }
+}
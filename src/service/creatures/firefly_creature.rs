@@ -0,0 +1,220 @@
+use std::f32::consts::TAU;
+
+use bincode::{Decode, Encode};
+use macroquad::{
+    math::{Vec3, vec3},
+    models::Mesh,
+    rand::gen_range,
+};
+
+use crate::{
+    graphics::{
+        mesh_manager::{MeshId, MeshManager},
+        mesh_transformer::move_mesh,
+    },
+    model::{
+        player_info::PlayerInfo,
+        voxel::{LightColor, Voxel},
+        world::World,
+    },
+    service::{
+        activity_timer::ActivityTimer,
+        creatures::{
+            creature::{Creature, collides},
+            creature_manager::{CreatureDTO, CreatureId, CreatureManager},
+        },
+    },
+    utils::{arr_to_vec3, vec3_to_arr, vector_to_location},
+};
+
+const SIZE: Vec3 = vec3(0.2, 0.2, 0.2);
+const SPEED: f32 = 1.2;
+/// fireflies fly towards the player when they are further away than this
+const CLUSTER_DISTANCE: f32 = 6.0;
+/// how strongly fireflies are pulled towards the player compared to wandering
+const PLAYER_ATTRACTION: f32 = 1.5;
+const MIN_HOVER_HEIGHT: f32 = 1.0;
+const MAX_HOVER_HEIGHT: f32 = 3.0;
+const MAX_WANDER_VERTICAL: f32 = 0.4;
+
+const MIN_WANDER_TIME: f32 = 0.5;
+const MAX_WANDER_TIME: f32 = 2.0;
+
+const LIGHT_COLOR: LightColor = LightColor::new(0.55, 0.7, 0.2);
+const LIGHT_RADIUS: f32 = 2.5;
+/// the glow fades between this and full brightness
+const MIN_GLOW: f32 = 0.3;
+const GLOW_SPEED: f32 = 2.5;
+
+fn random_wander_time() -> f32 {
+    gen_range(MIN_WANDER_TIME, MAX_WANDER_TIME)
+}
+
+fn random_wander_direction() -> Vec3 {
+    let angle = gen_range(0.0, TAU);
+    vec3(
+        angle.cos(),
+        angle.sin(),
+        gen_range(-MAX_WANDER_VERTICAL, MAX_WANDER_VERTICAL),
+    )
+    .normalize()
+}
+
+/// a small glowing insect that only comes out at night
+pub struct FireflyCreature {
+    position: Vec3,
+    wander_direction: Vec3,
+    wander_activity: ActivityTimer,
+    /// progress through the glow cycle in radians
+    glow_phase: f32,
+    mesh: Mesh,
+}
+impl FireflyCreature {
+    pub fn new(position: Vec3, mesh_manager: &MeshManager) -> Self {
+        Self {
+            position,
+            wander_direction: random_wander_direction(),
+            wander_activity: ActivityTimer::new(0.0, random_wander_time()),
+            glow_phase: gen_range(0.0, TAU),
+            mesh: mesh_manager.create_at(MeshId::Firefly, position),
+        }
+    }
+
+    /// wanders around, pulled towards the player when far from them
+    fn calculate_direction(&self, world: &mut World, player_position: Vec3) -> Vec3 {
+        let mut direction = self.wander_direction;
+        let to_player = vec3(
+            player_position.x - self.position.x,
+            player_position.y - self.position.y,
+            0.0,
+        );
+        if to_player.length() > CLUSTER_DISTANCE {
+            direction += to_player.normalize() * PLAYER_ATTRACTION;
+        }
+
+        let ground_z = world.get_height(vector_to_location(self.position)) as f32;
+        let height_above_ground = ground_z - self.position.z;
+        if height_above_ground < MIN_HOVER_HEIGHT {
+            direction.z = -direction.z.abs();
+        } else if height_above_ground > MAX_HOVER_HEIGHT {
+            direction.z = direction.z.abs();
+        }
+
+        direction.normalize_or_zero()
+    }
+
+    fn fly(&mut self, delta: f32, world: &mut World, player_position: Vec3) {
+        if self
+            .wander_activity
+            .tick_change_cooldown(delta, random_wander_time)
+        {
+            self.wander_direction = random_wander_direction();
+        }
+
+        let displacement = self.calculate_direction(world, player_position) * SPEED * delta;
+        self.position += displacement;
+        let top = vector_to_location(self.position - vec3(0.0, 0.0, SIZE.z * 0.5));
+        let bottom = vector_to_location(self.position + vec3(0.0, 0.0, SIZE.z * 0.5));
+        if collides(self, world).is_some()
+            || world.get(top).is_solid()
+            || world.get(bottom).is_solid()
+        {
+            self.position -= displacement;
+            self.wander_direction = -self.wander_direction;
+        }
+    }
+}
+impl Creature for FireflyCreature {
+    fn update(&mut self, delta: f32, world: &mut World, player_info: &PlayerInfo) {
+        let start_position = self.position;
+        self.glow_phase = (self.glow_phase + delta * GLOW_SPEED).rem_euclid(TAU);
+        self.fly(delta, world, player_info.camera_controller.get_position());
+
+        move_mesh(&mut self.mesh, self.position - start_position);
+    }
+
+    fn get_id(&self) -> CreatureId {
+        CreatureId::Firefly
+    }
+
+    fn get_mesh_with_index(&self) -> (&Mesh, usize) {
+        (&self.mesh, MeshId::Firefly.index())
+    }
+
+    fn get_position(&self) -> Vec3 {
+        self.position
+    }
+
+    fn move_by(&mut self, displacement: Vec3) {
+        self.position += displacement;
+        move_mesh(&mut self.mesh, displacement);
+    }
+
+    fn move_model_by(&mut self, displacement: Vec3) {
+        move_mesh(&mut self.mesh, displacement);
+    }
+
+    fn get_size(&self) -> Vec3 {
+        SIZE
+    }
+
+    fn get_light(&self) -> Option<(LightColor, f32)> {
+        let glow = MIN_GLOW + (1.0 - MIN_GLOW) * (self.glow_phase.sin() + 1.0) * 0.5;
+
+        Some((
+            LightColor::new(
+                LIGHT_COLOR.r * glow,
+                LIGHT_COLOR.g * glow,
+                LIGHT_COLOR.b * glow,
+            ),
+            LIGHT_RADIUS,
+        ))
+    }
+
+    fn create_dto(&self) -> Option<CreatureDTO> {
+        let dto = FireflyDto {
+            position: vec3_to_arr(self.position),
+            wander_direction: vec3_to_arr(self.wander_direction),
+            wander_activity: self.wander_activity,
+            glow_phase: self.glow_phase,
+        };
+
+        CreatureManager::encode_creature_dto(&dto, CreatureId::Firefly)
+    }
+
+    fn from_dto(creature_dto: CreatureDTO, mesh_manager: &MeshManager) -> Option<Box<dyn Creature>>
+    where
+        Self: Sized,
+    {
+        let dto: FireflyDto =
+            CreatureManager::decode_creature_dto(creature_dto, CreatureId::Firefly)?;
+
+        let position = arr_to_vec3(dto.position);
+        let firefly = Self {
+            position,
+            wander_direction: arr_to_vec3(dto.wander_direction)
+                .try_normalize()
+                .unwrap_or_else(random_wander_direction),
+            wander_activity: dto.wander_activity,
+            glow_phase: dto.glow_phase,
+            mesh: mesh_manager.create_at(MeshId::Firefly, position),
+        };
+
+        Some(Box::new(firefly))
+    }
+
+    fn get_allowed_spawn_voxels() -> &'static [Voxel]
+    where
+        Self: Sized,
+    {
+        &[Voxel::Grass, Voxel::Leaves]
+    }
+}
+
+#[derive(Debug, Encode, Decode)]
+struct FireflyDto {
+    position: [f32; 3],
+    wander_direction: [f32; 3],
+    wander_activity: ActivityTimer,
+    glow_phase: f32,
+}
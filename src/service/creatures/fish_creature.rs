@@ -0,0 +1,316 @@
+use std::f32::consts::{PI, TAU};
+
+use bincode::{Decode, Encode};
+use macroquad::{
+    math::{Vec3, vec3},
+    models::Mesh,
+    rand::{gen_range, rand},
+};
+
+use crate::{
+    graphics::{
+        mesh_manager::{MeshId, MeshManager},
+        mesh_transformer::{move_mesh, rotate_around_z_with_direction},
+    },
+    model::{player_info::PlayerInfo, voxel::Voxel, world::World},
+    service::{
+        activity_timer::ActivityTimer,
+        creatures::{
+            creature::{
+                Creature, collides, collides_with_ground, create_scaled_mesh, random_model_scale,
+            },
+            creature_manager::{CreatureDTO, CreatureId, CreatureManager},
+        },
+        physics::player_physics::{GRAVITY, MAX_FALL_SPEED},
+    },
+    utils::{arr_to_vec3, vec3_to_arr, vector_to_location},
+};
+
+const SIZE: Vec3 = vec3(0.3, 0.3, 0.3);
+const FORWAD_DIRECTION: Vec3 = vec3(0.0, 1.0, 0.0);
+const SWIM_SPEED: f32 = 1.5;
+const BOB_SPEED: f32 = 0.3;
+
+const MIN_BOB_TIME: f32 = 0.5;
+const MAX_BOB_TIME: f32 = 2.0;
+const MIN_TURN_TIME: f32 = 1.0;
+const MAX_TURN_TIME: f32 = 3.0;
+const TURN_SPEED: f32 = 0.8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+enum TurnDirection {
+    Left,
+    Right,
+    Middle,
+}
+
+fn random_bob_time() -> f32 {
+    gen_range(MIN_BOB_TIME, MAX_BOB_TIME)
+}
+
+fn random_turn_time() -> f32 {
+    gen_range(MIN_TURN_TIME, MAX_TURN_TIME)
+}
+
+pub struct FishCreature {
+    position: Vec3,
+    direction: Vec3,
+    angle: f32,
+    /// falling speed when out of water
+    velocity: f32,
+    /// 1.0 when swimming down, -1.0 when swimming up
+    vertical_direction: f32,
+    bob_activity: ActivityTimer,
+    turn_activity: ActivityTimer,
+    turn_direction: TurnDirection,
+    /// size multiplier of the model and collision box
+    scale: f32,
+    mesh: Mesh,
+}
+impl FishCreature {
+    /// creates a new fish creature at position with a random rotation and scale
+    pub fn new(position: Vec3, mesh_manager: &MeshManager) -> Self {
+        let scale = random_model_scale();
+        let mut mesh = create_scaled_mesh(mesh_manager, MeshId::Fish, position, scale);
+        let mut direction = FORWAD_DIRECTION;
+        let angle = gen_range(0.0, TAU);
+        rotate_around_z_with_direction(&mut mesh, &mut direction, position, angle);
+
+        Self {
+            position,
+            direction,
+            angle,
+            velocity: 0.0,
+            vertical_direction: 1.0,
+            bob_activity: ActivityTimer::new(0.0, random_bob_time()),
+            turn_activity: ActivityTimer::new(0.0, random_turn_time()),
+            turn_direction: TurnDirection::Middle,
+            scale,
+            mesh,
+        }
+    }
+
+    /// the top, middle and bottom of the fish are all in water
+    fn is_in_water(&self, world: &mut World) -> bool {
+        let half_z = self.get_size().z * 0.5;
+        [-half_z, 0.0, half_z].into_iter().all(|offset_z| {
+            let location = vector_to_location(self.position + vec3(0.0, 0.0, offset_z));
+            Voxel::WATER.contains(&world.get(location))
+        })
+    }
+
+    /// returns turn angle
+    fn turn(&mut self, delta: f32) -> f32 {
+        if self
+            .turn_activity
+            .tick_change_cooldown(delta, random_turn_time)
+        {
+            self.turn_direction = match self.turn_direction {
+                TurnDirection::Left | TurnDirection::Right => TurnDirection::Middle,
+                TurnDirection::Middle => {
+                    if rand().is_multiple_of(2) {
+                        TurnDirection::Left
+                    } else {
+                        TurnDirection::Right
+                    }
+                }
+            };
+        }
+
+        match self.turn_direction {
+            TurnDirection::Left => TURN_SPEED * delta,
+            TurnDirection::Right => TAU - TURN_SPEED * delta,
+            TurnDirection::Middle => 0.0,
+        }
+    }
+
+    /// wanders horizontally and bobs vertically, turns around when leaving the water,
+    /// returns turn angle
+    fn swim(&mut self, delta: f32, world: &mut World) -> f32 {
+        let mut turn_angle = 0.0;
+        let displacement = self.direction * SWIM_SPEED * delta;
+        self.position += displacement;
+        if collides(self, world).is_some() || !self.is_in_water(world) {
+            self.position -= displacement;
+            turn_angle = PI;
+        }
+
+        if self
+            .bob_activity
+            .tick_change_cooldown(delta, random_bob_time)
+        {
+            self.vertical_direction = -self.vertical_direction;
+        }
+        let vertical_displacement = vec3(0.0, 0.0, self.vertical_direction * BOB_SPEED * delta);
+        self.position += vertical_displacement;
+        if !self.is_in_water(world) {
+            self.position -= vertical_displacement;
+            self.vertical_direction = -self.vertical_direction;
+        }
+
+        turn_angle
+    }
+
+    /// falls down when out of water
+    fn fall(&mut self, delta: f32, world: &mut World) {
+        self.velocity += delta * GRAVITY;
+        self.velocity = self.velocity.min(MAX_FALL_SPEED);
+        self.position.z += self.velocity * delta;
+        let (new_z, is_on_ground) = collides_with_ground(self, world);
+
+        if new_z > self.position.z || is_on_ground {
+            self.velocity = 0.0;
+        }
+        self.position.z = new_z;
+    }
+}
+impl Creature for FishCreature {
+    fn update(&mut self, delta: f32, world: &mut World, _player_info: &PlayerInfo) {
+        let start_position = self.position;
+        let mut turn_angle = self.turn(delta);
+        if self.is_in_water(world) {
+            self.velocity = 0.0;
+            turn_angle += self.swim(delta, world);
+        } else {
+            self.fall(delta, world);
+        }
+
+        let turn_angle = turn_angle.rem_euclid(TAU);
+        self.angle = (self.angle + turn_angle).rem_euclid(TAU);
+
+        move_mesh(&mut self.mesh, self.position - start_position);
+        rotate_around_z_with_direction(
+            &mut self.mesh,
+            &mut self.direction,
+            self.position,
+            turn_angle,
+        );
+    }
+
+    fn get_id(&self) -> CreatureId {
+        CreatureId::Fish
+    }
+
+    fn get_mesh_with_index(&self) -> (&Mesh, usize) {
+        (&self.mesh, MeshId::Fish.index())
+    }
+
+    fn get_position(&self) -> Vec3 {
+        self.position
+    }
+
+    fn move_by(&mut self, displacement: Vec3) {
+        self.position += displacement;
+        move_mesh(&mut self.mesh, displacement);
+    }
+
+    fn move_model_by(&mut self, displacement: Vec3) {
+        move_mesh(&mut self.mesh, displacement);
+    }
+
+    fn get_size(&self) -> Vec3 {
+        SIZE * self.scale
+    }
+
+    fn create_dto(&self) -> Option<CreatureDTO> {
+        let dto = FishDto {
+            position: vec3_to_arr(self.position),
+            angle: self.angle,
+            velocity: self.velocity,
+            vertical_direction: self.vertical_direction,
+            bob_activity: self.bob_activity,
+            turn_activity: self.turn_activity,
+            turn_direction: self.turn_direction,
+            scale: self.scale,
+        };
+
+        CreatureManager::encode_creature_dto(&dto, CreatureId::Fish)
+    }
+
+    fn from_dto(creature_dto: CreatureDTO, mesh_manager: &MeshManager) -> Option<Box<dyn Creature>>
+    where
+        Self: Sized,
+    {
+        let dto: FishDto = CreatureManager::decode_creature_dto(creature_dto, CreatureId::Fish)?;
+
+        let position = arr_to_vec3(dto.position);
+        let mut mesh = create_scaled_mesh(mesh_manager, MeshId::Fish, position, dto.scale);
+        let mut direction = FORWAD_DIRECTION;
+        rotate_around_z_with_direction(&mut mesh, &mut direction, position, dto.angle);
+
+        let fish = Self {
+            position,
+            direction,
+            angle: dto.angle,
+            velocity: dto.velocity,
+            vertical_direction: dto.vertical_direction.signum(),
+            bob_activity: dto.bob_activity,
+            turn_activity: dto.turn_activity,
+            turn_direction: dto.turn_direction,
+            scale: dto.scale,
+            mesh,
+        };
+
+        Some(Box::new(fish))
+    }
+
+    fn get_allowed_spawn_voxels() -> &'static [Voxel]
+    where
+        Self: Sized,
+    {
+        &Voxel::WATER
+    }
+}
+
+#[derive(Debug, Encode, Decode)]
+struct FishDto {
+    position: [f32; 3],
+    angle: f32,
+    velocity: f32,
+    vertical_direction: f32,
+    bob_activity: ActivityTimer,
+    turn_activity: ActivityTimer,
+    turn_direction: TurnDirection,
+    scale: f32,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::{area::Area, location::Location};
+
+    use super::*;
+
+    #[test]
+    fn test_fish_stays_in_water() {
+        let water_location = Location::new(3, 3, 50);
+        let mut world = World::new("test_world_test_fish_stays_in_water");
+        let mut area = Area::new(water_location.into());
+        area.set(
+            World::convert_global_to_local_location(water_location.into()),
+            Voxel::WaterSource,
+        );
+        world.return_area(area);
+
+        let mut fish = FishCreature {
+            position: water_location.into(),
+            direction: FORWAD_DIRECTION,
+            angle: 0.0,
+            velocity: 0.0,
+            vertical_direction: 1.0,
+            bob_activity: ActivityTimer::new(0.0, MAX_BOB_TIME),
+            turn_activity: ActivityTimer::new(0.0, MAX_TURN_TIME),
+            turn_direction: TurnDirection::Middle,
+            scale: 1.0,
+            mesh: Mesh {
+                vertices: vec![],
+                indices: vec![],
+                texture: None,
+            },
+        };
+        let player_info = PlayerInfo::new(Vec3::ZERO);
+        for _ in 0..100 {
+            fish.update(0.05, &mut world, &player_info);
+            assert_eq!(vector_to_location(fish.position), water_location);
+        }
+    }
+}
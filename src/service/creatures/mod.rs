@@ -3,4 +3,6 @@ pub mod butterfly_creature;
 pub mod creature;
 pub mod creature_factory;
 pub mod creature_manager;
+pub mod firefly_creature;
+pub mod fish_creature;
 pub mod penguin_creature;
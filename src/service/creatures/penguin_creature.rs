@@ -17,8 +17,8 @@ use crate::{
         activity_timer::ActivityTimer,
         creatures::{
             creature::{
-                Creature, collides, collides_with_ground, collides_with_player,
-                perform_static_collisions, push_away_from,
+                Creature, collides, collides_with_ground, collides_with_player, create_scaled_mesh,
+                perform_static_collisions, push_away_from, random_model_scale,
             },
             creature_manager::{CreatureDTO, CreatureId, CreatureManager},
         },
@@ -74,15 +74,19 @@ pub struct PenguinCreature {
     activity: Activity,
     turn_activity_timer: ActivityTimer,
     turn_direction: TurnDirection,
+    /// size multiplier of the model and collision box
+    scale: f32,
     mesh: Mesh,
 }
 impl PenguinCreature {
-    /// creates a new penguin creature at position with a random rotation
+    /// creates a new penguin creature at position with a random rotation and scale
     pub fn new(position: Vec3, mesh_manager: &MeshManager) -> Self {
-        let mesh = mesh_manager.create_at(MeshId::Penguin, position);
+        let scale = random_model_scale();
+        let mesh = create_scaled_mesh(mesh_manager, MeshId::Penguin, position, scale);
         let random_rotation = gen_range(0.0, TAU);
         let mut penguin = Self {
             position,
+            scale,
             velocity: 0.0,
             mesh,
             activity_timer: ActivityTimer::new(0.0, IDLE_ACTIVITY_TIME),
@@ -249,6 +253,10 @@ impl Creature for PenguinCreature {
         );
     }
 
+    fn get_id(&self) -> CreatureId {
+        CreatureId::Penguin
+    }
+
     fn get_mesh_with_index(&self) -> (&Mesh, usize) {
         (&self.mesh, MeshId::Penguin.index())
     }
@@ -257,8 +265,17 @@ impl Creature for PenguinCreature {
         self.position
     }
 
+    fn move_by(&mut self, displacement: Vec3) {
+        self.position += displacement;
+        move_mesh(&mut self.mesh, displacement);
+    }
+
+    fn move_model_by(&mut self, displacement: Vec3) {
+        move_mesh(&mut self.mesh, displacement);
+    }
+
     fn get_size(&self) -> Vec3 {
-        SIZE
+        SIZE * self.scale
     }
 
     fn create_dto(&self) -> Option<CreatureDTO> {
@@ -272,6 +289,7 @@ impl Creature for PenguinCreature {
             turn_direction: self.turn_direction,
             animation_rotation: self.animation_rotation,
             animation_turn_direction: self.animation_turn_direction,
+            scale: self.scale,
         };
 
         CreatureManager::encode_creature_dto(&dto, CreatureId::Penguin)
@@ -285,7 +303,7 @@ impl Creature for PenguinCreature {
             CreatureManager::decode_creature_dto(creature_dto, CreatureId::Penguin)?;
 
         let position = arr_to_vec3(dto.position);
-        let mut mesh = mesh_manager.create_at(MeshId::Penguin, position);
+        let mut mesh = create_scaled_mesh(mesh_manager, MeshId::Penguin, position, dto.scale);
         let mut direction = FORWAD_DIRECTION;
         let total_rotation =
             (dto.rotation + dto.animation_rotation + ANIMATION_TURN_OFFSET).rem_euclid(TAU);
@@ -303,6 +321,7 @@ impl Creature for PenguinCreature {
             mesh,
             animation_rotation: dto.animation_rotation,
             animation_turn_direction: dto.animation_turn_direction,
+            scale: dto.scale,
         };
 
         Some(Box::new(penguin))
@@ -327,4 +346,5 @@ struct PenguinDto {
     turn_direction: TurnDirection,
     animation_rotation: f32,
     animation_turn_direction: AnimationTurnDirection,
+    scale: f32,
 }
@@ -0,0 +1,419 @@
+use bincode::{Decode, Encode};
+use macroquad::{
+    camera::Camera3D,
+    math::{Vec3, vec3},
+    models::{Mesh, draw_mesh},
+};
+
+use crate::{
+    graphics::mesh_generator::MeshGenerator,
+    model::{
+        area::AREA_HEIGHT,
+        inventory::{Item, MAX_ITEMS_PER_SLOT},
+        player_info::PlayerInfo,
+        world::World,
+    },
+    service::activity_timer::ActivityTimer,
+    utils::{arr_to_vec3, vec3_to_arr, vector_to_location},
+};
+
+/// time in seconds before an uncollected item disappears
+const DROPPED_ITEM_LIFETIME: f32 = 300.0;
+/// time in seconds before a dropped item can be picked up
+const PICKUP_DELAY: f32 = 1.5;
+const PICKUP_DISTANCE: f32 = 1.5;
+/// speed in voxels per second of items pulled towards the player
+const MAGNET_SPEED: f32 = 6.0;
+/// distance between the checked points when looking for voxels between an item and the player
+const LINE_OF_SIGHT_STEP: f32 = 0.25;
+const MERGE_DISTANCE: f32 = 1.0;
+const MERGE_CHECK_TIME: f32 = 0.5;
+const GRAVITY: f32 = 15.0;
+const MAX_FALL_SPEED: f32 = 10.0;
+/// items further away are not simulated to avoid loading distant areas
+const SIMULATION_DISTANCE: f32 = 48.0;
+const HALF_ITEM_SIZE: f32 = MeshGenerator::DROPPED_ITEM_SIZE * 0.5;
+
+struct DroppedItem {
+    item: Item,
+    position: Vec3,
+    velocity: f32,
+    lifetime: ActivityTimer,
+    mesh: Mesh,
+}
+impl DroppedItem {
+    fn from_dto(dto: DroppedItemDTO, mesh_generator: &MeshGenerator) -> Self {
+        let position = arr_to_vec3(dto.position);
+
+        Self {
+            item: dto.item,
+            position,
+            velocity: dto.velocity,
+            lifetime: dto.lifetime,
+            mesh: mesh_generator.generate_mesh_for_dropped_item(dto.item.voxel, position),
+        }
+    }
+
+    fn create_dto(&self) -> DroppedItemDTO {
+        DroppedItemDTO {
+            item: self.item,
+            position: vec3_to_arr(self.position),
+            velocity: self.velocity,
+            lifetime: self.lifetime,
+        }
+    }
+
+    /// restarts the time before the item disappears without delaying its pickup
+    fn refresh_lifetime(&mut self) {
+        let elapsed = self.lifetime.get_delta().min(PICKUP_DELAY);
+        self.lifetime = ActivityTimer::new(elapsed, DROPPED_ITEM_LIFETIME);
+    }
+
+    fn move_by(&mut self, offset: Vec3) {
+        self.position += offset;
+        for vertex in &mut self.mesh.vertices {
+            vertex.position += offset;
+        }
+    }
+
+    /// makes the item fall until it rests on a solid voxel,
+    /// returns false if it fell out of the world
+    fn fall(&mut self, world: &mut World, delta: f32) -> bool {
+        self.velocity = (self.velocity + GRAVITY * delta).min(MAX_FALL_SPEED);
+        self.move_by(vec3(0.0, 0.0, self.velocity * delta));
+        if self.position.z + HALF_ITEM_SIZE >= AREA_HEIGHT as f32 - 0.5 {
+            return false;
+        }
+
+        let below = vector_to_location(self.position + vec3(0.0, 0.0, HALF_ITEM_SIZE));
+        if world.get(below).is_solid() {
+            let resting_z = below.z as f32 - 0.5 - HALF_ITEM_SIZE;
+            self.move_by(vec3(0.0, 0.0, resting_z - self.position.z));
+            self.velocity = 0.0;
+        }
+
+        true
+    }
+
+    /// moves the item straight towards the target without passing it
+    fn move_towards(&mut self, target: Vec3, delta: f32) {
+        let offset = target - self.position;
+        let step = MAGNET_SPEED * delta;
+        self.velocity = 0.0;
+        if offset.length() <= step {
+            self.move_by(offset);
+        } else {
+            self.move_by(offset.normalize() * step);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Encode, Decode)]
+pub struct DroppedItemDTO {
+    item: Item,
+    position: [f32; 3],
+    velocity: f32,
+    lifetime: ActivityTimer,
+}
+
+/// voxel items lying in the world that can be picked up by the player
+pub struct DroppedItems {
+    items: Vec<DroppedItem>,
+    merge_timer: ActivityTimer,
+}
+impl DroppedItems {
+    pub fn new(dtos: Vec<DroppedItemDTO>, mesh_generator: &MeshGenerator) -> Self {
+        Self {
+            items: dtos
+                .into_iter()
+                .map(|dto| DroppedItem::from_dto(dto, mesh_generator))
+                .collect(),
+            merge_timer: ActivityTimer::new(0.0, MERGE_CHECK_TIME),
+        }
+    }
+
+    pub fn add(&mut self, item: Item, position: Vec3, mesh_generator: &MeshGenerator) {
+        self.items.push(DroppedItem {
+            item,
+            position,
+            velocity: 0.0,
+            lifetime: ActivityTimer::new(0.0, DROPPED_ITEM_LIFETIME),
+            mesh: mesh_generator.generate_mesh_for_dropped_item(item.voxel, position),
+        });
+    }
+
+    /// applies gravity, pulls items within the magnet radius towards the player,
+    /// despawns expired items, merges nearby stacks and lets the player collect items
+    pub fn update(
+        &mut self,
+        world: &mut World,
+        player_info: &mut PlayerInfo,
+        magnet_radius: f32,
+        delta: f32,
+    ) {
+        let player_position = player_info.camera_controller.get_position();
+        let player_center = Self::get_player_center(player_info);
+        self.items.retain_mut(|dropped_item| {
+            if dropped_item.position.distance(player_position) > SIMULATION_DISTANCE {
+                return true;
+            }
+            let is_attracted = dropped_item.lifetime.get_delta() >= PICKUP_DELAY
+                && dropped_item.position.distance(player_center) <= magnet_radius
+                && player_info.inventory.can_add_item(dropped_item.item)
+                && has_line_of_sight(world, dropped_item.position, player_center);
+            if is_attracted {
+                dropped_item.move_towards(player_center, delta);
+                true
+            } else {
+                dropped_item.fall(world, delta)
+            }
+        });
+        self.remove_expired(delta);
+        if self.merge_timer.tick(delta) {
+            self.merge_nearby();
+        }
+        self.pick_up(player_info);
+    }
+
+    fn get_player_center(player_info: &PlayerInfo) -> Vec3 {
+        player_info.camera_controller.get_position() + vec3(0.0, 0.0, 1.0)
+    }
+
+    fn remove_expired(&mut self, delta: f32) {
+        self.items
+            .retain_mut(|dropped_item| !dropped_item.lifetime.tick(delta));
+    }
+
+    /// merges stackable items that are close to each other without exceeding the slot limit
+    fn merge_nearby(&mut self) {
+        for i in 0..self.items.len() {
+            for j in (i + 1)..self.items.len() {
+                let (first, rest) = self.items.split_at_mut(j);
+                let target = &mut first[i];
+                let source = &mut rest[0];
+                let can_merge = target.item.can_stack_with(&source.item)
+                    && source.item.count > 0
+                    && target.position.distance(source.position) <= MERGE_DISTANCE;
+                if !can_merge {
+                    continue;
+                }
+
+                let to_add = (MAX_ITEMS_PER_SLOT - target.item.count).min(source.item.count);
+                target.item.count += to_add;
+                source.item.count -= to_add;
+                if source.item.count == 0 {
+                    target.refresh_lifetime();
+                }
+            }
+        }
+
+        self.items
+            .retain(|dropped_item| dropped_item.item.count > 0);
+    }
+
+    /// collects items by distance alone so buried items can still be picked up
+    fn pick_up(&mut self, player_info: &mut PlayerInfo) {
+        let player_center = Self::get_player_center(player_info);
+        self.items.retain(|dropped_item| {
+            let can_pick_up = dropped_item.lifetime.get_delta() >= PICKUP_DELAY
+                && dropped_item.position.distance(player_center) <= PICKUP_DISTANCE
+                && player_info.inventory.can_add_item(dropped_item.item);
+            if can_pick_up {
+                player_info.inventory.add_item(dropped_item.item);
+            }
+
+            !can_pick_up
+        });
+    }
+
+    pub fn draw(&self, camera: &Camera3D) {
+        for dropped_item in &self.items {
+            let is_behind_camera = (dropped_item.position - camera.position)
+                .dot(camera.target - camera.position)
+                < 0.0;
+            if !is_behind_camera {
+                draw_mesh(&dropped_item.mesh);
+            }
+        }
+    }
+
+    pub fn create_dtos(&self) -> Vec<DroppedItemDTO> {
+        self.items.iter().map(DroppedItem::create_dto).collect()
+    }
+}
+
+/// checks that no solid voxel lies between the points, the voxels at both ends are skipped
+/// so buried items can be pulled out, unloaded areas count as blocking
+fn has_line_of_sight(world: &World, from: Vec3, to: Vec3) -> bool {
+    let distance = from.distance(to);
+    let steps = (distance / LINE_OF_SIGHT_STEP).ceil() as usize;
+    let from_location = vector_to_location(from);
+    let to_location = vector_to_location(to);
+    (0..=steps).all(|step| {
+        let point = from.lerp(to, step as f32 / steps.max(1) as f32);
+        let location = vector_to_location(point);
+        location == from_location
+            || location == to_location
+            || world
+                .get_without_loading(location)
+                .is_some_and(|voxel| !voxel.is_solid())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::{location::Location, voxel::Voxel};
+
+    use super::*;
+
+    fn create_dropped_item(item: Item, position: Vec3, lifetime: f32) -> DroppedItem {
+        DroppedItem {
+            item,
+            position,
+            velocity: 0.0,
+            lifetime: ActivityTimer::new(lifetime, DROPPED_ITEM_LIFETIME),
+            mesh: Mesh {
+                vertices: vec![],
+                indices: vec![],
+                texture: None,
+            },
+        }
+    }
+
+    fn create_dropped_items(items: Vec<DroppedItem>) -> DroppedItems {
+        DroppedItems {
+            items,
+            merge_timer: ActivityTimer::new(0.0, MERGE_CHECK_TIME),
+        }
+    }
+
+    #[test]
+    fn test_merge_nearby_same_voxel() {
+        let mut dropped_items = create_dropped_items(vec![
+            create_dropped_item(Item::new(Voxel::Stone, 3), vec3(0.0, 0.0, 10.0), 0.0),
+            create_dropped_item(Item::new(Voxel::Stone, 4), vec3(0.5, 0.0, 10.0), 0.0),
+            create_dropped_item(Item::new(Voxel::Sand, 1), vec3(0.0, 0.5, 10.0), 0.0),
+            create_dropped_item(Item::new(Voxel::Stone, 1), vec3(5.0, 0.0, 10.0), 0.0),
+        ]);
+
+        dropped_items.merge_nearby();
+
+        let counts: Vec<_> = dropped_items
+            .items
+            .iter()
+            .map(|dropped_item| (dropped_item.item.voxel, dropped_item.item.count))
+            .collect();
+        assert_eq!(
+            counts,
+            vec![(Voxel::Stone, 7), (Voxel::Sand, 1), (Voxel::Stone, 1)]
+        );
+    }
+
+    #[test]
+    fn test_merge_skips_items_with_charges() {
+        let charged_item = Item {
+            charges: Some(10),
+            ..Item::new(Voxel::Bucket, 1)
+        };
+        let mut dropped_items = create_dropped_items(vec![
+            create_dropped_item(charged_item, vec3(0.0, 0.0, 10.0), 0.0),
+            create_dropped_item(charged_item, vec3(0.2, 0.0, 10.0), 0.0),
+        ]);
+
+        dropped_items.merge_nearby();
+
+        assert_eq!(dropped_items.items.len(), 2);
+        assert_eq!(dropped_items.items[0].item, charged_item);
+        assert_eq!(dropped_items.items[1].item, charged_item);
+    }
+
+    #[test]
+    fn test_merge_respects_slot_limit() {
+        let mut dropped_items = create_dropped_items(vec![
+            create_dropped_item(
+                Item::new(Voxel::Stone, MAX_ITEMS_PER_SLOT - 5),
+                vec3(0.0, 0.0, 10.0),
+                0.0,
+            ),
+            create_dropped_item(Item::new(Voxel::Stone, 10), vec3(0.2, 0.0, 10.0), 0.0),
+        ]);
+
+        dropped_items.merge_nearby();
+
+        assert_eq!(dropped_items.items.len(), 2);
+        assert_eq!(dropped_items.items[0].item.count, MAX_ITEMS_PER_SLOT);
+        assert_eq!(dropped_items.items[1].item.count, 5);
+    }
+
+    #[test]
+    fn test_merge_keeps_pickup_ready() {
+        let mut dropped_items = create_dropped_items(vec![
+            create_dropped_item(
+                Item::new(Voxel::Stone, 3),
+                vec3(0.0, 0.0, 10.0),
+                DROPPED_ITEM_LIFETIME - 1.0,
+            ),
+            create_dropped_item(Item::new(Voxel::Stone, 4), vec3(0.5, 0.0, 10.0), 0.0),
+        ]);
+
+        dropped_items.merge_nearby();
+
+        assert_eq!(dropped_items.items.len(), 1);
+        assert_eq!(dropped_items.items[0].lifetime.get_delta(), PICKUP_DELAY);
+        dropped_items.remove_expired(2.0);
+        assert_eq!(dropped_items.items.len(), 1);
+    }
+
+    #[test]
+    fn test_remove_expired() {
+        let mut dropped_items = create_dropped_items(vec![
+            create_dropped_item(
+                Item::new(Voxel::Stone, 1),
+                vec3(0.0, 0.0, 10.0),
+                DROPPED_ITEM_LIFETIME - 1.0,
+            ),
+            create_dropped_item(Item::new(Voxel::Sand, 1), vec3(0.0, 0.0, 10.0), 0.0),
+        ]);
+
+        dropped_items.remove_expired(2.0);
+
+        assert_eq!(dropped_items.items.len(), 1);
+        assert_eq!(dropped_items.items[0].item.voxel, Voxel::Sand);
+    }
+
+    #[test]
+    fn test_line_of_sight_blocked_by_solid_voxels() {
+        let mut world = World::new("test_world_test_line_of_sight_blocked_by_solid_voxels");
+        for x in 0..=4 {
+            world.set(Location::new(x, 0, 5), Voxel::None);
+        }
+        let from = vec3(0.0, 0.0, 5.0);
+        let to = vec3(4.0, 0.0, 5.0);
+        assert!(has_line_of_sight(&world, from, to));
+
+        world.set(Location::new(2, 0, 5), Voxel::Stone);
+        assert!(!has_line_of_sight(&world, from, to));
+
+        world.set(Location::new(2, 0, 5), Voxel::None);
+        world.set(Location::new(0, 0, 5), Voxel::Stone);
+        assert!(has_line_of_sight(&world, from, to));
+    }
+
+    #[test]
+    fn test_pick_up_buried_item() {
+        let mut world = World::new("test_world_test_pick_up_buried_item");
+        let mut player_info = PlayerInfo::new(Vec3::ZERO);
+        let player_center = DroppedItems::get_player_center(&player_info);
+        let position = player_center + vec3(1.0, 0.0, 0.0);
+        world.set(vector_to_location(position), Voxel::Sand);
+        let mut dropped_items = create_dropped_items(vec![create_dropped_item(
+            Item::new(Voxel::Stone, 1),
+            position,
+            PICKUP_DELAY,
+        )]);
+
+        dropped_items.pick_up(&mut player_info);
+
+        assert!(dropped_items.items.is_empty());
+    }
+}
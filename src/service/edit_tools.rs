@@ -0,0 +1,396 @@
+use macroquad::{
+    camera::Camera3D, color::YELLOW, math::vec3, models::draw_cube_wires, prelude::info,
+};
+
+use crate::model::{area::AREA_HEIGHT, location::Location, voxel::Voxel, world::World};
+
+/// limits the number of voxels that can be copied at once
+const MAX_SELECTION_VOLUME: usize = 32 * 32 * 32;
+
+/// a single voxel change to be applied to the world
+pub type VoxelEdit = (Location, Voxel);
+
+/// voxels captured from the world, stored relative to the minimum corner of the selection
+#[derive(Debug, Clone)]
+struct Clipboard {
+    size_x: i32,
+    size_y: i32,
+    voxels: Vec<(Location, Voxel)>,
+}
+
+/// in-memory copy and paste of a box selection
+#[derive(Debug, Clone)]
+pub struct EditTools {
+    first_corner: Option<Location>,
+    second_corner: Option<Location>,
+    clipboard: Option<Clipboard>,
+    /// number of 90 degree clockwise rotations applied when pasting
+    rotation: u8,
+}
+impl EditTools {
+    pub fn new() -> Self {
+        Self {
+            first_corner: None,
+            second_corner: None,
+            clipboard: None,
+            rotation: 0,
+        }
+    }
+
+    /// sets the next selection corner, alternating between the first and second corner
+    pub fn select_corner(&mut self, location: Location) {
+        if self.first_corner.is_none() || self.second_corner.is_some() {
+            self.first_corner = Some(location);
+            self.second_corner = None;
+        } else {
+            self.second_corner = Some(location);
+        }
+    }
+
+    /// returns the minimum and maximum corners of the selected box
+    fn get_selection_bounds(&self) -> Option<(Location, Location)> {
+        let first = self.first_corner?;
+        let second = self.second_corner.unwrap_or(first);
+
+        let min = Location::new(
+            first.x.min(second.x),
+            first.y.min(second.y),
+            first.z.min(second.z),
+        );
+        let max = Location::new(
+            first.x.max(second.x),
+            first.y.max(second.y),
+            first.z.max(second.z),
+        );
+
+        Some((min, max))
+    }
+
+    /// returns the volume of the selection if it isn't too large to edit
+    fn get_selection_volume(min: Location, max: Location) -> Option<usize> {
+        let volume = (max.x - min.x + 1) as usize
+            * (max.y - min.y + 1) as usize
+            * (max.z - min.z + 1) as usize;
+        if volume > MAX_SELECTION_VOLUME {
+            info!("Selection of {} voxels is too large", volume);
+            return None;
+        }
+
+        Some(volume)
+    }
+
+    /// captures the selected voxels into the clipboard, returns true if successful
+    pub fn copy(&mut self, world: &mut World) -> bool {
+        let Some((min, max)) = self.get_selection_bounds() else {
+            return false;
+        };
+        let Some(volume) = Self::get_selection_volume(min, max) else {
+            return false;
+        };
+        let size_x = max.x - min.x + 1;
+        let size_y = max.y - min.y + 1;
+
+        let mut voxels = Vec::with_capacity(volume);
+        for x in min.x..=max.x {
+            for y in min.y..=max.y {
+                for z in min.z..=max.z {
+                    let voxel = world.get(Location::new(x, y, z));
+                    if let Some(voxel) = Self::to_clipboard_voxel(voxel) {
+                        voxels.push((Location::new(x - min.x, y - min.y, z - min.z), voxel));
+                    }
+                }
+            }
+        }
+
+        info!("Copied {} voxels", voxels.len());
+        self.clipboard = Some(Clipboard {
+            size_x,
+            size_y,
+            voxels,
+        });
+        self.rotation = 0;
+
+        true
+    }
+
+    /// the voxel stored when copying, water and fire are left out as they are driven by the
+    /// simulators and can't be taken from the inventory, lit bombs are copied unlit
+    fn to_clipboard_voxel(voxel: Voxel) -> Option<Voxel> {
+        match voxel {
+            Voxel::ActiveBomb => Some(Voxel::Bomb),
+            voxel if !voxel.is_solid() || !voxel.is_placeable() => None,
+            voxel => Some(voxel),
+        }
+    }
+
+    /// creates the edits for replacing the source voxels in the selection with the target,
+    /// creates at most max_count edits, voxels outside the world height are skipped
+    pub fn create_replace_edits(
+        &self,
+        world: &mut World,
+        source: Voxel,
+        target: Voxel,
+        max_count: u32,
+    ) -> Vec<VoxelEdit> {
+        let Some((min, max)) = self.get_selection_bounds() else {
+            return vec![];
+        };
+        if source == target
+            || !target.is_placeable()
+            || Self::get_selection_volume(min, max).is_none()
+        {
+            return vec![];
+        }
+        let min_z = min.z.max(0);
+        let max_z = max.z.min(AREA_HEIGHT as i32 - 2);
+
+        let mut edits = vec![];
+        for x in min.x..=max.x {
+            for y in min.y..=max.y {
+                for z in min_z..=max_z {
+                    if edits.len() >= max_count as usize {
+                        return edits;
+                    }
+                    let location = Location::new(x, y, z);
+                    if world.get(location) == source {
+                        edits.push((location, target));
+                    }
+                }
+            }
+        }
+
+        edits
+    }
+
+    /// rotates the clipboard by 90 degrees clockwise around the vertical axis
+    pub fn rotate(&mut self) {
+        self.rotation = (self.rotation + 1) % 4;
+    }
+
+    /// creates the edits for pasting the clipboard with its minimum corner at the anchor,
+    /// voxels outside the world height are skipped
+    pub fn paste(&self, anchor: Location) -> Vec<VoxelEdit> {
+        let Some(clipboard) = &self.clipboard else {
+            return vec![];
+        };
+
+        clipboard
+            .voxels
+            .iter()
+            .map(|(offset, voxel)| {
+                let rotated = Self::rotate_offset(*offset, clipboard, self.rotation);
+                let location = Location::new(
+                    anchor.x + rotated.x,
+                    anchor.y + rotated.y,
+                    anchor.z + rotated.z,
+                );
+                (location, *voxel)
+            })
+            .filter(|(location, _voxel)| location.z >= 0 && location.z < AREA_HEIGHT as i32 - 1)
+            .collect()
+    }
+
+    /// transforms a relative location so that the rotated selection keeps its minimum corner at 0
+    fn rotate_offset(offset: Location, clipboard: &Clipboard, rotation: u8) -> Location {
+        let (x, y) = match rotation {
+            0 => (offset.x, offset.y),
+            1 => (clipboard.size_y - 1 - offset.y, offset.x),
+            2 => (
+                clipboard.size_x - 1 - offset.x,
+                clipboard.size_y - 1 - offset.y,
+            ),
+            _ => (offset.y, clipboard.size_x - 1 - offset.x),
+        };
+
+        Location::new(x, y, offset.z)
+    }
+
+    pub fn has_clipboard(&self) -> bool {
+        self.clipboard.is_some()
+    }
+
+    /// draws the outline of the current selection
+    pub fn draw_selection(&self, camera: &Camera3D) {
+        let Some((min, max)) = self.get_selection_bounds() else {
+            return;
+        };
+        let size = vec3(
+            (max.x - min.x + 1) as f32,
+            (max.y - min.y + 1) as f32,
+            (max.z - min.z + 1) as f32,
+        );
+        let center = vec3(
+            (min.x + max.x) as f32 / 2.0,
+            (min.y + max.y) as f32 / 2.0,
+            (min.z + max.z) as f32 / 2.0,
+        );
+
+        draw_cube_wires(center - camera.position, size, YELLOW);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::area::Area;
+
+    use super::*;
+
+    fn create_world_with_voxels(world_name: &str, voxels: &[VoxelEdit]) -> World {
+        let mut world = World::new(world_name);
+        let mut area = Area::new(voxels[0].0.into());
+        for (location, voxel) in voxels {
+            area.set(
+                World::convert_global_to_local_location((*location).into()),
+                *voxel,
+            );
+        }
+        world.return_area(area);
+
+        world
+    }
+
+    #[test]
+    fn test_copy_and_paste() {
+        let voxels = [
+            (Location::new(1, 1, 10), Voxel::Stone),
+            (Location::new(2, 1, 10), Voxel::Brick),
+            (Location::new(2, 3, 11), Voxel::Wood),
+        ];
+        let mut world = create_world_with_voxels("test_world_test_copy_and_paste", &voxels);
+        let mut edit_tools = EditTools::new();
+        assert!(!edit_tools.copy(&mut world));
+
+        edit_tools.select_corner(Location::new(1, 1, 10));
+        edit_tools.select_corner(Location::new(2, 3, 11));
+        assert!(edit_tools.copy(&mut world));
+
+        let mut edits = edit_tools.paste(Location::new(5, 5, 20));
+        edits.sort_by_key(|(location, _voxel)| (location.x, location.y, location.z));
+        assert_eq!(
+            edits,
+            vec![
+                (Location::new(5, 5, 20), Voxel::Stone),
+                (Location::new(6, 5, 20), Voxel::Brick),
+                (Location::new(6, 7, 21), Voxel::Wood),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_copy_skips_simulated_voxels() {
+        let voxels = [
+            (Location::new(0, 0, 10), Voxel::WaterSource),
+            (Location::new(1, 0, 10), Voxel::Fire),
+            (Location::new(2, 0, 10), Voxel::ActiveBomb),
+            (Location::new(3, 0, 10), Voxel::Furnace),
+        ];
+        let mut world =
+            create_world_with_voxels("test_world_test_copy_skips_simulated_voxels", &voxels);
+        let mut edit_tools = EditTools::new();
+        edit_tools.select_corner(Location::new(0, 0, 10));
+        edit_tools.select_corner(Location::new(3, 0, 10));
+        assert!(edit_tools.copy(&mut world));
+
+        let mut edits = edit_tools.paste(Location::new(0, 0, 20));
+        edits.sort_by_key(|(location, _voxel)| (location.x, location.y, location.z));
+        assert_eq!(
+            edits,
+            vec![
+                (Location::new(2, 0, 20), Voxel::Bomb),
+                (Location::new(3, 0, 20), Voxel::Furnace),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_paste_rotated() {
+        let voxels = [
+            (Location::new(0, 0, 10), Voxel::Stone),
+            (Location::new(2, 0, 10), Voxel::Brick),
+        ];
+        let mut world = create_world_with_voxels("test_world_test_paste_rotated", &voxels);
+        let mut edit_tools = EditTools::new();
+        edit_tools.select_corner(Location::new(0, 0, 10));
+        edit_tools.select_corner(Location::new(2, 1, 10));
+        assert!(edit_tools.copy(&mut world));
+
+        edit_tools.rotate();
+        let mut edits = edit_tools.paste(Location::new(0, 0, 10));
+        edits.sort_by_key(|(location, _voxel)| (location.x, location.y, location.z));
+        assert_eq!(
+            edits,
+            vec![
+                (Location::new(1, 0, 10), Voxel::Stone),
+                (Location::new(1, 2, 10), Voxel::Brick),
+            ]
+        );
+
+        for _ in 0..3 {
+            edit_tools.rotate();
+        }
+        let mut edits = edit_tools.paste(Location::new(0, 0, 10));
+        edits.sort_by_key(|(location, _voxel)| (location.x, location.y, location.z));
+        assert_eq!(
+            edits,
+            vec![
+                (Location::new(0, 0, 10), Voxel::Stone),
+                (Location::new(2, 0, 10), Voxel::Brick),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_paste_clamps_to_world_height() {
+        let voxels = [
+            (Location::new(0, 0, 10), Voxel::Stone),
+            (Location::new(0, 0, 11), Voxel::Brick),
+        ];
+        let mut world = create_world_with_voxels("test_world_test_paste_clamps", &voxels);
+        let mut edit_tools = EditTools::new();
+        edit_tools.select_corner(Location::new(0, 0, 10));
+        edit_tools.select_corner(Location::new(0, 0, 11));
+        assert!(edit_tools.copy(&mut world));
+
+        let edits = edit_tools.paste(Location::new(0, 0, AREA_HEIGHT as i32 - 2));
+        assert_eq!(
+            edits,
+            vec![(Location::new(0, 0, AREA_HEIGHT as i32 - 2), Voxel::Stone)]
+        );
+    }
+
+    #[test]
+    fn test_create_replace_edits() {
+        let voxels = [
+            (Location::new(0, 0, 10), Voxel::Stone),
+            (Location::new(1, 0, 10), Voxel::Brick),
+            (Location::new(2, 0, 10), Voxel::Stone),
+            (Location::new(3, 0, 10), Voxel::Stone),
+        ];
+        let mut world = create_world_with_voxels("test_world_test_create_replace_edits", &voxels);
+        let mut edit_tools = EditTools::new();
+        assert!(
+            edit_tools
+                .create_replace_edits(&mut world, Voxel::Stone, Voxel::Wood, 10)
+                .is_empty()
+        );
+
+        edit_tools.select_corner(Location::new(0, 0, 10));
+        edit_tools.select_corner(Location::new(2, 0, 10));
+        let edits = edit_tools.create_replace_edits(&mut world, Voxel::Stone, Voxel::Wood, 10);
+        assert_eq!(
+            edits,
+            vec![
+                (Location::new(0, 0, 10), Voxel::Wood),
+                (Location::new(2, 0, 10), Voxel::Wood),
+            ]
+        );
+
+        let edits = edit_tools.create_replace_edits(&mut world, Voxel::Stone, Voxel::Wood, 1);
+        assert_eq!(edits, vec![(Location::new(0, 0, 10), Voxel::Wood)]);
+        assert!(
+            edit_tools
+                .create_replace_edits(&mut world, Voxel::Stone, Voxel::Stone, 10)
+                .is_empty()
+        );
+    }
+}
@@ -3,82 +3,204 @@ use macroquad::input::{
     mouse_wheel,
 };
 
+use crate::model::{
+    key_bindings::{KeyAction, KeyBindings},
+    mouse_bindings::MouseBindings,
+};
+
 use super::camera_controller::CameraController;
 
-pub fn move_left() -> bool {
-    is_key_down(macroquad::input::KeyCode::A) || is_key_down(macroquad::input::KeyCode::Left)
+pub fn move_left(key_bindings: &KeyBindings) -> bool {
+    is_key_down(key_bindings.get(KeyAction::MoveLeft).to_key_code())
+}
+
+pub fn move_right(key_bindings: &KeyBindings) -> bool {
+    is_key_down(key_bindings.get(KeyAction::MoveRight).to_key_code())
+}
+
+pub fn move_forward(key_bindings: &KeyBindings) -> bool {
+    is_key_down(key_bindings.get(KeyAction::MoveForward).to_key_code())
+}
+
+pub fn move_back(key_bindings: &KeyBindings) -> bool {
+    is_key_down(key_bindings.get(KeyAction::MoveBack).to_key_code())
 }
 
-pub fn move_right() -> bool {
-    is_key_down(macroquad::input::KeyCode::D) || is_key_down(macroquad::input::KeyCode::Right)
+pub fn jump(key_bindings: &KeyBindings) -> bool {
+    is_key_pressed(key_bindings.get(KeyAction::Jump).to_key_code())
 }
 
-pub fn move_forward() -> bool {
-    is_key_down(macroquad::input::KeyCode::W) || is_key_down(macroquad::input::KeyCode::Up)
+pub fn swim(key_bindings: &KeyBindings) -> bool {
+    is_key_down(key_bindings.get(KeyAction::Jump).to_key_code())
 }
 
-pub fn move_back() -> bool {
-    is_key_down(macroquad::input::KeyCode::S) || is_key_down(macroquad::input::KeyCode::Down)
+pub fn sprint(key_bindings: &KeyBindings) -> bool {
+    is_key_down(key_bindings.get(KeyAction::Sprint).to_key_code())
 }
 
-pub fn jump() -> bool {
-    is_key_pressed(macroquad::input::KeyCode::Space)
+pub fn sneak(key_bindings: &KeyBindings) -> bool {
+    is_key_down(key_bindings.get(KeyAction::Sneak).to_key_code())
 }
 
-pub fn swim() -> bool {
-    is_key_down(macroquad::input::KeyCode::Space)
+pub fn zoom(key_bindings: &KeyBindings) -> bool {
+    is_key_down(key_bindings.get(KeyAction::Zoom).to_key_code())
 }
 
 pub fn exit_focus() -> bool {
     is_key_released(macroquad::input::KeyCode::Escape)
 }
 
-pub fn toggle_debug() -> bool {
-    is_key_released(macroquad::input::KeyCode::GraveAccent)
+pub fn toggle_debug(key_bindings: &KeyBindings) -> bool {
+    is_key_released(key_bindings.get(KeyAction::Debug).to_key_code())
+}
+
+pub fn cycle_noise_overlay(key_bindings: &KeyBindings) -> bool {
+    is_key_released(key_bindings.get(KeyAction::CycleNoiseOverlay).to_key_code())
+}
+
+pub fn toggle_x_ray(key_bindings: &KeyBindings) -> bool {
+    is_key_released(key_bindings.get(KeyAction::ToggleXRay).to_key_code())
+}
+
+pub fn toggle_all_area_borders(key_bindings: &KeyBindings) -> bool {
+    is_key_released(
+        key_bindings
+            .get(KeyAction::ToggleAllAreaBorders)
+            .to_key_code(),
+    )
+}
+
+pub fn toggle_changed_areas(key_bindings: &KeyBindings) -> bool {
+    is_key_released(
+        key_bindings
+            .get(KeyAction::ToggleChangedAreas)
+            .to_key_code(),
+    )
+}
+
+pub fn toggle_time_freeze(key_bindings: &KeyBindings) -> bool {
+    is_key_released(key_bindings.get(KeyAction::ToggleTimeFreeze).to_key_code())
+}
+
+pub fn is_lock_modifier_down(key_bindings: &KeyBindings) -> bool {
+    is_key_down(key_bindings.get(KeyAction::LockSlot).to_key_code())
+}
+
+pub fn is_quick_transfer_modifier_down(key_bindings: &KeyBindings) -> bool {
+    is_key_down(key_bindings.get(KeyAction::QuickTransfer).to_key_code())
+}
+
+pub fn is_open_console(key_bindings: &KeyBindings) -> bool {
+    is_key_released(key_bindings.get(KeyAction::Console).to_key_code())
+}
+
+pub fn toggle_ui(key_bindings: &KeyBindings) -> bool {
+    is_key_released(key_bindings.get(KeyAction::ToggleHud).to_key_code())
+}
+
+pub fn take_screenshot(key_bindings: &KeyBindings) -> bool {
+    is_key_released(key_bindings.get(KeyAction::Screenshot).to_key_code())
+}
+
+pub fn is_start_place_voxel(
+    camera_controller: &CameraController,
+    mouse_bindings: &MouseBindings,
+) -> bool {
+    camera_controller.is_focused()
+        && is_mouse_button_pressed(mouse_bindings.place.to_mouse_button())
+}
+
+pub fn is_place_voxel(
+    camera_controller: &CameraController,
+    mouse_bindings: &MouseBindings,
+) -> bool {
+    camera_controller.is_focused() && is_mouse_button_down(mouse_bindings.place.to_mouse_button())
+}
+
+pub fn is_start_replace_voxel(
+    camera_controller: &CameraController,
+    mouse_bindings: &MouseBindings,
+) -> bool {
+    camera_controller.is_focused()
+        && is_mouse_button_pressed(mouse_bindings.replace.to_mouse_button())
+}
+
+pub fn is_replace_voxel(
+    camera_controller: &CameraController,
+    mouse_bindings: &MouseBindings,
+) -> bool {
+    camera_controller.is_focused() && is_mouse_button_down(mouse_bindings.replace.to_mouse_button())
+}
+
+pub fn is_start_destroy_voxel(
+    camera_controller: &CameraController,
+    mouse_bindings: &MouseBindings,
+) -> bool {
+    camera_controller.is_focused()
+        && is_mouse_button_pressed(mouse_bindings.destroy.to_mouse_button())
+}
+
+pub fn is_destroy_voxel(
+    camera_controller: &CameraController,
+    mouse_bindings: &MouseBindings,
+) -> bool {
+    camera_controller.is_focused() && is_mouse_button_down(mouse_bindings.destroy.to_mouse_button())
 }
 
-pub fn toggle_ui() -> bool {
-    is_key_released(macroquad::input::KeyCode::F3)
+pub fn increase_render_distance(key_bindings: &KeyBindings) -> bool {
+    is_key_released(
+        key_bindings
+            .get(KeyAction::IncreaseRenderDistance)
+            .to_key_code(),
+    )
 }
 
-pub fn is_start_place_voxel(camera_controller: &CameraController) -> bool {
-    camera_controller.is_focused() && is_mouse_button_pressed(macroquad::input::MouseButton::Right)
+pub fn decrease_render_distance(key_bindings: &KeyBindings) -> bool {
+    is_key_released(
+        key_bindings
+            .get(KeyAction::DecreaseRenderDistance)
+            .to_key_code(),
+    )
 }
 
-pub fn is_place_voxel(camera_controller: &CameraController) -> bool {
-    camera_controller.is_focused() && is_mouse_button_down(macroquad::input::MouseButton::Right)
+pub fn is_enter_inventory(key_bindings: &KeyBindings) -> bool {
+    is_key_released(key_bindings.get(KeyAction::Inventory).to_key_code())
 }
 
-pub fn is_start_replace_voxel(camera_controller: &CameraController) -> bool {
-    camera_controller.is_focused() && is_mouse_button_pressed(macroquad::input::MouseButton::Middle)
+pub fn is_enter_crafting(key_bindings: &KeyBindings) -> bool {
+    is_key_released(key_bindings.get(KeyAction::Crafting).to_key_code())
 }
 
-pub fn is_replace_voxel(camera_controller: &CameraController) -> bool {
-    camera_controller.is_focused() && is_mouse_button_down(macroquad::input::MouseButton::Middle)
+pub fn is_select_corner(key_bindings: &KeyBindings) -> bool {
+    is_key_released(key_bindings.get(KeyAction::SelectCorner).to_key_code())
 }
 
-pub fn is_start_destroy_voxel(camera_controller: &CameraController) -> bool {
-    camera_controller.is_focused() && is_mouse_button_pressed(macroquad::input::MouseButton::Left)
+pub fn is_copy_selection(key_bindings: &KeyBindings) -> bool {
+    is_key_released(key_bindings.get(KeyAction::CopySelection).to_key_code())
 }
 
-pub fn is_destroy_voxel(camera_controller: &CameraController) -> bool {
-    camera_controller.is_focused() && is_mouse_button_down(macroquad::input::MouseButton::Left)
+pub fn is_paste_selection(key_bindings: &KeyBindings) -> bool {
+    is_key_released(key_bindings.get(KeyAction::PasteSelection).to_key_code())
 }
 
-pub fn increase_render_distance() -> bool {
-    is_key_released(macroquad::input::KeyCode::F2)
+pub fn is_rotate_clipboard(key_bindings: &KeyBindings) -> bool {
+    is_key_released(key_bindings.get(KeyAction::RotateClipboard).to_key_code())
 }
 
-pub fn decrease_render_distance() -> bool {
-    is_key_released(macroquad::input::KeyCode::F1)
+pub fn is_replace_in_selection(key_bindings: &KeyBindings) -> bool {
+    is_key_released(
+        key_bindings
+            .get(KeyAction::ReplaceInSelection)
+            .to_key_code(),
+    )
 }
 
-pub fn is_enter_inventory() -> bool {
-    is_key_released(macroquad::input::KeyCode::E)
+pub fn is_place_column(key_bindings: &KeyBindings) -> bool {
+    is_key_released(key_bindings.get(KeyAction::PlaceColumn).to_key_code())
 }
 
-pub fn is_enter_crafting() -> bool {
-    is_key_released(macroquad::input::KeyCode::C)
+pub fn is_drop_item(key_bindings: &KeyBindings) -> bool {
+    is_key_released(key_bindings.get(KeyAction::DropItem).to_key_code())
 }
 
 pub fn get_number_key() -> Option<u8> {
@@ -125,8 +247,8 @@ pub fn get_number_key() -> Option<u8> {
     None
 }
 
-pub fn is_show_map() -> bool {
-    is_key_released(macroquad::input::KeyCode::M)
+pub fn is_show_map(key_bindings: &KeyBindings) -> bool {
+    is_key_released(key_bindings.get(KeyAction::Map).to_key_code())
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -3,12 +3,19 @@ pub mod activity_timer;
 pub mod area_generation;
 pub mod asset_manager;
 pub mod camera_controller;
+pub mod commands;
 pub mod crafting;
 pub mod creatures;
+pub mod dropped_items;
+pub mod edit_tools;
 pub mod input;
+pub mod music_player;
+pub mod performance_log;
 pub mod persistence;
 pub mod physics;
 pub mod raycast;
+pub mod smelting;
 pub mod sound_manager;
 pub mod world_actions;
+pub mod world_statistics;
 pub mod world_time;
@@ -0,0 +1,173 @@
+use crate::{
+    model::user_settings::UserSettings,
+    service::sound_manager::{MUSIC_TRACKS, SoundManager},
+};
+
+/// time in seconds of silence between music tracks
+const GAP_BETWEEN_TRACKS: f32 = 30.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MusicState {
+    Stopped,
+    /// the index into the music tracks and the time left until it ends
+    Playing {
+        track_index: usize,
+        remaining: f32,
+    },
+    Gap {
+        remaining: f32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MusicAction {
+    None,
+    Play(usize),
+    Stop,
+}
+
+/// cycles through the music tracks with a gap between them
+pub struct MusicPlayer {
+    state: MusicState,
+    next_track_index: usize,
+    volume: f32,
+}
+impl MusicPlayer {
+    pub fn new() -> Self {
+        Self {
+            state: MusicState::Stopped,
+            next_track_index: 0,
+            volume: 0.0,
+        }
+    }
+
+    /// advances the current track, no new tracks are started while paused
+    pub fn update(
+        &mut self,
+        delta: f32,
+        is_paused: bool,
+        user_settings: &UserSettings,
+        sound_manager: &SoundManager,
+    ) {
+        let is_enabled = user_settings.has_sound && user_settings.has_music;
+        let volume = user_settings.get_music_volume();
+        match self.advance(delta, is_paused, is_enabled) {
+            MusicAction::None => {}
+            MusicAction::Play(track_index) => {
+                self.volume = volume;
+                sound_manager.play_music_track(MUSIC_TRACKS[track_index].0, volume);
+            }
+            MusicAction::Stop => sound_manager.stop_music(),
+        }
+
+        if let MusicState::Playing { track_index, .. } = self.state
+            && self.volume != volume
+        {
+            self.volume = volume;
+            sound_manager.set_music_track_volume(MUSIC_TRACKS[track_index].0, volume);
+        }
+    }
+
+    fn advance(&mut self, delta: f32, is_paused: bool, is_enabled: bool) -> MusicAction {
+        if !is_enabled {
+            let was_stopped = self.state == MusicState::Stopped;
+            self.state = MusicState::Stopped;
+            return if was_stopped {
+                MusicAction::None
+            } else {
+                MusicAction::Stop
+            };
+        }
+
+        match self.state {
+            MusicState::Stopped if !is_paused => self.start_next_track(),
+            MusicState::Stopped => MusicAction::None,
+            // the sound keeps playing while paused so its time keeps running
+            MusicState::Playing {
+                track_index,
+                remaining,
+            } => {
+                let remaining = remaining - delta;
+                self.state = if remaining > 0.0 {
+                    MusicState::Playing {
+                        track_index,
+                        remaining,
+                    }
+                } else {
+                    MusicState::Gap {
+                        remaining: GAP_BETWEEN_TRACKS,
+                    }
+                };
+                MusicAction::None
+            }
+            MusicState::Gap { .. } if is_paused => MusicAction::None,
+            MusicState::Gap { remaining } => {
+                let remaining = remaining - delta;
+                if remaining > 0.0 {
+                    self.state = MusicState::Gap { remaining };
+                    MusicAction::None
+                } else {
+                    self.start_next_track()
+                }
+            }
+        }
+    }
+
+    fn start_next_track(&mut self) -> MusicAction {
+        let track_index = self.next_track_index % MUSIC_TRACKS.len();
+        self.next_track_index = (track_index + 1) % MUSIC_TRACKS.len();
+        self.state = MusicState::Playing {
+            track_index,
+            remaining: MUSIC_TRACKS[track_index].2,
+        };
+
+        MusicAction::Play(track_index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracks_cycle_with_gap() {
+        let mut music_player = MusicPlayer::new();
+        let track_length = MUSIC_TRACKS[0].2;
+
+        assert_eq!(music_player.advance(0.1, false, true), MusicAction::Play(0));
+        assert_eq!(
+            music_player.advance(track_length, false, true),
+            MusicAction::None
+        );
+        assert_eq!(
+            music_player.state,
+            MusicState::Gap {
+                remaining: GAP_BETWEEN_TRACKS
+            }
+        );
+        assert_eq!(
+            music_player.advance(GAP_BETWEEN_TRACKS, false, true),
+            MusicAction::Play(0)
+        );
+    }
+
+    #[test]
+    fn test_no_track_started_while_paused() {
+        let mut music_player = MusicPlayer::new();
+        assert_eq!(music_player.advance(0.1, true, true), MusicAction::None);
+        assert_eq!(music_player.state, MusicState::Stopped);
+
+        music_player.state = MusicState::Gap { remaining: 1.0 };
+        assert_eq!(music_player.advance(5.0, true, true), MusicAction::None);
+        assert_eq!(music_player.state, MusicState::Gap { remaining: 1.0 });
+    }
+
+    #[test]
+    fn test_disabling_music_stops_track() {
+        let mut music_player = MusicPlayer::new();
+        music_player.advance(0.1, false, true);
+
+        assert_eq!(music_player.advance(0.1, false, false), MusicAction::Stop);
+        assert_eq!(music_player.advance(0.1, false, false), MusicAction::None);
+    }
+}
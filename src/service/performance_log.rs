@@ -0,0 +1,145 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+};
+
+use macroquad::prelude::{error, info};
+
+use crate::service::{
+    activity_timer::ActivityTimer, persistence::generic_persistence::open_append_file,
+};
+
+const PERFORMANCE_LOG_FILE: &str = "performance_log.csv";
+const CSV_HEADER: &str = "time,fps,max_frame_ms,loaded_areas,voxel_faces,creatures,areas_queued";
+/// time in seconds between logged rows
+const SAMPLE_INTERVAL: f32 = 1.0;
+/// time in seconds between writes to disk, so a crash still leaves most of the data
+const FLUSH_INTERVAL: f32 = 10.0;
+
+/// the world state recorded in each row
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PerformanceSample {
+    pub loaded_areas: usize,
+    pub voxel_faces: usize,
+    pub creatures: usize,
+    pub areas_queued: usize,
+}
+
+/// appends per second performance metrics to a csv file for offline analysis
+pub struct PerformanceLog {
+    writer: Option<BufWriter<File>>,
+    sample_timer: ActivityTimer,
+    flush_timer: ActivityTimer,
+    /// seconds since logging started
+    elapsed: f32,
+    frames: u32,
+    max_frame_time: f32,
+}
+impl PerformanceLog {
+    pub fn new() -> Self {
+        Self {
+            writer: None,
+            sample_timer: ActivityTimer::new(0.0, SAMPLE_INTERVAL),
+            flush_timer: ActivityTimer::new(0.0, FLUSH_INTERVAL),
+            elapsed: 0.0,
+            frames: 0,
+            max_frame_time: 0.0,
+        }
+    }
+
+    /// starts or stops logging, returns the message to display
+    pub fn toggle(&mut self) -> String {
+        if let Some(mut writer) = self.writer.take() {
+            Self::flush(&mut writer);
+            return "Performance log stopped".to_owned();
+        }
+
+        let Some(file) = open_append_file(PERFORMANCE_LOG_FILE) else {
+            return "Couldn't open the performance log file".to_owned();
+        };
+        let is_empty = file.metadata().is_ok_and(|metadata| metadata.len() == 0);
+        let mut writer = BufWriter::new(file);
+        if is_empty && let Err(err) = writeln!(writer, "{CSV_HEADER}") {
+            error!("Error writing performance log header: {}", err);
+            return "Couldn't write to the performance log file".to_owned();
+        }
+
+        self.writer = Some(writer);
+        self.sample_timer.reset();
+        self.flush_timer.reset();
+        self.elapsed = 0.0;
+        self.frames = 0;
+        self.max_frame_time = 0.0;
+        info!("Performance log started");
+        format!("Performance log started, writing to {PERFORMANCE_LOG_FILE}")
+    }
+
+    /// counts the frame and writes a row once per sample interval,
+    /// the sample is only taken when a row is written
+    pub fn update(&mut self, frame_time: f32, sample: impl FnOnce() -> PerformanceSample) {
+        let Some(writer) = &mut self.writer else {
+            return;
+        };
+        self.elapsed += frame_time;
+        self.frames += 1;
+        self.max_frame_time = self.max_frame_time.max(frame_time);
+
+        if self.sample_timer.tick(frame_time) {
+            let fps = self.frames as f32 / SAMPLE_INTERVAL;
+            let row = format_row(self.elapsed, fps, self.max_frame_time, sample());
+            if let Err(err) = writeln!(writer, "{row}") {
+                error!("Error writing performance log, stopping: {}", err);
+                self.writer = None;
+                return;
+            }
+            self.frames = 0;
+            self.max_frame_time = 0.0;
+        }
+
+        if self.flush_timer.tick(frame_time) {
+            Self::flush(writer);
+        }
+    }
+
+    fn flush(writer: &mut BufWriter<File>) {
+        if let Err(err) = writer.flush() {
+            error!("Error flushing performance log: {}", err);
+        }
+    }
+}
+
+fn format_row(time: f32, fps: f32, max_frame_time: f32, sample: PerformanceSample) -> String {
+    format!(
+        "{:.1},{:.0},{:.1},{},{},{},{}",
+        time,
+        fps,
+        max_frame_time * 1000.0,
+        sample.loaded_areas,
+        sample.voxel_faces,
+        sample.creatures,
+        sample.areas_queued
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_row_matches_header() {
+        let row = format_row(
+            12.04,
+            59.6,
+            0.0215,
+            PerformanceSample {
+                loaded_areas: 81,
+                voxel_faces: 120_000,
+                creatures: 7,
+                areas_queued: 3,
+            },
+        );
+
+        assert_eq!(row, "12.0,60,21.5,81,120000,7,3");
+        assert_eq!(row.split(',').count(), CSV_HEADER.split(',').count());
+    }
+}
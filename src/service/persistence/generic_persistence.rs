@@ -1,16 +1,18 @@
 use std::{
     any::type_name,
     borrow::Cow,
-    fs::{File, create_dir, create_dir_all, remove_dir_all},
+    fs::{File, OpenOptions, create_dir, create_dir_all, remove_dir_all},
     io::{Read, Write},
+    path::Path,
 };
 
-use bincode::{Decode, Encode, decode_from_slice, encode_to_vec};
+use bincode::{Decode, Encode, de::Decoder, decode_from_slice, encode_to_vec, error::DecodeError};
 use lz4_flex::{compress_prepend_size, decompress_size_prepended};
 use macroquad::prelude::{error, info};
 
 use crate::service::persistence::{
     config::{BASE_SAVE_PATH, SERIALIZATION_CONFIG},
+    screenshot_persistence::SCREENSHOT_DIRECTORY,
     world_persistence::get_world_directory,
 };
 
@@ -40,25 +42,7 @@ pub fn read_binary_object<T: Decode<()>>(filepath: &str, with_compression: bool)
         return None;
     };
 
-    let bytes_to_decode = if with_compression {
-        Cow::Owned(decompress::<T>(&buf, &filepath)?)
-    } else {
-        Cow::Borrowed(&buf)
-    };
-
-    let (object, _read): (T, usize) =
-        match decode_from_slice(&bytes_to_decode, SERIALIZATION_CONFIG) {
-            Ok(ok) => ok,
-            Err(err) => {
-                error!(
-                    "Error decoding {} file '{}': {}",
-                    type_name::<T>(),
-                    filepath,
-                    err
-                );
-                return None;
-            }
-        };
+    let object = decode_object(&buf, with_compression, &filepath)?;
     info!("Loaded {}: {}", type_name::<T>(), filepath);
 
     Some(object)
@@ -70,18 +54,8 @@ pub fn write_binary_object<T: Encode>(
     with_compression: bool,
 ) -> Result<(), ()> {
     let filepath = format!("{BASE_SAVE_PATH}{filepath}");
-    let encode_result = match encode_to_vec(object, SERIALIZATION_CONFIG) {
-        Ok(ok) => ok,
-        Err(err) => {
-            error!("Error encoding {}: {}", type_name::<T>(), err);
-            return Err(());
-        }
-    };
-
-    let bytes_to_save = if with_compression {
-        Cow::Owned(compress_prepend_size(&encode_result))
-    } else {
-        Cow::Borrowed(&encode_result)
+    let Some(bytes_to_save) = encode_object(object, with_compression) else {
+        return Err(());
     };
 
     let mut file = match File::create(&filepath) {
@@ -101,6 +75,85 @@ pub fn write_binary_object<T: Encode>(
     }
 }
 
+/// encodes the object into the bytes that are stored on disk
+pub fn encode_object<T: Encode>(object: &T, with_compression: bool) -> Option<Vec<u8>> {
+    let encoded = match encode_to_vec(object, SERIALIZATION_CONFIG) {
+        Ok(ok) => ok,
+        Err(err) => {
+            error!("Error encoding {}: {}", type_name::<T>(), err);
+            return None;
+        }
+    };
+
+    if with_compression {
+        Some(compress_prepend_size(&encoded))
+    } else {
+        Some(encoded)
+    }
+}
+
+/// decodes an object from the bytes stored on disk, the source is only used for logging
+pub fn decode_object<T: Decode<()>>(
+    bytes: &[u8],
+    with_compression: bool,
+    source: &str,
+) -> Option<T> {
+    let bytes_to_decode = if with_compression {
+        Cow::Owned(decompress::<T>(bytes, source)?)
+    } else {
+        Cow::Borrowed(bytes)
+    };
+
+    match decode_from_slice(&bytes_to_decode, SERIALIZATION_CONFIG) {
+        Ok((object, _read)) => Some(object),
+        Err(err) => {
+            error!(
+                "Error decoding {} file '{}': {}",
+                type_name::<T>(),
+                source,
+                err
+            );
+            None
+        }
+    }
+}
+
+/// decodes a field that was appended after the object was first saved,
+/// objects saved before the field existed end early and get the default instead
+pub fn decode_appended_field<T, D>(
+    decoder: &mut D,
+    default: impl FnOnce() -> T,
+) -> Result<T, DecodeError>
+where
+    T: Decode<D::Context>,
+    D: Decoder,
+{
+    match T::decode(decoder) {
+        Ok(value) => Ok(value),
+        Err(DecodeError::UnexpectedEnd { .. }) => Ok(default()),
+        Err(err) => Err(err),
+    }
+}
+
+/// opens a file for appending text, creating it if it doesn't exist
+pub fn open_append_file(filepath: &str) -> Option<File> {
+    let filepath = format!("{BASE_SAVE_PATH}{filepath}");
+    match OpenOptions::new().create(true).append(true).open(&filepath) {
+        Ok(file) => {
+            info!("Opened '{}' for appending", filepath);
+            Some(file)
+        }
+        Err(err) => {
+            error!("Error opening file '{}': {}", filepath, err);
+            None
+        }
+    }
+}
+
+pub fn file_exists(filepath: &str) -> bool {
+    Path::new(&format!("{BASE_SAVE_PATH}{filepath}")).exists()
+}
+
 pub fn initialise_save_directory() {
     if let Err(err) = create_dir(BASE_SAVE_PATH) {
         error!(
@@ -110,6 +163,14 @@ pub fn initialise_save_directory() {
     } else {
         info!("Save directory '{}' initialised", BASE_SAVE_PATH);
     }
+
+    let screenshot_path = format!("{BASE_SAVE_PATH}{SCREENSHOT_DIRECTORY}");
+    if let Err(err) = create_dir_all(&screenshot_path) {
+        error!(
+            "Error creating screenshot directory '{}': {}",
+            screenshot_path, err
+        );
+    }
 }
 
 pub fn create_directory(world_name: &str) -> Result<(), std::io::Error> {
@@ -0,0 +1,38 @@
+use rayon::spawn;
+
+use crate::{
+    graphics::mesh_cache::AreaMeshCacheDTO,
+    model::location::AreaLocation,
+    service::persistence::generic_persistence::{
+        create_directory, file_exists, read_binary_object, write_binary_object,
+    },
+};
+
+const IS_COMPRESSED: bool = true;
+
+fn get_filepath(area_location: AreaLocation, world_name: &str) -> String {
+    format!(
+        "{world_name}/mesh{}_{}.dat",
+        area_location.x, area_location.y
+    )
+}
+
+/// returns None without logging an error if the area has no cached meshes
+pub fn load_mesh_cache(world_name: &str, area_location: AreaLocation) -> Option<AreaMeshCacheDTO> {
+    let filepath = get_filepath(area_location, world_name);
+    if !file_exists(&filepath) {
+        return None;
+    }
+
+    read_binary_object(&filepath, IS_COMPRESSED)
+}
+
+/// non blocking write
+pub fn store_mesh_cache(world_name: &str, area_location: AreaLocation, cache: AreaMeshCacheDTO) {
+    let filepath = get_filepath(area_location, world_name);
+    let world_name = world_name.to_owned();
+    spawn(move || {
+        let _ = create_directory(&world_name);
+        let _result = write_binary_object(&filepath, &cache, IS_COMPRESSED);
+    });
+}
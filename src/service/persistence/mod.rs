@@ -1,6 +1,8 @@
 pub mod config;
 pub mod generic_persistence;
+pub mod mesh_cache_persistence;
 pub mod player_persistence;
+pub mod screenshot_persistence;
 pub mod user_settings_persistence;
 pub mod world_list_persistence;
 pub mod world_metadata_persistence;
@@ -0,0 +1,62 @@
+use std::{
+    fs::create_dir_all,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use macroquad::{
+    prelude::{error, info},
+    texture::Image,
+};
+
+use crate::service::persistence::config::BASE_SAVE_PATH;
+
+pub const SCREENSHOT_DIRECTORY: &str = "screenshots/";
+
+fn get_filepath(timestamp: u64, index: u32) -> String {
+    if index == 0 {
+        format!("{BASE_SAVE_PATH}{SCREENSHOT_DIRECTORY}screenshot_{timestamp}.png")
+    } else {
+        format!("{BASE_SAVE_PATH}{SCREENSHOT_DIRECTORY}screenshot_{timestamp}_{index}.png")
+    }
+}
+
+/// writes the captured screen as a timestamped png, returns the filepath if successful
+pub fn store_screenshot(image: &Image) -> Option<String> {
+    let directory = format!("{BASE_SAVE_PATH}{SCREENSHOT_DIRECTORY}");
+    if let Err(err) = create_dir_all(&directory) {
+        error!(
+            "Error creating screenshot directory '{}': {}",
+            directory, err
+        );
+        return None;
+    }
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+    // several screenshots can be taken in the same second
+    let filepath = (0..)
+        .map(|index| get_filepath(timestamp, index))
+        .find(|filepath| !Path::new(filepath).exists())?;
+
+    image.export_png(&filepath);
+    info!("Screenshot saved to '{}'", filepath);
+
+    Some(filepath)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_filepath_is_unique_per_index() {
+        assert_eq!(
+            get_filepath(100, 0),
+            format!("{BASE_SAVE_PATH}{SCREENSHOT_DIRECTORY}screenshot_100.png")
+        );
+        assert_ne!(get_filepath(100, 0), get_filepath(100, 1));
+    }
+}
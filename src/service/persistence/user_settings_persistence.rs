@@ -9,7 +9,9 @@ const IS_COMPRESSED: bool = false;
 const USER_SETTINGS_FILEPATH: &str = "settings.dat";
 
 pub fn read_or_initialise_user_settings() -> UserSettings {
-    read_binary_object(USER_SETTINGS_FILEPATH, IS_COMPRESSED).unwrap_or_default()
+    read_binary_object(USER_SETTINGS_FILEPATH, IS_COMPRESSED)
+        .map(UserSettings::validate)
+        .unwrap_or_default()
 }
 
 pub fn write_user_settings_blocking(user_settings: &UserSettings) {
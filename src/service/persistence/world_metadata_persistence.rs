@@ -1,4 +1,4 @@
-use bincode::{Decode, Encode};
+use bincode::{Decode, Encode, de::Decoder, error::DecodeError, impl_borrow_decode};
 
 use crate::{
     graphics::{
@@ -6,22 +6,28 @@ use crate::{
         sky::{Sky, SkyDTO},
     },
     interface::tutorial_messages::{TutorialMessages, TutorialMessagesDTO},
+    model::world_rules::WorldRules,
     service::{
+        area_generation::generator::hash_world_name,
         creatures::creature_manager::{CreatureManager, CreatureManagerDTO},
+        dropped_items::DroppedItemDTO,
         persistence::generic_persistence::{
-            create_directory, read_binary_object, write_binary_object,
+            create_directory, decode_appended_field, read_binary_object, write_binary_object,
         },
         physics::{
             falling_voxel_simulator::SimulatedVoxelDTO, voxel_simulator::VoxelSimulator,
             water_simulator::WaterSimulator,
         },
-        world_time::WorldTime,
+        smelting::{FurnaceDTO, Furnaces},
+        world_statistics::WorldStatistics,
+        world_time::{DEFAULT_DAY_LENGTH, WorldTime},
     },
 };
 
 const IS_COMPRESSED: bool = false;
 
-#[derive(Debug, Clone, Encode, Decode)]
+/// new fields are appended at the end so older saves can still be decoded
+#[derive(Debug, Clone, Encode)]
 pub struct WorldMetadata {
     pub delta: f32,
     pub simulated_voxels: Vec<SimulatedVoxelDTO>,
@@ -30,6 +36,17 @@ pub struct WorldMetadata {
     pub sky_dto: SkyDTO,
     pub tutorial_messages_dto: TutorialMessagesDTO,
     pub rain_system: RainSystemDTO,
+    pub statistics: WorldStatistics,
+    pub dropped_items: Vec<DroppedItemDTO>,
+    pub rules: WorldRules,
+    pub day: u32,
+    /// seconds for a full day and night cycle
+    pub day_length: f32,
+    pub is_time_frozen: bool,
+    /// the seed used to generate the world,
+    /// worlds saved before it was stored were generated from their name
+    seed: Option<u64>,
+    pub furnaces: Vec<FurnaceDTO>,
 }
 impl WorldMetadata {
     pub fn new(
@@ -39,19 +56,72 @@ impl WorldMetadata {
         sky: &Sky,
         tutorial_messages: &TutorialMessages,
         rain_system: &RainSystem,
+        statistics: &WorldStatistics,
     ) -> Self {
-        let (simulated_voxels, water_simulator) = voxel_simulator.create_dtos();
+        let (simulated_voxels, water_simulator, dropped_items) = voxel_simulator.create_dtos();
         Self {
             delta: world_time.get_delta(),
+            day: world_time.get_day(),
+            day_length: world_time.get_day_length(),
+            is_time_frozen: world_time.is_frozen(),
             simulated_voxels,
             water_simulator,
+            dropped_items,
             creature_manager: creature_manager.create_dto(),
             sky_dto: sky.create_dto(),
             tutorial_messages_dto: tutorial_messages.create_dto(),
             rain_system: rain_system.create_dto(),
+            statistics: *statistics,
+            rules: WorldRules::default(),
+            seed: None,
+            furnaces: vec![],
         }
     }
+
+    pub fn with_rules(mut self, rules: WorldRules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn get_seed(&self, world_name: &str) -> u64 {
+        self.seed.unwrap_or_else(|| hash_world_name(world_name))
+    }
+
+    pub fn with_furnaces(mut self, furnaces: &Furnaces) -> Self {
+        self.furnaces = furnaces.create_dtos();
+        self
+    }
+}
+
+impl<Context> Decode<Context> for WorldMetadata {
+    /// worlds saved before the appended fields existed get the default values,
+    /// the default rules match the terrain generated before the rules were stored
+    fn decode<D: Decoder<Context = Context>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        Ok(Self {
+            delta: f32::decode(decoder)?,
+            simulated_voxels: Vec::decode(decoder)?,
+            water_simulator: WaterSimulator::decode(decoder)?,
+            creature_manager: CreatureManagerDTO::decode(decoder)?,
+            sky_dto: SkyDTO::decode(decoder)?,
+            tutorial_messages_dto: TutorialMessagesDTO::decode(decoder)?,
+            rain_system: RainSystemDTO::decode(decoder)?,
+            statistics: decode_appended_field(decoder, WorldStatistics::new)?,
+            dropped_items: decode_appended_field(decoder, Vec::new)?,
+            rules: decode_appended_field(decoder, WorldRules::default)?,
+            day: decode_appended_field(decoder, || 0)?,
+            day_length: decode_appended_field(decoder, || DEFAULT_DAY_LENGTH)?,
+            is_time_frozen: decode_appended_field(decoder, || false)?,
+            seed: decode_appended_field(decoder, || None)?,
+            furnaces: decode_appended_field(decoder, Vec::new)?,
+        })
+    }
 }
+impl_borrow_decode!(WorldMetadata);
 
 fn get_metadata_filepath(world_name: &str) -> String {
     format!("{world_name}/world.dat")
@@ -69,3 +139,73 @@ pub fn load_world_metadata(world_name: &str) -> Option<WorldMetadata> {
     let filepath = get_metadata_filepath(world_name);
     read_binary_object(&filepath, IS_COMPRESSED)
 }
+
+#[cfg(test)]
+mod tests {
+    use bincode::{decode_from_slice, encode_to_vec};
+
+    use crate::{
+        interface::tutorial_messages::TutorialMessage,
+        service::{activity_timer::ActivityTimer, persistence::config::SERIALIZATION_CONFIG},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_decode_legacy_world_metadata() {
+        // the metadata layout before the statistics and the later fields were saved
+        let legacy_bytes = encode_to_vec(
+            (
+                1.25_f32,
+                Vec::<SimulatedVoxelDTO>::new(),
+                WaterSimulator::new(),
+                CreatureManager::new().create_dto(),
+                // sky: cloud spawn delta and cloud positions
+                (0.5_f32, vec![[1.0_f32, 2.0, 3.0]]),
+                // tutorial messages: the seen messages
+                vec![TutorialMessage::Map],
+                // rain: is raining, rain drops, remove, change state and sky modifier deltas
+                (
+                    true,
+                    Vec::<[f32; 4]>::new(),
+                    0.0_f32,
+                    2.0_f32,
+                    0.8_f32,
+                    ActivityTimer::new(0.0, 1.0),
+                ),
+            ),
+            SERIALIZATION_CONFIG,
+        )
+        .unwrap();
+
+        let (world_metadata, _): (WorldMetadata, usize) =
+            decode_from_slice(&legacy_bytes, SERIALIZATION_CONFIG).unwrap();
+
+        assert_eq!(world_metadata.delta, 1.25);
+        assert!(world_metadata.rain_system.is_raining());
+        assert!(world_metadata.dropped_items.is_empty());
+        assert!(world_metadata.rules.mob_griefing);
+        assert_eq!(
+            world_metadata.rules.world_gen,
+            WorldRules::default().world_gen
+        );
+        assert_eq!(world_metadata.day, 0);
+        assert_eq!(world_metadata.day_length, DEFAULT_DAY_LENGTH);
+        assert!(!world_metadata.is_time_frozen);
+        assert_eq!(
+            world_metadata.get_seed("legacy world"),
+            hash_world_name("legacy world")
+        );
+        assert!(world_metadata.furnaces.is_empty());
+
+        let mut world_metadata = world_metadata.with_seed(42);
+        world_metadata.day = 3;
+        world_metadata.is_time_frozen = true;
+        let bytes = encode_to_vec(&world_metadata, SERIALIZATION_CONFIG).unwrap();
+        let (world_metadata, _): (WorldMetadata, usize) =
+            decode_from_slice(&bytes, SERIALIZATION_CONFIG).unwrap();
+        assert_eq!(world_metadata.get_seed("legacy world"), 42);
+        assert_eq!(world_metadata.day, 3);
+        assert!(world_metadata.is_time_frozen);
+    }
+}
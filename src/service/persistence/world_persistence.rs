@@ -1,7 +1,9 @@
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet, VecDeque},
     mem::take,
     sync::{Arc, Mutex},
+    thread,
+    time::Duration,
 };
 
 use macroquad::logging::{error, info};
@@ -11,6 +13,7 @@ use crate::{
     model::{
         area::{Area, AreaDTO},
         location::AreaLocation,
+        world_rules::{TreeDensity, WorldGenConfig},
     },
     service::{
         area_generation::generator::AreaGenerator,
@@ -41,16 +44,6 @@ pub fn store_blocking(area: Area, world_name: &str) {
     let _result = write_binary_object(&filepath, &area_dto, IS_COMPRESSED);
 }
 
-/// stores all areas on a background thread
-pub fn store(areas: Vec<Area>, world_name: String) {
-    debug_assert!(areas.iter().all(|area| area.has_changed));
-    rayon::spawn(move || {
-        for area in areas {
-            store_blocking(area, &world_name);
-        }
-    });
-}
-
 /// stores all areas and blocks the main thread
 pub fn store_all_blocking(areas: Vec<Area>, world_name: String) {
     areas.into_par_iter().for_each(|area| {
@@ -58,14 +51,20 @@ pub fn store_all_blocking(areas: Vec<Area>, world_name: String) {
     });
 }
 
-/// loads an area from disk
-pub fn load_blocking(area_location: AreaLocation, world_name: &str) -> Area {
+/// loads an area from disk or generates it if it was never saved
+pub fn load_blocking(
+    area_location: AreaLocation,
+    world_name: &str,
+    seed: u64,
+    tree_density: TreeDensity,
+    config: WorldGenConfig,
+) -> Area {
     let filepath = get_filepath(area_location.x, area_location.y, world_name);
     let area_dto: Option<AreaDTO> = read_binary_object(&filepath, IS_COMPRESSED);
 
     area_dto
         .map(|dto| dto.into_area(area_location, false))
-        .unwrap_or_else(|| AreaGenerator::generate_area(area_location, world_name))
+        .unwrap_or_else(|| AreaGenerator::generate_area(area_location, seed, tree_density, config))
 }
 
 /// struct to load areas asynchronously
@@ -86,15 +85,27 @@ impl AreaLoader {
         &mut self,
         areas_to_load: &[AreaLocation],
         world_name: &str,
+        seed: u64,
+        tree_density: TreeDensity,
+        config: WorldGenConfig,
     ) -> Vec<Area> {
         areas_to_load
             .par_iter()
-            .map(|area_location| load_blocking(*area_location, world_name))
+            .map(|area_location| {
+                load_blocking(*area_location, world_name, seed, tree_density, config)
+            })
             .collect()
     }
 
     /// starts background threads to load areas from disk
-    pub fn batch_load(&mut self, areas_to_load: &[AreaLocation], world_name: &str) {
+    pub fn batch_load(
+        &mut self,
+        areas_to_load: &[AreaLocation],
+        world_name: &str,
+        seed: u64,
+        tree_density: TreeDensity,
+        config: WorldGenConfig,
+    ) {
         let mut to_load_lock = self.to_load.lock().unwrap();
         let areas_to_load = areas_to_load
             .iter()
@@ -106,10 +117,23 @@ impl AreaLoader {
         }
         drop(to_load_lock);
 
-        self.schedule_load(areas_to_load, world_name.to_owned());
+        self.schedule_load(
+            areas_to_load,
+            world_name.to_owned(),
+            seed,
+            tree_density,
+            config,
+        );
     }
 
-    fn schedule_load(&self, areas_to_load: Vec<AreaLocation>, world_name: String) {
+    fn schedule_load(
+        &self,
+        areas_to_load: Vec<AreaLocation>,
+        world_name: String,
+        seed: u64,
+        tree_density: TreeDensity,
+        config: WorldGenConfig,
+    ) {
         if areas_to_load.is_empty() {
             return;
         }
@@ -119,7 +143,7 @@ impl AreaLoader {
 
         rayon::spawn(move || {
             for area_to_load in areas_to_load {
-                let area = load_blocking(area_to_load, &world_name);
+                let area = load_blocking(area_to_load, &world_name, seed, tree_density, config);
                 let mut to_load_lock = to_load.lock().unwrap();
                 let mut loaded_lock = loaded.lock().unwrap();
                 to_load_lock.remove(&area.get_area_location());
@@ -134,6 +158,90 @@ impl AreaLoader {
     }
 }
 
+/// struct to store areas on a background thread in bounded batches,
+/// queued areas can be taken back before they are written
+pub struct AreaSaver {
+    max_writes_per_batch: usize,
+    queued: VecDeque<Area>,
+    /// areas being written by the background thread
+    in_flight: Arc<Mutex<HashMap<AreaLocation, Area>>>,
+}
+impl AreaSaver {
+    pub fn new(max_writes_per_batch: usize) -> Self {
+        debug_assert!(max_writes_per_batch > 0);
+        Self {
+            max_writes_per_batch,
+            queued: VecDeque::new(),
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// queues areas to be written by later calls to `flush`
+    pub fn queue(&mut self, areas: Vec<Area>) {
+        debug_assert!(areas.iter().all(|area| area.has_changed));
+        for area in areas {
+            let area_location = area.get_area_location();
+            self.queued
+                .retain(|queued| queued.get_area_location() != area_location);
+            self.queued.push_back(area);
+        }
+    }
+
+    /// starts writing the next batch of queued areas if the previous batch has finished,
+    /// returns the number of areas dispatched
+    pub fn flush(&mut self, world_name: &str) -> usize {
+        if self.queued.is_empty() || !self.in_flight.lock().unwrap().is_empty() {
+            return 0;
+        }
+
+        let batch_size = self.max_writes_per_batch.min(self.queued.len());
+        let batch: Vec<_> = self.queued.drain(..batch_size).collect();
+        let area_locations: Vec<_> = batch.iter().map(|area| area.get_area_location()).collect();
+        self.in_flight.lock().unwrap().extend(
+            batch
+                .into_iter()
+                .map(|area| (area.get_area_location(), area)),
+        );
+
+        let in_flight = self.in_flight.clone();
+        let world_name = world_name.to_owned();
+        rayon::spawn(move || {
+            for area_location in area_locations {
+                let area = in_flight.lock().unwrap().get(&area_location).cloned();
+                if let Some(area) = area {
+                    store_blocking(area, &world_name);
+                }
+                in_flight.lock().unwrap().remove(&area_location);
+            }
+        });
+
+        batch_size
+    }
+
+    /// returns an area that hasn't been written yet so it isn't read stale from disk
+    pub fn take(&mut self, area_location: AreaLocation) -> Option<Area> {
+        if let Some(index) = self
+            .queued
+            .iter()
+            .position(|area| area.get_area_location() == area_location)
+        {
+            return self.queued.remove(index);
+        }
+
+        // the background thread still writes its copy, the area stays changed
+        // so it gets queued again when unloaded
+        self.in_flight.lock().unwrap().get(&area_location).cloned()
+    }
+
+    /// waits for the current batch and stores the rest of the queue, blocks the main thread
+    pub fn store_all_blocking(&mut self, world_name: &str) {
+        while !self.in_flight.lock().unwrap().is_empty() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        store_all_blocking(self.queued.drain(..).collect(), world_name.to_owned());
+    }
+}
+
 /// deletes all world files and directory
 pub fn delete_world(world_name: &str) {
     let is_path_invalid =
@@ -158,13 +266,23 @@ pub fn delete_world(world_name: &str) {
 mod tests {
     use std::{collections::HashMap, fs::remove_dir_all, path::Path, time::Instant};
 
-    use crate::model::{
-        area::{AREA_HEIGHT, AREA_SIZE},
-        location::InternalLocation,
+    use crate::{
+        model::{
+            area::{AREA_HEIGHT, AREA_SIZE},
+            location::InternalLocation,
+            orientation::Orientation,
+            voxel::Voxel,
+        },
+        service::{
+            area_generation::generator::hash_world_name,
+            persistence::generic_persistence::{decode_object, encode_object},
+        },
     };
 
     use super::*;
 
+    const TEST_SEED: u64 = 42;
+
     struct TestWorldName {
         name: &'static str,
     }
@@ -185,10 +303,21 @@ mod tests {
         let world_name = TestWorldName::new("test_world_persistence_load_temp_test_world");
 
         let area_location = AreaLocation::new(0, 0);
-        let area = AreaGenerator::generate_area(area_location, world_name.name);
+        let area = AreaGenerator::generate_area(
+            area_location,
+            TEST_SEED,
+            TreeDensity::Normal,
+            WorldGenConfig::default(),
+        );
         store_blocking(area.clone(), world_name.name);
 
-        let loaded_area = load_blocking(area_location, world_name.name);
+        let loaded_area = load_blocking(
+            area_location,
+            world_name.name,
+            TEST_SEED,
+            TreeDensity::Normal,
+            WorldGenConfig::default(),
+        );
 
         assert!(!loaded_area.has_changed);
         assert_eq!(loaded_area.get_x(), area_location.x);
@@ -208,7 +337,17 @@ mod tests {
         ];
         let mut areas: HashMap<_, _> = area_locations
             .into_iter()
-            .map(|loc| (loc, AreaGenerator::generate_area(loc, world_name.name)))
+            .map(|loc| {
+                (
+                    loc,
+                    AreaGenerator::generate_area(
+                        loc,
+                        TEST_SEED,
+                        TreeDensity::Normal,
+                        WorldGenConfig::default(),
+                    ),
+                )
+            })
             .collect();
 
         store_all_blocking(
@@ -217,7 +356,13 @@ mod tests {
         );
 
         let mut area_loader = AreaLoader::new();
-        area_loader.batch_load(&area_locations, world_name.name);
+        area_loader.batch_load(
+            &area_locations,
+            world_name.name,
+            TEST_SEED,
+            TreeDensity::Normal,
+            WorldGenConfig::default(),
+        );
 
         let start = Instant::now();
         loop {
@@ -237,6 +382,49 @@ mod tests {
         assert!(areas.is_empty());
     }
 
+    #[test]
+    pub fn test_area_saver_batches_writes() {
+        let world_name = TestWorldName::new("test_world_persistence_area_saver_batches_writes");
+        let area_locations = [
+            AreaLocation::new(0, 0),
+            AreaLocation::new(1, 0),
+            AreaLocation::new(2, 0),
+        ];
+        let areas: Vec<_> = area_locations
+            .into_iter()
+            .map(|loc| {
+                AreaGenerator::generate_area(
+                    loc,
+                    TEST_SEED,
+                    TreeDensity::Normal,
+                    WorldGenConfig::default(),
+                )
+            })
+            .collect();
+
+        let mut area_saver = AreaSaver::new(2);
+        area_saver.queue(areas.clone());
+        assert_eq!(area_saver.flush(world_name.name), 2);
+        assert_eq!(area_saver.queued.len(), 1);
+
+        let taken = area_saver.take(area_locations[2]).unwrap();
+        assert!(taken.has_changed);
+        assert_areas_equal(&taken, &areas[2]);
+        assert_eq!(area_saver.queued.len(), 0);
+
+        area_saver.store_all_blocking(world_name.name);
+        for area in &areas[..2] {
+            let loaded = load_blocking(
+                area.get_area_location(),
+                world_name.name,
+                TEST_SEED,
+                TreeDensity::Normal,
+                WorldGenConfig::default(),
+            );
+            assert_areas_equal(&loaded, area);
+        }
+    }
+
     #[test]
     pub fn test_delete_world() {
         let world_name = "test_world_persistence_test_delete_world";
@@ -244,7 +432,14 @@ mod tests {
         let area_locations = [AreaLocation::new(0, 0), AreaLocation::new(1, 0)];
         let areas: Vec<_> = area_locations
             .into_iter()
-            .map(|loc| AreaGenerator::generate_area(loc, world_name))
+            .map(|loc| {
+                AreaGenerator::generate_area(
+                    loc,
+                    TEST_SEED,
+                    TreeDensity::Normal,
+                    WorldGenConfig::default(),
+                )
+            })
             .collect();
 
         store_all_blocking(areas.clone(), world_name.to_owned());
@@ -256,12 +451,141 @@ mod tests {
         assert!(!Path::new(&get_world_directory(world_name)).exists());
     }
 
+    /// expected hash of the uncompressed serialized areas, a change means existing saves may not load
+    const EXPECTED_SERIALIZED_HASH: u64 = 9096438980554947321;
+
+    /// a stable hash that does not depend on the standard library hasher implementation
+    fn fnv1a_hash(bytes: &[u8]) -> u64 {
+        bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+        })
+    }
+
+    const PATTERN_VOXELS: [Voxel; 9] = [
+        Voxel::None,
+        Voxel::Stone,
+        Voxel::Dirt,
+        Voxel::Grass,
+        Voxel::Sand,
+        Voxel::WaterSource,
+        Voxel::Glass,
+        Voxel::Lamp,
+        Voxel::Brick,
+    ];
+
+    /// creates an area with a fixed pattern that doesn't depend on world generation
+    fn create_patterned_area(area_location: AreaLocation) -> Area {
+        let mut area = Area::new(area_location);
+        for x in 0..AREA_SIZE {
+            for y in 0..AREA_SIZE {
+                let column_height = (x * 3 + y * 5 + area_location.x) % AREA_HEIGHT;
+                for z in column_height..AREA_HEIGHT {
+                    let index =
+                        (x * 7 + y * 13 + z * 3 + area_location.y) as usize % PATTERN_VOXELS.len();
+                    let voxel = PATTERN_VOXELS[index];
+                    area.set_without_updating_max_height(InternalLocation::new(x, y, z), voxel);
+                }
+            }
+        }
+        area.update_all_column_heights();
+
+        area
+    }
+
+    #[test]
+    pub fn test_area_serialization_round_trip() {
+        let area_locations = [
+            AreaLocation::new(0, 0),
+            AreaLocation::new(3, 1),
+            AreaLocation::new(62_500, 62_499),
+        ];
+        let mut serialized_bytes = vec![];
+        for area_location in area_locations {
+            let area = create_patterned_area(area_location);
+            let area_dto: AreaDTO = area.clone().into();
+            serialized_bytes.extend(encode_object(&area_dto, false).unwrap());
+
+            let encoded = encode_object(&area_dto, IS_COMPRESSED).unwrap();
+            let decoded: AreaDTO = decode_object(&encoded, IS_COMPRESSED, "test").unwrap();
+            let loaded_area = decoded.into_area(area_location, false);
+
+            assert_areas_equal(&area, &loaded_area);
+            for x in 0..AREA_SIZE {
+                for y in 0..AREA_SIZE {
+                    assert_eq!(area.sample_height(x, y), loaded_area.sample_height(x, y));
+                }
+            }
+        }
+
+        assert_eq!(fnv1a_hash(&serialized_bytes), EXPECTED_SERIALIZED_HASH);
+    }
+
+    #[test]
+    pub fn test_legacy_area_decodes_as_modified() {
+        let area_location = AreaLocation::new(3, 1);
+        let area = create_patterned_area(area_location);
+        let area_dto: AreaDTO = area.clone().into();
+        // areas saved before the generation version only contain the voxels
+        let legacy_bytes = encode_object(&area_dto.voxels, false).unwrap();
+
+        let decoded: AreaDTO = decode_object(&legacy_bytes, false, "test").unwrap();
+
+        assert_eq!(decoded.generation_version, 0);
+        assert!(decoded.is_modified);
+        assert_areas_equal(&area, &decoded.into_area(area_location, false));
+    }
+
+    #[test]
+    pub fn test_area_orientation_round_trip() {
+        let area_location = AreaLocation::new(3, 1);
+        let mut area = create_patterned_area(area_location);
+        area.set_with_orientation(
+            InternalLocation::new(2, 3, AREA_HEIGHT - 2),
+            Voxel::WoodStairs,
+            Orientation::PositiveX,
+        );
+        let area_dto: AreaDTO = area.clone().into();
+
+        let encoded = encode_object(&area_dto, IS_COMPRESSED).unwrap();
+        let decoded: AreaDTO = decode_object(&encoded, IS_COMPRESSED, "test").unwrap();
+        let loaded_area = decoded.into_area(area_location, false);
+
+        assert_areas_equal(&area, &loaded_area);
+        assert_eq!(area.calculate_checksum(), loaded_area.calculate_checksum());
+    }
+
+    #[test]
+    pub fn test_generated_area_serialization_round_trip() {
+        let area_location = AreaLocation::new(62_500, 62_500);
+        let area = AreaGenerator::generate_area(
+            area_location,
+            hash_world_name("test_generated_round_trip"),
+            TreeDensity::Normal,
+            WorldGenConfig::default(),
+        );
+        let area_dto: AreaDTO = area.clone().into();
+
+        let encoded = encode_object(&area_dto, IS_COMPRESSED).unwrap();
+        let decoded: AreaDTO = decode_object(&encoded, IS_COMPRESSED, "test").unwrap();
+        let loaded_area = decoded.into_area(area_location, false);
+
+        assert_areas_equal(&area, &loaded_area);
+        assert_eq!(
+            encode_object(&AreaDTO::from(loaded_area), false),
+            encode_object(&area_dto, false)
+        );
+    }
+
     fn assert_areas_equal(area1: &Area, area2: &Area) {
         for z in 0..AREA_HEIGHT {
             for y in 0..AREA_SIZE {
                 for x in 0..AREA_SIZE {
                     let local_location = InternalLocation::new(x, y, z);
                     assert_eq!(area1.get(local_location), area2.get(local_location));
+                    assert_eq!(
+                        area1.get_orientation(local_location),
+                        area2.get_orientation(local_location)
+                    );
                 }
             }
         }
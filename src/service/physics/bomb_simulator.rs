@@ -1,5 +1,3 @@
-use std::collections::HashSet;
-
 use macroquad::{
     camera::Camera3D,
     math::{Vec3, vec3},
@@ -32,6 +30,8 @@ const INITIAL_BOMB_VELOCITY: f32 = -4.0;
 const EXPLOSION_RADIUS: f32 = 4.5;
 const EXPLOSION_RADIUS_SQ: f32 = EXPLOSION_RADIUS * EXPLOSION_RADIUS;
 const EXPLOSION_DURATION_S: f32 = 0.2;
+/// solid voxels that aren't destroyed by explosions
+const EXPLOSION_RESISTANT: [Voxel; 2] = [Voxel::Iron, Voxel::Furnace];
 
 struct ActiveBomb {
     position: Vec3,
@@ -67,19 +67,20 @@ impl BombSimulator {
     }
 
     pub fn add_active_bomb(&mut self, location: Location) {
-        if self.active_bombs.len() >= MAX_ACTIVE_BOMBS {
-            return;
+        self.activate_bomb(location, BOMB_DELAY_S);
+    }
+
+    /// lights the placed bomb at the location, returns true if it was lit
+    pub fn light_placed_bomb(&mut self, location: Location, world: &mut World) -> bool {
+        if world.get(location) != Voxel::Bomb || !self.activate_bomb(location, BOMB_DELAY_S) {
+            return false;
         }
+        world.set(location, Voxel::None);
 
-        let bomb = ActiveBomb {
-            position: location.into(),
-            velocity: INITIAL_BOMB_VELOCITY,
-            life_s: BOMB_DELAY_S,
-        };
-        self.active_bombs.push(bomb);
+        true
     }
 
-    /// returns locations to be checked by other systems
+    /// returns the removed voxels and their locations to be checked by other systems
     pub fn update(
         &mut self,
         world: &mut World,
@@ -88,7 +89,7 @@ impl BombSimulator {
         asset_manager: &AssetManager,
         user_settings: &UserSettings,
         delta: f32,
-    ) -> HashSet<Location> {
+    ) -> Vec<(Location, Voxel)> {
         let mut explosion_at = vec![];
         for bomb in &mut self.active_bombs {
             Self::update_bomb(bomb, world, delta);
@@ -195,35 +196,45 @@ impl BombSimulator {
         world: &mut World,
         renderer: &mut Renderer,
         asset_manager: &AssetManager,
-    ) -> HashSet<Location> {
-        let mut locations_to_update = HashSet::new();
+    ) -> Vec<(Location, Voxel)> {
+        let mut removed = vec![];
         while !explosion_positions.is_empty() {
-            let updated_locations = self.explode_at(
+            let removed_by_explosion = self.explode_at(
                 explosion_positions
                     .pop()
                     .expect("Missing explosion location"),
                 world,
                 asset_manager,
             );
-            locations_to_update.extend(updated_locations);
+            removed.extend(removed_by_explosion);
         }
 
-        for loc in &locations_to_update {
+        for (loc, _) in &removed {
             renderer.update_location(world, *loc);
         }
 
-        locations_to_update
+        removed
     }
 
-    /// returns removed locations
+    /// returns the removed voxels and their locations
     fn explode_at(
         &mut self,
         position: Vec3,
         world: &mut World,
         asset_manager: &AssetManager,
-    ) -> Vec<Location> {
+    ) -> Vec<(Location, Voxel)> {
         self.explosions
             .push(Explosion::new(position, &asset_manager.mesh_manager));
+        self.remove_voxels_in_radius(position, world)
+    }
+
+    /// removes the destructible voxels in the explosion radius,
+    /// bombs in the radius are lit with a short fuse
+    fn remove_voxels_in_radius(
+        &mut self,
+        position: Vec3,
+        world: &mut World,
+    ) -> Vec<(Location, Voxel)> {
         let mut to_update = Vec::with_capacity(64);
 
         let cx = position.x.floor() as i32;
@@ -248,13 +259,14 @@ impl BombSimulator {
 
                     let loc = Location::new(x, y, z);
                     let voxel = world.get(loc);
-                    if voxel == Voxel::Bomb {
-                        self.add_active_bomb_from_explosion(loc);
-                    }
-
-                    if voxel.is_solid() {
+                    let is_removed = if voxel == Voxel::Bomb {
+                        self.activate_bomb(loc, SHORT_BOMB_DELAY_S)
+                    } else {
+                        Self::is_destructible(voxel)
+                    };
+                    if is_removed {
                         world.set(loc, Voxel::None);
-                        to_update.push(loc);
+                        to_update.push((loc, voxel));
                     }
                 }
             }
@@ -263,17 +275,23 @@ impl BombSimulator {
         to_update
     }
 
-    fn add_active_bomb_from_explosion(&mut self, location: Location) {
+    fn is_destructible(voxel: Voxel) -> bool {
+        voxel.is_solid() && !EXPLOSION_RESISTANT.contains(&voxel)
+    }
+
+    /// returns false if there are too many active bombs
+    fn activate_bomb(&mut self, location: Location, delay_s: f32) -> bool {
         if self.active_bombs.len() >= MAX_ACTIVE_BOMBS {
-            return;
+            return false;
         }
 
         let bomb = ActiveBomb {
             position: location.into(),
             velocity: INITIAL_BOMB_VELOCITY,
-            life_s: SHORT_BOMB_DELAY_S,
+            life_s: delay_s,
         };
         self.active_bombs.push(bomb);
+        true
     }
 
     fn animate_explosions(&mut self, delta: f32) {
@@ -285,3 +303,42 @@ impl BombSimulator {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::model::area::Area;
+
+    use super::*;
+
+    #[test]
+    fn test_explosion_chain_and_resistant_voxels() {
+        let center = Location::new(3, 3, 50);
+        let bomb_location = Location::new(5, 3, 50);
+        let iron_location = Location::new(3, 5, 50);
+        let far_location = Location::new(10, 3, 50);
+        let mut world = World::new("test_world_test_explosion_chain_and_resistant_voxels");
+        let mut area = Area::new(center.into());
+        for (location, voxel) in [
+            (center, Voxel::Stone),
+            (bomb_location, Voxel::Bomb),
+            (iron_location, Voxel::Iron),
+            (far_location, Voxel::Stone),
+        ] {
+            area.set(
+                World::convert_global_to_local_location(location.into()),
+                voxel,
+            );
+        }
+        world.return_area(area);
+
+        let mut bomb_simulator = BombSimulator::new();
+        let removed = bomb_simulator.remove_voxels_in_radius(center.into(), &mut world);
+
+        assert!(removed.contains(&(center, Voxel::Stone)));
+        assert!(removed.contains(&(bomb_location, Voxel::Bomb)));
+        assert_eq!(world.get(bomb_location), Voxel::None);
+        assert!(bomb_simulator.location_has_bomb(bomb_location));
+        assert_eq!(world.get(iron_location), Voxel::Iron);
+        assert_eq!(world.get(far_location), Voxel::Stone);
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::VecDeque;
+
 use bincode::{Decode, Encode};
 use macroquad::{
     camera::Camera3D,
@@ -7,14 +9,16 @@ use macroquad::{
 
 use crate::{
     graphics::{mesh_generator::MeshGenerator, renderer::Renderer},
-    model::{area::AREA_HEIGHT, location::Location, voxel::Voxel, world::World},
-    service::physics::water_simulator::WaterSimulator,
+    model::{area::AREA_HEIGHT, inventory::Item, location::Location, voxel::Voxel, world::World},
+    service::{dropped_items::DroppedItems, physics::water_simulator::WaterSimulator},
     utils::{StackVec, arr_to_vec3, vec3_to_arr, vector_to_location},
 };
 
 const MAX_FALL_SPEED: f32 = 3.0;
 const GRAVITY: f32 = 0.2;
 const VIEW_CULLING_COEFFICIENT: f32 = 0.7;
+/// when exceeded the oldest falling voxels are placed on the ground without simulating the fall
+pub const MAX_FALLING_VOXELS: usize = 256;
 
 struct SimulatedVoxel {
     voxel_type: Voxel,
@@ -52,7 +56,8 @@ pub struct SimulatedVoxelDTO {
 }
 
 pub struct FallingVoxelSimulator {
-    simulated_voxels: Vec<SimulatedVoxel>,
+    /// ordered from oldest to newest
+    simulated_voxels: VecDeque<SimulatedVoxel>,
 }
 impl FallingVoxelSimulator {
     pub fn new(
@@ -107,6 +112,7 @@ impl FallingVoxelSimulator {
         world: &mut World,
         renderer: &mut Renderer,
         water_simulator: &mut WaterSimulator,
+        dropped_items: &mut DroppedItems,
         location_to_check: Location,
     ) {
         let mut to_check = StackVec::new();
@@ -129,7 +135,12 @@ impl FallingVoxelSimulator {
             world.set(location, Voxel::None);
             renderer.update_location(world, location);
             water_simulator.location_updated(location);
-            self.simulated_voxels.push(SimulatedVoxel {
+            if self.simulated_voxels.len() >= MAX_FALLING_VOXELS
+                && let Some(oldest) = self.simulated_voxels.pop_front()
+            {
+                Self::settle_voxel(&oldest, world, renderer, water_simulator, dropped_items);
+            }
+            self.simulated_voxels.push_back(SimulatedVoxel {
                 voxel_type: voxel,
                 mesh: renderer
                     .get_mesh_generator()
@@ -144,7 +155,7 @@ impl FallingVoxelSimulator {
                 ..location
             };
             if up_location.z >= 0 && Voxel::FALLING.contains(&world.get(up_location)) {
-                self.update_voxels(world, renderer, water_simulator, up_location);
+                self.update_voxels(world, renderer, water_simulator, dropped_items, up_location);
             }
         }
     }
@@ -155,6 +166,7 @@ impl FallingVoxelSimulator {
         world: &mut World,
         renderer: &mut Renderer,
         water_simulator: &mut WaterSimulator,
+        dropped_items: &mut DroppedItems,
         delta: f32,
     ) {
         for voxel in &mut self.simulated_voxels {
@@ -166,8 +178,9 @@ impl FallingVoxelSimulator {
             });
         }
 
-        self.simulated_voxels
-            .retain(|voxel| Self::retain_or_place_voxel(voxel, world, renderer, water_simulator));
+        self.simulated_voxels.retain(|voxel| {
+            Self::retain_or_place_voxel(voxel, world, renderer, water_simulator, dropped_items)
+        });
     }
 
     pub fn draw(&self, camera: &Camera3D) {
@@ -182,6 +195,10 @@ impl FallingVoxelSimulator {
         }
     }
 
+    pub fn get_falling_voxel_count(&self) -> usize {
+        self.simulated_voxels.len()
+    }
+
     pub fn location_has_voxel(&self, location: Location) -> bool {
         self.simulated_voxels.iter().any(|voxel| {
             let voxel_location = vector_to_location(voxel.position);
@@ -196,12 +213,14 @@ impl FallingVoxelSimulator {
             .collect()
     }
 
-    /// returns true if the voxel should continue falling, otherwise returns false and places it in the world
+    /// returns true if the voxel should continue falling, otherwise returns false and places it
+    /// in the world, it is dropped as an item if it lands where a voxel has been placed
     fn retain_or_place_voxel(
         voxel: &SimulatedVoxel,
         world: &mut World,
         renderer: &mut Renderer,
         simulator: &mut WaterSimulator,
+        dropped_items: &mut DroppedItems,
     ) -> bool {
         let location = vector_to_location(voxel.position + vec3(0.0, 0.0, Voxel::HALF_SIZE));
         if location.z >= AREA_HEIGHT as i32 {
@@ -219,7 +238,9 @@ impl FallingVoxelSimulator {
             ..location
         };
         let up_voxel = world.get(up_location);
-        if !up_voxel.is_solid() {
+        if up_voxel.is_solid() {
+            Self::drop_as_item(voxel, renderer, dropped_items);
+        } else {
             world.set(up_location, voxel.voxel_type);
             renderer.update_location(world, up_location);
             simulator.location_updated(up_location);
@@ -228,6 +249,58 @@ impl FallingVoxelSimulator {
         false
     }
 
+    /// places the voxel on the ground directly below it,
+    /// it is dropped as an item if its location is already occupied
+    fn settle_voxel(
+        voxel: &SimulatedVoxel,
+        world: &mut World,
+        renderer: &mut Renderer,
+        water_simulator: &mut WaterSimulator,
+        dropped_items: &mut DroppedItems,
+    ) {
+        let Some(location) = Self::find_settle_location(world, vector_to_location(voxel.position))
+        else {
+            Self::drop_as_item(voxel, renderer, dropped_items);
+            return;
+        };
+        world.set(location, voxel.voxel_type);
+        renderer.update_location(world, location);
+        water_simulator.location_updated(location);
+    }
+
+    /// drops the voxel as an item where it is, used when there is nowhere to place it
+    fn drop_as_item(voxel: &SimulatedVoxel, renderer: &Renderer, dropped_items: &mut DroppedItems) {
+        dropped_items.add(
+            Item::new(voxel.voxel_type, 1),
+            voxel.position,
+            renderer.get_mesh_generator(),
+        );
+    }
+
+    /// returns the lowest empty location above the ground starting from the location,
+    /// none if the location is already occupied
+    fn find_settle_location(world: &mut World, location: Location) -> Option<Location> {
+        let mut location = Location {
+            z: location.z.clamp(0, AREA_HEIGHT as i32 - 1),
+            ..location
+        };
+        if world.get(location).is_solid() {
+            return None;
+        }
+        while location.z + 1 < AREA_HEIGHT as i32 {
+            let lower = Location {
+                z: location.z + 1,
+                ..location
+            };
+            if world.get(lower).is_solid() {
+                break;
+            }
+            location = lower;
+        }
+
+        Some(location)
+    }
+
     fn cull_voxels<'a, T>(
         iter: T,
         look: Vec3,
@@ -243,3 +316,37 @@ impl FallingVoxelSimulator {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::model::area::Area;
+
+    use super::*;
+
+    #[test]
+    fn test_find_settle_location() {
+        const GROUND_Z: i32 = 40;
+        let mut world = World::new("test_world_test_find_settle_location");
+        let mut area = Area::new(Location::new(0, 0, 0).into());
+        for z in GROUND_Z..AREA_HEIGHT as i32 {
+            area.set(
+                World::convert_global_to_local_location(Location::new(2, 2, z).into()),
+                Voxel::Sand,
+            );
+        }
+        world.return_area(area);
+
+        assert_eq!(
+            FallingVoxelSimulator::find_settle_location(&mut world, Location::new(2, 2, 10)),
+            Some(Location::new(2, 2, GROUND_Z - 1))
+        );
+        assert_eq!(
+            FallingVoxelSimulator::find_settle_location(&mut world, Location::new(2, 2, GROUND_Z)),
+            None
+        );
+        assert_eq!(
+            FallingVoxelSimulator::find_settle_location(&mut world, Location::new(3, 2, 10)),
+            Some(Location::new(3, 2, AREA_HEIGHT as i32 - 1))
+        );
+    }
+}
@@ -0,0 +1,229 @@
+use std::collections::HashMap;
+
+use macroquad::rand::gen_range;
+
+use crate::{
+    graphics::renderer::Renderer,
+    model::{location::InternalLocation, voxel::Voxel, world::World},
+    service::activity_timer::ActivityTimer,
+};
+
+/// time in seconds between fire updates
+const FIRE_CHECK_TIME: f32 = 0.5;
+/// time in seconds a fire burns before going out
+const BURN_TIME: f32 = 6.0;
+/// time in seconds a flammable voxel needs to be next to a fire before catching fire
+const IGNITE_TIME: f32 = 2.0;
+/// fires burn out this many times faster in the rain
+const RAIN_BURN_MULTIPLIER: f32 = 3.0;
+/// chance for a lightning strike on a flammable voxel to start a fire
+const LIGHTNING_IGNITE_CHANCE: f32 = 0.5;
+/// limits the number of fires updated per tick
+const MAX_FIRES: usize = 256;
+
+/// spreads fire to the flammable voxels next to it and burns them out
+pub struct FireSimulator {
+    activity_timer: ActivityTimer,
+    /// time left until each fire burns out
+    fires: HashMap<InternalLocation, f32>,
+    ignite_progress: HashMap<InternalLocation, f32>,
+}
+impl FireSimulator {
+    pub fn new() -> Self {
+        Self {
+            activity_timer: ActivityTimer::new(0.0, FIRE_CHECK_TIME),
+            fires: HashMap::new(),
+            ignite_progress: HashMap::new(),
+        }
+    }
+
+    /// has a chance to set the struck voxel on fire if it is flammable, returns true if it did
+    pub fn strike_lightning(&mut self, world: &mut World, location: InternalLocation) -> bool {
+        gen_range(0.0, 1.0) < LIGHTNING_IGNITE_CHANCE && self.ignite(world, location)
+    }
+
+    /// sets a flammable voxel on fire, returns true if it did
+    fn ignite(&mut self, world: &mut World, location: InternalLocation) -> bool {
+        let is_flammable = world
+            .get_without_loading(location)
+            .is_some_and(|voxel| Voxel::FLAMMABLE.contains(&voxel));
+        if is_flammable {
+            world.set(location, Voxel::Fire);
+            self.fires.insert(location, BURN_TIME);
+        }
+
+        is_flammable
+    }
+
+    /// returns the placed bombs next to a fire
+    pub fn find_bombs_near_fires(&self, world: &World) -> Vec<InternalLocation> {
+        self.fires
+            .keys()
+            .flat_map(|location| Renderer::get_neighbours(*location))
+            .filter(|neighbour| world.get_without_loading(*neighbour) == Some(Voxel::Bomb))
+            .collect()
+    }
+
+    /// burns the fires and spreads them, returns the locations that have changed,
+    /// fires in the light locations that aren't tracked (e.g. loaded from disk) start burning again
+    pub fn update(
+        &mut self,
+        world: &mut World,
+        light_locations: impl Iterator<Item = InternalLocation>,
+        is_raining: bool,
+        delta: f32,
+    ) -> Vec<InternalLocation> {
+        if !self.activity_timer.tick(delta) {
+            return vec![];
+        }
+
+        for location in light_locations {
+            if world.get_without_loading(location) == Some(Voxel::Fire) {
+                self.fires.entry(location).or_insert(BURN_TIME);
+            }
+        }
+        self.fires
+            .retain(|location, _| world.get_without_loading(*location) == Some(Voxel::Fire));
+
+        let burn_time = if is_raining {
+            FIRE_CHECK_TIME * RAIN_BURN_MULTIPLIER
+        } else {
+            FIRE_CHECK_TIME
+        };
+        let fires: Vec<_> = self
+            .fires
+            .iter()
+            .map(|(location, time_left)| (*location, *time_left))
+            .take(MAX_FIRES)
+            .collect();
+
+        let mut ignite_progress = HashMap::with_capacity(self.ignite_progress.len());
+        let mut changed = vec![];
+        for (location, time_left) in fires {
+            let time_left = time_left - burn_time;
+            if time_left <= 0.0 {
+                world.set(location, Voxel::None);
+                self.fires.remove(&location);
+                changed.push(location);
+                continue;
+            }
+            self.fires.insert(location, time_left);
+
+            for neighbour in Renderer::get_neighbours(location) {
+                let is_flammable = world
+                    .get_without_loading(neighbour)
+                    .is_some_and(|voxel| Voxel::FLAMMABLE.contains(&voxel));
+                if !is_flammable || ignite_progress.contains_key(&neighbour) {
+                    continue;
+                }
+
+                let progress =
+                    self.ignite_progress.get(&neighbour).copied().unwrap_or(0.0) + FIRE_CHECK_TIME;
+                if progress >= IGNITE_TIME && self.ignite(world, neighbour) {
+                    changed.push(neighbour);
+                } else {
+                    ignite_progress.insert(neighbour, progress);
+                }
+            }
+        }
+        self.ignite_progress = ignite_progress;
+
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::{area::Area, location::Location};
+
+    use super::*;
+
+    fn create_world(name: &str, voxels: &[(Location, Voxel)]) -> World {
+        let mut world = World::new(name);
+        let mut area = Area::new(voxels[0].0.into());
+        for (location, voxel) in voxels {
+            area.set(
+                World::convert_global_to_local_location((*location).into()),
+                *voxel,
+            );
+        }
+        world.return_area(area);
+        world
+    }
+
+    #[test]
+    fn test_fire_spreads_and_burns_out() {
+        let fire_location = Location::new(3, 3, 50);
+        let wood_location = Location::new(4, 3, 50);
+        let stone_location = Location::new(3, 4, 50);
+        let mut world = create_world(
+            "test_world_test_fire_spreads_and_burns_out",
+            &[
+                (fire_location, Voxel::Wood),
+                (wood_location, Voxel::Wood),
+                (stone_location, Voxel::Stone),
+            ],
+        );
+
+        let mut fire_simulator = FireSimulator::new();
+        assert!(fire_simulator.ignite(&mut world, fire_location.into()));
+        assert!(!fire_simulator.ignite(&mut world, stone_location.into()));
+        assert_eq!(world.get(fire_location), Voxel::Fire);
+
+        for _ in 0..((IGNITE_TIME / FIRE_CHECK_TIME) as usize - 1) {
+            let changed = fire_simulator.update(&mut world, [].into_iter(), false, FIRE_CHECK_TIME);
+            assert!(changed.is_empty());
+        }
+        let changed = fire_simulator.update(&mut world, [].into_iter(), false, FIRE_CHECK_TIME);
+        assert_eq!(changed, vec![InternalLocation::from(wood_location)]);
+        assert_eq!(world.get(wood_location), Voxel::Fire);
+        assert_eq!(world.get(stone_location), Voxel::Stone);
+
+        for _ in 0..((BURN_TIME / FIRE_CHECK_TIME) as usize) {
+            fire_simulator.update(&mut world, [].into_iter(), false, FIRE_CHECK_TIME);
+        }
+        assert_eq!(world.get(fire_location), Voxel::None);
+        assert_eq!(world.get(stone_location), Voxel::Stone);
+    }
+
+    #[test]
+    fn test_find_bombs_near_fires() {
+        let fire_location = Location::new(3, 3, 50);
+        let bomb_location = Location::new(3, 3, 49);
+        let mut world = create_world(
+            "test_world_test_find_bombs_near_fires",
+            &[
+                (fire_location, Voxel::Wood),
+                (bomb_location, Voxel::Bomb),
+                (Location::new(5, 3, 50), Voxel::Bomb),
+            ],
+        );
+
+        let mut fire_simulator = FireSimulator::new();
+        assert!(fire_simulator.find_bombs_near_fires(&world).is_empty());
+        fire_simulator.ignite(&mut world, fire_location.into());
+        assert_eq!(
+            fire_simulator.find_bombs_near_fires(&world),
+            vec![InternalLocation::from(bomb_location)]
+        );
+    }
+
+    #[test]
+    fn test_rain_extinguishes_faster() {
+        let fire_location = Location::new(3, 3, 50);
+        let mut world = create_world(
+            "test_world_test_rain_extinguishes_faster",
+            &[(fire_location, Voxel::Fire)],
+        );
+        let lights = [InternalLocation::from(fire_location)];
+
+        let mut fire_simulator = FireSimulator::new();
+        for _ in 0..((BURN_TIME / FIRE_CHECK_TIME / RAIN_BURN_MULTIPLIER) as usize - 1) {
+            fire_simulator.update(&mut world, lights.into_iter(), true, FIRE_CHECK_TIME);
+        }
+        assert_eq!(world.get(fire_location), Voxel::Fire);
+
+        fire_simulator.update(&mut world, lights.into_iter(), true, FIRE_CHECK_TIME);
+        assert_eq!(world.get(fire_location), Voxel::None);
+    }
+}
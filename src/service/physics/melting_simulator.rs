@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+use crate::{
+    graphics::renderer::Renderer,
+    model::{location::InternalLocation, voxel::Voxel, world::World},
+    service::activity_timer::ActivityTimer,
+};
+
+/// time in seconds between checks for melting voxels
+const MELT_CHECK_TIME: f32 = 1.0;
+/// time in seconds a voxel needs to be next to a light source before melting
+const MELT_TIME: f32 = 8.0;
+/// limits the number of light sources checked per tick
+const MAX_LIGHTS_CHECKED: usize = 256;
+
+/// melts snow and ice that are next to light sources
+pub struct MeltingSimulator {
+    activity_timer: ActivityTimer,
+    melt_progress: HashMap<InternalLocation, f32>,
+}
+impl MeltingSimulator {
+    pub fn new() -> Self {
+        Self {
+            activity_timer: ActivityTimer::new(0.0, MELT_CHECK_TIME),
+            melt_progress: HashMap::new(),
+        }
+    }
+
+    /// advances melting next to the lights, returns the locations that have changed
+    pub fn update(
+        &mut self,
+        world: &mut World,
+        light_locations: impl Iterator<Item = InternalLocation>,
+        delta: f32,
+    ) -> Vec<InternalLocation> {
+        if !self.activity_timer.tick(delta) {
+            return vec![];
+        }
+
+        let mut melt_progress = HashMap::with_capacity(self.melt_progress.len());
+        let mut melted = vec![];
+        for light_location in light_locations.take(MAX_LIGHTS_CHECKED) {
+            for neighbour in Renderer::get_neighbours(light_location) {
+                let Some(voxel) = world.get_without_loading(neighbour) else {
+                    continue;
+                };
+                let Some(melted_voxel) = Self::get_melted_voxel(voxel) else {
+                    continue;
+                };
+                if melt_progress.contains_key(&neighbour) {
+                    continue;
+                }
+
+                let progress =
+                    self.melt_progress.get(&neighbour).copied().unwrap_or(0.0) + MELT_CHECK_TIME;
+                if progress >= MELT_TIME {
+                    world.set(neighbour, melted_voxel);
+                    melted.push(neighbour);
+                } else {
+                    melt_progress.insert(neighbour, progress);
+                }
+            }
+        }
+        self.melt_progress = melt_progress;
+
+        melted
+    }
+
+    fn get_melted_voxel(voxel: Voxel) -> Option<Voxel> {
+        match voxel {
+            Voxel::Ice => Some(Voxel::WaterSource),
+            Voxel::Snow => Some(Voxel::None),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::{area::Area, location::Location};
+
+    use super::*;
+
+    #[test]
+    fn test_ice_next_to_lamp_melts() {
+        let lamp_location = Location::new(3, 3, 50);
+        let ice_location = Location::new(4, 3, 50);
+        let snow_location = Location::new(3, 3, 49);
+        let far_ice_location = Location::new(6, 3, 50);
+        let mut world = World::new("test_world_test_ice_next_to_lamp_melts");
+        let mut area = Area::new(lamp_location.into());
+        for (location, voxel) in [
+            (lamp_location, Voxel::Lamp),
+            (ice_location, Voxel::Ice),
+            (snow_location, Voxel::Snow),
+            (far_ice_location, Voxel::Ice),
+        ] {
+            area.set(
+                World::convert_global_to_local_location(location.into()),
+                voxel,
+            );
+        }
+        world.return_area(area);
+
+        let mut melting_simulator = MeltingSimulator::new();
+        let lights = [InternalLocation::from(lamp_location)];
+        for _ in 0..((MELT_TIME / MELT_CHECK_TIME) as usize - 1) {
+            let melted = melting_simulator.update(&mut world, lights.into_iter(), MELT_CHECK_TIME);
+            assert!(melted.is_empty());
+        }
+        assert_eq!(world.get(ice_location), Voxel::Ice);
+
+        let melted = melting_simulator.update(&mut world, lights.into_iter(), MELT_CHECK_TIME);
+        assert_eq!(melted.len(), 2);
+        assert_eq!(world.get(ice_location), Voxel::WaterSource);
+        assert_eq!(world.get(snow_location), Voxel::None);
+        assert_eq!(world.get(far_ice_location), Voxel::Ice);
+    }
+}
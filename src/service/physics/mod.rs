@@ -1,5 +1,7 @@
 pub mod bomb_simulator;
 pub mod falling_voxel_simulator;
+pub mod fire_simulator;
+pub mod melting_simulator;
 pub mod player_physics;
 pub mod voxel_simulator;
 pub mod water_simulator;
@@ -6,7 +6,10 @@ use macroquad::{
 };
 
 use crate::{
-    model::{area::Area, location::Location, player_info::PlayerInfo, voxel::Voxel, world::World},
+    model::{
+        area::Area, location::Location, player_info::PlayerInfo, user_settings::UserSettings,
+        voxel::Voxel, world::World,
+    },
     utils::{StackVec, vector_to_location},
 };
 
@@ -18,14 +21,21 @@ const MOVE_CHECKS: usize = 4;
 const MIN_VELOCITY_TO_BOUNCE: f32 = 1.5;
 const MAX_SWIM_SPEED: f32 = -25.0;
 const GAIN_SWIM_SPEED: f32 = -25.0;
+/// multiplies the swim speed gain while the head is under water, helps to reach the surface
+const SUBMERGED_SWIM_BOOST: f32 = 1.5;
 const IN_WATER_FALL_SPEED_MODIFIER: f32 = 0.2;
 const IN_WATER_MAX_FALL_SPEED: f32 = 15.0;
 const IN_WATER_MOVE_SPEED_MODIFIER: f32 = 0.5;
+/// fastest upward speed while the head is above the water and the legs are in it,
+/// keeps the player from launching out of the water but still allows climbing onto a ledge
+const SURFACE_MAX_RISE_SPEED: f32 = PlayerInfo::JUMP_VELOCITY;
 const BOTTOM_WALL_COLLISION_OFFSET: Vec3 = vec3(0.0, 0.0, -0.1);
 const MID_WALL_COLLISION_OFFSET: Vec3 = vec3(0.0, 0.0, -0.55);
 const HORIZONTAL_VELOCITY_LOSS: f32 = 3.0;
 const ICE_SLIDE: f32 = 20.0;
 const ICE_MAX_HORIZONTAL_VELOCITY: f32 = 5.0;
+/// from the camera to just below the feet, inside the voxels the player is standing on
+const STANDING_ON_OFFSET: Vec3 = vec3(0.0, 0.0, 1.6);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CollisionType {
@@ -38,7 +48,7 @@ pub enum CollisionType {
 pub fn push_player_up_if_stuck(player_info: &mut PlayerInfo, world: &mut World) {
     let down_position = player_info.camera_controller.get_position() + vec3(0.0, 0.0, 1.0);
     let down_location: Location = vector_to_location(down_position);
-    if !is_location_non_empty(down_location, world) {
+    if !is_location_non_empty(down_location, down_position.z, world) {
         return;
     }
 
@@ -71,7 +81,7 @@ pub fn process_collisions(
         |world, area| {
             for down_location in down_locations {
                 let voxel_hit = world.get_with_cache(down_location, Some(area));
-                if !voxel_hit.is_solid() {
+                if !is_blocking(voxel_hit, down_location, down_position.z) {
                     continue;
                 }
                 let is_bounce_collision =
@@ -85,20 +95,29 @@ pub fn process_collisions(
                     !player_info.is_in_water && player_info.velocity.z >= STRONG_COLLISION_SPEED;
                 if is_strong_collision {
                     collision_type = CollisionType::Strong { voxel: voxel_hit };
+                    if voxel_hit != Voxel::Trampoline && voxel_hit != Voxel::WaterSource {
+                        player_info
+                            .apply_fall_damage(player_info.velocity.z, STRONG_COLLISION_SPEED);
+                    }
                 }
                 player_info.velocity.z = 0.0;
 
-                // set player location 2 voxels up from the hit voxel
+                // set player location 2 voxels up from the hit voxel, slabs are half a voxel lower
+                let surface_offset = if Voxel::SLABS.contains(&voxel_hit) {
+                    Voxel::HALF_SIZE
+                } else {
+                    0.0
+                };
                 player_info.camera_controller.set_position(
                     vec3(top_position.x, top_position.y, down_location.z as f32)
-                        - vec3(0.0, 0.0, 2.0),
+                        - vec3(0.0, 0.0, 2.0 - surface_offset),
                 );
 
                 return collision_type;
             }
 
             for top_location in top_locations {
-                if is_location_non_empty_with_cache(top_location, world, area) {
+                if is_location_non_empty_with_cache(top_location, top_position.z, world, area) {
                     player_info.velocity.z = 0.0;
                     return CollisionType::Weak;
                 }
@@ -116,8 +135,14 @@ fn calculate_fall_velocity(player_info: &PlayerInfo, delta: f32) -> f32 {
     } else {
         (1.0, MAX_FALL_SPEED)
     };
+    let velocity = (player_info.velocity.z + GRAVITY * delta * water_modifier).min(max_fall_speed);
 
-    (player_info.velocity.z + GRAVITY * delta * water_modifier).min(max_fall_speed)
+    let is_at_water_surface = player_info.is_in_water && !player_info.is_head_in_water;
+    if is_at_water_surface {
+        velocity.max(SURFACE_MAX_RISE_SPEED)
+    } else {
+        velocity
+    }
 }
 
 fn should_bounce_from_trampoline(player_info: &mut PlayerInfo) -> bool {
@@ -144,7 +169,7 @@ pub fn try_jump(player_info: &mut PlayerInfo, world: &mut World) {
 
     let is_on_ground = down_locations
         .into_iter()
-        .any(|location| is_location_non_empty(location, world));
+        .any(|location| is_location_non_empty(location, bottom_voxel_position.z, world));
 
     if is_on_ground {
         player_info.velocity.z = PlayerInfo::JUMP_VELOCITY;
@@ -156,7 +181,12 @@ pub fn try_swim(player_info: &mut PlayerInfo, delta: f32) {
         return;
     }
 
-    player_info.velocity.z += delta * GAIN_SWIM_SPEED;
+    let boost = if player_info.is_head_in_water {
+        SUBMERGED_SWIM_BOOST
+    } else {
+        1.0
+    };
+    player_info.velocity.z += delta * GAIN_SWIM_SPEED * boost;
     player_info.velocity.z = player_info.velocity.z.max(MAX_SWIM_SPEED);
 }
 
@@ -185,11 +215,19 @@ pub fn handle_horizontal_player_movement(
     world: &mut World,
     move_dir: Vec3,
     delta: f32,
+    user_settings: &UserSettings,
+    wants_to_sprint: bool,
 ) {
     debug_assert!(move_dir.is_normalized() || move_dir == Vec3::ZERO);
 
+    // sprinting starts on the ground and keeps going through jumps
+    let can_sprint = !player_info.is_in_water
+        && !player_info.is_sneaking
+        && (player_info.is_sprinting()
+            || is_standing_on_ground(player_info.camera_controller.get_position(), world));
+    player_info.update_sprint(wants_to_sprint && can_sprint, delta);
     update_horizontal_player_velocity(player_info, world, move_dir, delta);
-    try_move(player_info, world, move_dir, delta);
+    try_move(player_info, world, move_dir, delta, user_settings.auto_step);
 }
 
 fn update_horizontal_player_velocity(
@@ -223,17 +261,37 @@ fn update_horizontal_player_velocity(
 }
 
 /// move and process horizontal collisions for the player, accepts a normalized or zero vector
-fn try_move(player_info: &mut PlayerInfo, world: &mut World, move_dir: Vec3, delta: f32) {
-    let displacement = delta
-        * (PlayerInfo::PLAYER_MOVE_SPEED * move_dir
-            + vec3(player_info.velocity.x, player_info.velocity.y, 0.0));
+fn try_move(
+    player_info: &mut PlayerInfo,
+    world: &mut World,
+    move_dir: Vec3,
+    delta: f32,
+    auto_step: bool,
+) {
+    let move_speed = if player_info.is_sneaking {
+        PlayerInfo::PLAYER_MOVE_SPEED * PlayerInfo::SNEAK_SPEED_MODIFIER
+    } else if player_info.is_sprinting() {
+        PlayerInfo::PLAYER_MOVE_SPEED * PlayerInfo::SPRINT_SPEED_MODIFIER
+    } else {
+        PlayerInfo::PLAYER_MOVE_SPEED
+    };
+    let displacement =
+        delta * (move_speed * move_dir + vec3(player_info.velocity.x, player_info.velocity.y, 0.0));
 
     let top_position = player_info.camera_controller.get_position();
     let bottom_position =
         player_info.camera_controller.get_bottom_position() + BOTTOM_WALL_COLLISION_OFFSET;
     let mid_position =
         player_info.camera_controller.get_bottom_position() + MID_WALL_COLLISION_OFFSET;
-    let modified_displacement = modify_displacement_in_water(displacement, player_info);
+    let mut modified_displacement = modify_displacement_in_water(displacement, player_info);
+    // the position picked after backing off from a collision is checked again for ground
+    let must_stay_on_ground = player_info.is_sneaking
+        && !player_info.is_in_water
+        && is_standing_on_ground(top_position, world);
+    if must_stay_on_ground {
+        modified_displacement =
+            limit_displacement_to_edge(player_info, world, modified_displacement);
+    }
     let mut top_displaced = top_position + modified_displacement;
     let bottom_displaced = bottom_position + modified_displacement;
     let mid_displaced = mid_position + modified_displacement;
@@ -257,14 +315,21 @@ fn try_move(player_info: &mut PlayerInfo, world: &mut World, move_dir: Vec3, del
 
         let any_collision =
             world.with_cached_area(vector_to_location(top_displaced), |world, cached_area| {
-                top_locations
-                    .into_iter()
-                    .chain(bottom_locations)
-                    .chain(mid_locations)
-                    .any(|location| is_location_non_empty_with_cache(location, world, cached_area))
+                [
+                    (top_locations, top_displaced.z),
+                    (bottom_locations, bottom_displaced.z),
+                    (mid_locations, mid_displaced.z),
+                ]
+                .into_iter()
+                .any(|(locations, position_z)| {
+                    locations.into_iter().any(|location| {
+                        is_location_non_empty_with_cache(location, position_z, world, cached_area)
+                    })
+                })
             });
 
-        if any_collision {
+        let leaves_ground = must_stay_on_ground && !is_standing_on_ground(top_displaced, world);
+        if any_collision || leaves_ground {
             top_displaced -= delta_displacement;
         } else {
             player_info.camera_controller.set_position(top_displaced);
@@ -273,9 +338,86 @@ fn try_move(player_info: &mut PlayerInfo, world: &mut World, move_dir: Vec3, del
         }
     }
 
+    if auto_step && try_step_up(player_info, world, top_position + modified_displacement) {
+        dampen_horizontal_velocity(player_info, delta);
+        return;
+    }
+
     reset_horizontal_velocity(player_info)
 }
 
+/// moves the player up onto a one voxel high ledge if there is enough headroom,
+/// returns true if the player stepped up
+fn try_step_up(player_info: &mut PlayerInfo, world: &mut World, top_displaced: Vec3) -> bool {
+    let is_on_ground = player_info
+        .camera_controller
+        .get_standing_on_location()
+        .is_some_and(|location| is_location_non_empty(location, location.z as f32, world));
+    if !is_on_ground || player_info.is_in_water {
+        return false;
+    }
+
+    let step_up = vec3(0.0, 0.0, -Voxel::SIZE);
+    let above_head = player_info.camera_controller.get_position() + step_up;
+    let stepped_top = top_displaced + step_up;
+    if stepped_top.z < 0.0 || above_head.z < 0.0 {
+        return false;
+    }
+    let stepped_bottom = stepped_top + vec3(0.0, 0.0, 1.5) + BOTTOM_WALL_COLLISION_OFFSET;
+    let stepped_mid = stepped_top + vec3(0.0, 0.0, 1.5) + MID_WALL_COLLISION_OFFSET;
+
+    let has_collision = [above_head, stepped_top, stepped_mid, stepped_bottom]
+        .into_iter()
+        .any(|position| {
+            let mut locations = StackVec::new();
+            find_locations_for_collisions(position, PlayerInfo::PLAYER_SIZE, &mut locations);
+            locations
+                .into_iter()
+                .any(|location| is_location_non_empty(location, position.z, world))
+        });
+    if has_collision {
+        return false;
+    }
+
+    player_info.camera_controller.set_position(stepped_top);
+    true
+}
+
+/// keeps a sneaking player on the ground from walking off the voxels they are standing on,
+/// sliding along the edge if only one of the axes would leave the ground
+fn limit_displacement_to_edge(
+    player_info: &PlayerInfo,
+    world: &mut World,
+    displacement: Vec3,
+) -> Vec3 {
+    let position = player_info.camera_controller.get_position();
+    debug_assert!(is_standing_on_ground(position, world));
+
+    [
+        displacement,
+        vec3(displacement.x, 0.0, 0.0),
+        vec3(0.0, displacement.y, 0.0),
+    ]
+    .into_iter()
+    .find(|displacement| is_standing_on_ground(position + *displacement, world))
+    .unwrap_or(Vec3::ZERO)
+}
+
+/// checks if any voxel under the player's footprint is solid
+fn is_standing_on_ground(position: Vec3, world: &mut World) -> bool {
+    let standing_on_position = position + STANDING_ON_OFFSET;
+    let mut locations = StackVec::new();
+    find_locations_for_collisions(
+        standing_on_position,
+        PlayerInfo::PLAYER_SIZE,
+        &mut locations,
+    );
+
+    locations
+        .into_iter()
+        .any(|location| is_location_non_empty(location, standing_on_position.z, world))
+}
+
 fn reset_horizontal_velocity(player_info: &mut PlayerInfo) {
     player_info.velocity.x = 0.0;
     player_info.velocity.y = 0.0;
@@ -295,16 +437,27 @@ fn modify_displacement_in_water(displacement: Vec3, player_info: &PlayerInfo) ->
     displacement * IN_WATER_MOVE_SPEED_MODIFIER
 }
 
-fn is_location_non_empty(location: Location, world: &mut World) -> bool {
-    world.get(location).is_solid()
+/// checks if the voxel at the location blocks a point at the height,
+/// slabs only block the lower half of their location
+fn is_blocking(voxel: Voxel, location: Location, position_z: f32) -> bool {
+    voxel.is_solid() && (!Voxel::SLABS.contains(&voxel) || position_z >= location.z as f32)
+}
+
+fn is_location_non_empty(location: Location, position_z: f32, world: &mut World) -> bool {
+    is_blocking(world.get(location), location, position_z)
 }
 
 fn is_location_non_empty_with_cache(
     location: Location,
+    position_z: f32,
     world: &mut World,
     cached_area: &Area,
 ) -> bool {
-    world.get_with_cache(location, Some(cached_area)).is_solid()
+    is_blocking(
+        world.get_with_cache(location, Some(cached_area)),
+        location,
+        position_z,
+    )
 }
 
 /// finds locations around the position that could cause collisions
@@ -355,8 +508,227 @@ fn find_locations_for_collisions(
 mod tests {
     use std::collections::HashSet;
 
+    use crate::{
+        model::area::AREA_HEIGHT,
+        service::raycast::{RaycastResult, cast_ray},
+    };
+
     use super::*;
 
+    const GROUND_Z: i32 = 12;
+
+    fn create_world_with_obstacle(world_name: &str, obstacle_height: i32) -> World {
+        let mut world = World::new(world_name);
+        let mut area = Area::new(Location::new(0, 0, 0).into());
+        for x in 0..8 {
+            for y in 0..3 {
+                for z in GROUND_Z..AREA_HEIGHT as i32 {
+                    area.set(
+                        World::convert_global_to_local_location(Location::new(x, y, z).into()),
+                        Voxel::Stone,
+                    );
+                }
+            }
+        }
+        for z in (GROUND_Z - obstacle_height)..GROUND_Z {
+            area.set(
+                World::convert_global_to_local_location(Location::new(4, 1, z).into()),
+                Voxel::Stone,
+            );
+        }
+        world.return_area(area);
+
+        world
+    }
+
+    fn walk_towards_obstacle(world: &mut World, auto_step: bool) -> PlayerInfo {
+        let mut player_info = PlayerInfo::new(vec3(2.0, 1.0, (GROUND_Z - 2) as f32));
+        for _ in 0..100 {
+            try_move(
+                &mut player_info,
+                world,
+                vec3(1.0, 0.0, 0.0),
+                0.02,
+                auto_step,
+            );
+        }
+
+        player_info
+    }
+
+    #[test]
+    fn test_rise_speed_clamped_at_water_surface() {
+        let mut player_info = PlayerInfo::new(vec3(0.0, 0.0, 10.0));
+        player_info.velocity.z = MAX_SWIM_SPEED;
+        player_info.is_in_water = true;
+        player_info.is_head_in_water = true;
+        assert!(calculate_fall_velocity(&player_info, 0.01) < SURFACE_MAX_RISE_SPEED);
+
+        player_info.is_head_in_water = false;
+        assert_eq!(
+            calculate_fall_velocity(&player_info, 0.01),
+            SURFACE_MAX_RISE_SPEED
+        );
+
+        player_info.velocity.z = -1.0;
+        assert!(calculate_fall_velocity(&player_info, 0.01) > -1.0);
+    }
+
+    #[test]
+    fn test_auto_step_one_voxel_ledge() {
+        let mut world = create_world_with_obstacle("test_world_test_auto_step_ledge", 1);
+        let player_info = walk_towards_obstacle(&mut world, true);
+        let position = player_info.camera_controller.get_position();
+
+        assert!(position.x > 4.0);
+        assert_eq!(position.z, (GROUND_Z - 3) as f32);
+    }
+
+    #[test]
+    fn test_auto_step_disabled() {
+        let mut world = create_world_with_obstacle("test_world_test_auto_step_disabled", 1);
+        let player_info = walk_towards_obstacle(&mut world, false);
+        let position = player_info.camera_controller.get_position();
+
+        assert!(position.x < 4.0);
+        assert_eq!(position.z, (GROUND_Z - 2) as f32);
+    }
+
+    #[test]
+    fn test_auto_step_blocked_by_wall() {
+        let mut world = create_world_with_obstacle("test_world_test_auto_step_wall", 2);
+        let player_info = walk_towards_obstacle(&mut world, true);
+        let position = player_info.camera_controller.get_position();
+
+        assert!(position.x < 4.0);
+        assert_eq!(position.z, (GROUND_Z - 2) as f32);
+    }
+
+    #[test]
+    fn test_sneaking_stops_at_edge() {
+        let mut world = create_world_with_obstacle("test_world_test_sneaking_stops_at_edge", 0);
+        let start_position = vec3(6.0, 1.0, (GROUND_Z - 2) as f32);
+        let mut player_info = PlayerInfo::new(start_position);
+        player_info.set_sneaking(true);
+        for _ in 0..200 {
+            try_move(
+                &mut player_info,
+                &mut world,
+                vec3(1.0, 0.0, 0.0),
+                0.02,
+                false,
+            );
+        }
+        let position = player_info.camera_controller.get_position();
+        assert!(position.x > 7.0);
+        assert!(position.x < 7.5 + PlayerInfo::PLAYER_SIZE);
+
+        player_info.set_sneaking(false);
+        try_move(
+            &mut player_info,
+            &mut world,
+            vec3(1.0, 0.0, 0.0),
+            0.1,
+            false,
+        );
+        let position = player_info.camera_controller.get_position();
+        assert!(position.x > 7.5 + PlayerInfo::PLAYER_SIZE);
+    }
+
+    #[test]
+    fn test_sneaking_back_off_stays_on_ground() {
+        // ground with a gap and a voxel at head height past it
+        let mut world = World::new("test_world_test_sneaking_back_off_stays_on_ground");
+        let mut area = Area::new(Location::new(0, 0, 0).into());
+        let mut set = |location: Location| {
+            area.set(
+                World::convert_global_to_local_location(location.into()),
+                Voxel::Stone,
+            );
+        };
+        for x in [0, 1, 2, 5] {
+            set(Location::new(x, 1, GROUND_Z));
+        }
+        set(Location::new(6, 1, GROUND_Z - 2));
+        world.return_area(area);
+
+        let mut player_info = PlayerInfo::new(vec3(2.3, 1.0, (GROUND_Z - 2) as f32));
+        player_info.set_sneaking(true);
+        assert!(is_standing_on_ground(
+            player_info.camera_controller.get_position(),
+            &mut world
+        ));
+
+        // a single step that lands on the ground past the gap with the head in the voxel,
+        // backing off from it must not stop the player over the gap
+        let delta = 3.0 / (PlayerInfo::PLAYER_MOVE_SPEED * PlayerInfo::SNEAK_SPEED_MODIFIER);
+        try_move(
+            &mut player_info,
+            &mut world,
+            vec3(1.0, 0.0, 0.0),
+            delta,
+            false,
+        );
+
+        let position = player_info.camera_controller.get_position();
+        assert!(is_standing_on_ground(position, &mut world));
+        assert!(position.x < 2.5 + PlayerInfo::PLAYER_SIZE);
+    }
+
+    #[test]
+    fn test_sneaking_player_can_place_against_edge() {
+        let mut world = create_world_with_obstacle("test_world_test_sneaking_place_edge", 0);
+        let mut player_info = PlayerInfo::new(vec3(6.0, 1.0, (GROUND_Z - 2) as f32));
+        player_info.set_sneaking(true);
+        for _ in 0..200 {
+            try_move(
+                &mut player_info,
+                &mut world,
+                vec3(1.0, 0.0, 0.0),
+                0.02,
+                false,
+            );
+        }
+
+        // looking down and back at the side of the voxel the player is standing on
+        let eye = player_info.camera_controller.create_camera().position;
+        let edge_side = vec3(7.5, 1.0, GROUND_Z as f32);
+        let placed_location = Location::new(8, 1, GROUND_Z);
+        let RaycastResult::Hit {
+            first_non_empty,
+            last_empty,
+        } = cast_ray(&mut world, eye, edge_side, 5.0)
+        else {
+            panic!("The edge should be in reach");
+        };
+        assert_eq!(first_non_empty, Location::new(7, 1, GROUND_Z));
+        assert_eq!(last_empty, placed_location);
+        assert!(!will_new_voxel_cause_collision(
+            &player_info,
+            placed_location
+        ));
+    }
+
+    #[test]
+    fn test_land_on_slab() {
+        let mut world = create_world_with_obstacle("test_world_test_land_on_slab", 0);
+        for x in 0..4 {
+            for y in 0..3 {
+                world.set(Location::new(x, y, GROUND_Z - 1), Voxel::StoneSlab);
+            }
+        }
+        let mut player_info = PlayerInfo::new(vec3(2.0, 1.0, (GROUND_Z - 5) as f32));
+        for _ in 0..100 {
+            process_collisions(&mut player_info, &mut world, 0.02);
+        }
+        let position = player_info.camera_controller.get_position();
+        assert_eq!(position.z, GROUND_Z as f32 - 1.0 - 2.0 + Voxel::HALF_SIZE);
+        assert!(is_standing_on_ground(position, &mut world));
+
+        try_jump(&mut player_info, &mut world);
+        assert_eq!(player_info.velocity.z, PlayerInfo::JUMP_VELOCITY);
+    }
+
     #[test]
     fn test_find_locations_for_collisions() {
         let mut area_locations = StackVec::new();
@@ -1,15 +1,23 @@
 use macroquad::{camera::Camera3D, math::Vec3};
 
 use crate::{
-    graphics::renderer::Renderer,
+    graphics::{mesh_generator::MeshGenerator, renderer::Renderer},
     model::{
-        location::Location, player_info::PlayerInfo, user_settings::UserSettings, world::World,
+        inventory::Item,
+        location::{InternalLocation, Location},
+        player_info::PlayerInfo,
+        user_settings::UserSettings,
+        voxel::Voxel,
+        world::World,
     },
     service::{
         asset_manager::AssetManager,
+        dropped_items::{DroppedItemDTO, DroppedItems},
         physics::{
             bomb_simulator::BombSimulator,
             falling_voxel_simulator::{FallingVoxelSimulator, SimulatedVoxelDTO},
+            fire_simulator::FireSimulator,
+            melting_simulator::MeltingSimulator,
             water_simulator::WaterSimulator,
         },
     },
@@ -19,16 +27,23 @@ pub struct VoxelSimulator {
     water_simulator: WaterSimulator,
     falling_voxel_simulator: FallingVoxelSimulator,
     bomb_simulator: BombSimulator,
+    melting_simulator: MeltingSimulator,
+    fire_simulator: FireSimulator,
+    dropped_items: DroppedItems,
 }
 impl VoxelSimulator {
     pub fn new(
         water_simulator: WaterSimulator,
         falling_voxel_simulator: FallingVoxelSimulator,
+        dropped_items: DroppedItems,
     ) -> Self {
         Self {
             water_simulator,
             falling_voxel_simulator,
             bomb_simulator: BombSimulator::new(),
+            melting_simulator: MeltingSimulator::new(),
+            fire_simulator: FireSimulator::new(),
+            dropped_items,
         }
     }
 
@@ -40,15 +55,16 @@ impl VoxelSimulator {
         asset_manager: &AssetManager,
         user_settings: &UserSettings,
         delta: f32,
-    ) {
+    ) -> Vec<(Location, Voxel)> {
         self.falling_voxel_simulator.simulate_falling(
             world,
             renderer,
             &mut self.water_simulator,
+            &mut self.dropped_items,
             delta,
         );
         self.water_simulator.update(world, renderer, delta);
-        let updated_locations = self.bomb_simulator.update(
+        let exploded = self.bomb_simulator.update(
             world,
             renderer,
             player_info,
@@ -56,8 +72,60 @@ impl VoxelSimulator {
             user_settings,
             delta,
         );
-        for loc in updated_locations {
-            self.update_location(loc, world, renderer);
+        for (loc, _) in &exploded {
+            self.update_location(*loc, world, renderer);
+        }
+        let melted_locations =
+            self.melting_simulator
+                .update(world, renderer.get_light_locations(), delta);
+        for loc in melted_locations {
+            renderer.update_location(world, loc);
+            self.update_location(loc.into(), world, renderer);
+        }
+        self.dropped_items.update(
+            world,
+            player_info,
+            user_settings.get_item_magnet_radius(),
+            delta,
+        );
+
+        exploded
+    }
+
+    /// spreads and burns out fires, they burn out faster in the rain,
+    /// the bombs next to them are lit
+    pub fn update_fires(
+        &mut self,
+        world: &mut World,
+        renderer: &mut Renderer,
+        is_raining: bool,
+        delta: f32,
+    ) {
+        let burnt_locations =
+            self.fire_simulator
+                .update(world, renderer.get_light_locations(), is_raining, delta);
+        for loc in burnt_locations {
+            renderer.update_location(world, loc);
+            self.update_location(loc.into(), world, renderer);
+        }
+        for loc in self.fire_simulator.find_bombs_near_fires(world) {
+            if self.bomb_simulator.light_placed_bomb(loc.into(), world) {
+                renderer.update_location(world, loc);
+                self.update_location(loc.into(), world, renderer);
+            }
+        }
+    }
+
+    /// may start a fire if the struck voxel is flammable
+    pub fn strike_lightning(
+        &mut self,
+        location: Location,
+        world: &mut World,
+        renderer: &mut Renderer,
+    ) {
+        let location = InternalLocation::from(location);
+        if self.fire_simulator.strike_lightning(world, location) {
+            renderer.update_location(world, location);
         }
     }
 
@@ -71,6 +139,7 @@ impl VoxelSimulator {
             world,
             renderer,
             &mut self.water_simulator,
+            &mut self.dropped_items,
             location,
         );
         self.water_simulator.location_updated(location);
@@ -80,6 +149,7 @@ impl VoxelSimulator {
     pub fn draw_for_voxel_shader(&self, camera: &Camera3D, renderer: &Renderer) {
         self.falling_voxel_simulator.draw(camera);
         self.bomb_simulator.draw_bombs(renderer);
+        self.dropped_items.draw(camera);
     }
 
     /// returns a vector of explosion locations
@@ -87,19 +157,28 @@ impl VoxelSimulator {
         self.bomb_simulator.draw_explosions(camera)
     }
 
+    pub fn get_falling_voxel_count(&self) -> usize {
+        self.falling_voxel_simulator.get_falling_voxel_count()
+    }
+
     pub fn location_is_empty(&self, location: Location) -> bool {
         self.falling_voxel_simulator.location_has_voxel(location)
             || self.bomb_simulator.location_has_bomb(location)
     }
 
-    pub fn create_dtos(&self) -> (Vec<SimulatedVoxelDTO>, WaterSimulator) {
+    pub fn create_dtos(&self) -> (Vec<SimulatedVoxelDTO>, WaterSimulator, Vec<DroppedItemDTO>) {
         (
             self.falling_voxel_simulator.create_simulated_voxel_dtos(),
             self.water_simulator.clone(),
+            self.dropped_items.create_dtos(),
         )
     }
 
     pub fn add_bomb(&mut self, location: Location) {
         self.bomb_simulator.add_active_bomb(location);
     }
+
+    pub fn drop_item(&mut self, item: Item, position: Vec3, mesh_generator: &MeshGenerator) {
+        self.dropped_items.add(item, position, mesh_generator);
+    }
 }
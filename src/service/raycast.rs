@@ -16,14 +16,34 @@ pub enum RaycastResult {
     },
 }
 
-fn is_hit(world: &mut World, position: Location) -> bool {
-    let voxel = world.get(position);
-
+fn is_hit(voxel: Voxel) -> bool {
     voxel != Voxel::None && !Voxel::WATER.contains(&voxel)
 }
 
-/// DDA raycasting
+/// DDA raycasting, passes through water
 pub fn cast_ray(world: &mut World, from: Vec3, to: Vec3, max_distance: f32) -> RaycastResult {
+    cast_ray_until(world, from, to, max_distance, is_hit)
+}
+
+/// like `cast_ray`, but stops at water sources
+pub fn cast_ray_for_water_source(
+    world: &mut World,
+    from: Vec3,
+    to: Vec3,
+    max_distance: f32,
+) -> RaycastResult {
+    cast_ray_until(world, from, to, max_distance, |voxel| {
+        is_hit(voxel) || voxel == Voxel::WaterSource
+    })
+}
+
+fn cast_ray_until(
+    world: &mut World,
+    from: Vec3,
+    to: Vec3,
+    max_distance: f32,
+    is_hit: impl Fn(Voxel) -> bool,
+) -> RaycastResult {
     let ray = (to - from).normalize_or_zero();
     if ray == Vec3::ZERO {
         return RaycastResult::NoneHit;
@@ -81,7 +101,7 @@ pub fn cast_ray(world: &mut World, from: Vec3, to: Vec3, max_distance: f32) -> R
     if current_position.z < 0 || current_position.z >= AREA_HEIGHT as i32 {
         return RaycastResult::NoneHit;
     }
-    if is_hit(world, current_position) {
+    if is_hit(world.get(current_position)) {
         return RaycastResult::Hit {
             first_non_empty: current_position,
             last_empty: previous_position,
@@ -117,7 +137,7 @@ pub fn cast_ray(world: &mut World, from: Vec3, to: Vec3, max_distance: f32) -> R
             return RaycastResult::NoneHit;
         }
 
-        if is_hit(world, current_position) {
+        if is_hit(world.get(current_position)) {
             return RaycastResult::Hit {
                 first_non_empty: current_position,
                 last_empty: previous_position,
@@ -168,6 +188,36 @@ mod tests {
         assert!(matches!(result3, RaycastResult::NoneHit));
     }
 
+    #[test]
+    fn test_cast_ray_for_water_source() {
+        let world_name = "test_world_test_cast_ray_for_water_source";
+        let mut world = World::new(world_name);
+
+        let water_location = Location::new(5, 5, 8);
+        let mut area = Area::new(water_location.into());
+        area.set(
+            World::convert_global_to_local_location(water_location.into()),
+            Voxel::WaterSource,
+        );
+        world.return_area(area);
+
+        let result1 = cast_ray(&mut world, vec3(5.0, 5.0, 1.0), vec3(5.0, 5.0, 10.0), 9.0);
+        assert!(matches!(result1, RaycastResult::NoneHit));
+
+        let result2 =
+            cast_ray_for_water_source(&mut world, vec3(5.0, 5.0, 1.0), vec3(5.0, 5.0, 10.0), 9.0);
+        match result2 {
+            RaycastResult::Hit {
+                first_non_empty,
+                last_empty,
+            } => {
+                assert_eq!(first_non_empty, water_location);
+                assert_eq!(last_empty, Location::new(5, 5, 7));
+            }
+            _ => panic!("should be hit"),
+        }
+    }
+
     #[test]
     fn test_cast_ray_out_of_height() {
         let world_name = "test_world_test_cast_ray_out_of_height";
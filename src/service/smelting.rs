@@ -0,0 +1,393 @@
+use std::collections::HashMap;
+
+use bincode::{Decode, Encode};
+
+use crate::{
+    model::{
+        inventory::{HotbarLocks, Inventory, Item, MAX_ITEMS_PER_SLOT},
+        location::{InternalLocation, Location},
+        voxel::Voxel,
+        world::World,
+    },
+    service::activity_timer::ActivityTimer,
+};
+
+/// time in seconds to smelt a single item
+pub const SMELT_TIME: f32 = 4.0;
+
+/// input voxel and the item it smelts into
+const SMELTING_RECIPES: [(Voxel, Item); 4] = [
+    (Voxel::Sand, Item::new_c(Voxel::Glass, 1)),
+    (Voxel::IronOre, Item::new_c(Voxel::Iron, 1)),
+    (Voxel::Cobblestone, Item::new_c(Voxel::Stone, 1)),
+    (Voxel::Clay, Item::new_c(Voxel::Brick, 1)),
+];
+
+/// fuel voxel and the number of items it smelts
+const FUELS: [(Voxel, u32); 3] = [(Voxel::CoalOre, 8), (Voxel::Wood, 2), (Voxel::Boards, 1)];
+
+pub fn get_smelting_output(input: Voxel) -> Option<Item> {
+    SMELTING_RECIPES
+        .iter()
+        .find(|(recipe_input, _)| *recipe_input == input)
+        .map(|(_, output)| *output)
+}
+
+/// voxels that can be put into the input slot
+pub fn get_smeltable_voxels() -> impl Iterator<Item = Voxel> {
+    SMELTING_RECIPES.iter().map(|(input, _)| *input)
+}
+
+pub fn get_fuel_voxels() -> impl Iterator<Item = Voxel> {
+    FUELS.iter().map(|(fuel, _)| *fuel)
+}
+
+/// returns the number of items a single fuel voxel smelts
+pub fn get_fuel_value(fuel: Voxel) -> Option<u32> {
+    FUELS
+        .iter()
+        .find(|(fuel_voxel, _)| *fuel_voxel == fuel)
+        .map(|(_, value)| *value)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FurnaceSlot {
+    Input,
+    Fuel,
+    Output,
+}
+
+/// the contents of a placed furnace, items smelt over time while there is fuel
+#[derive(Debug, Clone, Copy, Encode, Decode)]
+pub struct Furnace {
+    pub input: Option<Item>,
+    pub fuel: Option<Item>,
+    pub output: Option<Item>,
+    smelt_timer: ActivityTimer,
+    /// items that can still be smelted with the already burnt fuel
+    burning_fuel: u32,
+}
+impl Furnace {
+    pub fn new() -> Self {
+        Self {
+            input: None,
+            fuel: None,
+            output: None,
+            smelt_timer: ActivityTimer::new(0.0, SMELT_TIME),
+            burning_fuel: 0,
+        }
+    }
+
+    pub fn get_slot(&self, slot: FurnaceSlot) -> Option<Item> {
+        match slot {
+            FurnaceSlot::Input => self.input,
+            FurnaceSlot::Fuel => self.fuel,
+            FurnaceSlot::Output => self.output,
+        }
+    }
+
+    pub fn set_slot(&mut self, slot: FurnaceSlot, item: Option<Item>) {
+        match slot {
+            FurnaceSlot::Input => self.input = item,
+            FurnaceSlot::Fuel => self.fuel = item,
+            FurnaceSlot::Output => self.output = item,
+        }
+    }
+
+    /// moves as many of the voxel from the inventory into the input or fuel slot as fit,
+    /// nothing is moved if the slot has a different voxel
+    pub fn fill_slot(
+        &mut self,
+        slot: FurnaceSlot,
+        voxel: Voxel,
+        inventory: &mut Inventory,
+        hotbar_locks: HotbarLocks,
+    ) {
+        debug_assert_ne!(slot, FurnaceSlot::Output);
+        let current_count = match self.get_slot(slot) {
+            Some(current) if current.voxel != voxel => return,
+            Some(current) => current.count,
+            None => 0,
+        };
+        let available = inventory.create_unlocked_items_map(hotbar_locks).get(voxel);
+        let amount = available.min((MAX_ITEMS_PER_SLOT - current_count) as u32) as u8;
+        if amount == 0 {
+            return;
+        }
+
+        inventory.remove_unlocked_item(Item::new(voxel, amount), hotbar_locks);
+        self.set_slot(slot, Some(Item::new(voxel, current_count + amount)));
+    }
+
+    /// moves the items in the slot to the inventory if they all fit
+    pub fn empty_slot(&mut self, slot: FurnaceSlot, inventory: &mut Inventory) {
+        let Some(item) = self.get_slot(slot) else {
+            return;
+        };
+        if inventory.can_add_item(item) {
+            inventory.add_item(item);
+            self.set_slot(slot, None);
+        }
+    }
+
+    /// progress of the current item from 0 to 1
+    pub fn get_progress(&self) -> f32 {
+        (self.smelt_timer.get_delta() / SMELT_TIME).clamp(0.0, 1.0)
+    }
+
+    pub fn is_burning(&self) -> bool {
+        self.burning_fuel > 0
+    }
+
+    fn is_empty(&self) -> bool {
+        self.input.is_none() && self.fuel.is_none() && self.output.is_none() && !self.is_burning()
+    }
+
+    /// returns the output of the next smelted item if there is an input and room for it
+    fn find_next_output(&self) -> Option<Item> {
+        let output = get_smelting_output(self.input?.voxel)?;
+        match self.output {
+            None => Some(output),
+            Some(current) if current.can_stack_with(&output) => {
+                (current.count + output.count <= MAX_ITEMS_PER_SLOT).then_some(output)
+            }
+            Some(_) => None,
+        }
+    }
+
+    /// burns a fuel item if the previous one has run out, returns false if there is no fuel
+    fn refuel(&mut self) -> bool {
+        if self.burning_fuel > 0 {
+            return true;
+        }
+        let Some((fuel, value)) = self
+            .fuel
+            .and_then(|fuel| get_fuel_value(fuel.voxel).map(|value| (fuel, value)))
+        else {
+            return false;
+        };
+
+        self.fuel = Self::reduce(fuel);
+        self.burning_fuel = value;
+        true
+    }
+
+    fn reduce(item: Item) -> Option<Item> {
+        (item.count > 1).then_some(Item {
+            count: item.count - 1,
+            ..item
+        })
+    }
+
+    pub fn update(&mut self, delta: f32) {
+        let Some(output) = self.find_next_output() else {
+            self.smelt_timer.reset();
+            return;
+        };
+        if !self.refuel() {
+            self.smelt_timer.reset();
+            return;
+        }
+        if !self.smelt_timer.tick(delta) {
+            return;
+        }
+
+        self.input = self.input.and_then(Self::reduce);
+        self.output = Some(match self.output {
+            Some(current) => Item {
+                count: current.count + output.count,
+                ..current
+            },
+            None => output,
+        });
+        self.burning_fuel -= 1;
+    }
+
+    /// returns the items left in the furnace
+    pub fn take_contents(self) -> impl Iterator<Item = Item> {
+        [self.input, self.fuel, self.output].into_iter().flatten()
+    }
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct FurnaceDTO {
+    location: InternalLocation,
+    furnace: Furnace,
+}
+
+/// the placed furnaces that have been used, furnaces without a state are empty
+pub struct Furnaces {
+    furnaces: HashMap<InternalLocation, Furnace>,
+}
+impl Furnaces {
+    pub fn new() -> Self {
+        Self {
+            furnaces: HashMap::new(),
+        }
+    }
+
+    pub fn from_dtos(dtos: Vec<FurnaceDTO>) -> Self {
+        Self {
+            furnaces: dtos
+                .into_iter()
+                .map(|dto| (dto.location, dto.furnace))
+                .collect(),
+        }
+    }
+
+    pub fn create_dtos(&self) -> Vec<FurnaceDTO> {
+        self.furnaces
+            .iter()
+            .map(|(location, furnace)| FurnaceDTO {
+                location: *location,
+                furnace: *furnace,
+            })
+            .collect()
+    }
+
+    pub fn get_mut(&mut self, location: Location) -> &mut Furnace {
+        self.furnaces
+            .entry(location.into())
+            .or_insert_with(Furnace::new)
+    }
+
+    /// removes the furnace at the location and returns its items
+    pub fn remove(&mut self, location: Location) -> Vec<Item> {
+        self.furnaces
+            .remove(&location.into())
+            .map(|furnace| furnace.take_contents().collect())
+            .unwrap_or_default()
+    }
+
+    /// smelts the items in all furnaces, empty furnaces and the furnaces in loaded areas
+    /// that no longer exist are dropped, returns the items left in the removed furnaces
+    pub fn update(&mut self, world: &World, delta: f32) -> Vec<(Location, Item)> {
+        let removed_contents = self
+            .furnaces
+            .extract_if(|location, furnace| {
+                furnace.is_empty()
+                    || world
+                        .get_without_loading(*location)
+                        .is_some_and(|voxel| voxel != Voxel::Furnace)
+            })
+            .flat_map(|(location, furnace)| {
+                furnace
+                    .take_contents()
+                    .map(move |item| (location.into(), item))
+            })
+            .collect();
+        for furnace in self.furnaces.values_mut() {
+            furnace.update(delta);
+        }
+
+        removed_contents
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::area::Area;
+
+    use super::*;
+
+    #[test]
+    fn test_furnace_smelts_with_fuel() {
+        let mut furnace = Furnace::new();
+        furnace.input = Some(Item::new(Voxel::Sand, 3));
+        furnace.update(SMELT_TIME);
+        assert!(furnace.output.is_none());
+
+        furnace.fuel = Some(Item::new(Voxel::Wood, 1));
+        furnace.update(SMELT_TIME * 0.5);
+        assert!(furnace.fuel.is_none());
+        assert!(furnace.is_burning());
+        assert!(furnace.output.is_none());
+        assert_eq!(furnace.get_progress(), 0.5);
+
+        furnace.update(SMELT_TIME * 0.5);
+        assert_eq!(furnace.output, Some(Item::new(Voxel::Glass, 1)));
+        assert_eq!(furnace.input, Some(Item::new(Voxel::Sand, 2)));
+
+        for _ in 0..3 {
+            furnace.update(SMELT_TIME);
+        }
+        assert_eq!(furnace.output, Some(Item::new(Voxel::Glass, 2)));
+        assert_eq!(furnace.input, Some(Item::new(Voxel::Sand, 1)));
+        assert!(!furnace.is_burning());
+    }
+
+    #[test]
+    fn test_furnace_stops_when_output_blocked() {
+        let mut furnace = Furnace::new();
+        furnace.input = Some(Item::new(Voxel::IronOre, 5));
+        furnace.fuel = Some(Item::new(Voxel::CoalOre, 1));
+        furnace.output = Some(Item::new(Voxel::Stone, 1));
+        furnace.update(SMELT_TIME);
+
+        assert_eq!(furnace.input, Some(Item::new(Voxel::IronOre, 5)));
+        assert_eq!(furnace.fuel, Some(Item::new(Voxel::CoalOre, 1)));
+        assert_eq!(furnace.get_progress(), 0.0);
+    }
+
+    #[test]
+    fn test_fill_and_empty_slot() {
+        let mut inventory = Inventory::default();
+        inventory.selected[0] = Some(Item::new(Voxel::Sand, 60));
+        inventory.items[0] = Some(Item::new(Voxel::Sand, 60));
+        let mut furnace = Furnace::new();
+        furnace.fill_slot(
+            FurnaceSlot::Input,
+            Voxel::Sand,
+            &mut inventory,
+            HotbarLocks::default(),
+        );
+        assert_eq!(
+            furnace.input,
+            Some(Item::new(Voxel::Sand, MAX_ITEMS_PER_SLOT))
+        );
+        assert_eq!(inventory.create_all_items_map().get(Voxel::Sand), 20);
+
+        furnace.fill_slot(
+            FurnaceSlot::Input,
+            Voxel::Clay,
+            &mut inventory,
+            HotbarLocks::default(),
+        );
+        assert_eq!(furnace.input.unwrap().voxel, Voxel::Sand);
+
+        furnace.empty_slot(FurnaceSlot::Input, &mut inventory);
+        assert!(furnace.input.is_none());
+        assert_eq!(inventory.create_all_items_map().get(Voxel::Sand), 120);
+    }
+
+    #[test]
+    fn test_remove_returns_contents() {
+        let location = Location::new(1, 2, 3);
+        let mut furnaces = Furnaces::new();
+        furnaces.get_mut(location).input = Some(Item::new(Voxel::Clay, 4));
+        furnaces.get_mut(location).output = Some(Item::new(Voxel::Brick, 2));
+
+        let dtos = furnaces.create_dtos();
+        let mut furnaces = Furnaces::from_dtos(dtos);
+        assert_eq!(
+            furnaces.remove(location),
+            vec![Item::new(Voxel::Clay, 4), Item::new(Voxel::Brick, 2)]
+        );
+        assert!(furnaces.remove(location).is_empty());
+    }
+
+    #[test]
+    fn test_update_returns_contents_of_removed_furnace() {
+        let location = Location::new(1, 2, 3);
+        let mut world = World::new("test_world_test_update_returns_contents");
+        world.return_area(Area::new(location.into()));
+        let mut furnaces = Furnaces::new();
+        furnaces.get_mut(location).input = Some(Item::new(Voxel::Clay, 4));
+
+        assert_eq!(
+            furnaces.update(&world, 0.0),
+            vec![(location, Item::new(Voxel::Clay, 4))]
+        );
+        assert!(furnaces.create_dtos().is_empty());
+    }
+}
@@ -1,7 +1,10 @@
 use std::collections::HashMap;
 
 use macroquad::{
-    audio::{PlaySoundParams, Sound, load_sound, play_sound, play_sound_once, stop_sound},
+    audio::{
+        PlaySoundParams, Sound, load_sound, play_sound, play_sound_once, set_sound_volume,
+        stop_sound,
+    },
     prelude::{error, info},
 };
 
@@ -21,26 +24,34 @@ pub enum SoundId {
     Click,
     Bounce,
     Thunder,
-    Music,
     Explosion,
     LightFuse,
 }
 
 const BASE_SOUNDS_PATH: &str = "assets/sounds/";
-const SOUNDS: [(SoundId, &str); 9] = [
+const SOUNDS: [(SoundId, &str); 8] = [
     (SoundId::Fall, "fall.wav"),
     (SoundId::Destroy, "destroy.wav"),
     (SoundId::Place, "place.wav"),
     (SoundId::Click, "click.wav"),
     (SoundId::Bounce, "bounce.wav"),
     (SoundId::Thunder, "thunder.wav"),
-    (SoundId::Music, "music.ogg"),
     (SoundId::Explosion, "explosion.wav"),
     (SoundId::LightFuse, "light_fuse.wav"),
 ];
 
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum MusicTrackId {
+    Ambient,
+}
+
+/// music tracks in the order they are cycled through with their length in seconds
+pub const MUSIC_TRACKS: [(MusicTrackId, &str, f32); 1] =
+    [(MusicTrackId::Ambient, "music.ogg", 40.4)];
+
 pub struct SoundManager {
     sounds: HashMap<SoundId, Sound>,
+    music_tracks: HashMap<MusicTrackId, Sound>,
 }
 impl SoundManager {
     /// loads sounds from files
@@ -62,8 +73,26 @@ impl SoundManager {
             }
         }
 
+        let mut music_tracks = HashMap::new();
+        for (id, path, _length) in MUSIC_TRACKS {
+            let full_path = format!("{BASE_SOUNDS_PATH}{path}");
+            match load_sound(&full_path).await {
+                Ok(sound) => {
+                    info!("Loaded music track with id {:?} from '{}'", id, path);
+                    music_tracks.insert(id, sound);
+                }
+                Err(err) => {
+                    error!("Failed to load '{path}':{}", err);
+                    errors.push(AssetError::MissingSound { path: full_path });
+                }
+            }
+        }
+
         if errors.is_empty() {
-            Ok(Self { sounds })
+            Ok(Self {
+                sounds,
+                music_tracks,
+            })
         } else {
             Err(AssetLoadingErrors::new(errors))
         }
@@ -81,31 +110,32 @@ impl SoundManager {
         }
     }
 
-    /// starts or stops the in-game music based on user settings
-    pub fn start_or_stop_music(&self, user_settings: &UserSettings) {
-        if !user_settings.has_sound {
-            self.stop_music();
-            return;
-        }
-
-        if let Some(sound) = self.sounds.get(&SoundId::Music) {
+    /// plays the music track once, the music player decides when the next one starts
+    pub fn play_music_track(&self, track_id: MusicTrackId, volume: f32) {
+        if let Some(sound) = self.music_tracks.get(&track_id) {
             play_sound(
                 sound,
                 PlaySoundParams {
-                    looped: true,
-                    ..Default::default()
+                    looped: false,
+                    volume,
                 },
             );
         } else {
-            error!("Failed to find sound for {:?}", SoundId::Music)
+            error!("Failed to find music track for {:?}", track_id)
+        }
+    }
+
+    pub fn set_music_track_volume(&self, track_id: MusicTrackId, volume: f32) {
+        if let Some(sound) = self.music_tracks.get(&track_id) {
+            set_sound_volume(sound, volume);
+        } else {
+            error!("Failed to find music track for {:?}", track_id)
         }
     }
 
     pub fn stop_music(&self) {
-        if let Some(sound) = self.sounds.get(&SoundId::Music) {
+        for sound in self.music_tracks.values() {
             stop_sound(sound);
-        } else {
-            error!("Failed to find sound for {:?}", SoundId::Music)
         }
     }
 
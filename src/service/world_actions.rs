@@ -1,6 +1,6 @@
 use std::rc::Rc;
 
-use macroquad::math::vec3;
+use macroquad::math::{Vec3, vec3};
 
 use crate::{
     graphics::{
@@ -9,11 +9,15 @@ use crate::{
     },
     interface::tutorial_messages::TutorialMessages,
     model::{
-        area::AREA_HEIGHT, location::Location, player_info::PlayerInfo, voxel::Voxel, world::World,
+        area::AREA_HEIGHT, location::Location, orientation::Orientation, player_info::PlayerInfo,
+        voxel::Voxel, world::World, world_rules::WorldRules,
     },
     service::{
+        area_generation::generator::hash_world_name,
         asset_manager::AssetManager,
         creatures::creature_manager::CreatureManager,
+        dropped_items::DroppedItems,
+        edit_tools::VoxelEdit,
         persistence::{
             player_persistence::load_player_info, world_metadata_persistence::load_world_metadata,
         },
@@ -22,6 +26,9 @@ use crate::{
             player_physics::will_new_voxel_cause_collision, voxel_simulator::VoxelSimulator,
             water_simulator::WaterSimulator,
         },
+        raycast::RaycastResult,
+        smelting::Furnaces,
+        world_statistics::WorldStatistics,
         world_time::WorldTime,
     },
     utils::vector_to_location,
@@ -30,56 +37,187 @@ use crate::{
 pub fn place_voxel(
     location: Location,
     voxel: Voxel,
+    orientation: Orientation,
     player_info: &PlayerInfo,
     world: &mut World,
     renderer: &mut Renderer,
     voxel_simulator: &mut VoxelSimulator,
-    creature_manager: &CreatureManager,
 ) -> bool {
     debug_assert!(voxel != Voxel::None);
-    let unable_to_place_voxel = world.get(location).is_solid()
+    let unable_to_place_voxel = !voxel.is_placeable()
+        || world.get(location).is_solid()
         || will_new_voxel_cause_collision(player_info, location)
-        || voxel_simulator.location_is_empty(location)
-        || !creature_manager.check_can_place_voxel(location);
+        || voxel_simulator.location_is_empty(location);
 
     if unable_to_place_voxel {
         return false;
     }
 
-    world.set(location, voxel);
+    world.set_with_orientation(location, voxel, orientation);
     renderer.update_location(world, location);
     voxel_simulator.update_location(location, world, renderer);
 
     true
 }
 
+/// checks that the player, a creature or a simulated voxel isn't in the way of a new voxel
+pub fn is_location_free(
+    location: Location,
+    player_info: &PlayerInfo,
+    voxel_simulator: &VoxelSimulator,
+    creature_manager: &CreatureManager,
+) -> bool {
+    !will_new_voxel_cause_collision(player_info, location)
+        && !voxel_simulator.location_is_empty(location)
+        && creature_manager.check_can_place_voxel(location)
+}
+
+/// the orientation of the voxel placed at the location against the looked at voxel,
+/// directional voxels are laid along the axis of the clicked face,
+/// stairs go up towards the clicked side or away from the player when placed on top, below
+/// or in place of the looked at voxel
+pub fn find_placement_orientation(
+    voxel: Voxel,
+    location: Location,
+    placed_against: Location,
+    look_direction: Vec3,
+) -> Orientation {
+    if Voxel::DIRECTIONAL.contains(&voxel) {
+        return match Orientation::from_placement(location, placed_against) {
+            Orientation::Down => Orientation::Up,
+            orientation => orientation,
+        };
+    }
+    if voxel != Voxel::WoodStairs {
+        return Orientation::Up;
+    }
+
+    let orientation = Orientation::from_placement(location, placed_against);
+    if orientation.is_horizontal() {
+        orientation
+    } else {
+        Orientation::from_horizontal_direction(look_direction)
+    }
+}
+
 pub fn replace_voxel(
     location: Location,
     voxel: Voxel,
+    orientation: Orientation,
     world: &mut World,
     renderer: &mut Renderer,
     voxel_simulator: &mut VoxelSimulator,
 ) -> Option<Voxel> {
     debug_assert!(voxel != Voxel::None);
     let to_be_replaced = world.get(location);
-    if !to_be_replaced.is_solid() || location.z == AREA_HEIGHT as i32 - 1 || to_be_replaced == voxel
+    if !voxel.is_placeable()
+        || !to_be_replaced.is_solid()
+        || location.z == AREA_HEIGHT as i32 - 1
+        || to_be_replaced == voxel
     {
         return None;
     }
 
-    world.set(location, voxel);
+    world.set_with_orientation(location, voxel, orientation);
     renderer.update_location(world, location);
     voxel_simulator.update_location(location, world, renderer);
 
     Some(to_be_replaced)
 }
 
+/// sets many voxels at once and re-meshes the affected locations in a single batch,
+/// returns the changed locations
+pub fn apply_edits(
+    edits: &[VoxelEdit],
+    world: &mut World,
+    renderer: &mut Renderer,
+    voxel_simulator: &mut VoxelSimulator,
+) -> Vec<Location> {
+    let mut changed = Vec::with_capacity(edits.len());
+    for (location, voxel) in edits {
+        if location.z < 0 || location.z >= AREA_HEIGHT as i32 - 1 {
+            continue;
+        }
+        if world.get(*location) == *voxel || voxel_simulator.location_is_empty(*location) {
+            continue;
+        }
+        world.set(*location, *voxel);
+        changed.push(*location);
+    }
+
+    renderer.update_locations(world, &changed);
+    for location in &changed {
+        voxel_simulator.update_location(*location, world, renderer);
+    }
+
+    changed
+}
+
+/// creates the edits for a column of voxels going down from the start location,
+/// stops at the first solid or blocked location or after max_count voxels
+pub fn create_column_edits(
+    start: Location,
+    voxel: Voxel,
+    max_count: u32,
+    world: &mut World,
+    can_place: impl Fn(Location) -> bool,
+) -> Vec<VoxelEdit> {
+    debug_assert!(voxel != Voxel::None);
+    let mut edits = vec![];
+    if !voxel.is_placeable() {
+        return edits;
+    }
+    let mut location = start;
+    while edits.len() < max_count as usize
+        && location.z < AREA_HEIGHT as i32 - 1
+        && !world.get(location).is_solid()
+        && can_place(location)
+    {
+        edits.push((location, voxel));
+        location.z += 1;
+    }
+
+    edits
+}
+
+/// returns the location of the looked at furnace, interacting with it opens the furnace menu
+pub fn find_interacted_furnace(
+    raycast_result: RaycastResult,
+    world: &mut World,
+) -> Option<Location> {
+    match raycast_result {
+        RaycastResult::Hit {
+            first_non_empty,
+            last_empty: _,
+        } if world.get(first_non_empty) == Voxel::Furnace => Some(first_non_empty),
+        _ => None,
+    }
+}
+
 pub enum DestroyActionEvent {
     None,
     GainVoxel(Voxel),
     StartBomb(Location),
 }
 
+/// removes the water source at the location to fill a bucket, returns true if there was one
+pub fn take_water_source(
+    location: Location,
+    world: &mut World,
+    renderer: &mut Renderer,
+    voxel_simulator: &mut VoxelSimulator,
+) -> bool {
+    if world.get(location) != Voxel::WaterSource {
+        return false;
+    }
+
+    world.set(location, Voxel::None);
+    renderer.update_location(world, location);
+    voxel_simulator.update_location(location, world, renderer);
+
+    true
+}
+
 pub fn destroy_voxel(
     location: Location,
     world: &mut World,
@@ -98,6 +236,8 @@ pub fn destroy_voxel(
 
     if voxel == Voxel::Bomb {
         DestroyActionEvent::StartBomb(location)
+    } else if voxel == Voxel::Fire {
+        DestroyActionEvent::None
     } else {
         voxel_particles.add_particles_for_destroyed(voxel, location, renderer.get_mesh_generator());
         DestroyActionEvent::GainVoxel(voxel)
@@ -125,54 +265,85 @@ pub struct WorldSystems {
     pub sky: Sky,
     pub tutorial_messages: TutorialMessages,
     pub rain_system: RainSystem,
+    pub statistics: WorldStatistics,
+    pub rules: WorldRules,
+    pub furnaces: Furnaces,
 }
 
-/// loads the saved world data or initialises it if not saved
+/// loads the saved world data or initialises it if not saved,
+/// the rules and seed are only given for new worlds, existing worlds without loaded metadata
+/// keep the default rules and the world name hash as the seed they were generated with
 pub fn initialise_world_systems(
     world_name: impl Into<String>,
     asset_manager: Rc<AssetManager>,
+    new_world_rules: Option<WorldRules>,
+    new_world_seed: Option<u64>,
 ) -> WorldSystems {
     let world_name = world_name.into();
     let (mut player_info, successful_load) = load_player_info(&world_name)
         .map(|info| (info, true))
-        .unwrap_or_else(|| (PlayerInfo::new(vec3(0.0, 0.0, 0.0)), false));
+        .unwrap_or_else(|| (PlayerInfo::new(PlayerInfo::SPAWN_LOCATION.into()), false));
 
     player_info.camera_controller.set_focus(true);
     let (
         world_time,
         simulated_voxels,
         water_simulator,
+        dropped_items,
         creature_manager,
         sky,
         tutorial_messages,
         rain_system,
+        statistics,
+        rules,
+        seed,
+        furnaces,
     ) = if let Some(world_metadata) = load_world_metadata(&world_name) {
+        let seed = world_metadata.get_seed(&world_name);
         (
-            WorldTime::new(world_metadata.delta),
+            WorldTime::new(world_metadata.delta)
+                .with_day(world_metadata.day)
+                .with_day_length(world_metadata.day_length)
+                .with_frozen(world_metadata.is_time_frozen),
             world_metadata.simulated_voxels,
             world_metadata.water_simulator,
+            world_metadata.dropped_items,
             CreatureManager::from_dto(world_metadata.creature_manager, &asset_manager.mesh_manager),
             Sky::from_dto(&asset_manager.texture_manager, world_metadata.sky_dto),
             world_metadata.tutorial_messages_dto.into(),
             RainSystem::from_dto(world_metadata.rain_system, &asset_manager.texture_manager),
+            world_metadata.statistics,
+            world_metadata.rules,
+            seed,
+            Furnaces::from_dtos(world_metadata.furnaces),
         )
     } else {
         (
             WorldTime::new(std::f32::consts::PI * 0.5),
             vec![],
             WaterSimulator::new(),
+            vec![],
             CreatureManager::new(),
             Sky::new(&asset_manager.texture_manager),
             TutorialMessages::new(),
             RainSystem::new(&asset_manager.texture_manager),
+            WorldStatistics::new(),
+            new_world_rules.unwrap_or_default(),
+            new_world_seed.unwrap_or_else(|| hash_world_name(&world_name)),
+            Furnaces::new(),
         )
     };
 
     let renderer = Renderer::new(asset_manager.clone());
     let falling_voxel_simulator =
         FallingVoxelSimulator::new(simulated_voxels, renderer.get_mesh_generator());
-    let voxel_simulator = VoxelSimulator::new(water_simulator, falling_voxel_simulator);
-    let mut world = World::new(world_name);
+    let dropped_items = DroppedItems::new(dropped_items, renderer.get_mesh_generator());
+    let voxel_simulator =
+        VoxelSimulator::new(water_simulator, falling_voxel_simulator, dropped_items);
+    let mut world = World::new(world_name)
+        .with_seed(seed)
+        .with_tree_density(rules.tree_density)
+        .with_gen_config(rules.world_gen);
 
     if !successful_load {
         put_player_on_ground(&mut player_info, &mut world);
@@ -188,15 +359,32 @@ pub fn initialise_world_systems(
         player_info,
         tutorial_messages,
         rain_system,
+        statistics,
+        rules,
+        furnaces,
     }
 }
 
-fn put_player_on_ground(player_info: &mut PlayerInfo, world: &mut World) {
+/// moves the player back to the surface at the spawn with full health
+pub fn respawn_player(player_info: &mut PlayerInfo, world: &mut World, renderer: &mut Renderer) {
+    player_info
+        .camera_controller
+        .set_position(PlayerInfo::SPAWN_LOCATION.into());
+    player_info.velocity = Vec3::ZERO;
+    player_info.restore_health();
+    player_info.restore_oxygen();
+    if let Some(replaced_location) = put_player_on_ground(player_info, world) {
+        renderer.update_location(world, replaced_location);
+    }
+}
+
+/// returns the location of the water replaced under the player
+fn put_player_on_ground(player_info: &mut PlayerInfo, world: &mut World) -> Option<Location> {
     loop {
         let bottom_position = player_info.camera_controller.get_bottom_position();
         let bottom_location = vector_to_location(bottom_position);
         if bottom_location.z + 1 >= AREA_HEIGHT as i32 {
-            return;
+            return None;
         }
         let voxel = world.get(bottom_location);
 
@@ -207,8 +395,9 @@ fn put_player_on_ground(player_info: &mut PlayerInfo, world: &mut World) {
 
             if Voxel::WATER.contains(&voxel) {
                 world.set(bottom_location, Voxel::Stone);
+                return Some(bottom_location);
             }
-            return;
+            return None;
         } else {
             player_info
                 .camera_controller
@@ -216,3 +405,54 @@ fn put_player_on_ground(player_info: &mut PlayerInfo, world: &mut World) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::model::area::Area;
+
+    use super::*;
+
+    #[test]
+    fn test_create_column_edits() {
+        let ground = Location::new(2, 2, 60);
+        let mut world = World::new("test_world_test_create_column_edits");
+        let mut area = Area::new(ground.into());
+        area.set(
+            World::convert_global_to_local_location(ground.into()),
+            Voxel::Stone,
+        );
+        world.return_area(area);
+        let start = Location::new(2, 2, 55);
+
+        let edits = create_column_edits(start, Voxel::Brick, 10, &mut world, |_| true);
+        let expected: Vec<_> = (55..60)
+            .map(|z| (Location::new(2, 2, z), Voxel::Brick))
+            .collect();
+        assert_eq!(edits, expected);
+
+        let edits = create_column_edits(start, Voxel::Brick, 2, &mut world, |_| true);
+        assert_eq!(edits.len(), 2);
+
+        let edits = create_column_edits(start, Voxel::Brick, 10, &mut world, |location| {
+            location.z < 57
+        });
+        assert_eq!(edits.len(), 2);
+    }
+
+    #[test]
+    fn test_find_placement_orientation() {
+        let location = Location::new(5, 5, 40);
+        let look_direction = vec3(0.2, -0.9, 0.0);
+        let on_side = Location::new(4, 5, 40);
+        let on_top = Location::new(5, 5, 41);
+
+        let find = |voxel, placed_against| {
+            find_placement_orientation(voxel, location, placed_against, look_direction)
+        };
+        assert_eq!(find(Voxel::StonePillar, on_side), Orientation::NegativeX);
+        assert_eq!(find(Voxel::Wood, on_top), Orientation::Up);
+        assert_eq!(find(Voxel::WoodStairs, on_side), Orientation::NegativeX);
+        assert_eq!(find(Voxel::WoodStairs, on_top), Orientation::NegativeY);
+        assert_eq!(find(Voxel::Stone, on_side), Orientation::Up);
+    }
+}
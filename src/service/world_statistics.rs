@@ -0,0 +1,88 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bincode::{Decode, Encode};
+
+const SECONDS_IN_DAY: u64 = 24 * 60 * 60;
+
+/// general information about a world shown before entering it
+#[derive(Debug, Clone, Copy, Encode, Decode)]
+pub struct WorldStatistics {
+    /// seconds since the unix epoch
+    creation_time: u64,
+    /// total time spent in the world in seconds
+    play_time: f32,
+}
+impl WorldStatistics {
+    pub fn new() -> Self {
+        let creation_time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+
+        Self {
+            creation_time,
+            play_time: 0.0,
+        }
+    }
+
+    pub fn update(&mut self, delta: f32) {
+        self.play_time += delta;
+    }
+
+    /// returns the play time formatted as hours and minutes
+    pub fn format_play_time(&self) -> String {
+        let total_minutes = (self.play_time / 60.0) as u64;
+        let hours = total_minutes / 60;
+        let minutes = total_minutes % 60;
+
+        format!("{hours}h {minutes}m")
+    }
+
+    /// returns the creation date formatted as YYYY-MM-DD
+    pub fn format_creation_date(&self) -> String {
+        let (year, month, day) = civil_from_days((self.creation_time / SECONDS_IN_DAY) as i64);
+
+        format!("{year:04}-{month:02}-{day:02}")
+    }
+}
+
+/// converts days since the unix epoch to a (year, month, day) date
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_civil_from_days() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(59), (1970, 3, 1));
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+        assert_eq!(civil_from_days(19_723), (2024, 1, 1));
+    }
+
+    #[test]
+    fn test_format_play_time() {
+        let mut world_statistics = WorldStatistics::new();
+        world_statistics.update(2.0 * 3600.0 + 5.0 * 60.0 + 30.0);
+
+        assert_eq!(world_statistics.format_play_time(), "2h 5m");
+    }
+}
@@ -1,13 +1,38 @@
-use std::f32::consts::PI;
+use std::f32::consts::{PI, TAU};
+
+use macroquad::{
+    logging::info,
+    math::{Vec3, vec3},
+};
 
 use crate::graphics::rain_system::RainLightLevelModifier;
 
-const LENGTH_OF_DAY: f32 = 200.0;
+/// seconds for a full day and night cycle
+pub const DEFAULT_DAY_LENGTH: f32 = 600.0;
 const LIGHT_LEVEL_COEF: f32 = -10.0;
 
+/// height of the sun at sunrise and sunset, the middle of the light level transition
+const TWILIGHT_SUN_HEIGHT: f32 = 0.5;
+/// how far from the twilight sun height the warm tint fades out
+const TWILIGHT_WIDTH: f32 = 0.25;
+const DAY_AMBIENT_COLOR: Vec3 = vec3(1.0, 1.0, 1.0);
+const TWILIGHT_AMBIENT_COLOR: Vec3 = vec3(1.0, 0.78, 0.55);
+const NIGHT_AMBIENT_COLOR: Vec3 = vec3(0.7, 0.8, 1.0);
+
+/// number of days from one full moon to the next
+const MOON_CYCLE_DAYS: u32 = 8;
+/// how much the night light floor changes between a new and a full moon
+const MOON_LIGHT_VARIATION: f32 = 0.04;
+
 pub struct WorldTime {
     delta: f32,
     light: f32,
+    /// number of full days passed, used for the moon phase
+    day: u32,
+    /// seconds for a full day and night cycle
+    day_length: f32,
+    /// time doesn't pass, the sun and the light level stay where they are
+    is_frozen: bool,
 }
 impl WorldTime {
     pub const MAX_LIGHT_LEVEL: f32 = 1.0;
@@ -17,12 +42,34 @@ impl WorldTime {
         Self {
             delta,
             light: Self::to_light_level(delta),
+            day: 0,
+            day_length: DEFAULT_DAY_LENGTH,
+            is_frozen: false,
         }
     }
 
+    pub fn with_day(mut self, day: u32) -> Self {
+        self.day = day;
+        self
+    }
+
+    pub fn with_day_length(mut self, day_length: f32) -> Self {
+        self.set_day_length(day_length);
+        self
+    }
+
+    pub fn with_frozen(mut self, is_frozen: bool) -> Self {
+        self.is_frozen = is_frozen;
+        self
+    }
+
     pub fn update(&mut self, delta: f32) {
-        self.delta += delta / LENGTH_OF_DAY;
-        self.delta %= PI;
+        if self.is_frozen {
+            return;
+        }
+        let total = self.delta + delta * PI / self.day_length;
+        self.day = self.day.wrapping_add((total / PI) as u32);
+        self.delta = total % PI;
         self.light = Self::to_light_level(self.delta);
     }
 
@@ -30,16 +77,77 @@ impl WorldTime {
         self.delta
     }
 
+    pub fn get_day(&self) -> u32 {
+        self.day
+    }
+
+    pub fn get_day_length(&self) -> f32 {
+        self.day_length
+    }
+
+    /// invalid lengths, e.g. from an old save, fall back to the default
+    pub fn set_day_length(&mut self, day_length: f32) {
+        self.day_length = if day_length.is_finite() && day_length > 0.0 {
+            day_length
+        } else {
+            DEFAULT_DAY_LENGTH
+        };
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.is_frozen
+    }
+
+    pub fn toggle_frozen(&mut self) {
+        self.is_frozen = !self.is_frozen;
+        info!("Time frozen:{}", self.is_frozen);
+    }
+
+    /// progress through the moon cycle in [0, 1), 0 is a new moon and 0.5 is a full moon
+    pub fn get_moon_phase(&self) -> f32 {
+        ((self.day % MOON_CYCLE_DAYS) as f32 + self.delta / PI) / MOON_CYCLE_DAYS as f32
+    }
+
+    /// visible part of the moon, 0 for a new moon and 1 for a full moon
+    pub fn get_moon_illumination(&self) -> f32 {
+        (1.0 - (self.get_moon_phase() * TAU).cos()) * 0.5
+    }
+
+    /// returns true if the sun is up
+    pub fn is_day(&self) -> bool {
+        self.light >= (Self::MAX_LIGHT_LEVEL + Self::MIN_LIGHT_LEVEL) * 0.5
+    }
+
     pub fn get_light_level(&self, rain_light_level_modifier: RainLightLevelModifier) -> f32 {
         match rain_light_level_modifier {
             RainLightLevelModifier::Multiply(x) => self.light * x,
             RainLightLevelModifier::Set(x) => x,
         }
-        .clamp(Self::MIN_LIGHT_LEVEL, Self::MAX_LIGHT_LEVEL)
+        .clamp(self.get_night_light_level(), Self::MAX_LIGHT_LEVEL)
+    }
+
+    /// the lowest light level, brighter during a full moon
+    fn get_night_light_level(&self) -> f32 {
+        Self::MIN_LIGHT_LEVEL + MOON_LIGHT_VARIATION * (self.get_moon_illumination() - 0.5)
+    }
+
+    /// tint for the whole scene, warm at sunrise and sunset, cool at night and neutral at midday
+    pub fn get_ambient_color(&self) -> Vec3 {
+        Self::to_ambient_color(self.delta.sin())
+    }
+
+    fn to_ambient_color(sun_height: f32) -> Vec3 {
+        let night_amount = (1.0 - sun_height / TWILIGHT_SUN_HEIGHT).clamp(0.0, 1.0);
+        let twilight_amount =
+            1.0 - ((sun_height - TWILIGHT_SUN_HEIGHT).abs() / TWILIGHT_WIDTH).clamp(0.0, 1.0);
+
+        DAY_AMBIENT_COLOR
+            .lerp(NIGHT_AMBIENT_COLOR, night_amount)
+            .lerp(TWILIGHT_AMBIENT_COLOR, twilight_amount)
     }
 
     fn to_light_level(delta: f32) -> f32 {
-        sigmoid(delta.sin(), LIGHT_LEVEL_COEF).clamp(Self::MIN_LIGHT_LEVEL, Self::MAX_LIGHT_LEVEL)
+        sigmoid(delta.sin(), LIGHT_LEVEL_COEF).clamp(0.0, Self::MAX_LIGHT_LEVEL)
     }
 }
 
@@ -71,6 +179,71 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_ambient_color() {
+        assert_eq!(
+            WorldTime::new(PI / 2.0).get_ambient_color(),
+            DAY_AMBIENT_COLOR
+        );
+        assert_eq!(WorldTime::new(0.0).get_ambient_color(), NIGHT_AMBIENT_COLOR);
+        assert_eq!(
+            WorldTime::to_ambient_color(TWILIGHT_SUN_HEIGHT),
+            TWILIGHT_AMBIENT_COLOR
+        );
+
+        let darkest = NIGHT_AMBIENT_COLOR.min(TWILIGHT_AMBIENT_COLOR);
+        for i in 0..=100 {
+            let color = WorldTime::to_ambient_color(i as f32 / 100.0);
+            assert!(color.cmple(DAY_AMBIENT_COLOR).all());
+            assert!(color.cmpge(darkest).all());
+        }
+    }
+
+    #[test]
+    fn test_moon_phase_follows_days() {
+        let mut world_time = WorldTime::new(0.0);
+        assert_eq!(world_time.get_moon_phase(), 0.0);
+        assert_eq!(world_time.get_moon_illumination(), 0.0);
+
+        world_time.update(DEFAULT_DAY_LENGTH * 1.5);
+        assert_eq!(world_time.get_day(), 1);
+
+        let full_moon = WorldTime::new(0.0).with_day(MOON_CYCLE_DAYS / 2);
+        assert_eq!(full_moon.get_moon_phase(), 0.5);
+        assert_eq!(full_moon.get_moon_illumination(), 1.0);
+        assert_eq!(
+            WorldTime::new(0.0)
+                .with_day(MOON_CYCLE_DAYS)
+                .get_moon_phase(),
+            0.0
+        );
+
+        let new_moon_night = WorldTime::new(0.0);
+        let no_modifier = RainLightLevelModifier::Multiply(1.0);
+        assert!(
+            full_moon.get_light_level(no_modifier) > new_moon_night.get_light_level(no_modifier)
+        );
+    }
+
+    #[test]
+    fn test_frozen_time_and_day_length() {
+        let mut world_time = WorldTime::new(0.0).with_day_length(100.0);
+        world_time.update(50.0);
+        assert!((world_time.get_delta() - PI * 0.5).abs() < 0.0001);
+
+        world_time.toggle_frozen();
+        let light = world_time.get_light_level(RainLightLevelModifier::Multiply(1.0));
+        world_time.update(30.0);
+        assert!((world_time.get_delta() - PI * 0.5).abs() < 0.0001);
+        assert_eq!(
+            world_time.get_light_level(RainLightLevelModifier::Multiply(1.0)),
+            light
+        );
+
+        world_time.set_day_length(f32::NAN);
+        assert_eq!(world_time.get_day_length(), DEFAULT_DAY_LENGTH);
+    }
+
     fn assert_in_range(world_time: &WorldTime) {
         let delta = world_time.get_delta();
         let light = world_time.get_light_level(RainLightLevelModifier::Multiply(1.0));
@@ -138,7 +138,7 @@ mod tests {
     #[should_panic]
     fn test_stack_vec_out_of_bounds() {
         let v: StackVec<i32, 10> = StackVec::new();
-        v[4];
+        let _ = v[4];
     }
 
     #[test]
@@ -1,47 +1,75 @@
-use std::rc::Rc;
+use std::{collections::HashMap, rc::Rc};
 
 use macroquad::{
     camera::{Camera3D, set_default_camera},
     math::Vec3,
     miniquad::window::screen_size,
-    prelude::gl_use_default_material,
+    prelude::{debug, gl_use_default_material, info},
+    texture::get_screen_data,
+    time::get_frame_time,
     window::next_frame,
 };
 
 use crate::{
     GameState,
     graphics::{
+        bloom::Bloom,
         debug_display::{DebugDisplay, DebugInfo},
         height_map::HeightMap,
+        noise_overlay::NoiseOverlay,
         rain_system::RainSystem,
+        recent_edits::RecentEdits,
         renderer::{Renderer, RendererParams},
         screen_effects::draw_water_effect,
         sky::Sky,
-        ui_display::{draw_crosshair, draw_selected_voxel},
+        ui_display::{
+            calculate_bearing, calculate_heading, draw_compass_arrow, draw_compass_bar,
+            draw_crosshair, draw_oxygen_indicator, draw_selected_voxel, draw_target_name,
+        },
         voxel_particle_system::VoxelParticleSystem,
         world_map::WorldMap,
     },
     interface::{
         game_menu::{
+            command_console::CommandConsole,
             crafting_menu::{CraftingMenuContext, CraftingMenuHandle},
-            game_menu_context::{MenuSelection, MenuState, draw_main_menu, draw_options_menu},
+            furnace_menu::draw_furnace_menu,
+            game_menu_context::{
+                MenuSelection, MenuState, draw_confirm_discard_menu, draw_main_menu,
+                draw_options_menu,
+            },
             voxel_selection_menu::draw_voxel_selection_menu,
         },
         interface_context::InterfaceContext,
         tutorial_messages::{TutorialMessage, TutorialMessages},
     },
-    model::{inventory::Item, player_info::PlayerInfo, user_settings::UserSettings, world::World},
+    model::{
+        inventory::{AvailableItems, Item, MAX_ITEMS_PER_SLOT},
+        location::Location,
+        orientation::Orientation,
+        player_info::PlayerInfo,
+        user_settings::{ScrollAction, UserSettings},
+        voxel::Voxel,
+        world::World,
+        world_rules::WorldRules,
+    },
     service::{
         active_zone::{
             get_load_zone, get_load_zone_on_world_load, get_render_zone,
             get_render_zone_on_world_load,
         },
         activity_timer::ActivityTimer,
+        area_generation::generator::AreaGenerator,
         asset_manager::AssetManager,
-        creatures::creature_manager::CreatureManager,
+        commands::{Command, CommandContext},
+        creatures::creature_manager::{CreatureManager, CreatureUpdateParams},
+        edit_tools::{EditTools, VoxelEdit},
         input::{self, ScrollDirection, move_right},
+        music_player::MusicPlayer,
+        performance_log::{PerformanceLog, PerformanceSample},
         persistence::{
             player_persistence::save_player_info,
+            screenshot_persistence::store_screenshot,
             user_settings_persistence::write_user_settings_blocking,
             world_metadata_persistence::{WorldMetadata, store_world_metadata},
         },
@@ -52,21 +80,35 @@ use crate::{
             },
             voxel_simulator::VoxelSimulator,
         },
-        raycast::{RaycastResult, cast_ray},
+        raycast::{RaycastResult, cast_ray, cast_ray_for_water_source},
+        smelting::Furnaces,
         sound_manager::SoundId,
         world_actions::{
-            DestroyActionEvent, destroy_voxel, initialise_world_systems, place_voxel,
-            replace_voxel, update_player_in_water,
+            DestroyActionEvent, apply_edits, create_column_edits, destroy_voxel,
+            find_interacted_furnace, find_placement_orientation, initialise_world_systems,
+            is_location_free, place_voxel, replace_voxel, respawn_player, take_water_source,
+            update_player_in_water,
         },
+        world_statistics::WorldStatistics,
         world_time::WorldTime,
     },
 };
 
+/// horizontal distance in front of the player where dropped items appear
+const DROP_ITEM_DISTANCE: f32 = 1.5;
+/// fixed time in seconds simulated by a single physics step
+const PHYSICS_TIME_STEP: f32 = 1.0 / 60.0;
+/// limits the physics steps per frame so low frame rates can't stall the game
+const MAX_PHYSICS_STEPS_PER_FRAME: usize = 8;
+/// placeholder, the cooldown is replaced by the interval from the settings before each tick
+const AUTO_SAVE_TIMER_COOLDOWN: f32 = 1.0;
+
 pub struct VoxelEngine {
     world: World,
     renderer: Renderer,
     player_info: PlayerInfo,
     debug_display: DebugDisplay,
+    noise_overlay: NoiseOverlay,
     voxel_simulator: VoxelSimulator,
     voxel_particles: VoxelParticleSystem,
     creature_manager: CreatureManager,
@@ -79,21 +121,45 @@ pub struct VoxelEngine {
     world_map: WorldMap,
     tutorial_messages: TutorialMessages,
     rain_system: RainSystem,
-    show_ui: bool,
+    edit_tools: EditTools,
+    statistics: WorldStatistics,
+    furnaces: Furnaces,
+    command_console: CommandConsole,
+    physics_timer: ActivityTimer,
+    world_rules: WorldRules,
+    bloom: Bloom,
+    performance_log: PerformanceLog,
+    /// voxels recently modified by the player, highlighted in the debug display
+    recent_edits: RecentEdits,
+    music_player: MusicPlayer,
+    auto_save_timer: ActivityTimer,
+    /// cleared to quit without saving, areas unloaded earlier in the session
+    /// have already been written and keep their changes
+    should_save_on_exit: bool,
+    /// captured on the next frame before the hud is drawn
+    is_screenshot_requested: bool,
 }
 impl VoxelEngine {
     pub fn new(
         world_name: impl Into<String>,
         asset_manager: Rc<AssetManager>,
         user_settings: UserSettings,
+        new_world_rules: Option<WorldRules>,
+        new_world_seed: Option<u64>,
     ) -> Self {
-        let world_systems = initialise_world_systems(world_name, asset_manager.clone());
+        let world_systems = initialise_world_systems(
+            world_name,
+            asset_manager.clone(),
+            new_world_rules,
+            new_world_seed,
+        );
 
         Self {
             world: world_systems.world,
             renderer: world_systems.renderer,
             player_info: world_systems.player_info,
             debug_display: DebugDisplay::new(),
+            noise_overlay: NoiseOverlay::new(),
             user_settings,
             voxel_simulator: world_systems.voxel_simulator,
             asset_manager,
@@ -106,7 +172,19 @@ impl VoxelEngine {
             world_map: WorldMap::new(),
             tutorial_messages: world_systems.tutorial_messages,
             rain_system: world_systems.rain_system,
-            show_ui: true,
+            edit_tools: EditTools::new(),
+            statistics: world_systems.statistics,
+            furnaces: world_systems.furnaces,
+            command_console: CommandConsole::new(),
+            physics_timer: ActivityTimer::new(0.0, PHYSICS_TIME_STEP),
+            world_rules: world_systems.rules,
+            bloom: Bloom::new(),
+            performance_log: PerformanceLog::new(),
+            recent_edits: RecentEdits::new(),
+            music_player: MusicPlayer::new(),
+            auto_save_timer: ActivityTimer::new(0.0, AUTO_SAVE_TIMER_COOLDOWN),
+            should_save_on_exit: true,
+            is_screenshot_requested: false,
         }
     }
 
@@ -126,18 +204,27 @@ impl VoxelEngine {
             self.user_settings.get_render_distance(),
         );
         self.world.load_all_blocking(&load_zone);
+        self.renderer
+            .set_smooth_lighting(self.user_settings.smooth_lighting);
+        self.renderer.set_mesh_cache(self.user_settings.mesh_cache);
+        self.world_time
+            .set_day_length(self.user_settings.get_day_length());
+        self.player_info
+            .camera_controller
+            .set_fov(self.user_settings.get_fov_degrees());
+        let _moved_origin = self
+            .renderer
+            .update_render_origin(self.player_info.camera_controller.get_position());
         self.renderer
             .load_all_blocking(&mut self.world, &render_zone);
-        self.tutorial_messages.show(TutorialMessage::Initial);
-        self.asset_manager
-            .sound_manager
-            .start_or_stop_music(&self.user_settings);
+        self.tutorial_messages
+            .show(TutorialMessage::Initial, &self.user_settings);
     }
 
     fn check_change_render_distance(&mut self) {
-        if input::decrease_render_distance() {
+        if input::decrease_render_distance(&self.user_settings.key_bindings) {
             let _changed = self.user_settings.decrease_render_distance();
-        } else if input::increase_render_distance() {
+        } else if input::increase_render_distance(&self.user_settings.key_bindings) {
             let _changed = self.user_settings.increase_render_distance();
         }
     }
@@ -146,16 +233,26 @@ impl VoxelEngine {
     pub fn process_input(&mut self, delta: f32) -> RaycastResult {
         self.manage_menu_state();
         self.check_change_render_distance();
+        let is_zooming = !self.menu_state.is_in_menu()
+            && !self.world_map.active
+            && input::zoom(&self.user_settings.key_bindings);
+        self.player_info
+            .camera_controller
+            .update_zoom(delta, is_zooming);
 
         let raycast_result = self.process_mouse_input(delta);
-        if input::toggle_ui() {
-            self.show_ui = !self.show_ui;
+        if input::toggle_ui(&self.user_settings.key_bindings) {
+            self.user_settings.show_hud = !self.user_settings.show_hud;
         }
         if self.menu_state.is_in_menu() {
             return raycast_result;
         }
-        if input::is_show_map() {
-            self.tutorial_messages.show(TutorialMessage::Map);
+        if input::take_screenshot(&self.user_settings.key_bindings) {
+            self.is_screenshot_requested = true;
+        }
+        if input::is_show_map(&self.user_settings.key_bindings) {
+            self.tutorial_messages
+                .show(TutorialMessage::Map, &self.user_settings);
             self.world_map.active = !self.world_map.active;
         }
         if self.world_map.active {
@@ -164,20 +261,57 @@ impl VoxelEngine {
             return raycast_result;
         }
 
-        if input::is_enter_inventory() {
+        if input::is_enter_inventory(&self.user_settings.key_bindings) {
             self.player_info.camera_controller.set_focus(false);
             self.menu_state = MenuState::ItemSelection {
                 currently_selected_item: None,
+                is_trash_armed: false,
             };
-        } else if input::is_enter_crafting() {
+        } else if input::is_enter_crafting(&self.user_settings.key_bindings) {
+            self.player_info.camera_controller.set_focus(false);
+            self.menu_state = MenuState::Crafting(CraftingMenuContext::new(
+                &self.player_info.inventory,
+                self.player_info.hotbar_locks,
+            ));
+        } else if input::is_open_console(&self.user_settings.key_bindings) {
             self.player_info.camera_controller.set_focus(false);
-            self.menu_state =
-                MenuState::Crafting(CraftingMenuContext::new(&self.player_info.inventory));
+            self.command_console.open();
+            self.menu_state = MenuState::Console;
+        }
+        self.process_edit_tools_input(raycast_result);
+        if input::is_drop_item(&self.user_settings.key_bindings) {
+            self.drop_selected_item();
+        }
+        if input::is_place_column(&self.user_settings.key_bindings) {
+            self.try_place_column(raycast_result);
         }
 
-        if input::is_start_place_voxel(&self.player_info.camera_controller) {
+        let is_start_place_voxel = input::is_start_place_voxel(
+            &self.player_info.camera_controller,
+            &self.user_settings.mouse_bindings,
+        );
+        let interacted_furnace = (is_start_place_voxel
+            && !input::sneak(&self.user_settings.key_bindings))
+        .then(|| find_interacted_furnace(raycast_result, &mut self.world))
+        .flatten();
+        if let Some(location) = interacted_furnace {
+            self.player_info.camera_controller.set_focus(false);
+            self.menu_state = MenuState::Furnace(location);
+        } else if is_start_place_voxel
+            && self.player_info.get_held_voxel() == Some(Voxel::WeatherTotem)
+        {
+            self.use_weather_totem();
+        } else if is_start_place_voxel
+            && let Some(bucket @ (Voxel::Bucket | Voxel::WaterBucket)) =
+                self.player_info.get_held_voxel()
+        {
+            self.use_bucket(bucket);
+        } else if is_start_place_voxel {
             self.try_place_voxel(raycast_result);
-        } else if input::is_place_voxel(&self.player_info.camera_controller) {
+        } else if input::is_place_voxel(
+            &self.player_info.camera_controller,
+            &self.user_settings.mouse_bindings,
+        ) {
             self.continue_world_action_progress(
                 delta,
                 raycast_result,
@@ -188,11 +322,25 @@ impl VoxelEngine {
             self.player_info.place_progress.reset();
         }
 
-        if input::is_start_destroy_voxel(&self.player_info.camera_controller) {
+        if input::is_start_destroy_voxel(
+            &self.player_info.camera_controller,
+            &self.user_settings.mouse_bindings,
+        ) {
             self.try_destroy_voxel(raycast_result);
-        } else if input::is_destroy_voxel(&self.player_info.camera_controller) {
+        } else if input::is_destroy_voxel(
+            &self.player_info.camera_controller,
+            &self.user_settings.mouse_bindings,
+        ) {
+            let tool_break_speed = match raycast_result {
+                RaycastResult::Hit {
+                    first_non_empty, ..
+                } => self
+                    .player_info
+                    .get_break_speed_against(self.world.get(first_non_empty)),
+                RaycastResult::NoneHit => 1.0,
+            };
             self.continue_world_action_progress(
-                delta,
+                delta * self.user_settings.get_break_speed() * tool_break_speed,
                 raycast_result,
                 |ve| &mut ve.player_info.destroy_progress,
                 |ve, res| ve.try_destroy_voxel(res),
@@ -201,9 +349,15 @@ impl VoxelEngine {
             self.player_info.destroy_progress.reset();
         }
 
-        if input::is_start_replace_voxel(&self.player_info.camera_controller) {
+        if input::is_start_replace_voxel(
+            &self.player_info.camera_controller,
+            &self.user_settings.mouse_bindings,
+        ) {
             self.try_replace_voxel(raycast_result);
-        } else if input::is_replace_voxel(&self.player_info.camera_controller) {
+        } else if input::is_replace_voxel(
+            &self.player_info.camera_controller,
+            &self.user_settings.mouse_bindings,
+        ) {
             self.continue_world_action_progress(
                 delta,
                 raycast_result,
@@ -214,32 +368,90 @@ impl VoxelEngine {
             self.player_info.replace_progress.reset();
         }
 
-        if input::jump() {
+        if input::jump(&self.user_settings.key_bindings) {
             try_jump(&mut self.player_info, &mut self.world);
         }
-        if input::swim() {
+        if input::swim(&self.user_settings.key_bindings) {
             try_swim(&mut self.player_info, delta);
         }
 
         self.handle_movement_input(delta);
 
-        if input::toggle_debug() {
+        if input::toggle_debug(&self.user_settings.key_bindings) {
             self.debug_display.toggle_display();
         }
+        if self.debug_display.is_displayed()
+            && input::cycle_noise_overlay(&self.user_settings.key_bindings)
+        {
+            self.noise_overlay.cycle_mode();
+        }
+        if self.debug_display.is_displayed()
+            && input::toggle_x_ray(&self.user_settings.key_bindings)
+        {
+            self.debug_display.toggle_x_ray();
+        }
+        if self.debug_display.is_displayed()
+            && input::toggle_all_area_borders(&self.user_settings.key_bindings)
+        {
+            self.debug_display.toggle_all_area_borders();
+        }
+        if self.debug_display.is_displayed()
+            && input::toggle_changed_areas(&self.user_settings.key_bindings)
+        {
+            self.debug_display.toggle_changed_areas();
+        }
+        if self.debug_display.is_displayed()
+            && input::toggle_time_freeze(&self.user_settings.key_bindings)
+        {
+            self.world_time.toggle_frozen();
+        }
         if let Some(number) = input::get_number_key() {
             self.player_info
                 .voxel_selector
                 .set_selected(number.wrapping_sub(1) as usize);
         }
-        match input::get_scroll_direction() {
-            ScrollDirection::Up => self.player_info.voxel_selector.select_next(),
-            ScrollDirection::Down => self.player_info.voxel_selector.select_prev(),
-            ScrollDirection::None => {}
+        match (
+            input::get_scroll_direction(),
+            self.user_settings.scroll_action,
+        ) {
+            (ScrollDirection::Up, ScrollAction::Hotbar) => {
+                self.player_info.voxel_selector.select_next()
+            }
+            (ScrollDirection::Down, ScrollAction::Hotbar) => {
+                self.player_info.voxel_selector.select_prev()
+            }
+            (ScrollDirection::Up, ScrollAction::Reach) => self.player_info.increase_voxel_reach(),
+            (ScrollDirection::Down, ScrollAction::Reach) => self.player_info.decrease_voxel_reach(),
+            (ScrollDirection::None, _) => {}
         }
 
         raycast_result
     }
 
+    fn process_edit_tools_input(&mut self, raycast_result: RaycastResult) {
+        let key_bindings = self.user_settings.key_bindings;
+        if input::is_select_corner(&key_bindings)
+            && let RaycastResult::Hit {
+                first_non_empty,
+                last_empty: _,
+            } = raycast_result
+        {
+            self.edit_tools.select_corner(first_non_empty);
+        }
+        if input::is_copy_selection(&key_bindings) {
+            let _copied = self.edit_tools.copy(&mut self.world);
+        }
+        if input::is_rotate_clipboard(&key_bindings) {
+            self.edit_tools.rotate();
+        }
+        if input::is_paste_selection(&key_bindings) {
+            self.try_paste_clipboard(raycast_result);
+        }
+        if input::is_replace_in_selection(&key_bindings) {
+            self.try_replace_in_selection(raycast_result);
+        }
+    }
+
     fn process_map_input(&mut self, delta: f32) {
         match input::get_scroll_direction() {
             ScrollDirection::Up => self.world_map.decrease_zoom(delta),
@@ -247,27 +459,32 @@ impl VoxelEngine {
             ScrollDirection::None => {}
         }
 
-        if input::move_forward() {
+        if input::move_forward(&self.user_settings.key_bindings) {
             self.world_map.increase_up_down_angle(delta);
-        } else if input::move_back() {
+        } else if input::move_back(&self.user_settings.key_bindings) {
             self.world_map.decrease_up_down_angle(delta);
         }
-        if input::move_left() {
+        if input::move_left(&self.user_settings.key_bindings) {
             self.world_map.increase_left_right_angle(delta);
-        } else if move_right() {
+        } else if move_right(&self.user_settings.key_bindings) {
             self.world_map.decrease_left_right_angle(delta);
         }
     }
 
     fn process_mouse_input(&mut self, delta: f32) -> RaycastResult {
-        self.player_info.camera_controller.update_look(delta);
+        self.player_info.camera_controller.update_look(
+            delta,
+            self.user_settings.invert_y,
+            self.user_settings.get_max_pitch(),
+            self.user_settings.get_mouse_sensitivity(),
+        );
         let camera = self.player_info.camera_controller.create_camera();
 
         cast_ray(
             &mut self.world,
             camera.position,
             camera.target,
-            PlayerInfo::VOXEL_REACH,
+            self.player_info.get_voxel_reach(),
         )
     }
 
@@ -283,31 +500,82 @@ impl VoxelEngine {
 
     /// updates time dependent processes
     pub fn update_processes(&mut self, delta: f32) {
+        self.music_player.update(
+            delta,
+            self.menu_state.is_in_menu(),
+            &self.user_settings,
+            &self.asset_manager.sound_manager,
+        );
         self.tutorial_messages.update(delta);
+        self.statistics.update(delta);
+        self.update_auto_save(delta);
+        if matches!(self.menu_state, MenuState::Hidden | MenuState::Furnace(_)) {
+            for (location, item) in self.furnaces.update(&self.world, delta) {
+                self.voxel_simulator.drop_item(
+                    item,
+                    location.into(),
+                    self.renderer.get_mesh_generator(),
+                );
+            }
+        }
+        self.performance_log
+            .update(get_frame_time(), || PerformanceSample {
+                loaded_areas: self.world.get_loaded_areas_count(),
+                voxel_faces: self.renderer.get_voxel_face_count(),
+                creatures: self.creature_manager.creature_count(),
+                areas_queued: self.renderer.get_areas_waiting_to_be_rendered(),
+            });
 
         if self.menu_state.is_in_menu() || self.world_map.active {
             return;
         }
 
-        self.rain_system.update(
+        let lightning_location = self.rain_system.update(
             delta,
             &self.player_info,
             &mut self.world,
             &self.user_settings,
             &self.asset_manager.sound_manager,
         );
+        if let Some(location) = lightning_location {
+            self.voxel_simulator
+                .strike_lightning(location, &mut self.world, &mut self.renderer);
+        }
         self.world_time.update(delta);
-        self.sky.update(delta);
-        self.process_physics(delta);
+        self.recent_edits.update(delta);
+        if !self.world_time.is_frozen() {
+            self.sky.update(delta);
+        }
         self.voxel_particles.update(delta);
-        self.creature_manager.update(
+
+        let physics_steps = self
+            .physics_timer
+            .tick_multiple(delta, MAX_PHYSICS_STEPS_PER_FRAME);
+        for _ in 0..physics_steps {
+            self.process_fixed_step(PHYSICS_TIME_STEP);
+        }
+    }
+
+    /// updates physics and creatures with a fixed delta, independent of the frame rate
+    fn process_fixed_step(&mut self, delta: f32) {
+        self.process_physics(delta);
+        let edited_locations = self.creature_manager.update(
             delta,
             &self.asset_manager.mesh_manager,
             &self.player_info,
             &mut self.world,
-            &self.user_settings,
+            CreatureUpdateParams {
+                user_settings: &self.user_settings,
+                world_rules: &self.world_rules,
+                is_night: !self.world_time.is_day(),
+            },
         );
+        if !edited_locations.is_empty() {
+            self.renderer
+                .update_locations(&mut self.world, &edited_locations);
+        }
         update_player_in_water(&mut self.player_info, &mut self.world);
+        self.player_info.update_oxygen(delta);
     }
 
     /// process falling and collisions
@@ -322,9 +590,12 @@ impl VoxelEngine {
             collision_type,
             self.renderer.get_mesh_generator(),
         );
+        if self.player_info.is_dead() {
+            respawn_player(&mut self.player_info, &mut self.world, &mut self.renderer);
+        }
 
         push_player_up_if_stuck(&mut self.player_info, &mut self.world);
-        self.voxel_simulator.update(
+        let exploded = self.voxel_simulator.update(
             &mut self.world,
             &mut self.renderer,
             &mut self.player_info,
@@ -332,6 +603,14 @@ impl VoxelEngine {
             &self.user_settings,
             delta,
         );
+        self.voxel_particles
+            .add_particles_for_exploded(&exploded, self.renderer.get_mesh_generator());
+        self.voxel_simulator.update_fires(
+            &mut self.world,
+            &mut self.renderer,
+            self.rain_system.is_raining(),
+            delta,
+        );
     }
 
     /// updates the areas loaded in memory and unloads old areas
@@ -341,17 +620,40 @@ impl VoxelEngine {
             .camera_controller
             .get_camera_voxel_location();
         let render_size = self.user_settings.get_render_distance();
-        self.renderer
-            .update_loaded_areas(&get_render_zone(camera_location.into(), render_size));
+        let render_zone = get_render_zone(camera_location.into(), render_size);
+        if self
+            .renderer
+            .update_render_origin(self.player_info.camera_controller.get_position())
+        {
+            self.renderer
+                .load_all_blocking(&mut self.world, &render_zone);
+        }
+        self.renderer.update_loaded_areas(&render_zone);
         self.renderer.load_areas_in_queue(&mut self.world);
-        self.world
-            .retain_areas(&get_load_zone(camera_location.into(), render_size));
+        self.world.set_keepalive_center(
+            self.user_settings
+                .keep_spawn_loaded
+                .then(|| PlayerInfo::SPAWN_LOCATION.into()),
+        );
+        self.world.retain_areas(
+            &get_load_zone(
+                camera_location.into(),
+                render_size,
+                self.user_settings.get_simulation_distance(),
+            ),
+            camera_location.into(),
+        );
     }
 
     /// draws the current frame, return the new context if changed
     pub async fn draw_scene(&mut self, raycast_result: RaycastResult) -> Option<GameState> {
         let (width, height) = screen_size();
-        let camera = self.create_3d_camera();
+        let mut camera = self.create_3d_camera();
+        self.bloom.prepare_camera(
+            &mut camera,
+            self.user_settings.bloom && !self.world_map.active,
+            (width, height),
+        );
         self.draw_background(&camera);
 
         // set 3D camera and voxel shader
@@ -366,8 +668,11 @@ impl VoxelEngine {
             &mut self.height_map,
             RendererParams {
                 explosion_positions,
+                creature_lights: self.creature_manager.get_lights(camera.position),
                 world_light_level,
+                ambient_color: self.world_time.get_ambient_color(),
                 should_show_map: self.world_map.active,
+                x_ray: self.debug_display.is_x_ray(),
             },
         );
         let creatures_drawn = self.creature_manager.draw(&camera, &self.user_settings);
@@ -430,35 +735,92 @@ impl VoxelEngine {
         creatures_drawn: u32,
     ) -> Option<GameState> {
         gl_use_default_material();
-        if self.show_ui && !self.world_map.active {
-            self.draw_in_game_ui_elements(
+        if !self.world_map.active {
+            self.debug_display.draw_area_border(
+                &self.world,
+                &self.player_info.camera_controller,
+                self.user_settings.get_render_distance(),
+            );
+            self.debug_display
+                .draw_creature_bounding_boxes(&self.creature_manager, camera);
+            if self.debug_display.is_displayed() {
+                self.recent_edits.draw(camera);
+            }
+            if !self.is_screenshot_requested {
+                self.draw_in_game_3d_ui_elements(camera, raycast_result);
+            }
+
+            set_default_camera();
+            self.bloom.draw(
                 width,
                 height,
-                camera,
-                raycast_result,
-                rendered,
-                creatures_drawn,
+                self.world_time
+                    .get_light_level(self.rain_system.get_light_level_modifier()),
             );
-        } else if self.show_ui {
+            self.capture_screenshot();
+            self.draw_in_game_2d_ui_elements(width, height, raycast_result);
+            if self.debug_display.is_displayed() {
+                self.noise_overlay.draw(
+                    &self.world,
+                    self.player_info
+                        .camera_controller
+                        .get_camera_voxel_location(),
+                    &self.user_settings.key_bindings,
+                    &self.asset_manager.font,
+                );
+            }
+            let player_location = self
+                .player_info
+                .camera_controller
+                .get_camera_voxel_location();
+            let debug_info = DebugInfo {
+                world: &self.world,
+                renderer: &self.renderer,
+                camera,
+                rendered_areas_faces: rendered,
+                creature_manager: &self.creature_manager,
+                rendered_creatures: creatures_drawn,
+                falling_voxels: self.voxel_simulator.get_falling_voxel_count(),
+                is_time_frozen: self.world_time.is_frozen(),
+                player_location,
+                biome: AreaGenerator::sample_biome(self.world.get_seed(), player_location),
+                key_bindings: &self.user_settings.key_bindings,
+            };
+            self.debug_display
+                .draw_debug_display(debug_info, &self.asset_manager.font);
+        } else {
             set_default_camera();
-            self.world_map
-                .draw_comapass(&self.asset_manager.texture_manager);
-            self.tutorial_messages.draw(height, &self.asset_manager);
+            self.capture_screenshot();
+            if self.user_settings.show_hud {
+                self.world_map
+                    .draw_comapass(&self.asset_manager.texture_manager);
+                self.tutorial_messages.draw(height, &self.asset_manager);
+            }
         }
 
         self.process_menu()
     }
 
-    /// draws the ui elements for the normal first person view
-    fn draw_in_game_ui_elements(
-        &self,
-        width: f32,
-        height: f32,
-        camera: &Camera3D,
-        raycast_result: RaycastResult,
-        rendered: (usize, usize),
-        creatures_drawn: u32,
-    ) {
+    /// saves the scene drawn so far if a screenshot was requested
+    fn capture_screenshot(&mut self) {
+        if !self.is_screenshot_requested {
+            return;
+        }
+        self.is_screenshot_requested = false;
+
+        let message = match store_screenshot(&get_screen_data()) {
+            Some(filepath) => format!("Screenshot saved to '{filepath}'"),
+            None => "Couldn't save the screenshot".to_owned(),
+        };
+        self.tutorial_messages.show_notification(message);
+    }
+
+    /// draws the 3d hud elements for the normal first person view
+    fn draw_in_game_3d_ui_elements(&self, camera: &Camera3D, raycast_result: RaycastResult) {
+        if !self.user_settings.show_hud {
+            return;
+        }
+
         if let RaycastResult::Hit {
             first_non_empty,
             last_empty: _,
@@ -466,32 +828,64 @@ impl VoxelEngine {
         {
             draw_selected_voxel(first_non_empty, camera);
         }
-        self.debug_display
-            .draw_area_border(&self.player_info.camera_controller);
-        self.debug_display
-            .draw_creature_bounding_boxes(&self.creature_manager, camera);
+        self.edit_tools.draw_selection(camera);
+    }
+
+    /// draws the 2d hud elements for the normal first person view, expects the default camera
+    fn draw_in_game_2d_ui_elements(&self, width: f32, height: f32, raycast_result: RaycastResult) {
+        if !self.user_settings.show_hud {
+            return;
+        }
 
-        // set 2D camera and draw 2D elements
-        set_default_camera();
         if self.player_info.is_head_in_water {
             draw_water_effect(width, height, &self.asset_manager.texture_manager);
+            draw_oxygen_indicator(
+                width,
+                height,
+                self.player_info.get_oxygen() / PlayerInfo::MAX_OXYGEN,
+            );
+        }
+        draw_crosshair(width, height, raycast_result);
+        if self.user_settings.show_compass_bar {
+            draw_compass_bar(
+                width,
+                calculate_heading(self.player_info.camera_controller.yaw),
+                &self.asset_manager.font,
+            );
+        }
+        if self.user_settings.show_target_name
+            && let RaycastResult::Hit {
+                first_non_empty, ..
+            } = raycast_result
+            && let Some(voxel) = self.world.get_without_loading(first_non_empty)
+        {
+            draw_target_name(
+                width,
+                height,
+                voxel.display_name(),
+                &self.asset_manager.font,
+            );
+        }
+        if self.player_info.is_holding_compass() {
+            let bearing = calculate_bearing(
+                self.player_info.camera_controller.get_position(),
+                self.player_info.camera_controller.yaw,
+                self.player_info.get_compass_target(),
+            );
+            draw_compass_arrow(width, height, bearing);
         }
-        draw_crosshair(width, height);
         self.tutorial_messages.draw(height, &self.asset_manager);
         self.player_info
             .voxel_selector
             .draw(&self.player_info.inventory.selected, &self.asset_manager);
-
-        let debug_info = DebugInfo {
-            world: &self.world,
-            renderer: &self.renderer,
-            camera,
-            rendered_areas_faces: rendered,
-            creature_manager: &self.creature_manager,
-            rendered_creatures: creatures_drawn,
-        };
-        self.debug_display
-            .draw_debug_display(debug_info, &self.asset_manager.font);
+        self.player_info
+            .voxel_selector
+            .draw_health_bar(self.player_info.get_health() / PlayerInfo::MAX_HEALTH);
+        if self.player_info.get_stamina() < PlayerInfo::MAX_STAMINA {
+            self.player_info
+                .voxel_selector
+                .draw_stamina_bar(self.player_info.get_stamina() / PlayerInfo::MAX_STAMINA);
+        }
     }
 
     /// returns the new game context only if changed
@@ -499,14 +893,51 @@ impl VoxelEngine {
         match self.menu_state.clone() {
             MenuState::Hidden => None,
             MenuState::Main => self.process_main_menu(),
+            MenuState::ConfirmDiscard => {
+                let selection = draw_confirm_discard_menu(&self.asset_manager, &self.user_settings);
+                self.handle_menu_selection(selection)
+            }
             MenuState::Options => self.process_options_menu(),
             MenuState::ItemSelection {
                 currently_selected_item,
-            } => self.process_voxel_selection_menu(currently_selected_item),
+                is_trash_armed,
+            } => self.process_voxel_selection_menu(currently_selected_item, is_trash_armed),
             MenuState::Crafting(handle) => self.process_crafting_menu(handle),
+            MenuState::Furnace(location) => {
+                let menu_selection = draw_furnace_menu(
+                    &self.asset_manager,
+                    &self.user_settings,
+                    &mut self.player_info,
+                    self.furnaces.get_mut(location),
+                );
+                self.handle_menu_selection(menu_selection)
+            }
+            MenuState::Console => {
+                self.process_command_console();
+                None
+            }
         }
     }
 
+    fn process_command_console(&mut self) {
+        let Some(input) = self.command_console.draw(&self.asset_manager.font) else {
+            return;
+        };
+        let message = match Command::parse(&input) {
+            Ok(command) => command.execute(CommandContext {
+                world: &mut self.world,
+                renderer: &mut self.renderer,
+                player_info: &mut self.player_info,
+                creature_manager: &mut self.creature_manager,
+                mesh_manager: &self.asset_manager.mesh_manager,
+                performance_log: &mut self.performance_log,
+                is_debug_enabled: self.debug_display.is_displayed(),
+            }),
+            Err(error) => error,
+        };
+        self.command_console.add_message(message);
+    }
+
     fn process_crafting_menu(
         &mut self,
         crafting_menu_handle: CraftingMenuHandle,
@@ -523,18 +954,19 @@ impl VoxelEngine {
     fn process_voxel_selection_menu(
         &mut self,
         currently_selected_item: Option<Item>,
+        mut is_trash_armed: bool,
     ) -> Option<GameState> {
         let (selected_item, menu_selection) = draw_voxel_selection_menu(
             &self.asset_manager,
             &mut self.player_info,
             currently_selected_item,
+            &mut is_trash_armed,
+            &self.user_settings.key_bindings,
         );
-        if let MenuState::ItemSelection {
-            currently_selected_item: _,
-        } = self.menu_state
-        {
+        if let MenuState::ItemSelection { .. } = self.menu_state {
             self.menu_state = MenuState::ItemSelection {
                 currently_selected_item: selected_item,
+                is_trash_armed,
             }
         }
 
@@ -542,8 +974,10 @@ impl VoxelEngine {
     }
 
     fn process_options_menu(&mut self) -> Option<GameState> {
-        let change_render_distance_callback = |settings: &UserSettings| {
+        let change_render_callback = |settings: &UserSettings| {
             let render_size = settings.get_render_distance();
+            self.renderer.set_smooth_lighting(settings.smooth_lighting);
+            self.renderer.set_mesh_cache(settings.mesh_cache);
             self.renderer.load_all_blocking(
                 &mut self.world,
                 &get_render_zone(
@@ -558,8 +992,13 @@ impl VoxelEngine {
         let selection = draw_options_menu(
             &self.asset_manager,
             &mut self.user_settings,
-            change_render_distance_callback,
+            change_render_callback,
         );
+        self.world_time
+            .set_day_length(self.user_settings.get_day_length());
+        self.player_info
+            .camera_controller
+            .set_fov(self.user_settings.get_fov_degrees());
         self.handle_menu_selection(selection)
     }
 
@@ -583,6 +1022,19 @@ impl VoxelEngine {
                 )),
             }),
             MenuSelection::Exit => Some(GameState::Exit),
+            MenuSelection::ToConfirmDiscard => {
+                self.menu_state = MenuState::ConfirmDiscard;
+                None
+            }
+            MenuSelection::DiscardAndQuit => {
+                self.should_save_on_exit = false;
+                Some(GameState::Menu {
+                    context: Box::new(InterfaceContext::new_world_selection(
+                        self.asset_manager.clone(),
+                        self.user_settings.clone(),
+                    )),
+                })
+            }
             MenuSelection::ToOptions => {
                 self.menu_state = MenuState::Options;
                 None
@@ -591,43 +1043,142 @@ impl VoxelEngine {
                 self.menu_state = MenuState::Main;
                 None
             }
+            MenuSelection::ReplayTutorials => {
+                self.tutorial_messages.reset();
+                self.tutorial_messages
+                    .show(TutorialMessage::Initial, &self.user_settings);
+                self.player_info.camera_controller.set_focus(true);
+                self.menu_state = MenuState::Hidden;
+                None
+            }
         }
     }
 
+    /// consumes the held totem to toggle the rain
+    fn use_weather_totem(&mut self) {
+        let selected_index = self.player_info.voxel_selector.get_selected_index();
+        self.player_info
+            .inventory
+            .reduce_selected_at(selected_index);
+        self.rain_system.set_raining(!self.rain_system.is_raining());
+        self.asset_manager
+            .sound_manager
+            .play_sound(SoundId::Thunder, &self.user_settings);
+    }
+
+    /// fills the bucket from the looked at water source
+    /// or empties the water bucket in front of the looked at voxel
+    fn use_bucket(&mut self, bucket: Voxel) {
+        let camera = self.player_info.camera_controller.create_camera();
+        let RaycastResult::Hit {
+            first_non_empty,
+            last_empty,
+        } = cast_ray_for_water_source(
+            &mut self.world,
+            camera.position,
+            camera.target,
+            self.player_info.get_voxel_reach(),
+        )
+        else {
+            return;
+        };
+        let selected_index = self.player_info.voxel_selector.get_selected_index();
+        let (emptied_bucket, edited_location) = if bucket == Voxel::Bucket {
+            (Voxel::WaterBucket, first_non_empty)
+        } else {
+            (Voxel::Bucket, last_empty)
+        };
+        let new_item = Item::new(emptied_bucket, 1);
+        if !self
+            .player_info
+            .inventory
+            .can_replace_selected_one(selected_index, new_item)
+        {
+            return;
+        }
+
+        let has_used = if bucket == Voxel::Bucket {
+            take_water_source(
+                first_non_empty,
+                &mut self.world,
+                &mut self.renderer,
+                &mut self.voxel_simulator,
+            )
+        } else {
+            self.creature_manager.check_can_place_voxel(last_empty)
+                && place_voxel(
+                    last_empty,
+                    Voxel::WaterSource,
+                    Orientation::Up,
+                    &self.player_info,
+                    &mut self.world,
+                    &mut self.renderer,
+                    &mut self.voxel_simulator,
+                )
+        };
+        if !has_used {
+            return;
+        }
+        self.recent_edits.add(edited_location);
+        self.player_info
+            .inventory
+            .replace_selected_one(selected_index, new_item);
+        self.asset_manager
+            .sound_manager
+            .play_sound(SoundId::Place, &self.user_settings);
+    }
+
     fn try_place_voxel(&mut self, raycast_result: RaycastResult) {
         match raycast_result {
             RaycastResult::NoneHit => {}
             RaycastResult::Hit {
-                first_non_empty: _,
+                first_non_empty,
                 last_empty,
             } => {
                 let selected_index = self.player_info.voxel_selector.get_selected_index();
-                let selected_item = self.player_info.inventory.selected[selected_index];
-                if selected_item.is_none() {
+                let Some(selected_item) = self.player_info.inventory.selected[selected_index]
+                else {
+                    return;
+                };
+                if !self.creature_manager.check_can_place_voxel(last_empty) {
                     return;
                 }
 
+                let orientation = find_placement_orientation(
+                    selected_item.voxel,
+                    last_empty,
+                    first_non_empty,
+                    self.player_info.camera_controller.get_forward_direction(),
+                );
                 let has_placed = place_voxel(
                     last_empty,
-                    selected_item.unwrap().voxel,
+                    selected_item.voxel,
+                    orientation,
                     &self.player_info,
                     &mut self.world,
                     &mut self.renderer,
                     &mut self.voxel_simulator,
-                    &self.creature_manager,
                 );
                 if !has_placed {
                     return;
                 }
+                self.recent_edits.add(last_empty);
                 self.player_info
                     .inventory
                     .reduce_selected_at(selected_index);
+                self.voxel_particles.add_particles_for_placed(
+                    selected_item.voxel,
+                    last_empty,
+                    first_non_empty,
+                    self.renderer.get_mesh_generator(),
+                );
 
                 self.asset_manager
                     .sound_manager
                     .play_sound(SoundId::Place, &self.user_settings);
 
-                self.tutorial_messages.show(TutorialMessage::Replacing);
+                self.tutorial_messages
+                    .show(TutorialMessage::Replacing, &self.user_settings);
             }
         }
     }
@@ -646,17 +1197,25 @@ impl VoxelEngine {
                     &mut self.voxel_simulator,
                     &mut self.voxel_particles,
                 );
+                if !matches!(destroy_event, DestroyActionEvent::None) {
+                    self.recent_edits.add(first_non_empty);
+                }
                 match destroy_event {
                     DestroyActionEvent::None => {}
                     DestroyActionEvent::GainVoxel(destroyed) => {
+                        if destroyed == Voxel::Furnace {
+                            self.drop_furnace_contents(first_non_empty);
+                        }
                         self.player_info.inventory.add_item(Item::new(destroyed, 1));
                         self.asset_manager
                             .sound_manager
                             .play_sound(SoundId::Destroy, &self.user_settings);
 
-                        self.tutorial_messages.show(TutorialMessage::Destroy);
+                        self.tutorial_messages
+                            .show(TutorialMessage::Destroy, &self.user_settings);
                         if self.player_info.inventory.is_hotbar_full() {
-                            self.tutorial_messages.show(TutorialMessage::Inventory);
+                            self.tutorial_messages
+                                .show(TutorialMessage::Inventory, &self.user_settings);
                         }
                     }
                     DestroyActionEvent::StartBomb(location) => {
@@ -678,19 +1237,26 @@ impl VoxelEngine {
                 last_empty: _,
             } => {
                 let index = self.player_info.voxel_selector.get_selected_index();
-                let selected_item = self.player_info.inventory.selected[index];
-                if selected_item.is_none() {
+                let Some(selected_item) = self.player_info.inventory.selected[index] else {
                     return;
-                }
+                };
 
+                let orientation = find_placement_orientation(
+                    selected_item.voxel,
+                    first_non_empty,
+                    first_non_empty,
+                    self.player_info.camera_controller.get_forward_direction(),
+                );
                 let maybe_replaced = replace_voxel(
                     first_non_empty,
-                    selected_item.unwrap().voxel,
+                    selected_item.voxel,
+                    orientation,
                     &mut self.world,
                     &mut self.renderer,
                     &mut self.voxel_simulator,
                 );
                 if let Some(replaced_voxel) = maybe_replaced {
+                    self.recent_edits.add(first_non_empty);
                     self.player_info.inventory.reduce_selected_at(index);
                     self.player_info
                         .inventory
@@ -703,6 +1269,265 @@ impl VoxelEngine {
         }
     }
 
+    /// drops the items left in a destroyed furnace where it stood
+    fn drop_furnace_contents(&mut self, location: Location) {
+        for item in self.furnaces.remove(location) {
+            self.voxel_simulator.drop_item(
+                item,
+                location.into(),
+                self.renderer.get_mesh_generator(),
+            );
+        }
+    }
+
+    /// drops one voxel of the selected hotbar item in front of the player, unless the slot is locked
+    fn drop_selected_item(&mut self) {
+        let selected_index = self.player_info.voxel_selector.get_selected_index();
+        if self.player_info.hotbar_locks.is_locked(selected_index) {
+            return;
+        }
+        let Some(selected_item) = self.player_info.inventory.selected[selected_index] else {
+            return;
+        };
+        self.player_info
+            .inventory
+            .reduce_selected_at(selected_index);
+
+        let camera_controller = &self.player_info.camera_controller;
+        let position = camera_controller.get_position()
+            + camera_controller.get_forward_direction() * DROP_ITEM_DISTANCE;
+        self.voxel_simulator.drop_item(
+            Item::new(selected_item.voxel, 1),
+            position,
+            self.renderer.get_mesh_generator(),
+        );
+    }
+
+    /// places the selected voxel from the targeted empty location down to the ground
+    fn try_place_column(&mut self, raycast_result: RaycastResult) {
+        let RaycastResult::Hit {
+            first_non_empty: _,
+            last_empty,
+        } = raycast_result
+        else {
+            return;
+        };
+        let selected_index = self.player_info.voxel_selector.get_selected_index();
+        let Some(selected_item) = self.player_info.inventory.selected[selected_index] else {
+            return;
+        };
+
+        let available = self
+            .player_info
+            .inventory
+            .create_all_items_map()
+            .get(selected_item.voxel);
+        let edits = create_column_edits(
+            last_empty,
+            selected_item.voxel,
+            available,
+            &mut self.world,
+            |location| {
+                is_location_free(
+                    location,
+                    &self.player_info,
+                    &self.voxel_simulator,
+                    &self.creature_manager,
+                )
+            },
+        );
+        let placed = apply_edits(
+            &edits,
+            &mut self.world,
+            &mut self.renderer,
+            &mut self.voxel_simulator,
+        );
+        if placed.is_empty() {
+            return;
+        }
+        self.recent_edits.add_all(&placed);
+
+        for _ in 0..placed.len() {
+            self.player_info
+                .inventory
+                .remove_item(Item::new(selected_item.voxel, 1));
+        }
+        self.asset_manager
+            .sound_manager
+            .play_sound(SoundId::Place, &self.user_settings);
+    }
+
+    /// pastes the clipboard at the targeted empty location, the pasted voxels are taken from
+    /// the unlocked inventory and nothing is pasted if any are missing,
+    /// the replaced voxels are collected like destroyed ones
+    fn try_paste_clipboard(&mut self, raycast_result: RaycastResult) {
+        let RaycastResult::Hit {
+            first_non_empty: _,
+            last_empty,
+        } = raycast_result
+        else {
+            return;
+        };
+        if !self.edit_tools.has_clipboard() {
+            return;
+        }
+
+        let edits: Vec<VoxelEdit> = self
+            .edit_tools
+            .paste(last_empty)
+            .into_iter()
+            .filter(|(location, voxel)| {
+                self.world.get(*location) != *voxel
+                    && is_location_free(
+                        *location,
+                        &self.player_info,
+                        &self.voxel_simulator,
+                        &self.creature_manager,
+                    )
+            })
+            .collect();
+        if edits.is_empty() {
+            return;
+        }
+
+        let hotbar_locks = self.player_info.hotbar_locks;
+        let available = self
+            .player_info
+            .inventory
+            .create_unlocked_items_map(hotbar_locks);
+        let mut required = AvailableItems::new_empty();
+        for (_, voxel) in &edits {
+            required.add(*voxel, 1u32);
+        }
+        if let Some((_, missing)) = edits
+            .iter()
+            .find(|(_, voxel)| required.get(*voxel) > available.get(*voxel))
+        {
+            self.tutorial_messages.show_notification(format!(
+                "Not enough {} to paste, {} needed",
+                missing.display_name(),
+                required.get(*missing)
+            ));
+            return;
+        }
+
+        let replaced: Vec<(Location, Voxel)> = edits
+            .iter()
+            .map(|(location, _)| (*location, self.world.get(*location)))
+            .collect();
+        let changed = apply_edits(
+            &edits,
+            &mut self.world,
+            &mut self.renderer,
+            &mut self.voxel_simulator,
+        );
+        debug_assert_eq!(changed.len(), edits.len());
+        self.recent_edits.add_all(&changed);
+
+        for (_, voxel) in &edits {
+            self.player_info
+                .inventory
+                .remove_unlocked_item(Item::new(*voxel, 1), hotbar_locks);
+        }
+        let mut collected = vec![];
+        for (location, voxel) in replaced {
+            if voxel == Voxel::Furnace {
+                self.drop_furnace_contents(location);
+            }
+            if voxel.is_solid() && voxel.is_placeable() {
+                collected.push(voxel);
+            }
+        }
+        self.collect_or_drop_voxels(collected);
+
+        self.asset_manager
+            .sound_manager
+            .play_sound(SoundId::Place, &self.user_settings);
+    }
+
+    /// adds the voxels to the inventory, the ones that don't fit are dropped in front of the player
+    fn collect_or_drop_voxels(&mut self, voxels: impl IntoIterator<Item = Voxel>) {
+        let mut overflow: HashMap<Voxel, u32> = HashMap::new();
+        for voxel in voxels {
+            let item = Item::new(voxel, 1);
+            if self.player_info.inventory.can_add_item(item) {
+                self.player_info.inventory.add_item(item);
+            } else {
+                *overflow.entry(voxel).or_default() += 1;
+            }
+        }
+
+        let camera_controller = &self.player_info.camera_controller;
+        let position = camera_controller.get_position()
+            + camera_controller.get_forward_direction() * DROP_ITEM_DISTANCE;
+        for (voxel, mut count) in overflow {
+            while count > 0 {
+                let stack_size = count.min(MAX_ITEMS_PER_SLOT as u32);
+                self.voxel_simulator.drop_item(
+                    Item::new(voxel, stack_size as u8),
+                    position,
+                    self.renderer.get_mesh_generator(),
+                );
+                count -= stack_size;
+            }
+        }
+    }
+
+    /// replaces the voxels in the selection matching the targeted voxel with the selected item,
+    /// the replaced voxels are added to the inventory or dropped if they don't fit
+    fn try_replace_in_selection(&mut self, raycast_result: RaycastResult) {
+        let RaycastResult::Hit {
+            first_non_empty,
+            last_empty: _,
+        } = raycast_result
+        else {
+            return;
+        };
+        let selected_index = self.player_info.voxel_selector.get_selected_index();
+        let Some(selected_item) = self.player_info.inventory.selected[selected_index] else {
+            return;
+        };
+        let source = self.world.get(first_non_empty);
+        if source == Voxel::None {
+            return;
+        }
+
+        let hotbar_locks = self.player_info.hotbar_locks;
+        let available = self
+            .player_info
+            .inventory
+            .create_unlocked_items_map(hotbar_locks)
+            .get(selected_item.voxel);
+        let edits = self.edit_tools.create_replace_edits(
+            &mut self.world,
+            source,
+            selected_item.voxel,
+            available,
+        );
+        let replaced = apply_edits(
+            &edits,
+            &mut self.world,
+            &mut self.renderer,
+            &mut self.voxel_simulator,
+        );
+        if replaced.is_empty() {
+            return;
+        }
+        self.recent_edits.add_all(&replaced);
+
+        for _ in 0..replaced.len() {
+            self.player_info
+                .inventory
+                .remove_unlocked_item(Item::new(selected_item.voxel, 1), hotbar_locks);
+        }
+        if source.is_solid() && source.is_placeable() {
+            self.collect_or_drop_voxels(vec![source; replaced.len()]);
+        }
+        self.asset_manager
+            .sound_manager
+            .play_sound(SoundId::Place, &self.user_settings);
+    }
+
     /// performs a world action (place, destroy, break) based on an activity timer
     fn continue_world_action_progress<G, A>(
         &mut self,
@@ -729,40 +1554,78 @@ impl VoxelEngine {
         }
     }
 
+    /// saves the player, the metadata and the changed areas every auto save interval
+    fn update_auto_save(&mut self, delta: f32) {
+        let Some(interval) = self.user_settings.get_auto_save_interval() else {
+            return;
+        };
+        self.auto_save_timer.set_cooldown(interval);
+        if !self.auto_save_timer.tick(delta) {
+            return;
+        }
+
+        self.save_player_and_metadata();
+        let saved_areas = self.world.save_changed_areas();
+        debug!("Auto save flushed {} areas", saved_areas);
+    }
+
+    fn save_player_and_metadata(&self) {
+        save_player_info(self.world.get_world_name(), &self.player_info);
+        let world_metadata = WorldMetadata::new(
+            &self.world_time,
+            &self.voxel_simulator,
+            &self.creature_manager,
+            &self.sky,
+            &self.tutorial_messages,
+            &self.rain_system,
+            &self.statistics,
+        )
+        .with_rules(self.world_rules)
+        .with_seed(self.world.get_seed())
+        .with_furnaces(&self.furnaces);
+        store_world_metadata(self.world.get_world_name(), world_metadata);
+    }
+
     fn handle_movement_input(&mut self, delta: f32) {
         let mut move_dir = Vec3::ZERO;
 
-        if input::move_forward() {
+        if input::move_forward(&self.user_settings.key_bindings) {
             move_dir += self.player_info.camera_controller.get_forward_direction();
         }
-        if input::move_back() {
+        if input::move_back(&self.user_settings.key_bindings) {
             move_dir += self.player_info.camera_controller.get_backwards_direction();
         }
-        if input::move_left() {
+        if input::move_left(&self.user_settings.key_bindings) {
             move_dir += self.player_info.camera_controller.get_left_direction();
         }
-        if input::move_right() {
+        if input::move_right(&self.user_settings.key_bindings) {
             move_dir += self.player_info.camera_controller.get_right_direction();
         }
 
         move_dir = move_dir.normalize_or_zero();
-        handle_horizontal_player_movement(&mut self.player_info, &mut self.world, move_dir, delta);
+        self.player_info
+            .set_sneaking(input::sneak(&self.user_settings.key_bindings));
+        handle_horizontal_player_movement(
+            &mut self.player_info,
+            &mut self.world,
+            move_dir,
+            delta,
+            &self.user_settings,
+            input::sprint(&self.user_settings.key_bindings)
+                && input::move_forward(&self.user_settings.key_bindings),
+        );
     }
 }
 impl Drop for VoxelEngine {
     fn drop(&mut self) {
-        save_player_info(self.world.get_world_name(), &self.player_info);
-        let world_metadata = WorldMetadata::new(
-            &self.world_time,
-            &self.voxel_simulator,
-            &self.creature_manager,
-            &self.sky,
-            &self.tutorial_messages,
-            &self.rain_system,
-        );
-        store_world_metadata(self.world.get_world_name(), world_metadata);
         write_user_settings_blocking(&self.user_settings);
-        self.world.save_all_blocking();
         self.asset_manager.sound_manager.stop_music();
+        if !self.should_save_on_exit {
+            info!("Quit without saving '{}'", self.world.get_world_name());
+            return;
+        }
+
+        self.save_player_and_metadata();
+        self.world.save_all_blocking();
     }
 }